@@ -0,0 +1,100 @@
+//! `#[derive(Generate)]` for genrex: implements `genrex::Generate` for a struct by generating
+//! each field in turn.
+//!
+//! A field marked `#[genrex(pattern = "...")]` gets a match of the pattern, and one marked
+//! `#[genrex(preset = "uuid")]` a value of the named preset. Either is parsed into the field's
+//! type with `FromStr`, so numeric fields work as well as `String`. Fields without an attribute
+//! must themselves implement `Generate`, which lets structs nest.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields, LitStr, parse_macro_input};
+
+#[proc_macro_derive(Generate, attributes(genrex))]
+pub fn derive_generate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Where a field's value comes from.
+enum Source {
+    Pattern(LitStr),
+    Preset(LitStr),
+    Nested,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "Generate can only be derived for structs"));
+    };
+    let body = match &data.fields {
+        Fields::Named(fields) => {
+            let values = fields
+                .named
+                .iter()
+                .map(|f| {
+                    let ident = f.ident.as_ref().expect("named field");
+                    let value = field_value(f, &ident.to_string())?;
+                    Ok(quote! { #ident: #value })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { Self { #(#values),* } }
+        }
+        Fields::Unnamed(fields) => {
+            let values = fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, f)| field_value(f, &i.to_string()))
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { Self(#(#values),*) }
+        }
+        Fields::Unit => quote! { Self },
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::genrex::Generate for #name #ty_generics #where_clause {
+            fn generate<R: ::genrex::derive::RngCore + ?Sized>(rng: &mut R) -> ::std::result::Result<Self, ::genrex::GenError> {
+                ::std::result::Result::Ok(#body)
+            }
+        }
+    })
+}
+
+/// The expression producing one field's value.
+fn field_value(field: &Field, name: &str) -> syn::Result<TokenStream2> {
+    let ty = &field.ty;
+    let generator = match source(field)? {
+        Source::Pattern(pattern) => quote! { ::genrex::derive::FieldGenerator::pattern(#pattern) },
+        Source::Preset(preset) => quote! { ::genrex::derive::FieldGenerator::preset(#preset) },
+        Source::Nested => return Ok(quote! { <#ty as ::genrex::Generate>::generate(rng)? }),
+    };
+    Ok(quote! {{
+        static FIELD: ::genrex::derive::FieldGenerator = #generator;
+        FIELD.generate_parsed(rng, #name)?
+    }})
+}
+
+fn source(field: &Field) -> syn::Result<Source> {
+    let mut found = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("genrex")) {
+        attr.parse_nested_meta(|meta| {
+            let source = if meta.path.is_ident("pattern") {
+                Source::Pattern(meta.value()?.parse()?)
+            } else if meta.path.is_ident("preset") {
+                Source::Preset(meta.value()?.parse()?)
+            } else {
+                return Err(meta.error("expected `pattern = \"...\"` or `preset = \"...\"`"));
+            };
+            if found.is_some() {
+                return Err(meta.error("a field takes only one of `pattern` and `preset`"));
+            }
+            found = Some(source);
+            Ok(())
+        })?;
+    }
+    Ok(found.unwrap_or(Source::Nested))
+}
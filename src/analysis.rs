@@ -0,0 +1,113 @@
+//! Static analysis of the language a pattern generates.
+
+use std::ops::{Bound, RangeBounds};
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use crate::tokens::Token;
+use crate::GenError;
+
+/// Properties of the set of strings a pattern can produce.
+///
+/// Lengths are in characters. The analysis follows the lexed token tree, so it describes what
+/// genrex generates: anchors and word boundaries are ignored, constructs degraded in lenient
+/// mode are analyzed as degraded, and unbounded repetitions count as unbounded even though
+/// generation caps them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Analysis {
+    /// Length of the shortest string.
+    pub min_len: usize,
+    /// Length of the longest string, `None` when unbounded.
+    pub max_len: Option<usize>,
+    /// True when the pattern matches finitely many strings.
+    pub finite: bool,
+    /// Exact number of distinct strings, when finite.
+    pub cardinality: Option<BigUint>,
+    /// `length_counts[l]`: number of distinct strings of `l` characters. Covers every length up
+    /// to `max_len`, or up to the generator's `max_len` setting when the language is infinite.
+    pub length_counts: Vec<BigUint>,
+}
+
+impl Analysis {
+    /// Bits of entropy of a uniform choice among the strings whose length is in `lengths`:
+    /// `log2` of their number. `entropy_bits(..)` covers the whole language.
+    ///
+    /// `None` when the range reaches past the counted lengths of an infinite language.
+    pub fn entropy_bits(&self, lengths: impl RangeBounds<usize>) -> Option<f64> {
+        let (lo, hi) = bounds(&lengths);
+        let counted = self.length_counts.len().checked_sub(1);
+        if !self.finite && counted.is_none_or(|last| hi > last) {
+            return None;
+        }
+        let total = self.length_counts.iter().take(hi.saturating_add(1)).skip(lo).fold(BigUint::zero(), |acc, n| acc + n);
+        Some(log2(&total))
+    }
+}
+
+/// Entropy of a generator's outputs within a length range; see `RegexGenerator::entropy`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entropy {
+    /// Bits of a uniform choice among the strings in range.
+    pub uniform_bits: f64,
+    /// Bits of the generator's own sampling distribution over those strings, when it samples
+    /// by NFA walks. Never more than `uniform_bits`.
+    pub sampled_bits: Option<f64>,
+}
+
+/// Inclusive `(low, high)` ends of `range`.
+pub(crate) fn bounds(range: &impl RangeBounds<usize>) -> (usize, usize) {
+    let lo = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let hi = match range.end_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => match n.checked_sub(1) {
+            Some(n) => n,
+            None => return (1, 0),
+        },
+        Bound::Unbounded => usize::MAX,
+    };
+    (lo, hi)
+}
+
+/// `log2(n)`, with 0 for an empty count.
+pub(crate) fn log2(n: &BigUint) -> f64 {
+    if n.bits() == 0 {
+        return 0.0;
+    }
+    // Keep the top 53 bits for the mantissa and add the rest back as an exponent.
+    let shift = n.bits().saturating_sub(53);
+    let top: BigUint = n >> shift;
+    let top = top.to_u64_digits().first().copied().unwrap_or(0) as f64;
+    top.log2() + shift as f64
+}
+
+pub(crate) fn build_dfa(tokens: &[Token]) -> Result<Dfa, GenError> {
+    Dfa::from_nfa(&Nfa::compile(tokens)?)
+}
+
+/// Analyze `dfa`, counting strings by length up to `length_cap` when the language is infinite.
+pub(crate) fn analyze_dfa(dfa: &Dfa, length_cap: usize) -> Analysis {
+    let Some(min_len) = dfa.shortest() else {
+        return Analysis { min_len: 0, max_len: Some(0), finite: true, cardinality: Some(BigUint::default()), length_counts: Vec::new() };
+    };
+    match dfa.reverse_topological() {
+        Some(order) => {
+            let max_len = dfa.longest(&order);
+            Analysis {
+                min_len,
+                max_len,
+                finite: true,
+                cardinality: Some(dfa.cardinality(&order)),
+                length_counts: dfa.count_lengths(max_len.unwrap_or(0)),
+            }
+        }
+        None => Analysis { min_len, max_len: None, finite: false, cardinality: None, length_counts: dfa.count_lengths(length_cap) },
+    }
+}
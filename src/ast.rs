@@ -28,7 +28,7 @@ pub enum AstNode {
     /// A backreference to a group (unit variant — backreference handled at token level).
     Backreference,
     /// A character class
-    Class(Vec<char>),
+    Class(std::sync::Arc<[char]>),
     /// A negated character class (unit variant — details handled by tokens).
     NegatedClass,
     /// A literal character
@@ -0,0 +1,57 @@
+//! Deterministic utilities for composing generated batches.
+//!
+//! Everything here is driven by an explicit seed, so a dataset assembled from several
+//! generators (a mixture of record types in randomized order) is reproducible end-to-end from
+//! one master seed: derive a seed per generator and per utility call with `derive_seed`.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Derive an independent seed for sub-stream `stream` from `seed` (SplitMix64 finalizer).
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Shuffle `items` in place; the same seed always produces the same order.
+pub fn shuffle<T>(items: &mut [T], seed: u64) {
+    items.shuffle(&mut StdRng::seed_from_u64(seed));
+}
+
+/// Randomly merge `batches` while keeping each batch's internal order.
+///
+/// At every step the next item comes from a batch chosen with probability proportional to the
+/// items it has left, so every merge order is equally likely.
+pub fn interleave<T: Clone>(batches: &[Vec<T>], seed: u64) -> Vec<T> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut next = vec![0usize; batches.len()];
+    let mut remaining: usize = batches.iter().map(Vec::len).sum();
+    let mut out = Vec::with_capacity(remaining);
+    while remaining > 0 {
+        let mut pick = rng.gen_range(0..remaining);
+        for (batch, pos) in batches.iter().zip(next.iter_mut()) {
+            let left = batch.len() - *pos;
+            if pick < left {
+                out.push(batch[*pos].clone());
+                *pos += 1;
+                break;
+            }
+            pick -= left;
+        }
+        remaining -= 1;
+    }
+    out
+}
+
+/// Take one item from each batch in turn, skipping batches that have run out.
+pub fn round_robin<T: Clone>(batches: &[Vec<T>]) -> Vec<T> {
+    let longest = batches.iter().map(Vec::len).max().unwrap_or(0);
+    let mut out = Vec::with_capacity(batches.iter().map(Vec::len).sum());
+    for i in 0..longest {
+        out.extend(batches.iter().filter_map(|b| b.get(i).cloned()));
+    }
+    out
+}
@@ -0,0 +1,164 @@
+//! Generation benchmarks for a single pattern, usable from tests and CI.
+//!
+//! `measure` builds a generator, draws a fixed number of samples and reports throughput,
+//! rejection rate and how many attempts each output took, so a project can fail its build when
+//! a pattern change makes generation much slower or much more wasteful. `measure_corpus` runs the
+//! same measurement over the canonical patterns of `genrex::patterns`:
+//!
+//! ```
+//! use genrex::bench::{measure, BenchConfig};
+//!
+//! let result = measure("^[a-f0-9]{8}-[a-f0-9]{4}$", &BenchConfig::default()).unwrap();
+//! assert_eq!(result.failed, 0);
+//! assert!(result.rejection_rate() < 0.5);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::patterns;
+use crate::{Engine, GenError, GenStats, GeneratorConfig, RegexGenerator, RejectReason};
+
+/// What `measure` and `measure_corpus` run.
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    /// Outputs to generate.
+    pub samples: usize,
+    /// Seed of the generator, so counts repeat from run to run.
+    pub seed: u64,
+    pub generator: GeneratorConfig,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig { samples: 1000, seed: 0, generator: GeneratorConfig::default() }
+    }
+}
+
+/// The outcome of `measure` or `measure_generator`.
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    /// Samples requested.
+    pub samples: usize,
+    /// Samples for which generation failed (e.g. `NoMatch` after `max_attempts`).
+    pub failed: usize,
+    /// Time spent generating all samples.
+    pub elapsed: Duration,
+    /// Number of outputs that took each number of attempts, failures excluded.
+    pub histogram: BTreeMap<u64, u64>,
+    /// Outputs produced by each engine, most used first.
+    pub engines: Vec<(Engine, u64)>,
+    /// Counters accumulated over the run, failed samples included.
+    pub stats: GenStats,
+    /// Time taken by each output, sorted.
+    latencies: Vec<Duration>,
+}
+
+impl BenchResult {
+    /// Outputs generated per second.
+    pub fn throughput(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 { 0.0 } else { self.stats.accepted as f64 / secs }
+    }
+
+    /// Fraction of candidates that were rejected.
+    pub fn rejection_rate(&self) -> f64 {
+        if self.stats.attempts == 0 { 0.0 } else { self.stats.rejected() as f64 / self.stats.attempts as f64 }
+    }
+
+    /// The `p`th percentile (nearest rank) of the time taken per output; `None` if nothing was
+    /// generated.
+    pub fn latency(&self, p: usize) -> Option<Duration> {
+        let rank = (self.latencies.len() * p).div_ceil(100).max(1);
+        self.latencies.get(rank - 1).copied()
+    }
+}
+
+impl fmt::Display for BenchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |count: u64, of: u64| if of > 0 { count as f64 * 100.0 / of as f64 } else { 0.0 };
+        writeln!(f, "{:<17}{}", "outputs:", self.stats.accepted)?;
+        if self.failed > 0 {
+            writeln!(f, "{:<17}{}", "failed:", self.failed)?;
+        }
+        writeln!(f, "{:<17}{:.3}s", "elapsed:", self.elapsed.as_secs_f64())?;
+        writeln!(f, "{:<17}{:.1}", "strings/sec:", self.throughput())?;
+        writeln!(f, "{:<17}{:.2}%", "rejection rate:", self.rejection_rate() * 100.0)?;
+        for reason in RejectReason::ALL {
+            let count = self.stats.rejected_for(reason);
+            if count > 0 {
+                writeln!(f, "  {:<15}{} ({:.2}%)", format!("{}:", reason), count, percent(count, self.stats.attempts))?;
+            }
+        }
+        writeln!(f, "{:<17}{:.2}", "attempts/output:", self.stats.attempts_per_output())?;
+        if let (Some(p50), Some(p99)) = (self.latency(50), self.latency(99)) {
+            writeln!(f, "{:<17}{:?}", "latency p50:", p50)?;
+            writeln!(f, "{:<17}{:?}", "latency p99:", p99)?;
+        }
+        for (engine, count) in &self.engines {
+            writeln!(f, "{:<17}{} ({:.2}%)", "engine:", engine, percent(*count, self.stats.accepted))?;
+        }
+        Ok(())
+    }
+}
+
+/// Generate `config.samples` values from `pattern` and measure the cost. Timings vary between
+/// runs and machines; the counts (failures, attempts, rejections, engines and histogram) depend
+/// only on the pattern and the config.
+///
+/// Fails only if the pattern does not build; failed samples are counted, not returned.
+pub fn measure(pattern: &str, config: &BenchConfig) -> Result<BenchResult, GenError> {
+    let mut generator = RegexGenerator::builder(pattern).config(config.generator.clone()).seed(config.seed).build()?;
+    Ok(measure_generator(&mut generator, config.samples))
+}
+
+/// `measure` for every pattern of the canonical corpus (`patterns::ALL`), in its order and each
+/// within its own length bounds; the other generator settings come from `config`.
+pub fn measure_corpus(config: &BenchConfig) -> Result<Vec<(&'static str, BenchResult)>, GenError> {
+    patterns::ALL
+        .iter()
+        .map(|p| {
+            let generator = GeneratorConfig { min_len: p.min_len, max_len: p.max_len, ..config.generator.clone() };
+            let mut builder = RegexGenerator::builder(p.pattern).config(generator).seed(config.seed);
+            if p.needs_backrefs {
+                builder = builder.allow_backrefs();
+            }
+            Ok((p.name, measure_generator(&mut builder.build()?, config.samples)))
+        })
+        .collect()
+}
+
+/// `measure` for a generator built with any other options. Counters are those of this run
+/// alone, but they are also added to the generator's own `stats`.
+pub fn measure_generator(generator: &mut RegexGenerator, samples: usize) -> BenchResult {
+    let before = generator.stats().clone();
+    let mut result = BenchResult {
+        samples,
+        failed: 0,
+        elapsed: Duration::ZERO,
+        histogram: BTreeMap::new(),
+        engines: Vec::new(),
+        stats: GenStats::default(),
+        latencies: Vec::with_capacity(samples),
+    };
+    let started = Instant::now();
+    for _ in 0..samples {
+        match generator.generate_one_with_report() {
+            Ok((_, report)) => {
+                result.latencies.push(report.elapsed);
+                *result.histogram.entry(report.attempts).or_insert(0) += 1;
+                match result.engines.iter_mut().find(|(engine, _)| *engine == report.engine) {
+                    Some((_, count)) => *count += 1,
+                    None => result.engines.push((report.engine, 1)),
+                }
+            }
+            Err(_) => result.failed += 1,
+        }
+    }
+    result.elapsed = started.elapsed();
+    result.stats = generator.stats().since(&before);
+    result.latencies.sort_unstable();
+    result.engines.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    result
+}
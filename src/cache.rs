@@ -0,0 +1,129 @@
+//! Opt-in process-wide cache of compiled patterns.
+//!
+//! Off by default. With a capacity set, `RegexGeneratorBuilder::build` reuses the tokens,
+//! verification regexes and generation program of the same pattern built with the same parsing
+//! options, so a service that builds a generator per request compiles each pattern once:
+//!
+//! ```
+//! genrex::cache::set_capacity(256);
+//! let a = genrex::RegexGenerator::builder("^[a-z]{8}$").build().unwrap();
+//! let b = genrex::RegexGenerator::builder("^[a-z]{8}$").build().unwrap(); // from the cache
+//! # drop((a, b));
+//! genrex::cache::set_capacity(0);
+//! ```
+//!
+//! Everything else (lengths, seeds, filters, constraints and other options) still applies per
+//! build. The least recently used pattern is dropped when the cache is full.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError};
+
+use regex::Regex;
+
+use crate::lexer::ParseMode;
+use crate::program::Program;
+use crate::tokens::Token;
+use crate::warnings::Warning;
+use crate::GenError;
+
+/// What a generator needs from its pattern, independent of lengths and other options.
+pub(crate) struct Compiled {
+    /// Lexed tokens; empty when the pattern lexed to nothing.
+    pub(crate) tokens: Arc<[Token]>,
+    pub(crate) program: Option<Arc<Program>>,
+    /// Verification regex.
+    pub(crate) re: Regex,
+    /// Byte-level verification regex, for `Dialect::Bytes`.
+    pub(crate) bytes_re: Option<regex::bytes::Regex>,
+    pub(crate) warnings: Vec<Warning>,
+    pub(crate) group_count: usize,
+    pub(crate) group_names: HashMap<String, usize>,
+    /// Inline `{genrex:...}` annotations as (group index, annotation body).
+    pub(crate) annotations: Vec<(usize, String)>,
+}
+
+/// A pattern and the builder options that change how it compiles.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Key {
+    /// The pattern as given.
+    pub(crate) source: String,
+    /// The pattern after dialect translation.
+    pub(crate) pattern: String,
+    pub(crate) mode: ParseMode,
+    pub(crate) max_depth: usize,
+    pub(crate) hir: bool,
+    pub(crate) allow_backrefs: bool,
+    pub(crate) bytes: bool,
+    pub(crate) full_match: bool,
+}
+
+#[derive(Default)]
+struct Lru {
+    capacity: usize,
+    /// Incremented on every use; entries remember the tick of their last use.
+    tick: u64,
+    entries: HashMap<Key, (Arc<Compiled>, u64)>,
+}
+
+static CACHE: LazyLock<Mutex<Lru>> = LazyLock::new(Mutex::default);
+
+fn cache() -> MutexGuard<'static, Lru> {
+    CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Keep up to `capacity` compiled patterns. Shrinking drops the least recently used ones;
+/// 0, the default, turns the cache off and empties it.
+pub fn set_capacity(capacity: usize) {
+    let mut lru = cache();
+    lru.capacity = capacity;
+    lru.evict();
+}
+
+/// Drop every cached pattern, keeping the capacity.
+pub fn clear() {
+    cache().entries.clear();
+}
+
+/// Number of patterns currently cached.
+pub fn len() -> usize {
+    cache().entries.len()
+}
+
+impl Lru {
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self.entries.iter().min_by_key(|(_, (_, used))| *used).map(|(key, _)| key.clone()) else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+/// The compiled form of `key`, from the cache when enabled and present, else from `compile`.
+/// Failures are not cached.
+pub(crate) fn get_or_compile(key: Key, compile: impl FnOnce() -> Result<Compiled, GenError>) -> Result<Arc<Compiled>, GenError> {
+    {
+        let mut lru = cache();
+        if lru.capacity == 0 {
+            drop(lru);
+            return compile().map(Arc::new);
+        }
+        lru.tick += 1;
+        let tick = lru.tick;
+        if let Some((compiled, used)) = lru.entries.get_mut(&key) {
+            *used = tick;
+            return Ok(Arc::clone(compiled));
+        }
+    }
+    // Compile without holding the lock; a racing build of the same pattern just compiles twice.
+    let compiled = Arc::new(compile()?);
+    let mut lru = cache();
+    if lru.capacity > 0 {
+        lru.tick += 1;
+        let tick = lru.tick;
+        lru.entries.insert(key, (Arc::clone(&compiled), tick));
+        lru.evict();
+    }
+    Ok(compiled)
+}
@@ -0,0 +1,210 @@
+//! Per-group generation constraints.
+//!
+//! Constraints can be declared inline with the `{genrex:...}` extension syntax directly after a
+//! capturing group (e.g. `(?P<id>[0-9]{8}){genrex:unique}`; anywhere else, including after a
+//! quantified group, the annotation is a parse error), or attached out-of-band through
+//! `RegexGeneratorBuilder::group_constraint` keyed by group name. Inline annotations are stripped
+//! from the pattern before it is compiled for verification, so the base pattern stays portable.
+//! A `\T{name:args}` placeholder is shorthand for a capturing group that matches anything and is
+//! annotated with `{genrex:token=name:args}`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use rand::{Rng, RngCore};
+
+use crate::custom::{CustomToken, TokenRegistry};
+use crate::GenError;
+
+/// Prefix that marks a `{...}` block as a genrex annotation rather than a quantifier.
+pub(crate) const ANNOTATION_PREFIX: &str = "genrex:";
+
+/// A constraint attached to one capturing group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupConstraint {
+    /// The group's value must not repeat within the generator's run.
+    Unique,
+    /// The group's value is a zero-padded decimal counter, starting at `start`
+    /// and advancing once per accepted output.
+    Sequential { start: u64 },
+    /// The group's value is drawn uniformly from the given list.
+    Values(Vec<String>),
+    /// The group's value is produced by the named custom token (see `TokenRegistry`), written
+    /// `NAME` or `NAME:ARGS` to configure the token with arguments.
+    Token(String),
+}
+
+impl GroupConstraint {
+    /// Parse the body of an inline annotation (the text after `genrex:`).
+    ///
+    /// Accepted forms: `unique`, `seq`, `seq=N`, `file=PATH` (one value per line), `token=NAME`,
+    /// `token=NAME:ARGS`.
+    pub fn parse(spec: &str) -> Result<Self, GenError> {
+        let spec = spec.trim();
+        let (key, arg) = match spec.split_once('=') {
+            Some((k, v)) => (k.trim(), Some(v.trim())),
+            None => (spec, None),
+        };
+        match (key, arg) {
+            ("unique", None) => Ok(GroupConstraint::Unique),
+            ("seq", None) => Ok(GroupConstraint::Sequential { start: 0 }),
+            ("seq", Some(n)) => n
+                .parse()
+                .map(|start| GroupConstraint::Sequential { start })
+                .map_err(|_| GenError::InvalidRegex(format!("invalid genrex:seq start '{}'", n))),
+            ("file", Some(path)) => {
+                let text = std::fs::read_to_string(path)
+                    .map_err(|e| GenError::InvalidRegex(format!("genrex:file '{}': {}", path, e)))?;
+                let values: Vec<String> = text.lines().filter(|l| !l.is_empty()).map(str::to_string).collect();
+                if values.is_empty() {
+                    return Err(GenError::InvalidRegex(format!("genrex:file '{}' contains no values", path)));
+                }
+                Ok(GroupConstraint::Values(values))
+            }
+            ("token", Some(name)) if !name.is_empty() => Ok(GroupConstraint::Token(name.to_string())),
+            _ => Err(GenError::InvalidRegex(format!("unknown genrex annotation '{}'", spec))),
+        }
+    }
+}
+
+/// Value forced onto a group during a single generation attempt.
+#[derive(Debug, Clone)]
+pub enum GroupOverride {
+    /// Use this exact value.
+    Fixed(String),
+    /// Replace the generated value with this counter, zero-padded to the generated width.
+    Counter(u64),
+}
+
+/// Run-time state for all group constraints of one generator.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConstraintSet {
+    constraints: HashMap<usize, GroupConstraint>,
+    seen: HashMap<usize, HashSet<String>>,
+    counters: HashMap<usize, u64>,
+    tokens: HashMap<usize, Arc<dyn CustomToken>>,
+}
+
+impl ConstraintSet {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    pub(crate) fn insert(&mut self, group: usize, constraint: GroupConstraint) {
+        if let GroupConstraint::Sequential { start } = constraint {
+            self.counters.insert(group, start);
+        }
+        self.constraints.insert(group, constraint);
+    }
+
+    /// Look up the custom token behind every `Token` constraint.
+    pub(crate) fn resolve_tokens(&mut self, registry: &TokenRegistry) -> Result<(), GenError> {
+        for (group, c) in &self.constraints {
+            if let GroupConstraint::Token(spec) = c {
+                let (name, args) = match spec.split_once(':') {
+                    Some((name, args)) => (name, Some(args)),
+                    None => (spec.as_str(), None),
+                };
+                let token = registry
+                    .get(name)
+                    .ok_or_else(|| GenError::InvalidRegex(format!("unknown custom token '{}'", name)))?;
+                let token = match args {
+                    Some(args) => token
+                        .with_args(args)
+                        .map_err(|e| GenError::InvalidRegex(format!("custom token '{}': {}", name, e)))?,
+                    None => token,
+                };
+                self.tokens.insert(*group, token);
+            }
+        }
+        Ok(())
+    }
+
+    /// Overrides to install in the `TokenContext` before an attempt.
+    pub(crate) fn overrides(&self, rng: &mut dyn RngCore) -> HashMap<usize, GroupOverride> {
+        let mut out = HashMap::new();
+        for (group, c) in &self.constraints {
+            match c {
+                GroupConstraint::Unique => {}
+                GroupConstraint::Sequential { .. } => {
+                    out.insert(*group, GroupOverride::Counter(self.counters[group]));
+                }
+                GroupConstraint::Values(values) => {
+                    let v = values[rng.gen_range(0..values.len())].clone();
+                    out.insert(*group, GroupOverride::Fixed(v));
+                }
+                GroupConstraint::Token(_) => {
+                    out.insert(*group, GroupOverride::Fixed(self.tokens[group].generate(rng)));
+                }
+            }
+        }
+        out
+    }
+
+    /// True when the captures of a candidate satisfy every uniqueness constraint.
+    pub(crate) fn admits(&self, captures: &[Option<String>]) -> bool {
+        self.constraints.iter().all(|(group, c)| match c {
+            GroupConstraint::Unique => match captures.get(group - 1).and_then(|c| c.as_ref()) {
+                Some(v) => !self.seen.get(group).is_some_and(|s| s.contains(v)),
+                None => true,
+            },
+            _ => true,
+        })
+    }
+
+    /// Record an accepted candidate: remember unique values and advance counters.
+    pub(crate) fn commit(&mut self, captures: &[Option<String>]) {
+        for (group, c) in &self.constraints {
+            match c {
+                GroupConstraint::Unique => {
+                    if let Some(Some(v)) = captures.get(group - 1) {
+                        self.seen.entry(*group).or_default().insert(v.clone());
+                    }
+                }
+                GroupConstraint::Sequential { .. } => {
+                    *self.counters.entry(*group).or_default() += 1;
+                }
+                GroupConstraint::Values(_) | GroupConstraint::Token(_) => {}
+            }
+        }
+    }
+}
+
+/// Remove inline `{genrex:...}` annotations and turn `\T{...}` placeholders into groups matching
+/// anything, so the pattern can be compiled by standard engines.
+pub(crate) fn strip_annotations(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(pos) = rest.find("\\T{") {
+        // `\\T{` is an escaped backslash followed by a literal `T{`.
+        let escaped = rest[..pos].chars().rev().take_while(|&c| c == '\\').count() % 2 == 1;
+        out.push_str(&rest[..pos]);
+        match rest[pos..].find('}') {
+            Some(end) if !escaped => {
+                out.push_str("((?s:.*))");
+                rest = &rest[pos + end + 1..];
+            }
+            _ => {
+                out.push_str("\\T");
+                rest = &rest[pos + 2..];
+            }
+        }
+    }
+    let placeholders_replaced = std::mem::take(&mut out) + rest;
+    let mut rest = placeholders_replaced.as_str();
+    let marker = format!("{{{}", ANNOTATION_PREFIX);
+    while let Some(pos) = rest.find(&marker) {
+        // An escaped brace is a literal, not an annotation.
+        let escaped = rest[..pos].chars().rev().take_while(|&c| c == '\\').count() % 2 == 1;
+        out.push_str(&rest[..pos]);
+        match rest[pos..].find('}') {
+            Some(end) if !escaped => rest = &rest[pos + end + 1..],
+            _ => {
+                out.push('{');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,288 @@
+//! Custom tokens: hand-written generators that stand in for a capturing group.
+//!
+//! Some formats (quoted CSV fields, JSON string literals, nested brackets) are approximated
+//! poorly by regexes, so strings generated from the regex alone often break downstream parsers.
+//! A `CustomToken` produces the group's text directly; the group's regex is still used to
+//! verify the whole output. Tokens are looked up by name in a `TokenRegistry` and attached to a
+//! group inline with `{genrex:token=NAME}` or out-of-band with `GroupConstraint::Token`, or
+//! placed anywhere in a pattern with `\T{NAME}`. Tokens that take arguments are configured with
+//! `NAME:ARGS`, e.g. `\T{date:%Y-%m-%d}`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use rand::{Rng, RngCore};
+
+/// A generator for the full text of one capturing group.
+pub trait CustomToken: fmt::Debug + Send + Sync {
+    fn generate(&self, rng: &mut dyn RngCore) -> String;
+
+    /// A copy of this token configured by the `ARGS` of a `NAME:ARGS` reference, or a message
+    /// saying why they are invalid. Tokens take no arguments unless they override this.
+    fn with_args(&self, args: &str) -> Result<Arc<dyn CustomToken>, String> {
+        Err(format!("takes no arguments, got '{}'", args))
+    }
+}
+
+/// Named custom tokens available to a builder. `default()` includes the built-in tokens:
+///
+/// | name            | output                                                    |
+/// |-----------------|-----------------------------------------------------------|
+/// | `json_string`   | `"..."` with `\"`, `\\`, `\n`, `\t` and `\uXXXX` escapes   |
+/// | `csv_field`     | `"..."` with doubled quotes, commas and line breaks inside |
+/// | `single_quoted` | `'...'` with doubled single quotes (SQL style)             |
+/// | `parens`        | nested, balanced `(...)`                                   |
+/// | `brackets`      | nested, balanced `[...]`                                   |
+/// | `braces`        | nested, balanced `{...}`                                   |
+/// | `date`          | a date and time from 1970-2099, `%Y-%m-%d` unless given a format |
+#[derive(Debug, Clone)]
+pub struct TokenRegistry {
+    tokens: HashMap<String, Arc<dyn CustomToken>>,
+}
+
+impl Default for TokenRegistry {
+    fn default() -> Self {
+        let mut registry = TokenRegistry::empty();
+        registry.register("json_string", QuotedString::json());
+        registry.register("csv_field", QuotedString::csv());
+        registry.register("single_quoted", QuotedString::new('\'', Escape::Doubled));
+        registry.register("parens", Balanced::new('(', ')'));
+        registry.register("brackets", Balanced::new('[', ']'));
+        registry.register("braces", Balanced::new('{', '}'));
+        registry.register("date", Date::default());
+        registry
+    }
+}
+
+impl TokenRegistry {
+    /// A registry without the built-in tokens.
+    pub fn empty() -> Self {
+        TokenRegistry { tokens: HashMap::new() }
+    }
+
+    /// Add or replace the token called `name`.
+    pub fn register<T: CustomToken + 'static>(&mut self, name: &str, token: T) {
+        self.tokens.insert(name.to_string(), Arc::new(token));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn CustomToken>> {
+        self.tokens.get(name).cloned()
+    }
+}
+
+/// How the quote character (and the escape character itself) is escaped inside a quoted string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    /// Backslash escapes, JSON style.
+    Backslash,
+    /// The quote is written twice, CSV/SQL style.
+    Doubled,
+}
+
+/// A quoted string whose content may contain the quote character, always escaped correctly.
+#[derive(Debug, Clone)]
+pub struct QuotedString {
+    quote: char,
+    escape: Escape,
+    /// Characters content is drawn from, besides the quote (and backslash for `Backslash`).
+    alphabet: Vec<char>,
+    min_len: usize,
+    max_len: usize,
+}
+
+impl QuotedString {
+    /// Content drawn from ASCII letters, digits and spaces, 0-16 characters.
+    pub fn new(quote: char, escape: Escape) -> Self {
+        let alphabet = ('a'..='z').chain('A'..='Z').chain('0'..='9').chain([' ']).collect();
+        QuotedString { quote, escape, alphabet, min_len: 0, max_len: 16 }
+    }
+
+    /// A JSON string literal.
+    pub fn json() -> Self {
+        QuotedString::new('"', Escape::Backslash)
+    }
+
+    /// A quoted CSV field (RFC 4180); content may hold commas and CRLF line breaks.
+    pub fn csv() -> Self {
+        let mut field = QuotedString::new('"', Escape::Doubled);
+        field.alphabet.extend([',', '\r', '\n']);
+        field
+    }
+
+    /// Replace the content alphabet.
+    pub fn alphabet(mut self, chars: &[char]) -> Self {
+        self.alphabet = chars.to_vec();
+        self
+    }
+
+    /// Bounds on the number of unescaped content characters.
+    pub fn content_len(mut self, min: usize, max: usize) -> Self {
+        self.min_len = min;
+        self.max_len = max.max(min);
+        self
+    }
+}
+
+impl CustomToken for QuotedString {
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
+        let len = rng.gen_range(self.min_len..=self.max_len);
+        let mut out = String::with_capacity(len + 2);
+        out.push(self.quote);
+        for _ in 0..len {
+            // Roughly one character in eight exercises an escape.
+            let c = if self.alphabet.is_empty() || rng.gen_ratio(1, 8) {
+                match self.escape {
+                    Escape::Backslash => [self.quote, '\\', '\n', '\t', 'é'][rng.gen_range(0..5)],
+                    Escape::Doubled => self.quote,
+                }
+            } else {
+                self.alphabet[rng.gen_range(0..self.alphabet.len())]
+            };
+            match (self.escape, c) {
+                (Escape::Backslash, c) if c == self.quote => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                (Escape::Backslash, '\\') => out.push_str("\\\\"),
+                (Escape::Backslash, '\n') => out.push_str("\\n"),
+                (Escape::Backslash, '\t') => out.push_str("\\t"),
+                // Other control characters may not appear raw in a JSON string.
+                (Escape::Backslash, c) if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+                // Characters beyond the BMP escape as a UTF-16 surrogate pair, as JSON requires.
+                (Escape::Backslash, c) if !c.is_ascii() => {
+                    for unit in c.encode_utf16(&mut [0; 2]) {
+                        out.push_str(&format!("\\u{:04x}", unit));
+                    }
+                }
+                (Escape::Doubled, c) if c == self.quote => {
+                    out.push(c);
+                    out.push(c);
+                }
+                (_, c) => out.push(c),
+            }
+        }
+        out.push(self.quote);
+        out
+    }
+}
+
+/// Nested, balanced open/close pairs with short alphanumeric runs between them.
+#[derive(Debug, Clone)]
+pub struct Balanced {
+    open: char,
+    close: char,
+    max_depth: usize,
+    max_children: usize,
+}
+
+impl Balanced {
+    /// Up to 3 levels deep with up to 3 children per level.
+    pub fn new(open: char, close: char) -> Self {
+        Balanced { open, close, max_depth: 3, max_children: 3 }
+    }
+
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    pub fn max_children(mut self, children: usize) -> Self {
+        self.max_children = children;
+        self
+    }
+
+    fn write(&self, rng: &mut dyn RngCore, depth: usize, out: &mut String) {
+        out.push(self.open);
+        let children = if depth < self.max_depth { rng.gen_range(0..=self.max_children) } else { 0 };
+        for _ in 0..children {
+            if rng.gen_bool(0.5) {
+                self.write(rng, depth + 1, out);
+            } else {
+                for _ in 0..rng.gen_range(1..=4) {
+                    out.push(rng.sample(rand::distributions::Alphanumeric) as char);
+                }
+            }
+        }
+        out.push(self.close);
+    }
+}
+
+impl CustomToken for Balanced {
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
+        let mut out = String::new();
+        self.write(rng, 1, &mut out);
+        out
+    }
+}
+
+/// A calendar date and time from 1970 to 2099, written with a strftime-style format.
+///
+/// Supported specifiers: `%Y` (year), `%m` (month), `%d` (day), `%H` (hour), `%M` (minute),
+/// `%S` (second), `%j` (day of the year) and `%%`; everything else is copied as is.
+#[derive(Debug, Clone)]
+pub struct Date {
+    format: String,
+}
+
+impl Default for Date {
+    fn default() -> Self {
+        Date { format: "%Y-%m-%d".to_string() }
+    }
+}
+
+impl Date {
+    /// A date written with `format`, or an error naming the first unsupported specifier.
+    pub fn new(format: &str) -> Result<Self, String> {
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c == '%' {
+                match chars.next() {
+                    Some('Y' | 'm' | 'd' | 'H' | 'M' | 'S' | 'j' | '%') => {}
+                    Some(spec) => return Err(format!("unsupported date specifier '%{}'", spec)),
+                    None => return Err("date format ends with '%'".to_string()),
+                }
+            }
+        }
+        Ok(Date { format: format.to_string() })
+    }
+}
+
+impl CustomToken for Date {
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
+        let year: u32 = rng.gen_range(1970..=2099);
+        let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let days_in = |month: u32| match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        let month = rng.gen_range(1..=12);
+        let day = rng.gen_range(1..=days_in(month));
+        let ordinal: u32 = (1..month).map(days_in).sum::<u32>() + day;
+        let mut out = String::new();
+        let mut chars = self.format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => out.push_str(&year.to_string()),
+                Some('m') => out.push_str(&format!("{:02}", month)),
+                Some('d') => out.push_str(&format!("{:02}", day)),
+                Some('H') => out.push_str(&format!("{:02}", rng.gen_range(0..24))),
+                Some('M') => out.push_str(&format!("{:02}", rng.gen_range(0..60))),
+                Some('S') => out.push_str(&format!("{:02}", rng.gen_range(0..60))),
+                Some('j') => out.push_str(&format!("{:03}", ordinal)),
+                _ => out.push('%'),
+            }
+        }
+        out
+    }
+
+    fn with_args(&self, args: &str) -> Result<Arc<dyn CustomToken>, String> {
+        Ok(Arc::new(Date::new(args)?))
+    }
+}
@@ -0,0 +1,75 @@
+//! Generating whole values, such as test-data structs, from a caller's RNG.
+//!
+//! With the `derive` feature, `#[derive(Generate)]` implements `Generate` for a struct from
+//! `#[genrex(pattern = "...")]` and `#[genrex(preset = "...")]` field attributes; the expansion
+//! uses `FieldGenerator` for each such field.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+#[doc(hidden)]
+pub use rand::RngCore;
+
+use crate::{GenError, RegexGenerator};
+
+/// A type whose values can be generated at random.
+pub trait Generate: Sized {
+    /// Generate one value, drawing all randomness from `rng`.
+    fn generate<R: RngCore + ?Sized>(rng: &mut R) -> Result<Self, GenError>;
+}
+
+enum Source {
+    Pattern(&'static str),
+    Preset(&'static str),
+}
+
+/// A generator for one field, built on first use and shared from a `static`.
+///
+/// Each value is generated from a seed drawn from the caller's RNG, so the same RNG state
+/// always gives the same value.
+pub struct FieldGenerator {
+    source: Source,
+    generator: OnceLock<Result<Mutex<RegexGenerator>, GenError>>,
+}
+
+impl FieldGenerator {
+    /// Matches of `pattern`, with default settings.
+    pub const fn pattern(pattern: &'static str) -> Self {
+        FieldGenerator { source: Source::Pattern(pattern), generator: OnceLock::new() }
+    }
+
+    /// Values of the preset called `name` (see `presets`).
+    pub const fn preset(name: &'static str) -> Self {
+        FieldGenerator { source: Source::Preset(name), generator: OnceLock::new() }
+    }
+
+    /// Generate one string.
+    pub fn generate<R: RngCore + ?Sized>(&self, rng: &mut R) -> Result<String, GenError> {
+        let generator = self
+            .generator
+            .get_or_init(|| {
+                let builder = match self.source {
+                    Source::Pattern(pattern) => Ok(RegexGenerator::builder(pattern)),
+                    Source::Preset(name) => crate::preset(name),
+                };
+                builder.and_then(|b| b.build()).map(Mutex::new)
+            })
+            .as_ref()
+            .map_err(Clone::clone)?;
+        let mut generator = generator.lock().unwrap_or_else(|e| e.into_inner());
+        generator.reseed(rng.next_u64());
+        generator.generate_one()
+    }
+
+    /// Generate one string and parse it as a `T`; `field` names the field in the parse error.
+    pub fn generate_parsed<T, R>(&self, rng: &mut R, field: &str) -> Result<T, GenError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+        R: RngCore + ?Sized,
+    {
+        let s = self.generate(rng)?;
+        s.parse().map_err(|e| GenError::InvalidConfig(format!("field {}: cannot parse {:?}: {}", field, s, e)))
+    }
+}
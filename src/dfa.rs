@@ -0,0 +1,571 @@
+//! Deterministic automata built from the NFA by subset construction, used for exact analysis.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use rand::Rng;
+
+use crate::nfa::Nfa;
+use crate::GenError;
+
+/// Upper bound on DFA states before analysis gives up.
+const MAX_DFA_STATES: usize = 20_000;
+
+/// A trimmed DFA: every state is reachable from the start and can reach an accepting state.
+/// Missing transitions go to an implicit dead state.
+#[derive(Debug, Clone)]
+pub(crate) struct Dfa {
+    /// Per state, transitions sorted by char.
+    pub(crate) trans: Vec<Vec<(char, usize)>>,
+    pub(crate) accept: Vec<bool>,
+    /// `None` when the language is empty.
+    pub(crate) start: Option<usize>,
+}
+
+impl Dfa {
+    pub(crate) fn from_nfa(nfa: &Nfa) -> Result<Dfa, GenError> {
+        let mut start = vec![nfa.start];
+        nfa.closure(&mut start);
+        let mut ids: HashMap<Vec<usize>, usize> = HashMap::new();
+        let mut sets = vec![start.clone()];
+        ids.insert(start, 0);
+        let mut trans: Vec<Vec<(char, usize)>> = Vec::new();
+        let mut queue = VecDeque::from([0usize]);
+        while let Some(id) = queue.pop_front() {
+            let mut moves: BTreeMap<char, Vec<usize>> = BTreeMap::new();
+            for &s in &sets[id] {
+                for (chars, to) in &nfa.states[s].edges {
+                    for &c in chars.iter() {
+                        moves.entry(c).or_default().push(*to);
+                    }
+                }
+            }
+            let mut out = Vec::with_capacity(moves.len());
+            for (c, mut targets) in moves {
+                targets.sort_unstable();
+                targets.dedup();
+                nfa.closure(&mut targets);
+                let next = match ids.get(&targets) {
+                    Some(&n) => n,
+                    None => {
+                        if sets.len() >= MAX_DFA_STATES {
+                            return Err(GenError::Unsupported(format!("pattern needs more than {} DFA states", MAX_DFA_STATES)));
+                        }
+                        let n = sets.len();
+                        ids.insert(targets.clone(), n);
+                        sets.push(targets);
+                        queue.push_back(n);
+                        n
+                    }
+                };
+                out.push((c, next));
+            }
+            if trans.len() <= id {
+                trans.resize(id + 1, Vec::new());
+            }
+            trans[id] = out;
+        }
+        trans.resize(sets.len(), Vec::new());
+        let accept = sets.iter().map(|s| s.binary_search(&nfa.accept).is_ok()).collect();
+        Ok(Dfa { trans, accept, start: Some(0) }.trim())
+    }
+
+    /// The product automaton accepting the strings every one of `dfas` accepts.
+    pub(crate) fn intersect(dfas: &[Dfa]) -> Result<Dfa, GenError> {
+        let Some(start) = dfas.iter().map(|d| d.start).collect::<Option<Vec<usize>>>() else {
+            return Ok(Dfa { trans: vec![Vec::new()], accept: vec![false], start: None });
+        };
+        let mut ids: HashMap<Vec<usize>, usize> = HashMap::from([(start.clone(), 0)]);
+        let mut tuples = vec![start];
+        let mut trans: Vec<Vec<(char, usize)>> = Vec::new();
+        let mut id = 0;
+        while id < tuples.len() {
+            let mut out = Vec::new();
+            let (first, rest) = (&dfas[0], &dfas[1..]);
+            'chars: for &(c, t) in &first.trans[tuples[id][0]] {
+                let mut next = Vec::with_capacity(dfas.len());
+                next.push(t);
+                for (d, &s) in rest.iter().zip(&tuples[id][1..]) {
+                    match d.step(s, c) {
+                        Some(t) => next.push(t),
+                        None => continue 'chars,
+                    }
+                }
+                let n = match ids.get(&next) {
+                    Some(&n) => n,
+                    None => {
+                        if tuples.len() >= MAX_DFA_STATES {
+                            return Err(GenError::Unsupported(format!("intersection needs more than {} DFA states", MAX_DFA_STATES)));
+                        }
+                        ids.insert(next.clone(), tuples.len());
+                        tuples.push(next);
+                        tuples.len() - 1
+                    }
+                };
+                out.push((c, n));
+            }
+            trans.push(out);
+            id += 1;
+        }
+        let accept = tuples.iter().map(|t| t.iter().zip(dfas).all(|(&s, d)| d.accept[s])).collect();
+        Ok(Dfa { trans, accept, start: Some(0) }.trim())
+    }
+
+    /// The automaton accepting the strings over `alphabet` (sorted, without duplicates) that
+    /// this one rejects.
+    pub(crate) fn complement(&self, alphabet: &[char]) -> Dfa {
+        // The implicit dead state becomes explicit, as the last state, and accepting.
+        let dead = self.len();
+        let mut trans: Vec<Vec<(char, usize)>> = self
+            .trans
+            .iter()
+            .enumerate()
+            .map(|(s, _)| alphabet.iter().map(|&c| (c, self.step(s, c).unwrap_or(dead))).collect())
+            .collect();
+        trans.push(alphabet.iter().map(|&c| (c, dead)).collect());
+        let mut accept: Vec<bool> = self.accept.iter().map(|a| !a).collect();
+        accept.push(true);
+        Dfa { trans, accept, start: Some(self.start.unwrap_or(dead)) }.trim()
+    }
+
+    /// The state reached from `state` on `c`, if any.
+    fn step(&self, state: usize, c: char) -> Option<usize> {
+        let edges = &self.trans[state];
+        edges.binary_search_by_key(&c, |&(d, _)| d).ok().map(|i| edges[i].1)
+    }
+
+    /// `table[r][s]`: number of strings of exactly `r` characters accepted from state `s`, for
+    /// `r` in `0..=max_len`.
+    pub(crate) fn exact_counts(&self, max_len: usize) -> Vec<Vec<BigUint>> {
+        let succ = self.weighted_successors();
+        let mut table: Vec<Vec<BigUint>> = Vec::with_capacity(max_len + 1);
+        table.push(self.accept.iter().map(|&a| if a { BigUint::one() } else { BigUint::zero() }).collect());
+        for r in 1..=max_len {
+            let prev = &table[r - 1];
+            let row = succ.iter().map(|edges| edges.iter().fold(BigUint::zero(), |acc, &(t, w)| acc + &prev[t] * w)).collect();
+            table.push(row);
+        }
+        table
+    }
+
+    /// A string drawn uniformly from the accepted strings of `min_len..=max_len` characters,
+    /// given `table = exact_counts(max_len)`. `None` if there are none.
+    pub(crate) fn sample<R: Rng + ?Sized>(&self, rng: &mut R, table: &[Vec<BigUint>], min_len: usize, max_len: usize) -> Option<String> {
+        let mut state = self.start?;
+        let lengths = min_len..=max_len.min(table.len().checked_sub(1)?);
+        let total: BigUint = lengths.clone().map(|r| &table[r][state]).sum();
+        let mut k = random_below(rng, &total)?;
+        let mut remaining = lengths.clone().find(|&r| {
+            let below = &table[r][state];
+            if k < *below {
+                return true;
+            }
+            k -= below;
+            false
+        })?;
+        let mut out = String::new();
+        while remaining > 0 {
+            let (c, t) = self.trans[state].iter().copied().find(|&(_, t)| {
+                let below = &table[remaining - 1][t];
+                if k < *below {
+                    return true;
+                }
+                k -= below;
+                false
+            })?;
+            out.push(c);
+            state = t;
+            remaining -= 1;
+        }
+        Some(out)
+    }
+
+    /// Drop states that cannot reach an accepting state, renumbering the rest.
+    fn trim(self) -> Dfa {
+        let n = self.trans.len();
+        let mut reverse: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (s, edges) in self.trans.iter().enumerate() {
+            for &(_, t) in edges {
+                reverse[t].push(s);
+            }
+        }
+        let mut live = self.accept.clone();
+        let mut stack: Vec<usize> = (0..n).filter(|&s| live[s]).collect();
+        while let Some(s) = stack.pop() {
+            for &p in &reverse[s] {
+                if !live[p] {
+                    live[p] = true;
+                    stack.push(p);
+                }
+            }
+        }
+        let mut remap = vec![usize::MAX; n];
+        let mut next = 0;
+        for s in 0..n {
+            if live[s] {
+                remap[s] = next;
+                next += 1;
+            }
+        }
+        let mut trans = Vec::with_capacity(next);
+        let mut accept = Vec::with_capacity(next);
+        for s in (0..n).filter(|&s| live[s]) {
+            trans.push(self.trans[s].iter().filter(|&&(_, t)| live[t]).map(|&(c, t)| (c, remap[t])).collect());
+            accept.push(self.accept[s]);
+        }
+        let start = self.start.filter(|&s| live[s]).map(|s| remap[s]);
+        Dfa { trans, accept, start }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.trans.len()
+    }
+
+    /// Per state, the distinct successor states with how many chars lead to each.
+    pub(crate) fn weighted_successors(&self) -> Vec<Vec<(usize, u64)>> {
+        self.trans
+            .iter()
+            .map(|edges| {
+                let mut counts: BTreeMap<usize, u64> = BTreeMap::new();
+                for &(_, t) in edges {
+                    *counts.entry(t).or_default() += 1;
+                }
+                counts.into_iter().collect()
+            })
+            .collect()
+    }
+
+    /// Length of the shortest accepted string.
+    pub(crate) fn shortest(&self) -> Option<usize> {
+        let start = self.start?;
+        let mut dist = vec![usize::MAX; self.len()];
+        dist[start] = 0;
+        let mut queue = VecDeque::from([start]);
+        while let Some(s) = queue.pop_front() {
+            if self.accept[s] {
+                return Some(dist[s]);
+            }
+            for &(_, t) in &self.trans[s] {
+                if dist[t] == usize::MAX {
+                    dist[t] = dist[s] + 1;
+                    queue.push_back(t);
+                }
+            }
+        }
+        None
+    }
+
+    /// States in reverse topological order, or `None` if the (trimmed) DFA has a cycle,
+    /// i.e. the language is infinite.
+    pub(crate) fn reverse_topological(&self) -> Option<Vec<usize>> {
+        // 0 = unvisited, 1 = on stack, 2 = done.
+        let mut mark = vec![0u8; self.len()];
+        let mut order = Vec::with_capacity(self.len());
+        let Some(start) = self.start else { return Some(order) };
+        let mut stack = vec![(start, 0usize)];
+        mark[start] = 1;
+        while let Some((s, i)) = stack.pop() {
+            if let Some(&(_, t)) = self.trans[s].get(i) {
+                stack.push((s, i + 1));
+                match mark[t] {
+                    0 => {
+                        mark[t] = 1;
+                        stack.push((t, 0));
+                    }
+                    1 => return None,
+                    _ => {}
+                }
+            } else {
+                mark[s] = 2;
+                order.push(s);
+            }
+        }
+        Some(order)
+    }
+
+    /// Length of the longest accepted string, for a finite language.
+    pub(crate) fn longest(&self, order: &[usize]) -> Option<usize> {
+        let mut best: Vec<Option<usize>> = vec![None; self.len()];
+        for &s in order {
+            let mut b = if self.accept[s] { Some(0) } else { None };
+            for &(_, t) in &self.trans[s] {
+                if let Some(l) = best[t] {
+                    b = Some(b.map_or(l + 1, |x: usize| x.max(l + 1)));
+                }
+            }
+            best[s] = b;
+        }
+        best[self.start?]
+    }
+
+    /// Number of accepted strings, for a finite language.
+    pub(crate) fn cardinality(&self, order: &[usize]) -> BigUint {
+        let Some(start) = self.start else { return BigUint::zero() };
+        let succ = self.weighted_successors();
+        let mut count: Vec<BigUint> = vec![BigUint::zero(); self.len()];
+        for &s in order {
+            let mut c = if self.accept[s] { BigUint::one() } else { BigUint::zero() };
+            for &(t, w) in &succ[s] {
+                c += &count[t] * w;
+            }
+            count[s] = c;
+        }
+        std::mem::take(&mut count[start])
+    }
+
+    /// Number of accepted strings of exactly `len` characters.
+    pub(crate) fn count_len(&self, len: usize) -> BigUint {
+        let Some(start) = self.start else { return BigUint::zero() };
+        let succ = self.weighted_successors();
+        let mut cur: Vec<BigUint> = self.accept.iter().map(|&a| if a { BigUint::one() } else { BigUint::zero() }).collect();
+        for _ in 0..len {
+            cur = succ
+                .iter()
+                .map(|edges| edges.iter().fold(BigUint::zero(), |acc, &(t, w)| acc + &cur[t] * w))
+                .collect();
+        }
+        std::mem::take(&mut cur[start])
+    }
+
+    /// `counts[l]`: number of accepted strings of exactly `l` characters, for `l` in
+    /// `0..=max_len`. Empty when the language is.
+    pub(crate) fn count_lengths(&self, max_len: usize) -> Vec<BigUint> {
+        let Some(start) = self.start else { return Vec::new() };
+        let succ = self.weighted_successors();
+        let mut cur = vec![BigUint::zero(); self.len()];
+        cur[start] = BigUint::one();
+        let mut counts = Vec::new();
+        for l in 0..=max_len {
+            counts.push(cur.iter().zip(&self.accept).filter(|(_, a)| **a).fold(BigUint::zero(), |acc, (c, _)| acc + c));
+            if l == max_len {
+                break;
+            }
+            let mut next = vec![BigUint::zero(); self.len()];
+            for (s, edges) in succ.iter().enumerate() {
+                if cur[s].is_zero() {
+                    continue;
+                }
+                for &(t, w) in edges {
+                    next[t] += &cur[s] * w;
+                }
+            }
+            cur = next;
+        }
+        counts
+    }
+
+    /// `table[r][s]`: number of strings of at most `r` characters accepted from state `s`,
+    /// for `r` in `0..=max_len`.
+    pub(crate) fn counts_up_to(&self, max_len: usize) -> Vec<Vec<BigUint>> {
+        let succ = self.weighted_successors();
+        let mut table: Vec<Vec<BigUint>> = Vec::with_capacity(max_len + 1);
+        table.push(self.accept.iter().map(|&a| if a { BigUint::one() } else { BigUint::zero() }).collect());
+        for r in 1..=max_len {
+            let prev = &table[r - 1];
+            let row = succ
+                .iter()
+                .enumerate()
+                .map(|(s, edges)| {
+                    let own = if self.accept[s] { BigUint::one() } else { BigUint::zero() };
+                    edges.iter().fold(own, |acc, &(t, w)| acc + &prev[t] * w)
+                })
+                .collect();
+            table.push(row);
+        }
+        table
+    }
+
+    /// The `k`-th (0-based) accepted string of at most `max_len` characters, in lexicographic
+    /// order by code point.
+    pub(crate) fn nth(&self, k: &BigUint, max_len: usize) -> Option<String> {
+        let mut state = self.start?;
+        let table = self.counts_up_to(max_len);
+        let mut k = k.clone();
+        let mut out = String::new();
+        let mut remaining = max_len;
+        'walk: loop {
+            if self.accept[state] {
+                if k.is_zero() {
+                    return Some(out);
+                }
+                k -= 1u32;
+            }
+            if remaining == 0 {
+                return None;
+            }
+            for &(c, t) in &self.trans[state] {
+                let below = &table[remaining - 1][t];
+                if &k < below {
+                    out.push(c);
+                    state = t;
+                    remaining -= 1;
+                    continue 'walk;
+                }
+                k -= below;
+            }
+            return None;
+        }
+    }
+
+    /// Inverse of `nth`: the 0-based lexicographic index of `s` among accepted strings of at most
+    /// `max_len` characters, or `None` if `s` is not one of them.
+    pub(crate) fn rank(&self, s: &str, max_len: usize) -> Option<BigUint> {
+        let mut state = self.start?;
+        let len = s.chars().count();
+        if len > max_len {
+            return None;
+        }
+        let table = self.counts_up_to(max_len);
+        let mut rank = BigUint::zero();
+        let mut remaining = max_len;
+        for c in s.chars() {
+            // The prefix itself sorts before its extensions.
+            if self.accept[state] {
+                rank += 1u32;
+            }
+            let mut next = None;
+            for &(d, t) in &self.trans[state] {
+                if d < c {
+                    rank += &table[remaining - 1][t];
+                } else {
+                    if d == c {
+                        next = Some(t);
+                    }
+                    break;
+                }
+            }
+            state = next?;
+            remaining -= 1;
+        }
+        self.accept[state].then_some(rank)
+    }
+
+    /// Most UTF-8 bytes any one character on a transition takes (1 for a DFA without any).
+    pub(crate) fn widest_char(&self) -> usize {
+        self.trans.iter().flatten().map(|&(c, _)| c.len_utf8()).max().unwrap_or(1)
+    }
+
+    /// The lexicographically smallest accepted string whose length is the smallest (or, with
+    /// `longest`, the largest) in `min_len..=max_len` characters.
+    pub(crate) fn extreme(&self, min_len: usize, max_len: usize, longest: bool) -> Option<String> {
+        let start = self.start?;
+        // reach[r][s]: some string of exactly r characters is accepted from s.
+        let mut reach: Vec<Vec<bool>> = vec![self.accept.clone()];
+        for r in 1..=max_len {
+            let row = self.trans.iter().map(|edges| edges.iter().any(|&(_, t)| reach[r - 1][t])).collect();
+            reach.push(row);
+        }
+        let mut lengths = min_len..=max_len;
+        let len = if longest {
+            lengths.rev().find(|&r| reach[r][start])?
+        } else {
+            lengths.find(|&r| reach[r][start])?
+        };
+        let mut out = String::with_capacity(len);
+        let mut state = start;
+        for remaining in (0..len).rev() {
+            let &(c, t) = self.trans[state].iter().find(|&&(_, t)| reach[remaining][t])?;
+            out.push(c);
+            state = t;
+        }
+        Some(out)
+    }
+
+    /// Every accepted string of at most `max_len` characters, or `None` if there are more than
+    /// `limit` of them.
+    pub(crate) fn enumerate(&self, max_len: usize, limit: usize) -> Option<Vec<String>> {
+        let mut out = Vec::new();
+        let Some(start) = self.start else { return Some(out) };
+        let mut stack = vec![(start, String::new())];
+        while let Some((state, prefix)) = stack.pop() {
+            if prefix.chars().count() < max_len {
+                for &(c, t) in self.trans[state].iter().rev() {
+                    let mut next = prefix.clone();
+                    next.push(c);
+                    stack.push((t, next));
+                }
+            }
+            if self.accept[state] {
+                if out.len() == limit {
+                    return None;
+                }
+                out.push(prefix);
+            }
+        }
+        Some(out)
+    }
+
+    /// The state reached from `state` by reading `s`, or `None` if it dies on the way.
+    fn walk(&self, mut state: usize, s: &str) -> Option<usize> {
+        for c in s.chars() {
+            let edges = &self.trans[state];
+            state = edges.binary_search_by_key(&c, |&(d, _)| d).ok().map(|i| edges[i].1)?;
+        }
+        Some(state)
+    }
+
+    /// Per state, whether reading `suffix` from it ends in an accepting state.
+    fn accepts_suffix(&self, suffix: &str) -> Vec<bool> {
+        (0..self.len()).map(|q| self.walk(q, suffix).is_some_and(|t| self.accept[t])).collect()
+    }
+
+    /// The shortest string, lexicographically smallest among those, that makes
+    /// `prefix + it + suffix` accepted.
+    pub(crate) fn bridge(&self, prefix: &str, suffix: &str) -> Option<String> {
+        let from = self.walk(self.start?, prefix)?;
+        let targets = self.accepts_suffix(suffix);
+        // Breadth-first over transitions in char order reaches each state first by its
+        // shortest, then smallest, path.
+        let mut parent: Vec<Option<(usize, char)>> = vec![None; self.len()];
+        let mut visited = vec![false; self.len()];
+        visited[from] = true;
+        let mut queue = VecDeque::from([from]);
+        while let Some(q) = queue.pop_front() {
+            if targets[q] {
+                let mut path = Vec::new();
+                let mut at = q;
+                while let Some((p, c)) = parent[at] {
+                    path.push(c);
+                    at = p;
+                }
+                return Some(path.into_iter().rev().collect());
+            }
+            for &(c, t) in &self.trans[q] {
+                if !visited[t] {
+                    visited[t] = true;
+                    parent[t] = Some((q, c));
+                    queue.push_back(t);
+                }
+            }
+        }
+        None
+    }
+
+    /// The smallest char that makes `prefix + it + suffix` accepted.
+    pub(crate) fn smallest_char(&self, prefix: &str, suffix: &str) -> Option<char> {
+        let from = self.walk(self.start?, prefix)?;
+        let targets = self.accepts_suffix(suffix);
+        self.trans[from].iter().find(|&&(_, t)| targets[t]).map(|&(c, _)| c)
+    }
+}
+
+/// A number drawn uniformly from `0..n`, or `None` when `n` is zero.
+fn random_below<R: Rng + ?Sized>(rng: &mut R, n: &BigUint) -> Option<BigUint> {
+    if n.is_zero() {
+        return None;
+    }
+    let bits = n.bits();
+    let words = bits.div_ceil(32) as usize;
+    let mask = if bits.is_multiple_of(32) { u32::MAX } else { (1u32 << (bits % 32)) - 1 };
+    loop {
+        let mut digits: Vec<u32> = (0..words).map(|_| rng.next_u32()).collect();
+        if let Some(top) = digits.last_mut() {
+            *top &= mask;
+        }
+        let k = BigUint::new(digits);
+        if &k < n {
+            return Some(k);
+        }
+    }
+}
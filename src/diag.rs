@@ -0,0 +1,115 @@
+//! Diagnostic events for rejected candidates and build warnings.
+//!
+//! With the `tracing` feature enabled every event is emitted through `tracing` under the
+//! `genrex` target with structured fields, so consumers can capture or filter it with their own
+//! subscriber. Independently of the feature, events are echoed to stderr while `VERBOSE` is set.
+//!
+//! With the `metrics` feature, every attempt, rejection and generation call is also counted
+//! through the `metrics` facade, for whatever recorder the application installed:
+//!
+//! - `genrex_attempts_total`: candidates built.
+//! - `genrex_rejections_total`, labeled `reason`: candidates discarded.
+//! - `genrex_outputs_total`, labeled `engine`: outputs produced.
+//! - `genrex_failures_total`: calls that produced nothing.
+//! - `genrex_generation_seconds`, labeled `outcome` (`ok` or `error`): time per call.
+
+use std::sync::atomic::Ordering;
+#[cfg(feature = "metrics")]
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use crate::stats::Engine;
+use crate::stats::RejectReason;
+use crate::warnings::Warning;
+use crate::VERBOSE;
+
+fn verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// A candidate built by `engine` ("nfa", "token", "ast" or "encode") was discarded.
+pub(crate) fn rejected(engine: &'static str, attempt: usize, reason: RejectReason, candidate: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: "genrex", engine, attempt, reason = %reason, len = candidate.len(), candidate, "candidate rejected");
+    if verbose() {
+        let prefix = match engine {
+            "token" => String::new(),
+            other => format!("{} ", other),
+        };
+        eprintln!("{}candidate rejected ({}, attempt {}, len {}): {:?}", prefix, reason, attempt, candidate.len(), candidate);
+    }
+}
+
+/// The encoder named `encoding` could not represent the candidate, or its round trip no longer matches.
+pub(crate) fn encode_rejected(encoding: &str, attempt: usize, detail: &dyn std::fmt::Display) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: "genrex", engine = "encode", encoding, attempt, detail = %detail, "candidate rejected");
+    if verbose() {
+        eprintln!("encoded candidate rejected ({}, attempt {}): {}", encoding, attempt, detail);
+    }
+}
+
+/// A non-fatal build notice.
+pub(crate) fn warning(w: &Warning) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        target: "genrex",
+        span_start = w.span.as_ref().map(|s| s.start),
+        span_end = w.span.as_ref().map(|s| s.end),
+        "{}",
+        w
+    );
+    if verbose() {
+        eprintln!("warning: {}", w);
+    }
+}
+
+/// A candidate is about to be built.
+pub(crate) fn attempt() {
+    #[cfg(feature = "metrics")]
+    metrics::counter!("genrex_attempts_total").increment(1);
+}
+
+/// A candidate was discarded and counted; unlike `rejected`, reported by every engine.
+pub(crate) fn count_reject(reason: RejectReason) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!("genrex_rejections_total", "reason" => reason_label(reason)).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = reason;
+}
+
+/// A generation call finished after `elapsed`, with the engine that produced its output.
+#[cfg(feature = "metrics")]
+pub(crate) fn generated(engine: Option<Engine>, elapsed: Duration) {
+    let outcome = match engine {
+        Some(engine) => {
+            let engine = match engine {
+                Engine::Nfa => "nfa",
+                Engine::Tokens => "tokens",
+                Engine::Ast => "ast",
+                Engine::Rejection => "rejection",
+            };
+            metrics::counter!("genrex_outputs_total", "engine" => engine).increment(1);
+            "ok"
+        }
+        None => {
+            metrics::counter!("genrex_failures_total").increment(1);
+            "error"
+        }
+    };
+    metrics::histogram!("genrex_generation_seconds", "outcome" => outcome).record(elapsed);
+}
+
+#[cfg(feature = "metrics")]
+fn reason_label(reason: RejectReason) -> &'static str {
+    match reason {
+        RejectReason::TokenError => "token_error",
+        RejectReason::UnresolvedBackref => "unresolved_backref",
+        RejectReason::Length => "length",
+        RejectReason::Forbidden => "forbidden",
+        RejectReason::Excluded => "excluded",
+        RejectReason::Filtered => "filtered",
+        RejectReason::Constraint => "constraint",
+        RejectReason::RegexMismatch => "regex_mismatch",
+    }
+}
@@ -0,0 +1,552 @@
+//! Pattern languages other than regex, translated to regex syntax before lexing.
+
+use std::collections::HashMap;
+
+use crate::lexer::{SPACE_CHARS, WORD_CHARS};
+use crate::tokens::{write_class_char, write_sequence, Token};
+use crate::GenError;
+
+/// A pattern language genrex reads by translating it to regex syntax. The built-in languages
+/// are the variants of `Dialect`; implement this trait to accept another one with
+/// `RegexGeneratorBuilder::dialect`.
+pub trait PatternDialect: Send + Sync {
+    /// `source` in the syntax of the `regex` crate.
+    fn translate(&self, source: &str) -> Result<String, GenError>;
+}
+
+/// The language a builder's pattern is written in; see `RegexGeneratorBuilder::dialect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// Regular expressions, as accepted by the `regex` crate.
+    #[default]
+    Regex,
+    /// SQL `LIKE` patterns: `%` is any run of characters, `_` any one character, `\` escapes
+    /// the next character, and `[abc]`, `[a-c]` and `[^abc]` are character classes (SQL Server).
+    SqlLike,
+    /// Shell globs: `*` is any run of characters, `?` any one character, `\` escapes the next
+    /// character, and `[abc]`, `[a-c]` and `[!abc]` (or `[^abc]`) are character classes.
+    Glob,
+    /// POSIX basic regular expressions (`grep`, `sed`): groups are `\(...\)`, intervals
+    /// `\{m,n\}`, and unescaped `(`, `)`, `{`, `}`, `+`, `?` and `|` are literals. The GNU
+    /// operators `\+`, `\?` and `\|` are accepted.
+    PosixBre,
+    /// POSIX extended regular expressions (`grep -E`, `awk`). Bracket expressions follow POSIX:
+    /// `\` is a literal inside them and `[:alpha:]`-style classes are expanded.
+    PosixEre,
+    /// JavaScript regular expressions, optionally in `/.../flags` literal form. `\uXXXX`,
+    /// `\u{...}`, `\xHH`, `\cX`, `\0` and `\k<name>` are translated; the `i`, `m` and `s` flags
+    /// become inline flags and the others are ignored.
+    EcmaScript,
+    /// Byte-level regular expressions, as if `(?-u)` were set: `\xNN` is the byte `NN` and
+    /// classes (negated ones included) range over the 256 byte values. Read with this dialect, a
+    /// pattern generates one character per byte, U+0000 to U+00FF, which
+    /// `RegexGenerator::generate_bytes` turns into bytes; non-ASCII literals stand for the bytes
+    /// of their UTF-8 encoding.
+    Bytes,
+}
+
+impl Dialect {
+    /// `source` in regex syntax. Regex patterns are returned unchanged. Fails with
+    /// `InvalidRegex` for an unknown POSIX class name such as `[[:nope:]]`.
+    pub fn to_regex(self, source: &str) -> Result<String, GenError> {
+        let tokens = match self {
+            Dialect::Regex => return Ok(source.to_string()),
+            Dialect::SqlLike => translate(source, '%', '_', '^')?,
+            Dialect::Glob => translate(source, '*', '?', '!')?,
+            Dialect::PosixBre => return posix_basic(source),
+            Dialect::PosixEre => return posix_extended(source),
+            Dialect::EcmaScript => return Ok(ecmascript(source)),
+            Dialect::Bytes => return byte_level(source),
+        };
+        let mut out = String::new();
+        write_sequence(&tokens, &mut out);
+        Ok(out)
+    }
+}
+
+impl PatternDialect for Dialect {
+    fn translate(&self, source: &str) -> Result<String, GenError> {
+        self.to_regex(source)
+    }
+}
+
+/// Tokens for a wildcard pattern with the given any-run and any-one characters. Classes are
+/// negated by `^` or `negate`; an unterminated `[` is a literal.
+fn translate(source: &str, any_run: char, any_one: char, negate: char) -> Result<Vec<Token>, GenError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        if c == any_run {
+            tokens.push(Token::Quantifier { token: Box::new(Token::Wildcard), min: 0, max: usize::MAX, greedy: true });
+        } else if c == any_one {
+            tokens.push(Token::Wildcard);
+        } else if c == '\\' && i < chars.len() {
+            tokens.push(Token::Literal(chars[i]));
+            i += 1;
+        } else if c == '[' {
+            match class(&chars[i..], negate)? {
+                Some((token, used)) => {
+                    tokens.push(token);
+                    i += used;
+                }
+                None => tokens.push(Token::Literal('[')),
+            }
+        } else {
+            tokens.push(Token::Literal(c));
+        }
+    }
+    Ok(tokens)
+}
+
+/// A POSIX basic regular expression in regex syntax.
+fn posix_basic(source: &str) -> Result<String, GenError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::new();
+    // At the start of the pattern, a group or a branch, where `^` is an anchor and `*` a literal.
+    let mut at_start = true;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        let start = std::mem::replace(&mut at_start, false);
+        match c {
+            '\\' if i < chars.len() => {
+                let e = chars[i];
+                i += 1;
+                match e {
+                    '(' | '|' => {
+                        out.push(e);
+                        at_start = true;
+                    }
+                    ')' | '{' | '}' | '+' | '?' => out.push(e),
+                    e => {
+                        out.push('\\');
+                        out.push(e);
+                    }
+                }
+            }
+            '^' if start => {
+                out.push('^');
+                at_start = true;
+            }
+            // `$` is an anchor only at the end of the pattern, a group or a branch.
+            '$' if matches!(chars.get(i..), Some([]) | Some(['\\', ')' | '|', ..])) => out.push('$'),
+            '*' if start => out.push_str("\\*"),
+            '.' | '*' => out.push(c),
+            '[' => bracket(&chars, &mut i, &mut out)?,
+            c => out.push_str(&Token::Literal(c).to_pattern()),
+        }
+    }
+    Ok(out)
+}
+
+/// A POSIX extended regular expression in regex syntax: only bracket expressions differ.
+fn posix_extended(source: &str) -> Result<String, GenError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '\\' if i < chars.len() => {
+                out.push(c);
+                out.push(chars[i]);
+                i += 1;
+            }
+            '[' => bracket(&chars, &mut i, &mut out)?,
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// Translate the POSIX bracket expression whose body starts at `chars[*i]`, or write a literal
+/// `[` when it is not terminated.
+fn bracket(chars: &[char], i: &mut usize, out: &mut String) -> Result<(), GenError> {
+    match class(&chars[*i..], '^')? {
+        Some((token, used)) => {
+            out.push_str(&token.to_pattern());
+            *i += used;
+        }
+        None => out.push_str("\\["),
+    }
+    Ok(())
+}
+
+/// The class whose body starts at `body` (just after `[`), and the number of characters it
+/// spans including the closing `]`; `None` when the class is unterminated or empty. A `]` first
+/// in the body is a member, and `[:name:]`, `[=c=]` and `[.c.]` follow POSIX.
+fn class(body: &[char], negate: char) -> Result<Option<(Token, usize)>, GenError> {
+    let negated = matches!(body.first(), Some(&c) if c == '^' || c == negate);
+    let mut j = usize::from(negated);
+    let mut members = Vec::new();
+    let mut first = true;
+    loop {
+        let Some(&c) = body.get(j) else {
+            return Ok(None);
+        };
+        if c == ']' && !first {
+            break;
+        }
+        first = false;
+        if let ('[', Some(&kind @ (':' | '=' | '.'))) = (c, body.get(j + 1)) {
+            let rest = &body[j + 2..];
+            let Some(len) = rest.windows(2).position(|w| w == [kind, ']']) else {
+                return Ok(None);
+            };
+            let name: String = rest[..len].iter().collect();
+            match kind {
+                ':' => members.extend(
+                    posix_class(&name).ok_or_else(|| GenError::InvalidRegex(format!("unknown character class [:{}:]", name)))?,
+                ),
+                _ => members.extend(name.chars()),
+            }
+            j += len + 4;
+            continue;
+        }
+        match (body.get(j + 1), body.get(j + 2)) {
+            (Some('-'), Some(&hi)) if hi != ']' => {
+                members.extend(c..=hi);
+                j += 3;
+            }
+            _ => {
+                members.push(c);
+                j += 1;
+            }
+        }
+    }
+    members.sort_unstable();
+    members.dedup();
+    if members.is_empty() {
+        return Ok(None);
+    }
+    let token = if negated { Token::NegatedClass(members.into()) } else { Token::Class(members.into()) };
+    Ok(Some((token, j + 1)))
+}
+
+/// The ASCII members of the POSIX character class `name`.
+fn posix_class(name: &str) -> Option<Vec<char>> {
+    let test: fn(&char) -> bool = match name {
+        "alpha" => char::is_ascii_alphabetic,
+        "digit" => char::is_ascii_digit,
+        "alnum" => char::is_ascii_alphanumeric,
+        "upper" => char::is_ascii_uppercase,
+        "lower" => char::is_ascii_lowercase,
+        "space" => |c| c.is_ascii_whitespace() || *c == '\x0B',
+        "blank" => |c| matches!(c, ' ' | '\t'),
+        "punct" => char::is_ascii_punctuation,
+        "xdigit" => char::is_ascii_hexdigit,
+        "cntrl" => char::is_ascii_control,
+        "print" => |c| matches!(c, ' '..='~'),
+        "graph" => char::is_ascii_graphic,
+        "word" => |c| c.is_ascii_alphanumeric() || *c == '_',
+        _ => return None,
+    };
+    Some(('\0'..='\x7F').filter(test).collect())
+}
+
+/// A JavaScript regular expression in regex syntax.
+fn ecmascript(source: &str) -> String {
+    let (body, flags) = match source.strip_prefix('/').and_then(|rest| rest.rsplit_once('/')) {
+        Some((body, flags)) if flags.chars().all(|c| c.is_ascii_alphabetic()) => (body, flags),
+        _ => (source, ""),
+    };
+    let chars: Vec<char> = body.chars().collect();
+    let names = group_names(&chars);
+    let mut out = String::new();
+    let inline: String = flags.chars().filter(|c| "ims".contains(*c)).collect();
+    if !inline.is_empty() {
+        out = format!("(?{})", inline);
+    }
+    let mut in_class = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '\\' if i < chars.len() => {
+                if let Some((value, used)) = js_escape(&chars[i..], in_class) {
+                    i += used;
+                    push_literal(value, in_class, &mut out);
+                    continue;
+                }
+                let e = chars[i];
+                i += 1;
+                if e == 'k'
+                    && let Some((group, used)) = named_reference(&chars[i..], &names)
+                {
+                    out.push_str(&format!("\\{}", group));
+                    i += used;
+                } else if e.is_ascii_punctuation() {
+                    // A JavaScript identity escape such as `\/` may not be valid regex syntax.
+                    push_literal(e, in_class, &mut out);
+                } else {
+                    out.push('\\');
+                    out.push(e);
+                }
+            }
+            // `[^]` matches any character, newlines included.
+            '[' if !in_class && chars.get(i..i + 2) == Some(&['^', ']']) => {
+                out.push_str("(?s:.)");
+                i += 2;
+            }
+            '[' if !in_class => {
+                in_class = true;
+                out.push('[');
+                if chars.get(i) == Some(&'^') {
+                    out.push('^');
+                    i += 1;
+                }
+            }
+            ']' if in_class => {
+                in_class = false;
+                out.push(']');
+            }
+            // Literal in a JavaScript class, but nested classes and set operators in the regex crate.
+            '[' | '&' | '~' if in_class => {
+                out.push('\\');
+                out.push(c);
+            }
+            ']' => out.push_str("\\]"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The character denoted by a JavaScript escape whose body starts at `body` (just after `\`),
+/// for escapes the regex crate reads differently, and the number of characters it spans.
+fn js_escape(body: &[char], in_class: bool) -> Option<(char, usize)> {
+    let hex = |digits: &[char]| u32::from_str_radix(&digits.iter().collect::<String>(), 16).ok().and_then(char::from_u32);
+    match body {
+        ['u', '{', rest @ ..] => {
+            let len = rest.iter().position(|&c| c == '}')?;
+            Some((hex(&rest[..len])?, len + 3))
+        }
+        ['u', a, b, c, d, ..] => Some((hex(&[*a, *b, *c, *d])?, 5)),
+        ['x', a, b, ..] => Some((hex(&[*a, *b])?, 3)),
+        ['c', letter, ..] if letter.is_ascii_alphabetic() => Some((char::from(*letter as u8 % 32), 2)),
+        ['0', next, ..] if next.is_ascii_digit() => None,
+        ['0', ..] => Some(('\0', 1)),
+        ['b', ..] if in_class => Some(('\x08', 1)),
+        _ => None,
+    }
+}
+
+fn push_literal(c: char, in_class: bool, out: &mut String) {
+    if in_class {
+        write_class_char(c, out);
+    } else {
+        out.push_str(&Token::Literal(c).to_pattern());
+    }
+}
+
+/// A byte-level regular expression in regex syntax over the characters U+0000 to U+00FF. The
+/// `u` flag is dropped.
+fn byte_level(source: &str) -> Result<String, GenError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '\\' if chars.get(i) == Some(&'x') => {
+                let (byte, used) = byte_escape(&chars[i..])?;
+                out.push_str(&Token::Literal(char::from(byte)).to_pattern());
+                i += used;
+            }
+            '\\' if i < chars.len() => {
+                out.push(c);
+                out.push(chars[i]);
+                i += 1;
+            }
+            '[' => {
+                let (token, used) = byte_class(&chars[i..])?;
+                out.push_str(&token.to_pattern());
+                i += used;
+            }
+            '(' if chars.get(i) == Some(&'?') => {
+                let rest = &chars[i + 1..];
+                let len = rest.iter().position(|c| !(c.is_ascii_alphabetic() || *c == '-')).unwrap_or(rest.len());
+                let flags: String = rest[..len].iter().filter(|&&c| c != 'u').collect();
+                let flags = flags.trim_end_matches('-');
+                i += 1 + len;
+                if flags.is_empty() && rest.get(len) == Some(&')') {
+                    // Nothing left of a bare `(?-u)`.
+                    i += 1;
+                } else {
+                    out.push_str("(?");
+                    out.push_str(flags);
+                }
+            }
+            c if !c.is_ascii() => {
+                out.push_str("(?:");
+                for byte in c.to_string().bytes() {
+                    out.push_str(&Token::Literal(char::from(byte)).to_pattern());
+                }
+                out.push(')');
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// The byte of a `\xNN` or `\x{N}` escape whose body starts at `body` (just after `\`), and
+/// the number of characters it spans.
+fn byte_escape(body: &[char]) -> Result<(u8, usize), GenError> {
+    let (digits, used) = match body {
+        ['x', '{', rest @ ..] => {
+            let len = rest.iter().position(|&c| c == '}').unwrap_or(rest.len());
+            (&rest[..len], len + 3)
+        }
+        ['x', rest @ ..] => (&rest[..rest.len().min(2)], 3),
+        _ => (&body[..0], 1),
+    };
+    if used > body.len() || digits.is_empty() || !digits.iter().all(char::is_ascii_hexdigit) {
+        return Err(GenError::InvalidRegex("invalid \\x escape".to_string()));
+    }
+    let digits: String = digits.iter().collect();
+    match u32::from_str_radix(&digits, 16) {
+        Ok(value) if value <= 0xFF => Ok((value as u8, used)),
+        _ => Err(GenError::InvalidRegex(format!("\\x{{{}}} is not a byte value", digits))),
+    }
+}
+
+/// The byte class whose body starts at `body` (just after `[`) as an explicit class over
+/// U+0000 to U+00FF, and the number of characters it spans including the closing `]`.
+fn byte_class(body: &[char]) -> Result<(Token, usize), GenError> {
+    let negated = body.first() == Some(&'^');
+    let mut j = usize::from(negated);
+    let mut members = [false; 256];
+    let mut first = true;
+    loop {
+        let Some(&c) = body.get(j) else {
+            return Err(GenError::InvalidRegex("unclosed character class".to_string()));
+        };
+        if c == ']' && !first {
+            break;
+        }
+        first = false;
+        if c == '[' && body.get(j + 1) == Some(&':') {
+            let rest = &body[j + 2..];
+            let len = rest.windows(2).position(|w| w == [':', ']']).ok_or_else(|| GenError::InvalidRegex("unclosed character class".to_string()))?;
+            let name: String = rest[..len].iter().collect();
+            let class = posix_class(&name).ok_or_else(|| GenError::InvalidRegex(format!("unknown character class [:{}:]", name)))?;
+            for m in class {
+                members[m as usize] = true;
+            }
+            j += len + 4;
+            continue;
+        }
+        let (lo, used) = match class_member(&body[j..])? {
+            ClassMember::Byte(byte, used) => (byte, used),
+            ClassMember::Set(set, used) => {
+                for m in set {
+                    members[m as usize] = true;
+                }
+                j += used;
+                continue;
+            }
+        };
+        j += used;
+        if body.get(j) == Some(&'-') && body.get(j + 1).is_some_and(|&c| c != ']') {
+            let ClassMember::Byte(hi, used) = class_member(&body[j + 1..])? else {
+                return Err(GenError::InvalidRegex("invalid range in character class".to_string()));
+            };
+            if lo > hi {
+                return Err(GenError::InvalidRegex("invalid range in character class".to_string()));
+            }
+            members[lo as usize..=hi as usize].fill(true);
+            j += 1 + used;
+        } else {
+            members[lo as usize] = true;
+        }
+    }
+    let class: Vec<char> = (0..=255u8).filter(|&b| members[b as usize] != negated).map(char::from).collect();
+    if class.is_empty() {
+        return Err(GenError::InvalidRegex("character class matches no byte".to_string()));
+    }
+    Ok((Token::Class(class.into()), j + 1))
+}
+
+/// One member of a byte class, with the number of characters it spans.
+enum ClassMember {
+    Byte(u8, usize),
+    Set(Vec<u8>, usize),
+}
+
+/// The byte or Perl class (`\d`, `\w`, `\s` and their negations, ASCII-only as under `(?-u)`) at
+/// the start of `body`.
+fn class_member(body: &[char]) -> Result<ClassMember, GenError> {
+    let ascii = |members: &str, negated: bool| -> Vec<u8> { (0..=255u8).filter(|&b| members.contains(char::from(b)) != negated).collect() };
+    match body {
+        ['\\', 'x', ..] => byte_escape(&body[1..]).map(|(byte, used)| ClassMember::Byte(byte, used + 1)),
+        ['\\', e @ ('d' | 'D' | 'w' | 'W' | 's' | 'S'), ..] => {
+            let members = match e.to_ascii_lowercase() {
+                'd' => "0123456789",
+                'w' => WORD_CHARS,
+                _ => SPACE_CHARS,
+            };
+            Ok(ClassMember::Set(ascii(members, e.is_ascii_uppercase()), 2))
+        }
+        ['\\', e, ..] => {
+            let byte = match e {
+                'n' => b'\n',
+                't' => b'\t',
+                'r' => b'\r',
+                'f' => b'\x0C',
+                'v' => b'\x0B',
+                'a' => b'\x07',
+                e if e.is_ascii_punctuation() => *e as u8,
+                e => return Err(GenError::InvalidRegex(format!("unsupported escape \\{} in a byte class", e))),
+            };
+            Ok(ClassMember::Byte(byte, 2))
+        }
+        [c, ..] if c.is_ascii() => Ok(ClassMember::Byte(*c as u8, 1)),
+        [c, ..] => Err(GenError::InvalidRegex(format!("non-ASCII character {:?} in a byte class", c))),
+        [] => Err(GenError::InvalidRegex("unclosed character class".to_string())),
+    }
+}
+
+/// The group number of a `<name>` reference starting at `body` (just after `\k`), and the
+/// number of characters it spans.
+fn named_reference(body: &[char], names: &HashMap<String, usize>) -> Option<(usize, usize)> {
+    let rest = body.strip_prefix(&['<'])?;
+    let len = rest.iter().position(|&c| c == '>')?;
+    let group = *names.get(&rest[..len].iter().collect::<String>())?;
+    Some((group, len + 2))
+}
+
+/// Capturing group numbers by name, for translating `\k<name>`.
+fn group_names(chars: &[char]) -> HashMap<String, usize> {
+    let mut names = HashMap::new();
+    let mut group = 0;
+    let mut in_class = false;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '(' if !in_class => match chars.get(i + 1..) {
+                Some(['?', '<', next, ..]) if *next != '=' && *next != '!' => {
+                    group += 1;
+                    if let Some(len) = chars[i + 3..].iter().position(|&c| c == '>') {
+                        names.insert(chars[i + 3..i + 3 + len].iter().collect(), group);
+                    }
+                }
+                Some(['?', ..]) => {}
+                _ => group += 1,
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+    names
+}
@@ -0,0 +1,147 @@
+//! Embedding matches in filler text, for scanner and grep testing: single values and whole
+//! documents with a manifest of match offsets.
+//!
+//! Filler is checked with a search regex derived from the pattern: a placement is accepted only
+//! when the search finds each embedded value exactly where it was placed and every other match
+//! lies inside a value, so the filler never matches on its own, by extending a value or by
+//! breaking the value's own match (e.g. gluing word characters onto `\bword\b`). Anchors keep
+//! their meaning, so `^` and `$` pin a value to the start or end of its text.
+
+use std::ops::Range;
+
+use rand::{Rng, RngCore};
+use regex::Regex;
+
+use crate::tokens::Token;
+
+/// Characters filler is drawn from.
+const FILLER_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789 .,;:-";
+
+/// Most filler characters on each side of an embedded value.
+pub(crate) const MAX_FILLER: usize = 32;
+
+/// A regex finding the pattern's matches inside larger text. None when it does not compile
+/// (e.g. it has backreferences).
+pub(crate) fn search_regex(tokens: &[Token]) -> Option<Regex> {
+    Regex::new(&crate::to_pattern(tokens)).ok()
+}
+
+/// Random filler of `len` characters.
+pub(crate) fn filler(rng: &mut dyn RngCore, len: usize) -> String {
+    (0..len).map(|_| FILLER_CHARS[rng.gen_range(0..FILLER_CHARS.len())] as char).collect()
+}
+
+/// True when `search` finds each of `spans` as a match of its own in `text`, and every other
+/// non-empty match lies within one of them.
+pub(crate) fn isolated(search: Option<&Regex>, text: &str, spans: &[Range<usize>]) -> bool {
+    let Some(search) = search else { return true };
+    spans.iter().all(|span| search.find_at(text, span.start).is_some_and(|m| m.range() == *span))
+        && search
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .all(|m| spans.iter().any(|span| m.start() >= span.start && m.end() <= span.end))
+}
+
+/// Surround `value` with filler, retrying with less filler each time the search regex finds a
+/// match outside it. Returns the text and the value's byte span within it.
+pub(crate) fn embed(rng: &mut dyn RngCore, value: &str, search: Option<&Regex>, attempts: usize) -> Option<(String, Range<usize>)> {
+    let (text, mut spans) = place(rng, &[value], search, 2 * MAX_FILLER, attempts)?;
+    Some((text, spans.pop().expect("one value placed")))
+}
+
+/// Lay `values` out in order with filler before, between and after them, up to `width` filler
+/// characters in all, retrying with less filler each time the search regex finds a match outside
+/// the values. Returns the text and each value's byte span within it.
+fn place(rng: &mut dyn RngCore, values: &[&str], search: Option<&Regex>, width: usize, attempts: usize) -> Option<(String, Vec<Range<usize>>)> {
+    let gaps = values.len() + 1;
+    for attempt in 0..attempts {
+        let cap = width * (attempts - attempt - 1) / attempts.max(1) / gaps;
+        let len = rng.gen_range(0..=cap);
+        let mut text = filler(rng, len);
+        let mut spans = Vec::with_capacity(values.len());
+        for value in values {
+            spans.push(text.len()..text.len() + value.len());
+            text.push_str(value);
+            let len = rng.gen_range(0..=cap);
+            text.push_str(&filler(rng, len));
+        }
+        if isolated(search, &text, &spans) {
+            return Some((text, spans));
+        }
+    }
+    None
+}
+
+/// Shape of a document from `generate_document`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentConfig {
+    /// Number of lines.
+    pub lines: usize,
+    /// Number of matches embedded across the lines; a line may hold several or none.
+    pub matches: usize,
+    /// Most filler characters per line.
+    pub line_width: usize,
+}
+
+impl DocumentConfig {
+    /// `lines` lines of up to 80 filler characters holding `matches` matches.
+    pub fn new(lines: usize, matches: usize) -> Self {
+        DocumentConfig { lines, matches, line_width: 80 }
+    }
+
+    /// Set the most filler characters per line.
+    pub fn line_width(mut self, line_width: usize) -> Self {
+        self.line_width = line_width;
+        self
+    }
+}
+
+/// One embedded match in a `Document`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentMatch {
+    /// Byte offsets of the match in `Document::text`.
+    pub span: Range<usize>,
+    /// Zero-based line the match starts on.
+    pub line: usize,
+}
+
+/// A multi-line document with matches embedded in filler, and a manifest of where they are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    /// The document, one `\n` after each line.
+    pub text: String,
+    /// The embedded matches in document order.
+    pub matches: Vec<DocumentMatch>,
+}
+
+impl Document {
+    /// Text of each embedded match, in document order.
+    pub fn values(&self) -> impl Iterator<Item = &str> + '_ {
+        self.matches.iter().map(|m| &self.text[m.span.clone()])
+    }
+}
+
+/// Scatter `values` over `config.lines` lines of filler. Lines are checked one at a time, so a
+/// pattern that matches across a line break may also match around the embedded values, and a
+/// pattern anchored with `^` or `$` fits only one value per line.
+pub(crate) fn document(rng: &mut dyn RngCore, values: &[String], search: Option<&Regex>, config: &DocumentConfig, attempts: usize) -> Option<Document> {
+    let mut per_line = vec![Vec::new(); config.lines];
+    for value in values {
+        per_line[rng.gen_range(0..config.lines)].push(value.as_str());
+    }
+    let mut doc = Document { text: String::new(), matches: Vec::with_capacity(values.len()) };
+    // Counted rather than taken from the loop index: values may contain line breaks.
+    let mut line = 0;
+    for values in &per_line {
+        let (text, spans) = place(rng, values, search, config.line_width, attempts)?;
+        let offset = doc.text.len();
+        for span in spans {
+            let line = line + text[..span.start].matches('\n').count();
+            doc.matches.push(DocumentMatch { span: offset + span.start..offset + span.end, line });
+        }
+        line += text.matches('\n').count() + 1;
+        doc.text.push_str(&text);
+        doc.text.push('\n');
+    }
+    Some(doc)
+}
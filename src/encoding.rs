@@ -0,0 +1,201 @@
+//! Output encoders: re-encode generated strings into legacy byte encodings.
+//!
+//! Encoders are applied after generation by `RegexGenerator::generate_encoded`, which decodes
+//! the bytes again and re-verifies them against the pattern, so lossy mappings can never
+//! silently produce a value that no longer matches.
+
+use thiserror::Error;
+
+/// What to do with a character the target encoding cannot represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unmappable {
+    /// Fail the encoding with an `EncodeError`.
+    Error,
+    /// Substitute the given character (which must itself be encodable).
+    Replace(char),
+    /// Drop the character.
+    Skip,
+}
+
+/// A character could not be represented in the target encoding.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("character {ch:?} at byte {position} cannot be encoded as {encoding}")]
+pub struct EncodeError {
+    pub ch: char,
+    /// Byte offset of the character in the source string.
+    pub position: usize,
+    pub encoding: &'static str,
+}
+
+/// Converts generated strings to bytes in some encoding, and back.
+pub trait Encoder {
+    /// Short encoding name used in diagnostics (e.g. "latin-1").
+    fn name(&self) -> &'static str;
+
+    /// Encode `s` to bytes.
+    ///
+    /// # Errors
+    /// Returns `EncodeError` for characters the encoding cannot represent under its policy.
+    fn encode(&self, s: &str) -> Result<Vec<u8>, EncodeError>;
+
+    /// Decode bytes produced by `encode` back to a string, used for verification.
+    fn decode(&self, bytes: &[u8]) -> String;
+}
+
+/// Encode `s` one byte per char, applying `policy` to chars that `map` cannot encode.
+fn encode_single_byte(
+    s: &str,
+    policy: Unmappable,
+    name: &'static str,
+    map: impl Fn(char) -> Option<u8>,
+) -> Result<Vec<u8>, EncodeError> {
+    let mut out = Vec::with_capacity(s.len());
+    for (position, ch) in s.char_indices() {
+        match map(ch) {
+            Some(b) => out.push(b),
+            None => match policy {
+                Unmappable::Error => return Err(EncodeError { ch, position, encoding: name }),
+                Unmappable::Replace(r) => match map(r) {
+                    Some(b) => out.push(b),
+                    None => return Err(EncodeError { ch: r, position, encoding: name }),
+                },
+                Unmappable::Skip => {}
+            },
+        }
+    }
+    Ok(out)
+}
+
+/// UTF-16 in either byte order, optionally with a byte-order mark. Every char is representable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Utf16 {
+    pub big_endian: bool,
+    pub bom: bool,
+}
+
+impl Utf16 {
+    pub fn le() -> Self {
+        Utf16 { big_endian: false, bom: false }
+    }
+
+    pub fn be() -> Self {
+        Utf16 { big_endian: true, bom: false }
+    }
+
+    /// Prefix output with a byte-order mark.
+    pub fn with_bom(mut self) -> Self {
+        self.bom = true;
+        self
+    }
+}
+
+impl Encoder for Utf16 {
+    fn name(&self) -> &'static str {
+        if self.big_endian { "utf-16be" } else { "utf-16le" }
+    }
+
+    fn encode(&self, s: &str) -> Result<Vec<u8>, EncodeError> {
+        let bom = self.bom.then_some(0xFEFFu16);
+        let units = bom.into_iter().chain(s.encode_utf16());
+        Ok(units
+            .flat_map(|u| if self.big_endian { u.to_be_bytes() } else { u.to_le_bytes() })
+            .collect())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| if self.big_endian { u16::from_be_bytes([c[0], c[1]]) } else { u16::from_le_bytes([c[0], c[1]]) })
+            .collect();
+        let units = if self.bom && units.first() == Some(&0xFEFF) { &units[1..] } else { &units[..] };
+        String::from_utf16_lossy(units)
+    }
+}
+
+/// ISO-8859-1: code points U+0000..=U+00FF map to the byte of the same value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Latin1 {
+    pub policy: Unmappable,
+}
+
+impl Latin1 {
+    pub fn new(policy: Unmappable) -> Self {
+        Latin1 { policy }
+    }
+}
+
+impl Encoder for Latin1 {
+    fn name(&self) -> &'static str {
+        "latin-1"
+    }
+
+    fn encode(&self, s: &str) -> Result<Vec<u8>, EncodeError> {
+        encode_single_byte(s, self.policy, self.name(), |c| u8::try_from(u32::from(c)).ok())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        bytes.iter().map(|&b| char::from(b)).collect()
+    }
+}
+
+/// EBCDIC code page 037 (US/Canada), the common mainframe encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ebcdic037 {
+    pub policy: Unmappable,
+}
+
+impl Ebcdic037 {
+    pub fn new(policy: Unmappable) -> Self {
+        Ebcdic037 { policy }
+    }
+}
+
+/// Code page 037 byte -> Unicode. The table is a permutation of U+0000..=U+00FF.
+const CP037: [char; 256] = [
+    '\u{00}', '\u{01}', '\u{02}', '\u{03}', '\u{9C}', '\u{09}', '\u{86}', '\u{7F}',
+    '\u{97}', '\u{8D}', '\u{8E}', '\u{0B}', '\u{0C}', '\u{0D}', '\u{0E}', '\u{0F}',
+    '\u{10}', '\u{11}', '\u{12}', '\u{13}', '\u{9D}', '\u{85}', '\u{08}', '\u{87}',
+    '\u{18}', '\u{19}', '\u{92}', '\u{8F}', '\u{1C}', '\u{1D}', '\u{1E}', '\u{1F}',
+    '\u{80}', '\u{81}', '\u{82}', '\u{83}', '\u{84}', '\u{0A}', '\u{17}', '\u{1B}',
+    '\u{88}', '\u{89}', '\u{8A}', '\u{8B}', '\u{8C}', '\u{05}', '\u{06}', '\u{07}',
+    '\u{90}', '\u{91}', '\u{16}', '\u{93}', '\u{94}', '\u{95}', '\u{96}', '\u{04}',
+    '\u{98}', '\u{99}', '\u{9A}', '\u{9B}', '\u{14}', '\u{15}', '\u{9E}', '\u{1A}',
+    '\u{20}', '\u{A0}', '\u{E2}', '\u{E4}', '\u{E0}', '\u{E1}', '\u{E3}', '\u{E5}',
+    '\u{E7}', '\u{F1}', '\u{A2}', '\u{2E}', '\u{3C}', '\u{28}', '\u{2B}', '\u{7C}',
+    '\u{26}', '\u{E9}', '\u{EA}', '\u{EB}', '\u{E8}', '\u{ED}', '\u{EE}', '\u{EF}',
+    '\u{EC}', '\u{DF}', '\u{21}', '\u{24}', '\u{2A}', '\u{29}', '\u{3B}', '\u{AC}',
+    '\u{2D}', '\u{2F}', '\u{C2}', '\u{C4}', '\u{C0}', '\u{C1}', '\u{C3}', '\u{C5}',
+    '\u{C7}', '\u{D1}', '\u{A6}', '\u{2C}', '\u{25}', '\u{5F}', '\u{3E}', '\u{3F}',
+    '\u{F8}', '\u{C9}', '\u{CA}', '\u{CB}', '\u{C8}', '\u{CD}', '\u{CE}', '\u{CF}',
+    '\u{CC}', '\u{60}', '\u{3A}', '\u{23}', '\u{40}', '\u{27}', '\u{3D}', '\u{22}',
+    '\u{D8}', '\u{61}', '\u{62}', '\u{63}', '\u{64}', '\u{65}', '\u{66}', '\u{67}',
+    '\u{68}', '\u{69}', '\u{AB}', '\u{BB}', '\u{F0}', '\u{FD}', '\u{FE}', '\u{B1}',
+    '\u{B0}', '\u{6A}', '\u{6B}', '\u{6C}', '\u{6D}', '\u{6E}', '\u{6F}', '\u{70}',
+    '\u{71}', '\u{72}', '\u{AA}', '\u{BA}', '\u{E6}', '\u{B8}', '\u{C6}', '\u{A4}',
+    '\u{B5}', '\u{7E}', '\u{73}', '\u{74}', '\u{75}', '\u{76}', '\u{77}', '\u{78}',
+    '\u{79}', '\u{7A}', '\u{A1}', '\u{BF}', '\u{D0}', '\u{DD}', '\u{DE}', '\u{AE}',
+    '\u{5E}', '\u{A3}', '\u{A5}', '\u{B7}', '\u{A9}', '\u{A7}', '\u{B6}', '\u{BC}',
+    '\u{BD}', '\u{BE}', '\u{5B}', '\u{5D}', '\u{AF}', '\u{A8}', '\u{B4}', '\u{D7}',
+    '\u{7B}', '\u{41}', '\u{42}', '\u{43}', '\u{44}', '\u{45}', '\u{46}', '\u{47}',
+    '\u{48}', '\u{49}', '\u{AD}', '\u{F4}', '\u{F6}', '\u{F2}', '\u{F3}', '\u{F5}',
+    '\u{7D}', '\u{4A}', '\u{4B}', '\u{4C}', '\u{4D}', '\u{4E}', '\u{4F}', '\u{50}',
+    '\u{51}', '\u{52}', '\u{B9}', '\u{FB}', '\u{FC}', '\u{F9}', '\u{FA}', '\u{FF}',
+    '\u{5C}', '\u{F7}', '\u{53}', '\u{54}', '\u{55}', '\u{56}', '\u{57}', '\u{58}',
+    '\u{59}', '\u{5A}', '\u{B2}', '\u{D4}', '\u{D6}', '\u{D2}', '\u{D3}', '\u{D5}',
+    '\u{30}', '\u{31}', '\u{32}', '\u{33}', '\u{34}', '\u{35}', '\u{36}', '\u{37}',
+    '\u{38}', '\u{39}', '\u{B3}', '\u{DB}', '\u{DC}', '\u{D9}', '\u{DA}', '\u{9F}',
+];
+
+impl Encoder for Ebcdic037 {
+    fn name(&self) -> &'static str {
+        "ebcdic-cp037"
+    }
+
+    fn encode(&self, s: &str) -> Result<Vec<u8>, EncodeError> {
+        encode_single_byte(s, self.policy, self.name(), |c| CP037.iter().position(|&e| e == c).map(|b| b as u8))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        bytes.iter().map(|&b| CP037[b as usize]).collect()
+    }
+}
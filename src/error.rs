@@ -1,7 +1,12 @@
 //! Custom error types for the genrex library.
 
+use std::fmt;
+use std::ops::Range;
+
 use thiserror::Error;
 
+use crate::encoding::EncodeError;
+
 /// Errors that can occur during regex string generation.
 #[derive(Debug, Error)]
 pub enum GenrexError {
@@ -14,6 +19,18 @@ pub enum GenrexError {
     #[error("timeout reached during generation")]
     Timeout,
 
+    #[error("generation budget exhausted")]
+    BudgetExhausted,
+
+    #[error("generation cancelled")]
+    Cancelled,
+
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("pattern cannot match: {0}")]
+    Unsatisfiable(String),
+
     #[error("backreference or group error: {0}")]
     BackreferenceError(String),
 
@@ -22,4 +39,74 @@ pub enum GenrexError {
 
     #[error("internal error: {0}")]
     Internal(String),
+
+    #[error("parse error: {0}")]
+    Parse(ParseError),
+
+    #[error("encoding error: {0}")]
+    Encoding(EncodeError),
+}
+
+/// The structural problem found while lexing a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A `[` without a matching `]`.
+    UnclosedClass,
+    /// A `(` without a matching `)`.
+    UnclosedGroup,
+    /// A `)` without a matching `(`.
+    UnopenedGroup,
+    /// A quantifier (`*`, `+`, `?`, `{m,n}`) with nothing to repeat.
+    DanglingQuantifier,
+    /// A `(?` group whose prefix is not flags, a named group or a lookaround.
+    InvalidGroup,
+    /// A backslash at the very end of the pattern.
+    TrailingBackslash,
+    /// An escape sequence that is not recognized.
+    InvalidEscape(char),
+    /// A `{genrex:` annotation without a closing `}`.
+    UnclosedAnnotation,
+    /// A `{genrex:...}` annotation that does not directly follow a capturing group.
+    MisplacedAnnotation,
+    /// A counted repetition whose minimum exceeds its maximum.
+    InvalidRepetitionRange,
+    /// Valid regex syntax that genrex cannot generate faithfully (rejected in strict mode).
+    Unsupported(String),
+    /// Groups and quantifiers nested deeper than the limit (see
+    /// `RegexGeneratorBuilder::max_nesting_depth`).
+    NestingTooDeep(usize),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnclosedClass => f.write_str("unclosed character class"),
+            ParseErrorKind::UnclosedGroup => f.write_str("unclosed group"),
+            ParseErrorKind::UnopenedGroup => f.write_str("unopened group"),
+            ParseErrorKind::DanglingQuantifier => f.write_str("quantifier has nothing to repeat"),
+            ParseErrorKind::InvalidGroup => f.write_str("unrecognized group prefix"),
+            ParseErrorKind::TrailingBackslash => f.write_str("trailing backslash"),
+            ParseErrorKind::InvalidEscape(c) => write!(f, "invalid escape '\\{}'", c),
+            ParseErrorKind::UnclosedAnnotation => f.write_str("unclosed genrex annotation"),
+            ParseErrorKind::MisplacedAnnotation => f.write_str("genrex annotation does not follow a capturing group"),
+            ParseErrorKind::InvalidRepetitionRange => f.write_str("repetition minimum exceeds maximum"),
+            ParseErrorKind::Unsupported(what) => write!(f, "unsupported construct: {}", what),
+            ParseErrorKind::NestingTooDeep(limit) => write!(f, "nesting deeper than {} levels", limit),
+        }
+    }
+}
+
+/// A structural error in a pattern, with the byte span it covers.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{kind} at {}..{}", span.start, span.end)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Byte offsets into the original pattern.
+    pub span: Range<usize>,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, span: Range<usize>) -> Self {
+        ParseError { kind, span }
+    }
 }
@@ -0,0 +1,143 @@
+//! Generation traces: which token produced which part of an output, and the choices made.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::tokens::Token;
+use crate::traits::RegexToken;
+
+/// A random choice a token made while generating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice {
+    /// Zero-based alternation branch taken.
+    Branch(usize),
+    /// Number of repetitions a quantifier produced.
+    Repeat(usize),
+    /// A group constraint supplied the group's text.
+    Override,
+}
+
+/// One generated token and the part of the output it produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceNode {
+    /// Short description of the token, as `RegexToken::describe` gives it.
+    pub token: String,
+    /// Byte range of the output this token produced.
+    pub span: Range<usize>,
+    pub text: String,
+    pub choice: Option<Choice>,
+    pub children: Vec<TraceNode>,
+}
+
+/// A generated value with the trace of the top-level tokens that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub value: String,
+    pub nodes: Vec<TraceNode>,
+}
+
+impl Explanation {
+    /// Every node of the trace, depth first.
+    pub fn iter(&self) -> impl Iterator<Item = &TraceNode> {
+        let mut stack: Vec<&TraceNode> = self.nodes.iter().rev().collect();
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+}
+
+/// Indented tree, one node per line: `token [start..end] "text" (choice)`.
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:?}", self.value)?;
+        for node in &self.nodes {
+            write_node(f, node, 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_node(f: &mut fmt::Formatter<'_>, node: &TraceNode, depth: usize) -> fmt::Result {
+    write!(f, "{:indent$}{} [{}..{}] {:?}", "", node.token, node.span.start, node.span.end, node.text, indent = depth * 2)?;
+    match node.choice {
+        Some(Choice::Branch(i)) => writeln!(f, " (branch {})", i)?,
+        Some(Choice::Repeat(n)) => writeln!(f, " (x{})", n)?,
+        Some(Choice::Override) => writeln!(f, " (override)")?,
+        None => writeln!(f)?,
+    }
+    for child in &node.children {
+        write_node(f, child, depth + 1)?;
+    }
+    Ok(())
+}
+
+/// A node under construction. Spans are assigned once the whole output is known.
+#[derive(Debug)]
+struct Frame {
+    token: String,
+    text: String,
+    choice: Option<Choice>,
+    children: Vec<Frame>,
+    /// Group id of a backreference generated before its group; filled in afterwards.
+    pending: Option<usize>,
+}
+
+/// Records token generation as a tree; driven by `TokenContext`.
+#[derive(Debug, Default)]
+pub(crate) struct Recorder {
+    open: Vec<Frame>,
+    roots: Vec<Frame>,
+}
+
+impl Recorder {
+    pub(crate) fn open(&mut self, token: &Token) {
+        self.open.push(Frame { token: token.describe(), text: String::new(), choice: None, children: Vec::new(), pending: None });
+    }
+
+    pub(crate) fn choose(&mut self, choice: Choice) {
+        if let Some(frame) = self.open.last_mut() {
+            frame.choice = Some(choice);
+        }
+    }
+
+    /// Close the innermost node with the text it produced (`None` if generation failed).
+    pub(crate) fn close(&mut self, text: Option<&str>, pending: Option<usize>) {
+        let Some(mut frame) = self.open.pop() else { return };
+        let Some(text) = text else { return };
+        frame.text = text.to_string();
+        frame.pending = pending;
+        // Text not made of the children's (a counter override) is attributed to the node alone.
+        if frame.children.iter().map(|c| c.text.as_str()).collect::<String>() != frame.text {
+            frame.children.clear();
+        }
+        match self.open.last_mut() {
+            Some(parent) => parent.children.push(frame),
+            None => self.roots.push(frame),
+        }
+    }
+
+    /// Resolve pending backreferences with `capture` and lay the nodes out over the output.
+    pub(crate) fn finish(self, value: String, capture: impl Fn(usize) -> Option<String>) -> Explanation {
+        let mut pos = 0;
+        let nodes = self.roots.into_iter().map(|f| layout(f, &mut pos, &capture)).collect();
+        Explanation { value, nodes }
+    }
+}
+
+fn layout(frame: Frame, pos: &mut usize, capture: &impl Fn(usize) -> Option<String>) -> TraceNode {
+    let start = *pos;
+    let (text, children) = if frame.children.is_empty() {
+        let text = match frame.pending {
+            Some(group) => capture(group).unwrap_or_default(),
+            None => frame.text,
+        };
+        *pos += text.len();
+        (text, Vec::new())
+    } else {
+        let children: Vec<TraceNode> = frame.children.into_iter().map(|c| layout(c, pos, capture)).collect();
+        (children.iter().map(|c| c.text.as_str()).collect(), children)
+    };
+    TraceNode { token: frame.token, span: start..*pos, text, choice: frame.choice, children }
+}
@@ -0,0 +1,191 @@
+//! Exports of token trees for tools outside Rust: JSON for programs, DOT for Graphviz.
+
+use std::fmt::Write;
+
+use crate::tokens::Token;
+
+/// Version of the JSON layout; bumped whenever a field changes meaning or goes away.
+const JSON_VERSION: u32 = 1;
+
+/// The token sequence as one line of JSON:
+///
+/// ```text
+/// {"version":1,"tokens":[{"kind":"literal","pattern":"a","char":"a"}, ...]}
+/// ```
+///
+/// Every node has a `kind` and the `pattern` text it serializes to. By kind, the other fields
+/// are: `literal` has `char`; `class` and `negated_class` have `chars`; `concat` has
+/// `children` and `alternation` has `branches`; `repeat` has `min`, `max` (`null` when
+/// unbounded), `greedy` and `child`; `group` has `index` and `child`; `non_capturing_group` has
+/// `child`; `backreference` has `index`. `anchor_start`, `anchor_end`, `word_boundary` and
+/// `any` have no other fields.
+pub fn to_json(tokens: &[Token]) -> String {
+    let mut out = format!("{{\"version\":{},\"tokens\":", JSON_VERSION);
+    write_json_list(&mut out, tokens);
+    out.push('}');
+    out
+}
+
+fn write_json_list(out: &mut String, tokens: &[Token]) {
+    out.push('[');
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_json(out, token);
+    }
+    out.push(']');
+}
+
+fn write_json(out: &mut String, token: &Token) {
+    let kind = match token {
+        Token::Literal(_) => "literal",
+        Token::Class(_) => "class",
+        Token::NegatedClass(_) => "negated_class",
+        Token::Concatenation(_) => "concat",
+        Token::Alternation(_) => "alternation",
+        Token::Quantifier { .. } => "repeat",
+        Token::Group(..) => "group",
+        Token::NonCapturingGroup(_) => "non_capturing_group",
+        Token::Backreference(_) => "backreference",
+        Token::AnchorStart => "anchor_start",
+        Token::AnchorEnd => "anchor_end",
+        Token::WordBoundary => "word_boundary",
+        Token::Wildcard => "any",
+    };
+    let _ = write!(out, "{{\"kind\":\"{}\",\"pattern\":{}", kind, json_string(&token.to_pattern()));
+    match token {
+        Token::Literal(c) => {
+            let _ = write!(out, ",\"char\":{}", json_string(&c.to_string()));
+        }
+        Token::Class(chars) | Token::NegatedClass(chars) => {
+            let chars: Vec<String> = chars.iter().map(|c| json_string(&c.to_string())).collect();
+            let _ = write!(out, ",\"chars\":[{}]", chars.join(","));
+        }
+        Token::Concatenation(children) => {
+            out.push_str(",\"children\":");
+            write_json_list(out, children);
+        }
+        Token::Alternation(branches) => {
+            out.push_str(",\"branches\":");
+            write_json_list(out, branches);
+        }
+        Token::Quantifier { token, min, max, greedy } => {
+            let max = if *max == usize::MAX { "null".to_string() } else { max.to_string() };
+            let _ = write!(out, ",\"min\":{},\"max\":{},\"greedy\":{},\"child\":", min, max, greedy);
+            write_json(out, token);
+        }
+        Token::Group(inner, index) => {
+            let _ = write!(out, ",\"index\":{},\"child\":", index);
+            write_json(out, inner);
+        }
+        Token::NonCapturingGroup(inner) => {
+            out.push_str(",\"child\":");
+            write_json(out, inner);
+        }
+        Token::Backreference(index) => {
+            let _ = write!(out, ",\"index\":{}", index);
+        }
+        Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary | Token::Wildcard => {}
+    }
+    out.push('}');
+}
+
+/// The token sequence as a Graphviz DOT digraph, one node per token with edges to its
+/// children. Alternation edges are labeled with the zero-based branch index; a top-level
+/// sequence of several tokens hangs under one `concat` root.
+pub fn to_dot(tokens: &[Token]) -> String {
+    let mut out = String::from("digraph tokens {\n  node [shape=box, fontname=monospace];\n");
+    let mut next = 0;
+    match tokens {
+        [token] => write_dot(&mut out, token, &mut next),
+        tokens => write_dot(&mut out, &Token::Concatenation(tokens.to_vec()), &mut next),
+    };
+    out.push_str("}\n");
+    out
+}
+
+/// Write `token` and its subtree, returning the id of its node.
+fn write_dot(out: &mut String, token: &Token, next: &mut usize) -> usize {
+    let id = *next;
+    *next += 1;
+    let (label, shape) = match token {
+        Token::Literal(_) => (format!("literal {}", token.to_pattern()), None),
+        Token::Class(_) => (format!("class {}", token.to_pattern()), None),
+        Token::NegatedClass(_) => (format!("negated class {}", token.to_pattern()), None),
+        Token::Wildcard => ("any .".to_string(), None),
+        Token::Concatenation(_) => ("concat".to_string(), Some("ellipse")),
+        Token::Alternation(branches) => (format!("alternation of {}", branches.len()), Some("diamond")),
+        Token::Quantifier { min, max, greedy, .. } => {
+            let max = if *max == usize::MAX { String::new() } else { max.to_string() };
+            (format!("repeat {{{},{}}}{}", min, max, if *greedy { "" } else { " lazy" }), Some("hexagon"))
+        }
+        Token::Group(_, index) => (format!("group #{}", index), Some("ellipse")),
+        Token::NonCapturingGroup(_) => ("group (non-capturing)".to_string(), Some("ellipse")),
+        Token::Backreference(index) => (format!("backreference #{}", index), None),
+        Token::AnchorStart | Token::AnchorEnd => (format!("anchor {}", token.to_pattern()), Some("plaintext")),
+        Token::WordBoundary => ("word boundary".to_string(), Some("plaintext")),
+    };
+    let _ = write!(out, "  n{} [label={}", id, dot_string(&label));
+    if let Some(shape) = shape {
+        let _ = write!(out, ", shape={}", shape);
+    }
+    out.push_str("];\n");
+    match token {
+        Token::Concatenation(children) => {
+            for child in children {
+                let child = write_dot(out, child, next);
+                let _ = writeln!(out, "  n{} -> n{};", id, child);
+            }
+        }
+        Token::Alternation(branches) => {
+            for (i, branch) in branches.iter().enumerate() {
+                let child = write_dot(out, branch, next);
+                let _ = writeln!(out, "  n{} -> n{} [label=\"{}\"];", id, child, i);
+            }
+        }
+        Token::Quantifier { token: inner, .. } | Token::Group(inner, _) | Token::NonCapturingGroup(inner) => {
+            let child = write_dot(out, inner, next);
+            let _ = writeln!(out, "  n{} -> n{};", id, child);
+        }
+        _ => {}
+    }
+    id
+}
+
+/// A quoted DOT string for `value`.
+pub(crate) fn dot_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A JSON string literal for `value`, with `\"`, `\\`, `\n`, `\r`, `\t` and `\uXXXX` escapes.
+pub fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,98 @@
+//! Failure injection: deliberately malformed outputs for downstream resilience testing.
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::length::{self, LengthUnit};
+
+/// The kind of corruption applied to an otherwise valid output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaultKind {
+    /// The value was cut short at a random character boundary.
+    Truncated,
+    /// One character was replaced with a character outside the expected charset.
+    WrongCharset,
+    /// The value was padded beyond the configured `max_len`.
+    Overlong,
+}
+
+/// Configuration for failure injection.
+#[derive(Debug, Clone)]
+pub struct FaultConfig {
+    /// Fraction of outputs (0.0..=1.0) that should be malformed.
+    pub rate: f64,
+    /// Kinds of faults to choose from. Empty means all kinds.
+    pub kinds: Vec<FaultKind>,
+}
+
+impl FaultConfig {
+    /// Inject faults of every kind into roughly `rate` of the outputs.
+    pub fn new(rate: f64) -> Self {
+        FaultConfig { rate, kinds: Vec::new() }
+    }
+
+    /// Restrict injection to the given fault kinds.
+    pub fn kinds(mut self, kinds: &[FaultKind]) -> Self {
+        self.kinds = kinds.to_vec();
+        self
+    }
+}
+
+/// Characters substituted by `WrongCharset`; chosen to fall outside typical pattern alphabets.
+const FOREIGN_CHARS: &[char] = &['\u{0}', '\u{7F}', '\u{FFFD}', '\u{1F4A5}'];
+
+/// Upper bound on `max_len` for which `Overlong` padding is attempted.
+const MAX_OVERLONG_PAD: usize = 1 << 20;
+
+const ALL_KINDS: &[FaultKind] = &[FaultKind::Truncated, FaultKind::WrongCharset, FaultKind::Overlong];
+
+impl FaultConfig {
+    /// Fault kinds to try for one output, in random order.
+    pub(crate) fn shuffled_kinds<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<FaultKind> {
+        let mut kinds = if self.kinds.is_empty() { ALL_KINDS.to_vec() } else { self.kinds.clone() };
+        kinds.shuffle(rng);
+        kinds
+    }
+}
+
+/// Apply a single fault to `s`, whose length `max_len` bounds in `unit`. Returns None when the
+/// fault cannot apply (e.g. truncating "").
+pub(crate) fn apply_fault<R: Rng + ?Sized>(kind: FaultKind, s: &str, max_len: usize, unit: LengthUnit, rng: &mut R) -> Option<String> {
+    match kind {
+        FaultKind::Truncated => {
+            let n = s.chars().count();
+            if n == 0 {
+                return None;
+            }
+            let keep = rng.gen_range(0..n);
+            Some(s.chars().take(keep).collect())
+        }
+        FaultKind::WrongCharset => {
+            let n = s.chars().count();
+            let foreign = FOREIGN_CHARS[rng.gen_range(0..FOREIGN_CHARS.len())];
+            if n == 0 {
+                return Some(foreign.to_string());
+            }
+            let pos = rng.gen_range(0..n);
+            Some(s.chars().enumerate().map(|(i, c)| if i == pos { foreign } else { c }).collect())
+        }
+        FaultKind::Overlong => {
+            // Refuse to materialize absurdly large padding for effectively unbounded configs.
+            if max_len > MAX_OVERLONG_PAD {
+                return None;
+            }
+            let mut out = s.to_string();
+            // A pad that joins into the grapheme before it would never add to the length.
+            let pad = s.chars().last().filter(|&c| unit != LengthUnit::Graphemes || !length::may_join(c)).unwrap_or('x');
+            let step = if unit == LengthUnit::Bytes { pad.len_utf8() } else { 1 };
+            // The first pad may still join the end of `s`; measure again until past the bound.
+            loop {
+                let len = unit.measure(&out);
+                if len > max_len {
+                    return Some(out);
+                }
+                out.extend(std::iter::repeat_n(pad, (max_len + 1 - len).div_ceil(step)));
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! `arbitrary` support for structure-aware fuzzing: token trees whose pattern the `regex` crate
+//! accepts, for differential tests of genrex against it.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::tokens::Token;
+
+/// Deepest nesting of groups, repeats and alternations.
+const MAX_DEPTH: usize = 4;
+/// Most children of a concatenation, or branches of an alternation.
+const MAX_CHILDREN: usize = 4;
+/// Largest bounded repetition count.
+const MAX_COUNT: usize = 4;
+/// Characters for literals and classes, including ones the pattern text has to escape.
+const ALPHABET: &[char] = &['a', 'b', 'c', 'x', 'y', 'z', '0', '1', '9', ' ', '-', '.', '*', '(', '[', ']', '{', '|', '\\', '^', 'é'];
+
+/// Hands out capturing group numbers in the order the lexer would assign them.
+struct TreeBuilder {
+    next_group: usize,
+}
+
+impl TreeBuilder {
+    fn token(&mut self, u: &mut Unstructured<'_>, depth: usize) -> Result<Token> {
+        if depth >= MAX_DEPTH || u.is_empty() {
+            return self.atom(u);
+        }
+        Ok(match u.int_in_range(0..=9)? {
+            0..=3 => self.atom(u)?,
+            4 => Token::Concatenation(self.children(u, depth, 1)?),
+            5 => Token::Alternation(self.children(u, depth, 2)?.into_iter().map(|t| Token::Concatenation(vec![t])).collect()),
+            6 | 7 => self.quantifier(u, depth)?,
+            8 => self.group(u, depth)?,
+            _ => match u.int_in_range(0..=2)? {
+                0 => Token::AnchorStart,
+                1 => Token::AnchorEnd,
+                _ => Token::WordBoundary,
+            },
+        })
+    }
+
+    fn atom(&mut self, u: &mut Unstructured<'_>) -> Result<Token> {
+        Ok(match u.int_in_range(0..=5)? {
+            0..=2 => Token::Literal(*u.choose(ALPHABET)?),
+            3 => Token::Class(self.chars(u)?.into()),
+            4 => Token::NegatedClass(self.chars(u)?.into()),
+            _ => Token::Wildcard,
+        })
+    }
+
+    /// Between one and four distinct characters in ascending order.
+    fn chars(&mut self, u: &mut Unstructured<'_>) -> Result<Vec<char>> {
+        let mut chars = vec![*u.choose(ALPHABET)?];
+        for _ in 0..u.int_in_range(0..=3)? {
+            chars.push(*u.choose(ALPHABET)?);
+        }
+        chars.sort_unstable();
+        chars.dedup();
+        Ok(chars)
+    }
+
+    fn children(&mut self, u: &mut Unstructured<'_>, depth: usize, min: usize) -> Result<Vec<Token>> {
+        let count = u.int_in_range(min..=MAX_CHILDREN)?;
+        (0..count).map(|_| self.token(u, depth + 1)).collect()
+    }
+
+    fn group(&mut self, u: &mut Unstructured<'_>, depth: usize) -> Result<Token> {
+        if u.arbitrary()? {
+            return Ok(Token::NonCapturingGroup(Box::new(Token::Concatenation(self.children(u, depth, 0)?))));
+        }
+        // Number the group before its children, as an opening parenthesis does.
+        let index = self.next_group;
+        self.next_group += 1;
+        Ok(Token::Group(Box::new(Token::Concatenation(self.children(u, depth, 0)?)), index))
+    }
+
+    /// A repeat of an atom or group, so the pattern never stacks repetition operators.
+    fn quantifier(&mut self, u: &mut Unstructured<'_>, depth: usize) -> Result<Token> {
+        let token = if u.arbitrary()? { self.atom(u)? } else { self.group(u, depth)? };
+        let min = u.int_in_range(0..=MAX_COUNT)?;
+        let max = if u.ratio(1, 4)? { usize::MAX } else { u.int_in_range(min..=MAX_COUNT)? };
+        Ok(Token::Quantifier { token: Box::new(token), min, max, greedy: u.arbitrary()? })
+    }
+}
+
+/// A bounded token tree: at most four levels deep, with small repetition counts and no
+/// backreferences, so its pattern compiles with the `regex` crate.
+impl<'a> Arbitrary<'a> for Token {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        TreeBuilder { next_group: 1 }.token(u, 0)
+    }
+}
+
+/// A random pattern the `regex` crate accepts, anchored at both ends, with its tokens. Feed
+/// `pattern` to a generator and check its outputs with `regex::Regex` to differential-test the
+/// two.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzPattern {
+    pub tokens: Vec<Token>,
+    pub pattern: String,
+}
+
+impl<'a> Arbitrary<'a> for FuzzPattern {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut builder = TreeBuilder { next_group: 1 };
+        let mut tokens = vec![Token::AnchorStart];
+        tokens.extend(builder.children(u, 0, 1)?);
+        tokens.push(Token::AnchorEnd);
+        let pattern = crate::to_pattern(&tokens);
+        Ok(FuzzPattern { tokens, pattern })
+    }
+}
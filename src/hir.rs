@@ -0,0 +1,115 @@
+//! Alternative front-end: parse with `regex-syntax` and convert its HIR into genrex tokens.
+//!
+//! The `regex` crate's own parser understands its full syntax (Unicode classes, class set
+//! operations, case-insensitive flags, negated classes, every escape), so patterns lexed this
+//! way generate what the verification regex actually accepts. It cannot parse backreferences;
+//! the builder falls back to the hand-written lexer for those.
+
+use regex_syntax::hir::{Class, Hir, HirKind, Look};
+
+use crate::lexer::{LexState, MAX_REPEAT};
+use crate::tokens::Token;
+use crate::warnings::WarningKind;
+use crate::GenError;
+
+/// Classes with more members than this are narrowed before generation.
+const MAX_CLASS_CHARS: usize = 512;
+
+/// Parse `pattern` with `regex-syntax` into tokens, recording group names and warnings in `state`.
+pub(crate) fn lex(pattern: &str, state: &mut LexState) -> Result<Vec<Token>, GenError> {
+    let hir = regex_syntax::parse(pattern).map_err(|e| GenError::InvalidRegex(e.to_string()))?;
+    let mut tokens = Vec::new();
+    convert(&hir, state, &mut tokens)?;
+    Ok(tokens)
+}
+
+/// Append the tokens for `hir` to `out`; concatenations are flattened into the sequence.
+fn convert(hir: &Hir, state: &mut LexState, out: &mut Vec<Token>) -> Result<(), GenError> {
+    match hir.kind() {
+        HirKind::Empty => {}
+        HirKind::Literal(lit) => {
+            let text = std::str::from_utf8(&lit.0).map_err(|_| GenError::Unsupported("non-UTF-8 literal".to_string()))?;
+            out.extend(text.chars().map(Token::Literal));
+        }
+        HirKind::Class(class) => {
+            let chars = class_chars(class, state)?;
+            out.push(Token::Class(state.intern(chars)));
+        }
+        HirKind::Look(look) => out.push(match look {
+            Look::Start | Look::StartLF | Look::StartCRLF => Token::AnchorStart,
+            Look::End | Look::EndLF | Look::EndCRLF => Token::AnchorEnd,
+            // A non-boundary generates nothing, like the lexer's `\B`.
+            Look::WordAsciiNegate | Look::WordUnicodeNegate => Token::Concatenation(Vec::new()),
+            _ => Token::WordBoundary,
+        }),
+        HirKind::Repetition(rep) => {
+            let max = match rep.max {
+                Some(max) => max as usize,
+                None => {
+                    state.warnings.push(crate::Warning::new(WarningKind::UnboundedRepeatCapped(MAX_REPEAT), None));
+                    usize::MAX
+                }
+            };
+            out.push(Token::Quantifier { token: Box::new(single(&rep.sub, state)?), min: rep.min as usize, max, greedy: rep.greedy });
+        }
+        HirKind::Capture(cap) => {
+            let index = cap.index as usize;
+            if let Some(name) = &cap.name {
+                state.group_names.insert(name.to_string(), index);
+            }
+            state.next_group = state.next_group.max(index + 1);
+            let mut inner = Vec::new();
+            convert(&cap.sub, state, &mut inner)?;
+            out.push(Token::Group(Box::new(Token::Concatenation(inner)), index));
+        }
+        HirKind::Concat(subs) => {
+            for sub in subs {
+                convert(sub, state, out)?;
+            }
+        }
+        HirKind::Alternation(subs) => {
+            let mut branches = Vec::with_capacity(subs.len());
+            for sub in subs {
+                let mut branch = Vec::new();
+                convert(sub, state, &mut branch)?;
+                branches.push(Token::Concatenation(branch));
+            }
+            out.push(Token::Alternation(branches));
+        }
+    }
+    Ok(())
+}
+
+/// The tokens for `hir` as one token, for use as a quantifier operand.
+fn single(hir: &Hir, state: &mut LexState) -> Result<Token, GenError> {
+    let mut tokens = Vec::new();
+    convert(hir, state, &mut tokens)?;
+    Ok(match tokens.len() {
+        1 => tokens.pop().expect("one token"),
+        _ => Token::NonCapturingGroup(Box::new(Token::Concatenation(tokens))),
+    })
+}
+
+/// Members of a class. Classes too large to list (`.`, `\w` with Unicode, negations) keep only
+/// their printable ASCII members, or their first members when they have none.
+fn class_chars(class: &Class, state: &mut LexState) -> Result<Vec<char>, GenError> {
+    let ranges: Vec<(char, char)> = match class {
+        Class::Unicode(c) => c.ranges().iter().map(|r| (r.start(), r.end())).collect(),
+        Class::Bytes(c) => c
+            .ranges()
+            .iter()
+            .map(|r| if r.end().is_ascii() { Ok((r.start() as char, r.end() as char)) } else { Err(GenError::Unsupported("non-ASCII byte class".to_string())) })
+            .collect::<Result<_, _>>()?,
+    };
+    let total: usize = ranges.iter().map(|&(lo, hi)| hi as usize - lo as usize + 1).sum();
+    if total <= MAX_CLASS_CHARS {
+        return Ok(ranges.into_iter().flat_map(|(lo, hi)| lo..=hi).collect());
+    }
+    let printable: Vec<char> = ranges.iter().flat_map(|&(lo, hi)| lo..=hi.min('~')).filter(|&c| c >= ' ').collect();
+    let chars = if printable.is_empty() { ranges.into_iter().flat_map(|(lo, hi)| lo..=hi).take(MAX_CLASS_CHARS).collect() } else { printable };
+    state.warnings.push(crate::Warning::new(
+        WarningKind::Degraded(format!("class of {} characters narrowed to {}", total, chars.len())),
+        None,
+    ));
+    Ok(chars)
+}
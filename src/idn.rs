@@ -0,0 +1,262 @@
+//! Internationalized domain name (IDN) generation.
+//!
+//! `IdnGenerator` produces domains whose labels are drawn from non-ASCII scripts, together with
+//! their ASCII-compatible (punycode, `xn--`) form. Both forms are derived from the same labels, so
+//! they always agree; the ASCII form is additionally verified against `ASCII_DOMAIN_PATTERN`
+//! and the DNS length limits (63 bytes per label, 253 per name).
+
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use regex::Regex;
+
+use crate::GenError;
+
+/// LDH ("letters, digits, hyphen") pattern every generated ASCII form matches.
+pub const ASCII_DOMAIN_PATTERN: &str = r"^([a-z0-9]([a-z0-9-]*[a-z0-9])?\.)+(xn--[a-z0-9-]+|[a-z]{2,})$";
+
+const MAX_LABEL_BYTES: usize = 63;
+const MAX_DOMAIN_BYTES: usize = 253;
+
+/// A writing system to draw label characters from. Each label uses a single script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    /// ASCII letters mixed with Latin-1 accented letters (at least one per label).
+    Latin,
+    Cyrillic,
+    Greek,
+    Hiragana,
+    /// CJK unified ideographs.
+    Han,
+}
+
+impl Script {
+    pub const ALL: [Script; 5] = [Script::Latin, Script::Cyrillic, Script::Greek, Script::Hiragana, Script::Han];
+
+    /// Lowercase, IDNA-valid code point ranges for the script.
+    fn ranges(self) -> &'static [(char, char)] {
+        match self {
+            Script::Latin => &[('a', 'z'), ('à', 'ö'), ('ø', 'ÿ')],
+            Script::Cyrillic => &[('а', 'я')],
+            Script::Greek => &[('α', 'ω')],
+            Script::Hiragana => &[('ぁ', 'ゖ')],
+            Script::Han => &[('一', '龥')],
+        }
+    }
+
+    fn pick<R: Rng + ?Sized>(self, rng: &mut R, non_ascii: bool) -> char {
+        let ranges: Vec<(char, char)> = self.ranges().iter().copied().filter(|(lo, _)| !non_ascii || !lo.is_ascii()).collect();
+        let total: u32 = ranges.iter().map(|(lo, hi)| *hi as u32 - *lo as u32 + 1).sum();
+        let mut n = rng.gen_range(0..total);
+        for (lo, hi) in ranges {
+            let size = hi as u32 - lo as u32 + 1;
+            if n < size {
+                return char::from_u32(lo as u32 + n).expect("script ranges hold valid chars");
+            }
+            n -= size;
+        }
+        unreachable!("index within total range size")
+    }
+}
+
+/// A generated domain in both its Unicode and ASCII-compatible forms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdnDomain {
+    /// Display form, e.g. `bücher.example.рф`.
+    pub unicode: String,
+    /// ASCII-compatible form, e.g. `xn--bcher-kva.example.xn--p1ai`.
+    pub ascii: String,
+}
+
+/// Generator for internationalized domain names.
+pub struct IdnGenerator {
+    rng: Box<dyn RngCore + Send>,
+    labels: (usize, usize),
+    label_len: (usize, usize),
+    scripts: Vec<Script>,
+    tlds: Vec<String>,
+    max_attempts: usize,
+    verify: Regex,
+}
+
+impl Default for IdnGenerator {
+    fn default() -> Self {
+        IdnGenerator::new()
+    }
+}
+
+impl IdnGenerator {
+    /// A generator for 1-2 labels of 3-12 characters in any script under a mix of ASCII and IDN TLDs.
+    pub fn new() -> Self {
+        IdnGenerator {
+            rng: Box::new(StdRng::from_entropy()),
+            labels: (1, 2),
+            label_len: (3, 12),
+            scripts: Script::ALL.to_vec(),
+            tlds: ["com", "org", "net", "рф", "中国", "ελ"].iter().map(|t| t.to_string()).collect(),
+            max_attempts: 100,
+            verify: Regex::new(ASCII_DOMAIN_PATTERN).expect("ASCII_DOMAIN_PATTERN is valid"),
+        }
+    }
+
+    pub fn rng<R: RngCore + Send + 'static>(mut self, rng: R) -> Self {
+        self.rng = Box::new(rng);
+        self
+    }
+
+    /// Number of labels before the TLD.
+    pub fn labels(mut self, min: usize, max: usize) -> Self {
+        self.labels = (min.max(1), max.max(min).max(1));
+        self
+    }
+
+    /// Length of each generated label, in characters.
+    pub fn label_len(mut self, min: usize, max: usize) -> Self {
+        self.label_len = (min.max(1), max.max(min).max(1));
+        self
+    }
+
+    /// Restrict label characters to these scripts.
+    pub fn scripts(mut self, scripts: &[Script]) -> Self {
+        self.scripts = scripts.to_vec();
+        self
+    }
+
+    /// Top-level domains to choose from, in Unicode form.
+    pub fn tlds(mut self, tlds: &[&str]) -> Self {
+        self.tlds = tlds.iter().map(|t| t.to_lowercase()).collect();
+        self
+    }
+
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    pub fn generate_one(&mut self) -> Result<IdnDomain, GenError> {
+        if self.scripts.is_empty() || self.tlds.is_empty() {
+            return Err(GenError::InvalidConfig("IDN generation needs at least one script and one TLD".to_string()));
+        }
+        for _ in 0..self.max_attempts.max(1) {
+            let count = self.rng.gen_range(self.labels.0..=self.labels.1);
+            let mut labels: Vec<String> = (0..count).map(|_| self.label()).collect();
+            labels.push(self.tlds[self.rng.gen_range(0..self.tlds.len())].clone());
+            let ascii_labels: Vec<String> = labels.iter().map(|l| to_ascii_label(l)).collect();
+            if ascii_labels.iter().any(|l| l.len() > MAX_LABEL_BYTES) {
+                continue;
+            }
+            let ascii = ascii_labels.join(".");
+            if ascii.len() > MAX_DOMAIN_BYTES || !self.verify.is_match(&ascii) {
+                continue;
+            }
+            return Ok(IdnDomain { unicode: labels.join("."), ascii });
+        }
+        Err(GenError::NoMatch)
+    }
+
+    pub fn generate_n(&mut self, n: usize) -> Result<Vec<IdnDomain>, GenError> {
+        (0..n).map(|_| self.generate_one()).collect()
+    }
+
+    fn label(&mut self) -> String {
+        let script = self.scripts[self.rng.gen_range(0..self.scripts.len())];
+        let len = self.rng.gen_range(self.label_len.0..=self.label_len.1);
+        let mut chars: Vec<char> = (0..len).map(|_| script.pick(&mut self.rng, false)).collect();
+        // Make sure every label actually exercises the IDN path.
+        if chars.iter().all(char::is_ascii) {
+            let at = self.rng.gen_range(0..chars.len());
+            chars[at] = script.pick(&mut self.rng, true);
+        }
+        chars.into_iter().collect()
+    }
+}
+
+/// ASCII-compatible form of one label: unchanged if already ASCII, else `xn--` + punycode.
+pub fn to_ascii_label(label: &str) -> String {
+    if label.is_ascii() {
+        label.to_string()
+    } else {
+        format!("xn--{}", punycode_encode(label))
+    }
+}
+
+/// ASCII-compatible form of a whole domain, label by label.
+pub fn to_ascii(domain: &str) -> String {
+    domain.split('.').map(to_ascii_label).collect::<Vec<_>>().join(".")
+}
+
+// RFC 3492 parameters.
+const BASE: u32 = 36;
+const TMIN: u32 = 1;
+const TMAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+/// Punycode (RFC 3492) encoding of `input`, without the `xn--` prefix.
+pub fn punycode_encode(input: &str) -> String {
+    let input: Vec<u32> = input.chars().map(|c| c as u32).collect();
+    let mut output: String = input.iter().filter(|&&c| c < 0x80).map(|&c| c as u8 as char).collect();
+    let basic = output.len() as u32;
+    if basic > 0 {
+        output.push('-');
+    }
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut handled = basic;
+    while (handled as usize) < input.len() {
+        let m = input.iter().copied().filter(|&c| c >= n).min().expect("unhandled code points remain");
+        delta += (m - n) * (handled + 1);
+        n = m;
+        for &c in &input {
+            if c < n {
+                delta += 1;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = if k <= bias {
+                        TMIN
+                    } else if k >= bias + TMAX {
+                        TMAX
+                    } else {
+                        k - bias
+                    };
+                    if q < t {
+                        break;
+                    }
+                    output.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+    output
+}
+
+fn digit(d: u32) -> char {
+    match d {
+        0..=25 => (b'a' + d as u8) as char,
+        _ => (b'0' + (d - 26) as u8) as char,
+    }
+}
+
+fn adapt(delta: u32, points: u32, first: bool) -> u32 {
+    let mut delta = if first { delta / DAMP } else { delta / 2 };
+    delta += delta / points;
+    let mut k = 0;
+    while delta > ((BASE - TMIN) * TMAX) / 2 {
+        delta /= BASE - TMIN;
+        k += BASE;
+    }
+    k + (BASE - TMIN + 1) * delta / (delta + SKEW)
+}
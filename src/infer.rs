@@ -0,0 +1,152 @@
+//! Pattern induction from example values, for masking datasets with look-alike synthetic data.
+//!
+//! Each example is split into runs of digits, lowercase letters, uppercase letters and other
+//! characters. When every example has the same sequence of runs, each run becomes a class with
+//! the observed length range, except runs whose text all examples share, which stay literal
+//! (prefixes such as `INV-`). Examples that disagree are compared again with letter case merged,
+//! then with letters and digits merged; failing that, the pattern is a single class of every
+//! character seen.
+//!
+//! ```
+//! let mut g = genrex::infer::from_examples(&["AB-1234", "XY-98", "QZ-5521"]).unwrap();
+//! assert_eq!(genrex::infer::infer_pattern(&["AB-1234", "XY-98"]).unwrap(), "[A-Z]{2}-[0-9]{2,4}");
+//! let masked = g.generate_one().unwrap();
+//! assert!(regex::Regex::new("^[A-Z]{2}-[0-9]{2,4}$").unwrap().is_match(&masked));
+//! ```
+
+use crate::tokens::{write_sequence, Token};
+use crate::{GenError, GeneratorConfig, RegexGenerator};
+
+/// How finely characters are told apart; each level merges more kinds than the one before.
+const LEVELS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Digit,
+    Lower,
+    Upper,
+    Letter,
+    Alnum,
+    /// Any other character, matched exactly.
+    Other(char),
+}
+
+impl Kind {
+    fn of(c: char, level: usize) -> Kind {
+        let kind = match c {
+            '0'..='9' => Kind::Digit,
+            'a'..='z' => Kind::Lower,
+            'A'..='Z' => Kind::Upper,
+            c => return Kind::Other(c),
+        };
+        match (level, kind) {
+            (0, kind) | (1, kind @ Kind::Digit) => kind,
+            (1, _) => Kind::Letter,
+            _ => Kind::Alnum,
+        }
+    }
+
+    fn chars(self) -> Vec<char> {
+        match self {
+            Kind::Digit => ('0'..='9').collect(),
+            Kind::Lower => ('a'..='z').collect(),
+            Kind::Upper => ('A'..='Z').collect(),
+            Kind::Letter => ('A'..='Z').chain('a'..='z').collect(),
+            Kind::Alnum => ('0'..='9').chain('A'..='Z').chain('a'..='z').collect(),
+            Kind::Other(c) => vec![c],
+        }
+    }
+}
+
+/// A maximal run of characters of one kind, as `(kind, text)`.
+type Run<'a> = (Kind, &'a str);
+
+fn runs(s: &str, level: usize) -> Vec<Run<'_>> {
+    let mut out: Vec<Run<'_>> = Vec::new();
+    let mut start = 0;
+    let mut current = None;
+    for (i, c) in s.char_indices() {
+        let kind = Kind::of(c, level);
+        if let Some(prev) = current.filter(|&k| k != kind) {
+            out.push((prev, &s[start..i]));
+            start = i;
+        }
+        current = Some(kind);
+    }
+    if let Some(kind) = current {
+        out.push((kind, &s[start..]));
+    }
+    out
+}
+
+/// The induced tokens and the longest output they generate, in bytes.
+fn induce(examples: &[&str]) -> Result<(Vec<Token>, usize), GenError> {
+    if examples.is_empty() {
+        return Err(GenError::InvalidConfig("no examples to infer a pattern from".to_string()));
+    }
+    for level in 0..LEVELS {
+        let split: Vec<Vec<Run<'_>>> = examples.iter().map(|e| runs(e, level)).collect();
+        let shape = |runs: &[Run<'_>]| runs.iter().map(|r| r.0).collect::<Vec<_>>();
+        if split.iter().all(|r| shape(r) == shape(&split[0])) {
+            return Ok(tokens_for(&split));
+        }
+    }
+    let mut chars: Vec<char> = examples.iter().flat_map(|e| e.chars()).collect();
+    chars.sort_unstable();
+    chars.dedup();
+    let widest = chars.iter().map(|c| c.len_utf8()).max().unwrap_or(1);
+    let (min, max) = length_range(examples.iter().map(|e| e.chars().count()));
+    Ok((vec![repeat(Token::Class(chars.into()), min, max)], max * widest))
+}
+
+/// Tokens for examples that all have the same sequence of run kinds.
+fn tokens_for(split: &[Vec<Run<'_>>]) -> (Vec<Token>, usize) {
+    let mut tokens = Vec::new();
+    let mut max_bytes = 0;
+    for (i, &(kind, text)) in split[0].iter().enumerate() {
+        if split.len() > 1 && split.iter().all(|runs| runs[i].1 == text) {
+            tokens.extend(text.chars().map(Token::Literal));
+            max_bytes += text.len();
+            continue;
+        }
+        let (min, max) = length_range(split.iter().map(|runs| runs[i].1.chars().count()));
+        let token = match kind.chars().as_slice() {
+            [c] => Token::Literal(*c),
+            chars => Token::Class(chars.into()),
+        };
+        max_bytes += max * kind.chars()[0].len_utf8();
+        tokens.push(repeat(token, min, max));
+    }
+    (tokens, max_bytes)
+}
+
+fn length_range(lengths: impl Iterator<Item = usize>) -> (usize, usize) {
+    lengths.fold((usize::MAX, 0), |(lo, hi), n| (lo.min(n), hi.max(n)))
+}
+
+fn repeat(token: Token, min: usize, max: usize) -> Token {
+    if (min, max) == (1, 1) {
+        return token;
+    }
+    Token::Quantifier { token: Box::new(token), min, max, greedy: true }
+}
+
+/// A pattern describing the shape shared by `examples`, e.g. `[A-Z]{2}-[0-9]{2,4}` for
+/// `["AB-1234", "XY-98"]`. Fails with `InvalidConfig` when `examples` is empty.
+pub fn infer_pattern(examples: &[&str]) -> Result<String, GenError> {
+    let (tokens, _) = induce(examples)?;
+    let mut out = String::new();
+    write_sequence(&tokens, &mut out);
+    Ok(out)
+}
+
+/// A generator of synthetic values shaped like `examples` (see `infer_pattern`), with length
+/// bounds that fit every output. Fails with `InvalidConfig` when `examples` is empty.
+pub fn from_examples(examples: &[&str]) -> Result<RegexGenerator, GenError> {
+    let (tokens, max_bytes) = induce(examples)?;
+    let mut pattern = String::new();
+    write_sequence(&tokens, &mut pattern);
+    let config = GeneratorConfig { max_len: max_bytes.max(GeneratorConfig::default().max_len), ..GeneratorConfig::default() };
+    // The inferred pattern is unanchored; outputs must match it in full, not merely contain it.
+    RegexGenerator::builder(&pattern).config(config).full_match(true).build()
+}
@@ -0,0 +1,107 @@
+//! Units for the `min_len`/`max_len` bounds.
+
+use std::fmt;
+
+/// How `min_len` and `max_len` measure an output; see `RegexGeneratorBuilder::length_unit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LengthUnit {
+    /// UTF-8 bytes (`str::len`).
+    #[default]
+    Bytes,
+    /// Unicode scalar values (`str::chars`).
+    Chars,
+    /// User-perceived characters: a base character with the combining marks, variation
+    /// selectors and emoji modifiers that follow it, emoji joined by U+200D, flag pairs of
+    /// regional indicators and CRLF each count once. This approximates the extended grapheme
+    /// clusters of UAX #29 without its full tables.
+    Graphemes,
+}
+
+impl LengthUnit {
+    /// Length of `s` in this unit.
+    pub fn measure(self, s: &str) -> usize {
+        match self {
+            LengthUnit::Bytes => s.len(),
+            LengthUnit::Chars => s.chars().count(),
+            LengthUnit::Graphemes => graphemes(s),
+        }
+    }
+
+    /// Character counts covering every string of `min_len..=max_len` in this unit whose
+    /// characters take at most `widest` UTF-8 bytes. Graphemes count as single characters;
+    /// callers widen the upper bound when characters may join.
+    pub(crate) fn char_bounds(self, min_len: usize, max_len: usize, widest: usize) -> (usize, usize) {
+        match self {
+            LengthUnit::Bytes => (min_len.div_ceil(widest.max(1)), max_len),
+            LengthUnit::Chars | LengthUnit::Graphemes => (min_len, max_len),
+        }
+    }
+}
+
+impl fmt::Display for LengthUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LengthUnit::Bytes => "bytes",
+            LengthUnit::Chars => "chars",
+            LengthUnit::Graphemes => "graphemes",
+        })
+    }
+}
+
+/// Most characters a grapheme is assumed to span when bounding walks over patterns that can
+/// generate combining characters.
+pub(crate) const MAX_GRAPHEME_CHARS: usize = 4;
+
+fn graphemes(s: &str) -> usize {
+    let mut count = 0;
+    let mut prev: Option<char> = None;
+    // Regional indicators in the current run; every second one completes a flag.
+    let mut regional = 0usize;
+    for c in s.chars() {
+        let joined = match prev {
+            None => false,
+            Some('\r') => c == '\n',
+            Some(p) if p.is_control() || c.is_control() => false,
+            Some('\u{200D}') => true,
+            Some(p) if is_regional(p) && is_regional(c) => regional % 2 == 1,
+            _ => extends(c),
+        };
+        regional = if is_regional(c) { regional + 1 } else { 0 };
+        if !joined {
+            count += 1;
+        }
+        prev = Some(c);
+    }
+    count
+}
+
+fn is_regional(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Whether `c` can share a grapheme with a neighbor.
+pub(crate) fn may_join(c: char) -> bool {
+    c == '\r' || is_regional(c) || extends(c)
+}
+
+/// Whether `c` attaches to the character before it.
+fn extends(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}' // combining diacritical marks
+        | '\u{0483}'..='\u{0489}'
+        | '\u{0591}'..='\u{05BD}'
+        | '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0900}'..='\u{0903}'
+        | '\u{093A}'..='\u{094F}'
+        | '\u{1160}'..='\u{11FF}' // Hangul medial vowels and final consonants
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{200C}'..='\u{200D}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE00}'..='\u{FE0F}' // variation selectors
+        | '\u{FE20}'..='\u{FE2F}'
+        | '\u{1F3FB}'..='\u{1F3FF}' // emoji skin tones
+        | '\u{E0020}'..='\u{E007F}' // tags
+        | '\u{E0100}'..='\u{E01EF}')
+}
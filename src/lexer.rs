@@ -0,0 +1,540 @@
+//! Pattern lexer: converts a regex pattern string into a tree of Tokens.
+//!
+//! Structural problems (unbalanced brackets, unterminated groups, dangling quantifiers,
+//! invalid escapes) are reported as `ParseError`s carrying byte offsets into the original
+//! pattern.
+
+use std::collections::{HashMap, HashSet};
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+use std::sync::Arc;
+
+use crate::constraints::ANNOTATION_PREFIX;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::tokens::Token;
+use crate::warnings::{Warning, WarningKind};
+
+pub(crate) const WORD_CHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_";
+pub(crate) const SPACE_CHARS: &str = " \t\n\r\x0B\x0C";
+
+/// Default limit on how deeply groups and quantifiers may nest, matching the `regex` crate.
+pub(crate) const MAX_NESTING_DEPTH: usize = 250;
+
+/// Extra repetitions generated for open-ended quantifiers (mirrors `Token::Quantifier` generation).
+pub(crate) const MAX_REPEAT: usize = 32;
+
+type Chars<'a> = Peekable<CharIndices<'a>>;
+
+/// How the lexer treats valid syntax that genrex cannot generate faithfully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ParseMode {
+    /// Fail with `ParseErrorKind::Unsupported`.
+    Strict,
+    /// Degrade (ignore or approximate the construct) and record the problem.
+    #[default]
+    Lenient,
+}
+
+/// Bookkeeping shared across recursive `lex_pattern` calls.
+pub(crate) struct LexState {
+    /// Next capturing group index to assign (1-based).
+    pub(crate) next_group: usize,
+    /// Named capturing groups (`(?P<name>...)` / `(?<name>...)`) by name.
+    pub(crate) group_names: HashMap<String, usize>,
+    /// Inline `{genrex:...}` annotations as (group index, annotation body).
+    pub(crate) annotations: Vec<(usize, String)>,
+    pub(crate) mode: ParseMode,
+    /// Non-fatal notices, including constructs degraded in lenient mode.
+    pub(crate) warnings: Vec<Warning>,
+    /// Deepest nesting of groups and quantifiers allowed; generation recurses once per level.
+    pub(crate) max_depth: usize,
+    /// Groups open around the text being lexed.
+    depth: usize,
+    /// Class sets seen so far, so repeated classes share one allocation.
+    classes: HashSet<Arc<[char]>>,
+}
+
+impl LexState {
+    pub(crate) fn new(mode: ParseMode) -> Self {
+        LexState {
+            next_group: 1,
+            group_names: HashMap::new(),
+            annotations: Vec::new(),
+            mode,
+            warnings: Vec::new(),
+            max_depth: MAX_NESTING_DEPTH,
+            depth: 0,
+            classes: HashSet::new(),
+        }
+    }
+
+    /// The shared copy of class set `chars`.
+    pub(crate) fn intern(&mut self, chars: Vec<char>) -> Arc<[char]> {
+        if let Some(set) = self.classes.get(&chars[..]) {
+            return Arc::clone(set);
+        }
+        let set: Arc<[char]> = chars.into();
+        self.classes.insert(Arc::clone(&set));
+        set
+    }
+
+    /// Report a construct genrex cannot generate faithfully: an error in strict mode,
+    /// a recorded degradation in lenient mode.
+    fn unsupported(&mut self, what: &str, span: Range<usize>) -> Result<(), ParseError> {
+        match self.mode {
+            ParseMode::Strict => Err(ParseError::new(ParseErrorKind::Unsupported(what.to_string()), span)),
+            ParseMode::Lenient => {
+                self.warn(WarningKind::Degraded(what.to_string()), span);
+                Ok(())
+            }
+        }
+    }
+
+    fn warn(&mut self, kind: WarningKind, span: Range<usize>) {
+        self.warnings.push(Warning::new(kind, Some(span)));
+    }
+}
+
+/// Lex a complete pattern.
+pub(crate) fn lex(pattern: &str, state: &mut LexState) -> Result<Vec<Token>, ParseError> {
+    lex_pattern(pattern, 0, state)
+}
+
+/// Lex `pattern`, whose first byte sits at offset `base` of the original pattern. Precedence
+/// follows the regex crate: a quantifier applies to the atom before it, and concatenation binds
+/// tighter than `|`, so `ab*|c` is `(?:a(?:b*))|c`.
+fn lex_pattern(pattern: &str, base: usize, state: &mut LexState) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    // Branches before the latest top-level `|`, collected in a loop so long alternations do not
+    // recurse.
+    let mut branches = Vec::new();
+    let mut chars = pattern.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let pos = base + i;
+        match c {
+            '[' => tokens.push(lex_class(&mut chars, pos, state)?),
+            '.' => {
+                // Once per pattern, at the first wildcard.
+                if !state.warnings.iter().any(|w| w.kind == WarningKind::WildcardApproximated) {
+                    state.warn(WarningKind::WildcardApproximated, pos..pos + 1);
+                }
+                tokens.push(Token::Wildcard);
+            }
+            '^' => tokens.push(Token::AnchorStart),
+            '$' => tokens.push(Token::AnchorEnd),
+            '\\' => tokens.push(lex_escape(&mut chars, pos, state)?),
+            '(' => {
+                let end = find_group_end(pattern, i + 1)
+                    .ok_or_else(|| ParseError::new(ParseErrorKind::UnclosedGroup, pos..pos + 1))?;
+                while chars.next_if(|&(j, _)| j <= end).is_some() {}
+                if let Some(tok) = lex_group(&pattern[i + 1..end], pos..base + end + 1, state)? {
+                    tokens.push(tok);
+                }
+            }
+            ')' => return Err(ParseError::new(ParseErrorKind::UnopenedGroup, pos..pos + 1)),
+            '?' => quantify(&mut tokens, &mut chars, 0, 1, pos..pos + 1, state)?,
+            '*' => quantify(&mut tokens, &mut chars, 0, usize::MAX, pos..pos + 1, state)?,
+            '+' => quantify(&mut tokens, &mut chars, 1, usize::MAX, pos..pos + 1, state)?,
+            '{' => {
+                let ahead: String = chars.clone().map(|(_, ch)| ch).take_while(|&ch| ch != '}').collect();
+                let closed = chars.clone().nth(ahead.chars().count()).is_some_and(|(_, ch)| ch == '}');
+                let end = pos + ahead.len() + 2;
+                // Inline genrex annotation attached to the preceding group: `(...){genrex:...}`.
+                if let Some(spec) = ahead.strip_prefix(ANNOTATION_PREFIX) {
+                    if !closed {
+                        return Err(ParseError::new(ParseErrorKind::UnclosedAnnotation, pos..pos + 1));
+                    }
+                    chars.nth(ahead.chars().count());
+                    let Some(Token::Group(_, idx)) = tokens.last() else {
+                        return Err(ParseError::new(ParseErrorKind::MisplacedAnnotation, pos..end));
+                    };
+                    state.annotations.push((*idx, spec.to_string()));
+                    continue;
+                }
+                // Parse {min,max}; anything that is not a well-formed counted repetition
+                // (e.g. `a{foo}`) is a literal brace.
+                match parse_repetition(&ahead).filter(|_| closed && !tokens.is_empty()) {
+                    Some((min, max)) => {
+                        if min > max {
+                            return Err(ParseError::new(ParseErrorKind::InvalidRepetitionRange, pos..end));
+                        }
+                        chars.nth(ahead.chars().count());
+                        quantify(&mut tokens, &mut chars, min, max, pos..end, state)?;
+                    }
+                    None => tokens.push(Token::Literal('{')),
+                }
+            }
+            // `a|b|c` is one alternation so branches are chosen uniformly.
+            '|' => branches.push(Token::Concatenation(std::mem::take(&mut tokens))),
+            _ => {
+                tokens.push(Token::Literal(c));
+            }
+        }
+    }
+    if branches.is_empty() {
+        return Ok(tokens);
+    }
+    branches.push(Token::Concatenation(tokens));
+    Ok(vec![Token::Alternation(branches)])
+}
+
+/// Wrap the last token in a quantifier, consuming a trailing lazy `?` modifier.
+fn quantify(tokens: &mut Vec<Token>, chars: &mut Chars, min: usize, max: usize, span: Range<usize>, state: &mut LexState) -> Result<(), ParseError> {
+    let last = tokens.pop().ok_or_else(|| ParseError::new(ParseErrorKind::DanglingQuantifier, span.clone()))?;
+    let mut stacked = 1;
+    let mut inner = &last;
+    while let Token::Quantifier { token, .. } = inner {
+        stacked += 1;
+        inner = token;
+    }
+    if state.depth + stacked > state.max_depth {
+        return Err(ParseError::new(ParseErrorKind::NestingTooDeep(state.max_depth), span));
+    }
+    if max == usize::MAX {
+        state.warn(WarningKind::UnboundedRepeatCapped(MAX_REPEAT), span);
+    }
+    // Detect lazy modifier ("*?", "+?", "??", "{m,n}?") -> non-greedy
+    let greedy = chars.next_if(|&(_, ch)| ch == '?').is_none();
+    tokens.push(Token::Quantifier { token: Box::new(last), min, max, greedy });
+    Ok(())
+}
+
+/// Lex the body of a `(...)` group spanning `span` (parentheses included). Returns None for
+/// constructs that generate nothing (inline flags, lookarounds).
+fn lex_group(group: &str, span: Range<usize>, state: &mut LexState) -> Result<Option<Token>, ParseError> {
+    if state.depth >= state.max_depth {
+        return Err(ParseError::new(ParseErrorKind::NestingTooDeep(state.max_depth), span));
+    }
+    state.depth += 1;
+    let token = lex_group_body(group, span, state);
+    state.depth -= 1;
+    token
+}
+
+fn lex_group_body(group: &str, span: Range<usize>, state: &mut LexState) -> Result<Option<Token>, ParseError> {
+    let base = span.start + 1;
+    // Lookarounds are not generated.
+    for prefix in ["?=", "?!", "?<=", "?<!"] {
+        if group.starts_with(prefix) {
+            state.unsupported("lookaround", span)?;
+            return Ok(None);
+        }
+    }
+    // Named group: record the name and lex only the body.
+    let named = group.strip_prefix("?P<").or_else(|| group.strip_prefix("?<"))
+        .and_then(|rest| rest.split_once('>'));
+    if let Some((name, body)) = named {
+        let group_id = state.next_group;
+        state.next_group += 1;
+        state.group_names.insert(name.to_string(), group_id);
+        let inner = lex_pattern(body, base + group.len() - body.len(), state)?;
+        return Ok(Some(Token::Group(Box::new(Token::Concatenation(inner)), group_id)));
+    }
+    if let Some(rest) = group.strip_prefix('?') {
+        // Inline flags `(?i)` or a flagged non-capturing group `(?i:...)` / `(?:...)`.
+        let flags_len = rest.find(|c: char| !(c.is_ascii_alphabetic() || c == '-')).unwrap_or(rest.len());
+        // Verbose mode changes how the pattern itself is read; the lexer does not implement it.
+        if rest[..flags_len].split('-').next().is_some_and(|on| on.contains('x')) {
+            state.unsupported("verbose flag (?x)", span.clone())?;
+        }
+        return match rest[flags_len..].strip_prefix(':') {
+            Some(body) => {
+                let inner = lex_pattern(body, base + group.len() - body.len(), state)?;
+                Ok(Some(Token::NonCapturingGroup(Box::new(Token::Concatenation(inner)))))
+            }
+            None if flags_len == rest.len() => Ok(None),
+            None => Err(ParseError::new(ParseErrorKind::InvalidGroup, base..base + 1)),
+        };
+    }
+    // Assign the capturing group index before lexing nested groups (source order).
+    let group_id = state.next_group;
+    state.next_group += 1;
+    let inner = lex_pattern(group, base, state)?;
+    Ok(Some(Token::Group(Box::new(Token::Concatenation(inner)), group_id)))
+}
+
+/// Find the byte index of the `)` closing a group whose body starts at `from`,
+/// skipping escapes and character classes.
+fn find_group_end(pattern: &str, from: usize) -> Option<usize> {
+    let mut depth = 1usize;
+    let mut chars = pattern[from..].char_indices().map(|(i, c)| (from + i, c));
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => { chars.next(); }
+            '[' => {
+                // Skip the class if it is closed; an unclosed class is reported by the class lexer.
+                if let Some(close) = find_class_end(pattern, i + 1) {
+                    for _ in chars.by_ref().take_while(|&(j, _)| j < close) {}
+                }
+            }
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Find the byte index of the `]` closing a class whose body starts at `from`.
+fn find_class_end(pattern: &str, from: usize) -> Option<usize> {
+    let mut chars = pattern[from..].char_indices().map(|(i, c)| (from + i, c)).peekable();
+    chars.next_if(|&(_, c)| c == '^');
+    // A `]` immediately after `[` or `[^` is a literal.
+    chars.next_if(|&(_, c)| c == ']');
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => { chars.next(); }
+            ']' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Lex a character class; `chars` is positioned just after the opening `[` at `pos`.
+fn lex_class(chars: &mut Chars, pos: usize, state: &mut LexState) -> Result<Token, ParseError> {
+    let mut class = Vec::new();
+    let negated = chars.next_if(|&(_, ch)| ch == '^').is_some();
+    let mut first = true;
+    loop {
+        let Some((i, next)) = chars.next() else {
+            return Err(ParseError::new(ParseErrorKind::UnclosedClass, pos..pos + 1));
+        };
+        if next == ']' && !first {
+            break;
+        }
+        first = false;
+        if next == '[' && chars.peek().is_some_and(|&(_, ch)| ch == ':') {
+            state.unsupported("POSIX character class", i..i + 2)?;
+        }
+        let lo = if next == '\\' {
+            match chars.peek() {
+                Some((_, 'd')) => { chars.next(); class.extend('0'..='9'); continue; }
+                Some((_, 'w')) => { chars.next(); class.extend(WORD_CHARS.chars()); continue; }
+                Some((_, 's')) => { chars.next(); class.extend(SPACE_CHARS.chars()); continue; }
+                _ => class_escape(chars, i, state)?,
+            }
+        } else {
+            next
+        };
+        // Expand `a-z` style ranges; a trailing '-' before ']' is a literal.
+        let mut lookahead = chars.clone();
+        if lookahead.next().is_some_and(|(_, ch)| ch == '-')
+            && let Some((j, hi)) = lookahead.next().filter(|&(_, ch)| ch != ']') {
+            chars.next();
+            chars.next();
+            let hi = if hi == '\\' { class_escape(chars, j, state)? } else { hi };
+            class.extend(lo..=hi);
+            continue;
+        }
+        class.push(lo);
+    }
+    if negated {
+        state.unsupported("negated class", pos..pos + 1)?;
+    }
+    let class = state.intern(class);
+    Ok(if negated { Token::NegatedClass(class) } else { Token::Class(class) })
+}
+
+/// The character denoted by an escape inside a class, such as `\n` or `\012`; `chars` is
+/// positioned just after the `\` at `pos`.
+fn class_escape(chars: &mut Chars, pos: usize, state: &mut LexState) -> Result<char, ParseError> {
+    Ok(match chars.next() {
+        Some((j, esc @ ('D' | 'W' | 'S' | 'p' | 'P' | 'x' | 'u' | 'U'))) => {
+            state.unsupported(&format!("escape \\{} inside a class", esc), pos..j + 1)?;
+            esc
+        }
+        Some((_, '0')) => octal_escape(chars),
+        Some((j, 'c')) => control_escape(chars).ok_or_else(|| ParseError::new(ParseErrorKind::InvalidEscape('c'), pos..j + 1))?,
+        Some((_, esc)) => simple_escape(esc).unwrap_or(esc),
+        None => return Err(ParseError::new(ParseErrorKind::TrailingBackslash, pos..pos + 1)),
+    })
+}
+
+/// Map a single-character escape such as `\n` to the character it denotes.
+fn simple_escape(c: char) -> Option<char> {
+    match c {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        'f' => Some('\x0C'),
+        'v' => Some('\x0B'),
+        'a' => Some('\x07'),
+        _ => None,
+    }
+}
+
+/// The octal escape `\0` followed by up to two more octal digits, as in PCRE and grep: `\0` is
+/// NUL and `\012` a newline. `chars` is positioned just after the `0`.
+fn octal_escape(chars: &mut Chars) -> char {
+    let mut value = 0;
+    for _ in 0..2 {
+        let Some((_, digit)) = chars.next_if(|&(_, ch)| ch.is_digit(8)) else { break };
+        value = value * 8 + digit.to_digit(8).unwrap_or(0);
+    }
+    char::from_u32(value).unwrap_or('\0')
+}
+
+/// The control character of a `\cX` escape, for a printable ASCII `X` as in PCRE: `\cJ` (or
+/// `\cj`) is a newline and `\c?` is DEL. `chars` is positioned just after the `c`.
+fn control_escape(chars: &mut Chars) -> Option<char> {
+    let (_, letter) = chars.next_if(|&(_, ch)| ch.is_ascii_graphic() || ch == ' ')?;
+    Some(char::from(letter.to_ascii_uppercase() as u8 ^ 0x40))
+}
+
+/// Lex an escape sequence; `chars` is positioned just after the `\` at `pos`.
+fn lex_escape(chars: &mut Chars, pos: usize, state: &mut LexState) -> Result<Token, ParseError> {
+    let Some((_, next)) = chars.next() else {
+        return Err(ParseError::new(ParseErrorKind::TrailingBackslash, pos..pos + 1));
+    };
+    let tok = match next {
+        'b' => Token::WordBoundary,
+        'B' => Token::Concatenation(Vec::new()),
+        'A' => Token::AnchorStart,
+        'z' => Token::AnchorEnd,
+        'd' => Token::Class(state.intern(('0'..='9').collect())),
+        'D' | 'W' | 'S' => {
+            state.unsupported("negated class", pos..pos + 2)?;
+            let chars = match next { 'D' => "0123456789", 'W' => WORD_CHARS, _ => SPACE_CHARS };
+            Token::NegatedClass(state.intern(chars.chars().collect()))
+        }
+        'w' => Token::Class(state.intern(WORD_CHARS.chars().collect())),
+        's' => Token::Class(state.intern(SPACE_CHARS.chars().collect())),
+        '0' => Token::Literal(octal_escape(chars)),
+        'c' => match control_escape(chars) {
+            Some(ch) => Token::Literal(ch),
+            None => return Err(ParseError::new(ParseErrorKind::InvalidEscape('c'), pos..pos + 2)),
+        },
+        '1'..='9' => Token::Backreference(next.to_digit(10).unwrap() as usize),
+        // Custom token placeholder `\T{name}` / `\T{name:args}`: a capturing group whose value
+        // comes from the named token, matching anything in the verification regex.
+        'T' if chars.peek().is_some_and(|&(_, c)| c == '{') => {
+            let body: String = chars.clone().skip(1).map(|(_, c)| c).take_while(|&c| c != '}').collect();
+            let closed = chars.clone().nth(body.chars().count() + 1).is_some_and(|(_, c)| c == '}');
+            if !closed || body.is_empty() {
+                return Err(ParseError::new(ParseErrorKind::InvalidEscape('T'), pos..pos + 2));
+            }
+            chars.nth(body.chars().count() + 1);
+            let group_id = state.next_group;
+            state.next_group += 1;
+            state.annotations.push((group_id, format!("token={}", body)));
+            let any = Token::Quantifier { token: Box::new(Token::Wildcard), min: 0, max: usize::MAX, greedy: true };
+            Token::Group(Box::new(any), group_id)
+        }
+        // Recognized by the regex crate but not generated specially yet.
+        'x' | 'u' | 'U' | 'p' | 'P' => {
+            state.unsupported(&format!("escape \\{}", next), pos..pos + 2)?;
+            Token::Literal(next)
+        }
+        c if c.is_ascii_alphanumeric() => match simple_escape(c) {
+            Some(ch) => Token::Literal(ch),
+            None => return Err(ParseError::new(ParseErrorKind::InvalidEscape(c), pos..pos + 1 + c.len_utf8())),
+        },
+        c => Token::Literal(c),
+    };
+    Ok(tok)
+}
+
+/// Parse the body of a counted repetition (`n`, `n,`, `n,m` or `,m`) into (min, max).
+/// Returns None when the body is not a valid repetition.
+pub(crate) fn parse_repetition(body: &str) -> Option<(usize, usize)> {
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    match body.split_once(',') {
+        None if digits(body) => {
+            let n = body.parse().ok()?;
+            Some((n, n))
+        }
+        Some((lo, hi)) if (lo.is_empty() || digits(lo)) && (hi.is_empty() || digits(hi)) && !(lo.is_empty() && hi.is_empty()) => {
+            let min = if lo.is_empty() { 0 } else { lo.parse().ok()? };
+            let max = if hi.is_empty() { usize::MAX } else { hi.parse().ok()? };
+            Some((min, max))
+        }
+        _ => None,
+    }
+}
+
+/// Rewrite octal (`\012`) and control (`\cJ`) escapes, which the `regex` crate does not
+/// read, as the `\x{..}` escapes of the characters the lexer gives them.
+pub(crate) fn normalize_escapes(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        out.push(c);
+        if c != '\\' {
+            continue;
+        }
+        let Some((_, n)) = chars.next() else { continue };
+        let escaped = match n {
+            '0' => Some(octal_escape(&mut chars)),
+            'c' => control_escape(&mut chars),
+            _ => None,
+        };
+        match escaped {
+            Some(ch) => out.push_str(&format!("x{{{:02X}}}", u32::from(ch))),
+            None => out.push(n),
+        }
+    }
+    out
+}
+
+/// Rewrite brace constructs that genrex accepts but the `regex` crate rejects, so the
+/// verification regex agrees with the lexer: `{,m}` becomes `{0,m}` and a `{` that does
+/// not start a valid repetition is escaped.
+pub(crate) fn normalize_repetitions(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    let mut in_class = false;
+    let mut has_operand = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                out.push(c);
+                if let Some(n) = chars.next() {
+                    out.push(n);
+                    // Braced escape bodies such as `\p{Greek}` or `\x{41}` are not repetitions.
+                    if matches!(n, 'p' | 'P' | 'x' | 'u' | 'U') && chars.peek() == Some(&'{') {
+                        for ch in chars.by_ref() {
+                            out.push(ch);
+                            if ch == '}' {
+                                break;
+                            }
+                        }
+                    }
+                }
+                has_operand = true;
+                continue;
+            }
+            '[' if !in_class => in_class = true,
+            ']' if in_class => in_class = false,
+            '{' if !in_class => {
+                let ahead: String = chars.clone().take_while(|&ch| ch != '}').collect();
+                let closed = chars.clone().nth(ahead.chars().count()) == Some('}');
+                if closed && ahead.starts_with(ANNOTATION_PREFIX) {
+                    out.push(c);
+                    continue;
+                }
+                match parse_repetition(&ahead).filter(|_| closed && has_operand) {
+                    Some(_) if ahead.starts_with(',') => {
+                        out.push_str("{0");
+                        continue;
+                    }
+                    Some(_) => {}
+                    None => {
+                        out.push_str("\\{");
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+        out.push(c);
+        has_operand = in_class || !matches!(c, '(' | '|');
+    }
+    out
+}
@@ -1,179 +1,96 @@
-/// Minimal lexer: converts a regex pattern string into a vector of Tokens.
-/// Only supports literals and character classes for now.
-fn lex_pattern(pattern: &str, next_group: &mut usize) -> Vec<Token> {
-    let mut tokens = Vec::new();
-    let mut chars = pattern.chars().peekable();
-    while let Some(c) = chars.next() {
-        match c {
-            '[' => {
-                let mut class = Vec::new();
-                let mut negated = false;
-                if let Some('^') = chars.peek() {
-                    chars.next();
-                    negated = true;
-                }
-                while let Some(&next) = chars.peek() {
-                    if next == ']' {
-                        chars.next();
-                        break;
-                    }
-                    class.push(chars.next().unwrap());
-                }
-                if negated {
-                    tokens.push(Token::NegatedClass(class));
-                } else {
-                    tokens.push(Token::Class(class));
-                }
-            }
-            '.' => tokens.push(Token::Wildcard),
-            '^' => tokens.push(Token::AnchorStart),
-            '$' => tokens.push(Token::AnchorEnd),
-            '\\' => {
-                if let Some(next) = chars.next() {
-                    match next {
-                        'b' => tokens.push(Token::WordBoundary),
-                        'd' => tokens.push(Token::Class(('0'..='9').collect())),
-                        'D' => tokens.push(Token::NegatedClass(('0'..='9').collect())),
-                        'w' => tokens.push(Token::Class("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_".chars().collect())),
-                        'W' => tokens.push(Token::NegatedClass("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_".chars().collect())),
-                        's' => tokens.push(Token::Class(" \t\n\r\x0B\x0C".chars().collect())),
-                        'S' => tokens.push(Token::NegatedClass(" \t\n\r\x0B\x0C".chars().collect())),
-                        '1'..='9' => tokens.push(Token::Backreference(next.to_digit(10).unwrap() as usize)),
-                        _ => tokens.push(Token::Literal(next)),
-                    }
-                }
-            }
-            '(' => {
-                // Assign a capturing group index and parse its contents.
-                let group_id = *next_group;
-                *next_group += 1;
-                let mut group = String::new();
-                let mut depth = 1;
-                while let Some(next) = chars.next() {
-                    match next {
-                        '(' => { depth += 1; group.push(next); },
-                        ')' => {
-                            depth -= 1;
-                            if depth == 0 { break; }
-                            group.push(next);
-                        }
-                        _ => group.push(next),
-                    }
-                }
-                let inner_tokens = lex_pattern(&group, next_group);
-                tokens.push(Token::Group(Box::new(Token::Concatenation(inner_tokens)), group_id));
-            }
-            '?' => {
-                // Non-capturing group or quantifier
-                if let Some(&':') = chars.peek() {
-                    chars.next();
-                    // Parse non-capturing group (do NOT assign a group index)
-                    let mut group = String::new();
-                    let mut depth = 1;
-                    while let Some(next) = chars.next() {
-                        match next {
-                            '(' => { depth += 1; group.push(next); },
-                            ')' => {
-                                depth -= 1;
-                                if depth == 0 { break; }
-                                group.push(next);
-                            }
-                            _ => group.push(next),
-                        }
-                    }
-                    let inner_tokens = lex_pattern(&group, next_group);
-                    tokens.push(Token::NonCapturingGroup(Box::new(Token::Concatenation(inner_tokens))));
-                } else {
-                    // Quantifier ? (zero or one)
-                    if let Some(last) = tokens.pop() {
-                        // Support lazy modifier "??" (non-greedy for the '?' quantifier).
-                        let mut greedy = true;
-                        if let Some(&'?') = chars.peek() {
-                            chars.next();
-                            greedy = false;
-                        }
-                        tokens.push(Token::Quantifier { token: Box::new(last), min: 0, max: 1, greedy });
-                    }
-                }
-            }
-            '*' => {
-                if let Some(last) = tokens.pop() {
-                    // Detect lazy modifier "*?" -> non-greedy
-                    let mut greedy = true;
-                    if let Some(&'?') = chars.peek() {
-                        chars.next();
-                        greedy = false;
-                    }
-                    tokens.push(Token::Quantifier { token: Box::new(last), min: 0, max: usize::MAX, greedy });
-                }
-            }
-            '+' => {
-                if let Some(last) = tokens.pop() {
-                    // Detect lazy modifier "+?" -> non-greedy
-                    let mut greedy = true;
-                    if let Some(&'?') = chars.peek() {
-                        chars.next();
-                        greedy = false;
-                    }
-                    tokens.push(Token::Quantifier { token: Box::new(last), min: 1, max: usize::MAX, greedy });
-                }
-            }
-            '{' => {
-                // Parse {min,max}
-                let mut num = String::new();
-                while let Some(&ch) = chars.peek() {
-                    if ch == ',' || ch == '}' { break; }
-                    num.push(chars.next().unwrap());
-                }
-                let min = num.parse::<usize>().unwrap_or(0);
-                let mut max = min;
-                if let Some(&',') = chars.peek() {
-                    chars.next();
-                    let mut num2 = String::new();
-                    while let Some(&ch) = chars.peek() {
-                        if ch == '}' { break; }
-                        num2.push(chars.next().unwrap());
-                    }
-                    if !num2.is_empty() {
-                        max = num2.parse::<usize>().unwrap_or(min);
-                    } else {
-                        max = usize::MAX;
-                    }
-                }
-                if let Some('}') = chars.peek() { chars.next(); }
-                if let Some(last) = tokens.pop() {
-                    // Detect lazy modifier "{m,n}?" -> non-greedy
-                    let mut greedy = true;
-                    if let Some(&'?') = chars.peek() {
-                        chars.next();
-                        greedy = false;
-                    }
-                    tokens.push(Token::Quantifier { token: Box::new(last), min, max, greedy });
-                }
-            }
-            '|' => {
-                // Alternation: split tokens at this point
-                let rest: String = chars.collect();
-                let right = lex_pattern(&rest, next_group);
-                let left = std::mem::take(&mut tokens);
-                tokens.push(Token::Alternation(vec![Token::Concatenation(left), Token::Concatenation(right)]));
-                break;
-            }
-            _ => {
-                tokens.push(Token::Literal(c));
-            }
-        }
-    }
-    tokens
-}
 pub use crate::traits::{RegexStringGenerator, GeneratorConfigurable, GenerationAgent};
-pub use crate::error::GenrexError;
+pub use crate::error::{GenrexError, ParseError, ParseErrorKind};
 mod traits;
 mod error;
 mod tokens;
 mod ast;
 mod parser;
+mod lexer;
+mod program;
+pub mod cache;
+#[cfg(feature = "hir")]
+mod hir;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+#[cfg(feature = "arbitrary")]
+pub use crate::fuzz::FuzzPattern;
+pub mod patterns;
+pub mod presets;
+pub use crate::presets::preset;
+pub mod idn;
+pub mod infer;
+pub mod openapi;
+pub mod nfa;
+pub mod batch;
+pub mod bench;
+mod faults;
+mod constraints;
+pub mod custom;
+mod stats;
+mod observer;
+mod relax;
+mod satisfy;
+pub mod encoding;
+pub use crate::encoding::{EncodeError, Encoder};
+mod stream;
+mod dfa;
+mod analysis;
+pub use crate::analysis::{Analysis, Entropy};
+pub use num_bigint::BigUint;
+mod pool;
+pub use crate::pool::PrefetchPool;
+pub use crate::stream::{BoundedStream, Quota, StreamSummary};
+mod warnings;
+mod diag;
+pub use crate::warnings::{Warning, WarningKind};
+pub use crate::stats::{Engine, GenReport, GenStats, RejectReason};
+pub use crate::observer::Observer;
+pub use crate::relax::{RelaxPolicy, Relaxation};
+pub use crate::constraints::{GroupConstraint, GroupOverride};
+pub use crate::custom::{CustomToken, TokenRegistry};
+use crate::constraints::ConstraintSet;
+use crate::lexer::LexState;
+use crate::traits::burn;
+pub use crate::lexer::ParseMode;
+mod dialect;
+pub use crate::dialect::{Dialect, PatternDialect};
+pub use crate::faults::{FaultConfig, FaultKind};
+mod output;
+mod explain;
+mod embed;
+mod multi;
+pub use crate::multi::{Interleave, MultiPatternGenerator};
+mod record;
+pub use crate::record::{FieldSpec, RecordGenerator};
+pub use crate::embed::{Document, DocumentConfig, DocumentMatch};
+pub use crate::explain::{Choice, Explanation, TraceNode};
+pub use crate::output::{BranchTag, Captures, LabeledOutput};
 pub use crate::tokens::Token;
+pub mod visit;
+mod pretty;
+pub use crate::pretty::TokenTree;
+mod template;
+pub use crate::template::Template;
+mod shrink;
+mod selfcheck;
+mod export;
+mod policy;
+pub use crate::policy::{PolicyBuilder, PolicyGenerator};
+mod weights;
+pub use crate::weights::CharWeights;
+mod length;
+pub use crate::length::LengthUnit;
+#[cfg(feature = "jsonschema")]
+mod schema;
+#[cfg(feature = "jsonschema")]
+pub use crate::schema::SchemaSampler;
+pub use crate::export::{json_string, to_dot, to_json};
+pub mod derive;
+pub use crate::derive::Generate;
+#[cfg(feature = "derive")]
+pub use genrex_derive::Generate;
+pub use crate::selfcheck::{verify_self, Mismatch, SelfCheckReport};
+pub use crate::visit::{TokenVisitor, TokenVisitorMut};
 pub use crate::traits::{RegexToken, TokenContext};
 // use crate::traits::{RegexStringGenerator, GeneratorConfigurable, GenerationAgent}; // removed duplicate import, now re-exported
 // use crate::error::GenrexError; // removed duplicate import, now re-exported
@@ -182,17 +99,11 @@ use crate::parser::AstParser;
 use crate::ast::AstNode;
 impl RegexStringGenerator for RegexGenerator {
     fn generate_one(&mut self) -> Result<String, GenrexError> {
-        self.generate_one().map_err(|e| match e {
-            GenError::InvalidRegex(s) => GenrexError::InvalidRegex(s),
-            GenError::NoMatch => GenrexError::NoMatch,
-        })
+        self.generate_one().map_err(GenrexError::from)
     }
 
     fn generate_n(&mut self, n: usize) -> Result<Vec<String>, GenrexError> {
-        self.generate_n(n).map_err(|e| match e {
-            GenError::InvalidRegex(s) => GenrexError::InvalidRegex(s),
-            GenError::NoMatch => GenrexError::NoMatch,
-        })
+        self.generate_n(n).map_err(GenrexError::from)
     }
 
     fn is_multiline(&self) -> bool {
@@ -224,12 +135,19 @@ impl GeneratorConfigurable for RegexGenerator {
 }
 
 impl GenerationAgent for RegexGenerator {
-    fn generate_with_strategy(&mut self, _strategy: &str) -> Result<String, GenrexError> {
-        // For now, just call the default generator
-        self.generate_one().map_err(|e| match e {
-            GenError::InvalidRegex(s) => GenrexError::InvalidRegex(s),
-            GenError::NoMatch => GenrexError::NoMatch,
-        })
+    /// Strategies:
+    /// - `"random"` (or `"default"`): same as `generate_one`.
+    /// - `"shortest"`: the shortest matching string within the length bounds (deterministic).
+    /// - `"longest"`: the longest matching string within the length bounds (deterministic).
+    ///
+    /// Among strings of the chosen length the lexicographically smallest is returned.
+    fn generate_with_strategy(&mut self, strategy: &str) -> Result<String, GenrexError> {
+        match strategy {
+            "random" | "default" => self.generate_one().map_err(GenrexError::from),
+            "shortest" => self.generate_extreme(false).map_err(GenrexError::from),
+            "longest" => self.generate_extreme(true).map_err(GenrexError::from),
+            other => Err(GenrexError::UnsupportedFeature(format!("generation strategy '{}'", other))),
+        }
     }
 }
 // genrex — minimal MVP crate to generate random strings matching a regex (rejection sampling).
@@ -239,13 +157,18 @@ impl GenerationAgent for RegexGenerator {
 // - No support for backreferences/lookarounds.
 // - May be inefficient for very constrained patterns; later versions will add AST->NFA bounded sampling.
 
-use rand::{distributions::Alphanumeric, RngCore, Rng, SeedableRng, rngs::StdRng};
+use rand::{distributions::{Alphanumeric, Distribution}, RngCore, Rng, SeedableRng, rngs::{mock::StepRng, StdRng}, seq::SliceRandom};
 use regex::Regex;
 use thiserror::Error;
+use std::fmt;
 use std::time::{Duration, Instant};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::ops::{Range, RangeBounds};
+use std::collections::{HashMap, HashSet};
 
-/// Global verbose flag — when enabled the crate will print internal warnings and rejection diagnostics.
+/// Global verbose flag — when enabled the crate will print internal warnings and rejection diagnostics
+/// to stderr. Build with the `tracing` feature to receive the same events through a subscriber instead.
 pub static VERBOSE: AtomicBool = AtomicBool::new(false);
 
 /// Convenience to set verbosity from binaries.
@@ -253,17 +176,66 @@ pub fn set_verbose(v: bool) {
     VERBOSE.store(v, Ordering::Relaxed);
 }
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum GenError {
     #[error("invalid regex: {0}")]
     InvalidRegex(String),
 
     #[error("no match found within constraints")]
     NoMatch,
+
+    #[error("timeout reached during generation")]
+    Timeout,
+
+    #[error("generation budget exhausted")]
+    BudgetExhausted,
+
+    #[error("generation cancelled")]
+    Cancelled,
+
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("pattern cannot match: {0}")]
+    Unsatisfiable(String),
+
+    #[error("unsupported for analysis: {0}")]
+    Unsupported(String),
+
+    #[error("parse error: {0}")]
+    Parse(#[from] ParseError),
+
+    #[error("encoding error: {0}")]
+    Encoding(#[from] EncodeError),
+
+    #[error("writing output failed")]
+    Write(#[from] fmt::Error),
+}
+
+impl From<GenError> for GenrexError {
+    fn from(e: GenError) -> Self {
+        match e {
+            GenError::InvalidRegex(s) => GenrexError::InvalidRegex(s),
+            GenError::NoMatch => GenrexError::NoMatch,
+            GenError::Timeout => GenrexError::Timeout,
+            GenError::BudgetExhausted => GenrexError::BudgetExhausted,
+            GenError::Cancelled => GenrexError::Cancelled,
+            GenError::InvalidConfig(s) => GenrexError::InvalidConfig(s),
+            GenError::Unsatisfiable(s) => GenrexError::Unsatisfiable(s),
+            GenError::Unsupported(s) => GenrexError::UnsupportedFeature(s),
+            GenError::Parse(p) => GenrexError::Parse(p),
+            GenError::Encoding(e) => GenrexError::Encoding(e),
+            GenError::Write(e) => GenrexError::Internal(e.to_string()),
+        }
+    }
 }
 
 /// Configuration for the generator.
+///
+/// With the `serde` feature it (de)serializes, and missing fields take their default values, so
+/// a config file only needs the settings it changes.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
 pub struct GeneratorConfig {
     pub min_len: usize,
     pub max_len: usize,
@@ -284,29 +256,180 @@ impl Default for GeneratorConfig {
     }
 }
 
+/// Default `max_output_bytes` when lengths count graphemes, which have no size bound.
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 1 << 20;
+
+/// An acceptance predicate added with `RegexGeneratorBuilder::filter`, shared with forks.
+type Filter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// An RNG that can be duplicated through a trait object, so generators can be cloned. `Sync`
+/// keeps generators shareable between threads for `generate_one_ref`.
+trait CloneRng: RngCore + Send + Sync {
+    fn clone_rng(&self) -> Box<dyn CloneRng>;
+}
+
+impl<R: RngCore + Clone + Send + Sync + 'static> CloneRng for R {
+    fn clone_rng(&self) -> Box<dyn CloneRng> {
+        Box::new(self.clone())
+    }
+}
+
+/// Bookkeeping from one generation call: its counters, and what the token engine recorded
+/// about the output it produced.
+#[derive(Default)]
+struct Run {
+    stats: GenStats,
+    branch: Option<usize>,
+    captures: Option<Vec<Option<String>>>,
+    explanation: Option<Explanation>,
+    /// Told about each attempt and rejection as they are counted.
+    observer: Option<Arc<dyn Observer>>,
+}
+
+impl Run {
+    fn new(observer: &Option<Arc<dyn Observer>>) -> Run {
+        Run { observer: observer.clone(), ..Run::default() }
+    }
+
+    fn attempt(&mut self) {
+        self.stats.attempts += 1;
+        diag::attempt();
+        if let Some(observer) = &self.observer {
+            observer.on_attempt();
+        }
+    }
+
+    fn reject(&mut self, reason: RejectReason) {
+        self.stats.record_reject(reason);
+        diag::count_reject(reason);
+        if let Some(observer) = &self.observer {
+            observer.on_reject(reason);
+        }
+    }
+}
 
 /// A generator for strings matching a provided regex, with a configurable PRNG, multiline mode, and parsed AST/tokens.
+///
+/// Cloning shares the lexed pattern and compiled automata instead of rebuilding them. A clone
+/// continues from the same RNG state, so it repeats the original's next outputs; call `reseed`
+/// on it, or use `fork`, for an independent stream.
 pub struct RegexGenerator {
     re: Regex,
     config: GeneratorConfig,
-    rng: Box<dyn RngCore + Send>,
+    rng: Box<dyn CloneRng>,
+    /// Seed of `rng` when it is a `StdRng` genrex seeded itself; the base for `fork`.
+    seed: Option<u64>,
     multiline: bool,
-    ast: Option<AstNode>,
+    // Compiled artifacts are shared between clones and forks, and the lazy ones are built once
+    // for all of them.
+    /// Legacy AST for the fallback engine, parsed from `tokens` the first time it is needed.
+    ast: Arc<OnceLock<Option<AstNode>>>,
     /// Lexer tokens (prefer token-based generation when available).
-    tokens: Option<Vec<Token>>,
+    tokens: Option<Arc<[Token]>>,
+    /// `tokens` compiled for the token engine; traced generation walks the tokens instead.
+    program: Option<Arc<program::Program>>,
     /// Number of capturing groups discovered by the lexer.
     group_count: usize,
+    /// Optional failure injection applied by `generate_labeled`.
+    faults: Option<FaultConfig>,
+    /// Per-group constraints (uniqueness, sequences, value lists).
+    constraints: ConstraintSet,
+    /// Cumulative attempt/rejection counters.
+    stats: GenStats,
+    /// Non-fatal notices collected while lexing and building.
+    warnings: Vec<Warning>,
+    /// Labels for top-level branches when tagging is enabled (empty = index only).
+    branch_labels: Option<Vec<String>>,
+    /// Top-level branch of the most recent token-generated output.
+    last_branch: Option<usize>,
+    /// Group captures recorded by the token engine for the last output.
+    last_captures: Option<Vec<Option<String>>>,
+    /// Set while `generate_explained` runs: use the token engine and trace it.
+    explain: bool,
+    last_explanation: Option<Explanation>,
+    /// False when outputs embed the match in filler (`anchored(false)`).
+    anchored: bool,
+    /// Unanchored form of the pattern, for checking filler around embedded matches; built on
+    /// first use.
+    search: Arc<OnceLock<Option<Regex>>>,
+    /// Substrings banned by `must_not_contain`.
+    forbidden: Vec<String>,
+    /// Weights for class and `.` picks, from `realistic` or `char_weights`.
+    char_weights: Option<Arc<CharWeights>>,
+    /// Prefer readable characters, from `humanize`.
+    humanize: bool,
+    /// Deny-list patterns from `except`.
+    except: Vec<Regex>,
+    /// Acceptance predicates from `filter`.
+    filters: Vec<Filter>,
+    /// Automaton for exact analysis and counting, built lazily.
+    dfa: Arc<OnceLock<Result<dfa::Dfa, GenError>>>,
+    /// NFA walker, when the pattern and options allow NFA generation.
+    walker: Option<Arc<nfa::Walker>>,
+    /// Unit of `min_len` and `max_len`, from `length_unit`.
+    length_unit: LengthUnit,
+    /// Bytes a candidate may reach before it is abandoned, from `max_output_bytes`.
+    max_output_bytes: usize,
+    /// Work units each generation call may spend, from `fuel`.
+    fuel: Option<u64>,
+    /// Set by another thread to stop generation, from `cancel_flag`.
+    cancel: Option<Arc<AtomicBool>>,
+    observer: Option<Arc<dyn Observer>>,
+    /// Extra repetitions for open-ended quantifiers in token generation, from `max_repeat`.
+    max_repeat: usize,
+    /// Retries on `NoMatch`, from `relax`.
+    relax: Option<RelaxPolicy>,
+    /// Relaxations in effect for the last output.
+    last_relaxations: Vec<Relaxation>,
+    /// Read with `Dialect::Bytes`: lengths count characters, one per byte.
+    bytes: bool,
+    /// The byte-level pattern, checked against the bytes of each output in byte mode.
+    bytes_re: Option<regex::bytes::Regex>,
+    /// Buffer reused by `generate_write`.
+    scratch: String,
 }
 
 /// Builder for RegexGenerator.
 pub struct RegexGeneratorBuilder {
     pattern: String,
-    config: GeneratorConfig,
-    rng: Option<Box<dyn RngCore + Send>>,
+    /// `None` until `config` is called; lengths are then fitted to the pattern.
+    config: Option<GeneratorConfig>,
+    rng: Option<Box<dyn CloneRng>>,
+    seed: Option<u64>,
     multiline: bool,
     /// When true, skip strict `regex::Regex` compilation errors (useful to allow backreferences);
     /// the generator will fall back to a permissive `.*` matcher and rely on token-generation instead.
     allow_backrefs: bool,
+    faults: Option<FaultConfig>,
+    /// Out-of-band group constraints keyed by group name.
+    group_constraints: Vec<(String, GroupConstraint)>,
+    parse_mode: ParseMode,
+    max_nesting_depth: usize,
+    dialect: Box<dyn PatternDialect>,
+    /// Set when `dialect` is `Dialect::Bytes`.
+    bytes: bool,
+    length_unit: LengthUnit,
+    max_output_bytes: Option<usize>,
+    fuel: Option<u64>,
+    cancel: Option<Arc<AtomicBool>>,
+    observer: Option<Arc<dyn Observer>>,
+    max_repeat: usize,
+    relax: Option<RelaxPolicy>,
+    /// Record the top-level alternation branch of each output, optionally with labels.
+    branch_tagging: bool,
+    branch_labels: Option<Vec<String>>,
+    forbidden: Vec<String>,
+    char_weights: Option<Arc<CharWeights>>,
+    humanize: bool,
+    except: Vec<String>,
+    filters: Vec<Filter>,
+    tokens: TokenRegistry,
+    use_nfa: bool,
+    #[cfg(feature = "hir")]
+    use_hir: bool,
+    anchored: bool,
+    /// Verify outputs against the whole pattern rather than a match anywhere in them.
+    full_match: bool,
 }
 
 impl RegexGeneratorBuilder {
@@ -314,20 +437,59 @@ impl RegexGeneratorBuilder {
     pub fn new(pattern: &str) -> Self {
         RegexGeneratorBuilder {
             pattern: pattern.to_string(),
-            config: GeneratorConfig::default(),
+            config: None,
             rng: None,
+            seed: None,
             multiline: false,
             allow_backrefs: false,
+            faults: None,
+            group_constraints: Vec::new(),
+            parse_mode: ParseMode::default(),
+            max_nesting_depth: lexer::MAX_NESTING_DEPTH,
+            dialect: Box::new(Dialect::default()),
+            bytes: false,
+            length_unit: LengthUnit::default(),
+            max_output_bytes: None,
+            fuel: None,
+            cancel: None,
+            observer: None,
+            max_repeat: lexer::MAX_REPEAT,
+            relax: None,
+            branch_tagging: false,
+            branch_labels: None,
+            forbidden: Vec::new(),
+            char_weights: None,
+            humanize: false,
+            except: Vec::new(),
+            filters: Vec::new(),
+            tokens: TokenRegistry::default(),
+            use_nfa: true,
+            #[cfg(feature = "hir")]
+            use_hir: false,
+            anchored: true,
+            full_match: false,
         }
     }
 
+    /// Set lengths, attempts and the timeout. Without a config, `min_len` is 0 and `max_len` is
+    /// the longest match of the pattern, or for unbounded patterns 64 more than the shortest.
     pub fn config(mut self, config: GeneratorConfig) -> Self {
-        self.config = config;
+        self.config = Some(config);
         self
     }
 
-    pub fn rng<R: RngCore + Send + 'static>(mut self, rng: R) -> Self {
+    /// Use a custom RNG. It must be `Clone` so the generator can be cloned.
+    pub fn rng<R: RngCore + Clone + Send + Sync + 'static>(mut self, rng: R) -> Self {
         self.rng = Some(Box::new(rng));
+        self.seed = None;
+        self
+    }
+
+    /// Use a `StdRng` seeded with `seed`. Unlike `rng(StdRng::seed_from_u64(seed))`, the seed is
+    /// remembered, so `RegexGenerator::fork` can derive sub-generators from it.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.rng = None;
         self
     }
 
@@ -343,47 +505,465 @@ impl RegexGeneratorBuilder {
         self
     }
 
-    pub fn build(self) -> Result<RegexGenerator, GenError> {
+    /// Deliberately emit malformed outputs from `generate_labeled` at the configured rate.
+    /// `build` fails with `InvalidConfig` for a rate outside `0.0..=1.0` (or NaN).
+    pub fn inject_faults(mut self, faults: FaultConfig) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Attach a constraint to the capturing group named `name`, without changing the pattern.
+    pub fn group_constraint(mut self, name: &str, constraint: GroupConstraint) -> Self {
+        self.group_constraints.push((name.to_string(), constraint));
+        self
+    }
+
+    /// Make the custom token `token` available as `{genrex:token=NAME}` / `GroupConstraint::Token`.
+    /// Replaces a built-in token of the same name.
+    pub fn register_token<T: CustomToken + 'static>(mut self, name: &str, token: T) -> Self {
+        self.tokens.register(name, token);
+        self
+    }
+
+    /// Generate by length-aware random walks over the pattern's NFA (the default) when the
+    /// pattern allows it. Patterns with backreferences, negated classes, group constraints or
+    /// branch tagging always use token-based generation.
+    pub fn nfa(mut self, enabled: bool) -> Self {
+        self.use_nfa = enabled;
+        self
+    }
+
+    /// Ban `substring` from every output. Generation steers character choices away from it and
+    /// verification rejects any candidate containing it, as if the pattern were prefixed with
+    /// the negative lookahead `(?!.*substring)`.
+    pub fn must_not_contain(mut self, substring: &str) -> Self {
+        self.forbidden.push(substring.to_string());
+        self
+    }
+
+    /// Pick characters from classes and `.` by English text frequencies (see
+    /// `CharWeights::english`) instead of uniformly, so outputs look like real text.
+    pub fn realistic(mut self, enabled: bool) -> Self {
+        self.char_weights = enabled.then(|| Arc::new(CharWeights::english()));
+        self
+    }
+
+    /// Pick characters from classes and `.` with probability proportional to `weights`.
+    /// Outputs still match the pattern; only how often each member of a class appears changes.
+    pub fn char_weights(mut self, weights: CharWeights) -> Self {
+        self.char_weights = Some(Arc::new(weights));
+        self
+    }
+
+    /// Pick readable characters from classes and `.` where the class permits: alternate
+    /// consonants and vowels, and avoid confusables (`0`/`O`/`o`, `1`/`l`/`I`). Suits usernames
+    /// and codes that people read or type; combines with `realistic`.
+    pub fn humanize(mut self, enabled: bool) -> Self {
+        self.humanize = enabled;
+        self
+    }
+
+    /// Reject every output that `deny` matches, so outputs match the primary pattern but not
+    /// this one (e.g. usernames that are not reserved words). May be called repeatedly.
+    pub fn except(mut self, deny: &str) -> Self {
+        self.except.push(deny.to_string());
+        self
+    }
+
+    /// Accept only outputs for which `predicate` returns true (e.g. Luhn-valid card numbers).
+    /// Rejected candidates count as attempts and are reported as `RejectReason::Filtered`, so
+    /// `max_attempts` and the timeout bound the search. May be called repeatedly.
+    pub fn filter<F: Fn(&str) -> bool + Send + Sync + 'static>(mut self, predicate: F) -> Self {
+        self.filters.push(Arc::new(predicate));
+        self
+    }
+
+    /// Parse the pattern with `regex-syntax` instead of the built-in lexer, for full coverage of
+    /// the `regex` crate's syntax. Patterns `regex-syntax` rejects (backreferences) still go
+    /// through the built-in lexer. Inline `{genrex:...}` annotations are ignored on this path;
+    /// use `group_constraint` instead.
+    #[cfg(feature = "hir")]
+    pub fn hir(mut self, enabled: bool) -> Self {
+        self.use_hir = enabled;
+        self
+    }
+
+    /// With `true` (the default) every output matches the pattern in full. With `false` each
+    /// output is a match embedded at a random position in filler text that contains no other
+    /// match (see `generate_embedded`); length bounds apply to the match, not the filler. The
+    /// pattern still finds the match where it was placed, so `^` and `$` keep filler off that
+    /// side and `\b` keeps word characters away from the edges.
+    pub fn anchored(mut self, anchored: bool) -> Self {
+        self.anchored = anchored;
+        self
+    }
+
+    /// With `true`, a value verifies only if the pattern matches all of it, as if wrapped in
+    /// `\A(?:...)\z`. By default an unanchored pattern such as `[0-9]{3}` is checked like
+    /// `Regex::is_match`, which also accepts `ab123cd`: a candidate from the rejection-sampling
+    /// fallback, or a value re-checked after encoding or failure injection, may then carry text
+    /// around the match.
+    pub fn full_match(mut self, full_match: bool) -> Self {
+        self.full_match = full_match;
+        self
+    }
+
+    /// Choose whether unsupported-but-valid syntax fails the build (`Strict`) or is degraded
+    /// and recorded (`Lenient`, the default).
+    pub fn parse_mode(mut self, mode: ParseMode) -> Self {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Let open-ended quantifiers (`*`, `+`, `{n,}`) repeat up to `n` times beyond their minimum
+    /// in token generation. Defaults to 32; NFA walks are bounded by the length limits instead.
+    pub fn max_repeat(mut self, n: usize) -> Self {
+        self.max_repeat = n;
+        self
+    }
+
+    /// Reject patterns whose groups and quantifiers nest more than `depth` levels deep with
+    /// `ParseErrorKind::NestingTooDeep`, rather than risk overflowing the stack while generating.
+    /// Defaults to 250, the `regex` crate's limit.
+    pub fn max_nesting_depth(mut self, depth: usize) -> Self {
+        self.max_nesting_depth = depth;
+        self
+    }
+
+    /// The language the pattern is written in: regex (the default), another `Dialect` such as
+    /// SQL `LIKE`, shell glob, POSIX or JavaScript, or a `PatternDialect` of your own.
+    ///
+    /// With `Dialect::Bytes`, use `RegexGenerator::generate_bytes` to get the generated bytes.
+    pub fn dialect<D: PatternDialect + 'static>(mut self, dialect: D) -> Self {
+        self.bytes = (&dialect as &dyn std::any::Any).downcast_ref::<Dialect>() == Some(&Dialect::Bytes);
+        self.dialect = Box::new(dialect);
+        self
+    }
+
+    /// Measure `min_len` and `max_len` in `unit`: UTF-8 bytes (the default), characters, or
+    /// graphemes, the visible length that form validation usually means. Ignored for
+    /// `Dialect::Bytes`, whose lengths count bytes.
+    pub fn length_unit(mut self, unit: LengthUnit) -> Self {
+        self.length_unit = unit;
+        self
+    }
+
+    /// Abandon a candidate as soon as it has produced more than `limit` bytes, instead of
+    /// generating it in full and rejecting it by length afterwards; no output is longer. The
+    /// default is the most bytes `max_len` allows in the length unit, or 1 MiB for graphemes.
+    pub fn max_output_bytes(mut self, limit: usize) -> Self {
+        self.max_output_bytes = Some(limit);
+        self
+    }
+
+    /// Give each generation call `units` of work to spend: one per candidate attempt and one
+    /// per token generated or NFA step taken. A call that runs out fails with
+    /// `GenError::BudgetExhausted`. Unlike `GeneratorConfig::timeout`, where the limit falls
+    /// depends only on the pattern, options and seed, never on the machine or a clock.
+    pub fn fuel(mut self, units: u64) -> Self {
+        self.fuel = Some(units);
+        self
+    }
+
+    /// Stop generating once `flag` is set, e.g. from another thread: the call in progress fails
+    /// with `GenError::Cancelled` between tokens, without waiting for a timeout, and so does
+    /// every later call until the flag is cleared. Forks share the flag.
+    pub fn cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(flag);
+        self
+    }
+
+    /// Report every attempt, rejection and accepted output to `observer`, e.g. to feed the
+    /// application's metrics. Replaces any observer set before.
+    pub fn observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Retry calls that fail with `GenError::NoMatch` under looser settings, as `policy`
+    /// describes, instead of failing at once; see `RegexGenerator::last_relaxations`. Not
+    /// applied by `generate_one_ref` or `generate_exact_len`.
+    pub fn relax(mut self, policy: RelaxPolicy) -> Self {
+        self.relax = Some(policy);
+        self
+    }
+
+    /// Record which top-level alternation branch produced each output (see `last_branch`).
+    pub fn tag_branches(mut self) -> Self {
+        self.branch_tagging = true;
+        self
+    }
+
+    /// Tag outputs with a label per top-level alternation branch, in source order.
+    /// The number of labels must equal the number of branches.
+    pub fn branch_labels(mut self, labels: &[&str]) -> Self {
+        self.branch_tagging = true;
+        self.branch_labels = Some(labels.iter().map(|l| l.to_string()).collect());
+        self
+    }
+
+    /// Tokens from the `regex-syntax` front-end, when enabled and it accepts the pattern.
+    #[cfg(feature = "hir")]
+    fn lex_hir(&self, state: &mut LexState) -> Option<Vec<Token>> {
+        if !self.use_hir {
+            return None;
+        }
+        let pattern = crate::constraints::strip_annotations(&self.pattern);
+        let mut hir_state = LexState::new(self.parse_mode);
+        let tokens = hir::lex(&pattern, &mut hir_state).ok()?;
+        if pattern != self.pattern {
+            hir_state.warnings.push(Warning::new(WarningKind::Degraded("inline annotation".to_string()), None));
+        }
+        *state = hir_state;
+        Some(tokens)
+    }
+
+    #[cfg(not(feature = "hir"))]
+    fn lex_hir(&self, _state: &mut LexState) -> Option<Vec<Token>> {
+        None
+    }
+
+    /// `pattern` as checked against outputs: escapes and repetitions normalized, and anchored
+    /// at both ends with `full_match`.
+    fn verified(&self, pattern: &str) -> String {
+        let pattern = lexer::normalize_repetitions(&lexer::normalize_escapes(pattern));
+        if self.full_match { format!(r"\A(?:{})\z", pattern) } else { pattern }
+    }
+
+    /// Lex the translated pattern and compile everything that depends on it alone; `source` is
+    /// the pattern as given. Cached by `build` when the pattern cache is on.
+    fn compile(&self, source: &str) -> Result<cache::Compiled, GenError> {
+        // Lex first so structural problems are reported with positions rather than as regex errors.
+        let mut state = LexState::new(self.parse_mode);
+        state.max_depth = self.max_nesting_depth;
+        let tokens = match self.lex_hir(&mut state) {
+            Some(tokens) => tokens,
+            None => lexer::lex(&self.pattern, &mut state)?,
+        };
+
         // Try to compile the regex; if allow_backrefs is enabled, fall back to a permissive matcher on error.
+        // Inline genrex annotations are not part of the regex language and are removed first.
+        let verify_pattern = self.verified(&crate::constraints::strip_annotations(&self.pattern));
+        let mut warnings = std::mem::take(&mut state.warnings);
         let re = if !self.allow_backrefs {
-            Regex::new(&self.pattern).map_err(|e| GenError::InvalidRegex(e.to_string()))?
+            verify_regex(&verify_pattern, self.max_nesting_depth).map_err(|e| GenError::InvalidRegex(e.to_string()))?
         } else {
-            match Regex::new(&self.pattern) {
+            match verify_regex(&verify_pattern, self.max_nesting_depth) {
                 Ok(r) => r,
-                Err(_) => {
-                    if VERBOSE.load(Ordering::Relaxed) {
-                        eprintln!("warning: pattern failed to compile with regex crate; proceeding with token-based generation (allow_backrefs enabled)");
+                Err(e) => match without_backrefs(&tokens).and_then(|t| Regex::new(&self.verified(&to_pattern(&t))).ok()) {
+                    Some(relaxed) => {
+                        warnings.push(Warning::new(WarningKind::VerificationRelaxed, None));
+                        relaxed
                     }
-                    Regex::new(".*").unwrap()
-                }
+                    None => {
+                        let msg = e.to_string().lines().last().unwrap_or_default().trim_start_matches("error: ").to_string();
+                        warnings.push(Warning::new(WarningKind::VerificationDisabled(msg), None));
+                        Regex::new(".*").unwrap()
+                    }
+                },
             }
         };
+        let bytes_re = if self.bytes {
+            let pattern = format!("(?-u){}", self.verified(&crate::constraints::strip_annotations(source)));
+            match regex::bytes::Regex::new(&pattern) {
+                Ok(re) => Some(re),
+                // Backreferences: the character-level check above is all there is.
+                Err(_) if self.allow_backrefs => None,
+                Err(e) => return Err(GenError::InvalidRegex(e.to_string())),
+            }
+        } else {
+            None
+        };
+        let program = (!tokens.is_empty()).then(|| Arc::new(program::Program::compile(&tokens)));
+        Ok(cache::Compiled {
+            tokens: Arc::from(tokens),
+            program,
+            re,
+            bytes_re,
+            warnings,
+            group_count: state.next_group.saturating_sub(1),
+            group_names: state.group_names,
+            annotations: state.annotations,
+        })
+    }
 
-        let rng: Box<dyn RngCore + Send> = self.rng.unwrap_or_else(|| Box::new(StdRng::from_entropy()));
+    pub fn build(mut self) -> Result<RegexGenerator, GenError> {
+        let translated = self.dialect.translate(&self.pattern)?;
+        let source = std::mem::replace(&mut self.pattern, translated);
+        let fit_lengths = self.config.is_none();
+        let mut cfg = self.config.take().unwrap_or_default();
+        if cfg.min_len > cfg.max_len {
+            return Err(GenError::InvalidConfig(format!("min_len {} exceeds max_len {}", cfg.min_len, cfg.max_len)));
+        }
+        if cfg.max_attempts == 0 {
+            return Err(GenError::InvalidConfig("max_attempts must be at least 1".to_string()));
+        }
+        if let Some(faults) = self.faults.as_ref().filter(|f| !(0.0..=1.0).contains(&f.rate)) {
+            return Err(GenError::InvalidConfig(format!("fault rate {} is not within 0.0..=1.0", faults.rate)));
+        }
 
-        // Use the minimal lexer to tokenize the pattern (assign group indices)
-        let mut next_group: usize = 1;
-        let tokens = lex_pattern(&self.pattern, &mut next_group);
-        let ast = if !tokens.is_empty() {
-            AstParser::new(&tokens).parse()
+        #[cfg(feature = "hir")]
+        let hir = self.use_hir;
+        #[cfg(not(feature = "hir"))]
+        let hir = false;
+        let key = cache::Key {
+            source: source.clone(),
+            pattern: self.pattern.clone(),
+            mode: self.parse_mode,
+            max_depth: self.max_nesting_depth,
+            hir,
+            allow_backrefs: self.allow_backrefs,
+            bytes: self.bytes,
+            full_match: self.full_match,
+        };
+        let compiled = cache::get_or_compile(key, || self.compile(&source))?;
+        let tokens = &compiled.tokens[..];
+        let multiline = self.multiline || satisfy::has_multiline_flag(&self.pattern);
+        satisfy::check(tokens, multiline).map_err(GenError::Unsatisfiable)?;
+        let unit = if self.bytes { LengthUnit::Chars } else { self.length_unit };
+        let shortest: usize = match unit {
+            LengthUnit::Bytes => tokens.iter().map(Token::min_len).sum(),
+            LengthUnit::Chars => tokens.iter().map(Token::min_chars).sum(),
+            // Characters may join into fewer graphemes; leave it to generation.
+            LengthUnit::Graphemes => 0,
+        };
+        if fit_lengths {
+            // Up to the longest match; past the shortest by the default allowance if unbounded.
+            let longest = tokens.iter().try_fold(0usize, |sum, t| {
+                sum.checked_add(if unit == LengthUnit::Bytes { t.max_len() } else { t.max_chars() }?)
+            });
+            cfg.max_len = longest.unwrap_or_else(|| shortest.saturating_add(GeneratorConfig::default().max_len));
+        }
+        if shortest > cfg.max_len {
+            return Err(GenError::Unsatisfiable(format!(
+                "pattern needs at least {} {} but max_len is {}",
+                // In byte mode each char stands for a byte.
+                shortest, if self.bytes { LengthUnit::Bytes } else { unit }, cfg.max_len
+            )));
+        }
+        let max_output_bytes = self.max_output_bytes.unwrap_or(match unit {
+            LengthUnit::Bytes => cfg.max_len,
+            LengthUnit::Chars => cfg.max_len.saturating_mul(4),
+            LengthUnit::Graphemes => DEFAULT_MAX_OUTPUT_BYTES,
+        });
+        let least_bytes: usize = tokens.iter().map(Token::min_len).sum();
+        if least_bytes > max_output_bytes {
+            return Err(GenError::InvalidConfig(format!(
+                "pattern needs at least {} bytes but max_output_bytes is {}",
+                least_bytes, max_output_bytes
+            )));
+        }
+
+        let mut warnings = compiled.warnings.clone();
+        for w in &mut warnings {
+            if let WarningKind::UnboundedRepeatCapped(n) = &mut w.kind {
+                *n = self.max_repeat;
+            }
+            diag::warning(w);
+        }
+
+        // Without a custom RNG, pick the seed here so the generator can be forked.
+        let (rng, seed): (Box<dyn CloneRng>, _) = match self.rng {
+            Some(rng) => (rng, None),
+            None => {
+                let seed = self.seed.unwrap_or_else(rand::random);
+                (Box::new(StdRng::seed_from_u64(seed)), Some(seed))
+            }
+        };
+
+        let mut constraints = ConstraintSet::default();
+        for (group, spec) in &compiled.annotations {
+            constraints.insert(*group, GroupConstraint::parse(spec)?);
+        }
+        for (name, constraint) in self.group_constraints {
+            let group = *compiled.group_names.get(&name)
+                .ok_or_else(|| GenError::InvalidRegex(format!("no capturing group named '{}'", name)))?;
+            constraints.insert(group, constraint);
+        }
+        constraints.resolve_tokens(&self.tokens)?;
+        if self.forbidden.iter().any(String::is_empty) {
+            return Err(GenError::InvalidConfig("must_not_contain requires a non-empty substring".to_string()));
+        }
+        let except = self
+            .except
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| GenError::InvalidRegex(format!("except pattern: {}", e))))
+            .collect::<Result<Vec<_>, _>>()?;
+        let branch_labels = if self.branch_tagging {
+            let count = top_level_branch_count(tokens)
+                .ok_or_else(|| GenError::InvalidConfig("branch tagging requires a top-level alternation".to_string()))?;
+            match self.branch_labels {
+                Some(labels) if labels.len() != count => {
+                    return Err(GenError::InvalidConfig(format!("{} branch labels given for {} branches", labels.len(), count)));
+                }
+                Some(labels) => Some(labels),
+                None => Some(Vec::new()),
+            }
+        } else {
+            None
+        };
+        let walker = if self.use_nfa && !tokens.is_empty() && constraints.is_empty() && branch_labels.is_none() {
+            // Walks count characters; let them run longer when characters may join into graphemes,
+            // and shorter when multi-byte characters fill a byte bound.
+            let joins = unit == LengthUnit::Graphemes && to_pattern(tokens).chars().any(length::may_join);
+            nfa::Nfa::compile(tokens).ok().map(|n| {
+                let (min_len, max_len) = unit.char_bounds(cfg.min_len, cfg.max_len, n.widest_char());
+                let max_len = if joins { max_len.saturating_mul(length::MAX_GRAPHEME_CHARS) } else { max_len };
+                Arc::new(nfa::Walker::new(n, min_len, max_len))
+            })
         } else {
             None
         };
-
-        let tokens_field = if tokens.is_empty() { None } else { Some(tokens) };
         Ok(RegexGenerator {
-            re,
-            config: self.config,
+            re: compiled.re.clone(),
+            config: cfg,
             rng,
+            seed,
             multiline: self.multiline,
-            ast,
-            tokens: tokens_field,
-            group_count: next_group.saturating_sub(1),
+            ast: Arc::default(),
+            tokens: (!tokens.is_empty()).then(|| Arc::clone(&compiled.tokens)),
+            program: compiled.program.clone(),
+            group_count: compiled.group_count,
+            faults: self.faults,
+            constraints,
+            stats: GenStats::default(),
+            warnings,
+            branch_labels,
+            last_branch: None,
+            last_captures: None,
+            explain: false,
+            last_explanation: None,
+            anchored: self.anchored,
+            search: Arc::default(),
+            forbidden: self.forbidden,
+            char_weights: self.char_weights,
+            humanize: self.humanize,
+            except,
+            filters: self.filters,
+            dfa: Arc::default(),
+            walker,
+            length_unit: self.length_unit,
+            max_output_bytes,
+            fuel: self.fuel,
+            cancel: self.cancel,
+            observer: self.observer,
+            max_repeat: self.max_repeat,
+            relax: self.relax,
+            last_relaxations: Vec::new(),
+            bytes: self.bytes,
+            bytes_re: compiled.bytes_re.clone(),
+            scratch: String::new(),
         })
     }
 }
 
 impl RegexGenerator {
+    /// A generator for `pattern` with default options and a random seed; use
+    /// `builder` to set lengths, a seed or anything else.
+    pub fn new(pattern: &str) -> Result<RegexGenerator, GenError> {
+        RegexGeneratorBuilder::new(pattern).build()
+    }
+
     /// Create a new builder for RegexGenerator.
     pub fn builder(pattern: &str) -> RegexGeneratorBuilder {
         RegexGeneratorBuilder::new(pattern)
@@ -397,44 +977,580 @@ impl RegexGenerator {
 
     /// Generate one matching string using lexer tokens if available, then AST, otherwise fallback to rejection sampling.
     pub fn generate_one(&mut self) -> Result<String, GenError> {
-        // 1) Token-based generation (preferred)
+        if !self.anchored {
+            return self.generate_embedded().map(|(text, _)| text);
+        }
+        self.generate_tracked().map(|(s, _)| s)
+    }
+
+    /// Append one value to `buf`, as `generate_one` would return it. Generating into a reused
+    /// buffer (cleared between calls) avoids allocating a `String` per output; for anchored
+    /// patterns no other allocation is made per character or token. On error `buf` is left as
+    /// it was.
+    pub fn generate_into(&mut self, buf: &mut String) -> Result<(), GenError> {
+        if !self.anchored {
+            buf.push_str(&self.generate_embedded()?.0);
+            return Ok(());
+        }
+        self.generate_tracked_into(buf).map(|_| ())
+    }
+
+    /// Write one value to `out`, as `generate_one` would return it, through a buffer the
+    /// generator keeps between calls.
+    pub fn generate_write<W: fmt::Write + ?Sized>(&mut self, out: &mut W) -> Result<(), GenError> {
+        let mut scratch = std::mem::take(&mut self.scratch);
+        scratch.clear();
+        let res = self.generate_into(&mut scratch).and_then(|()| out.write_str(&scratch).map_err(GenError::from));
+        self.scratch = scratch;
+        res
+    }
+
+    /// Generate one value from `&self`, drawing randomness from `rng` instead of the generator's
+    /// own RNG, so one generator can serve many threads (e.g. behind an `Arc`) without a
+    /// `Mutex`. Statistics, `last_branch` and captures are not recorded. Fails with
+    /// `Unsupported` for group constraints, whose uniqueness and sequences need state between
+    /// outputs.
+    pub fn generate_one_ref<R: Rng + ?Sized>(&self, mut rng: &mut R) -> Result<String, GenError> {
+        if !self.constraints.is_empty() {
+            return Err(GenError::Unsupported("group constraints keep state between outputs; use generate_one".to_string()));
+        }
+        // `&mut R` is a sized `RngCore` even when `R` is not.
+        let rng = &mut rng;
+        let mut value = String::new();
+        self.candidate(rng, &mut Run::new(&self.observer), &mut value)?;
+        if self.anchored {
+            return Ok(value);
+        }
+        let search = self.search_regex();
+        embed::embed(rng, &value, search.as_ref(), self.config.max_attempts).map(|(text, _)| text).ok_or(GenError::NoMatch)
+    }
+
+    /// Generate one value from a one-off RNG seeded with `seed`, leaving the generator's own RNG
+    /// where it was. The value is the one `generate_one` would return after `reseed(seed)`, so
+    /// stable ids such as row numbers map to the same values on every run, in any order.
+    pub fn generate_with_seed(&mut self, seed: u64) -> Result<String, GenError> {
+        let own = std::mem::replace(&mut self.rng, Box::new(StdRng::seed_from_u64(seed)));
+        let res = self.generate_one();
+        self.rng = own;
+        res
+    }
+
+    /// Generate one value of exactly `len` characters, whatever `min_len`, `max_len` and the
+    /// length unit say. Regular patterns are generated by NFA walks steered to that length, so
+    /// repetition counts and branches are chosen to fit rather than rejected afterwards; other
+    /// patterns try candidates until one has the length or `max_attempts` run out. The output
+    /// size cap is raised to fit `len` characters when needed. Generators built with
+    /// `anchored(false)` return the match alone, without filler around it.
+    pub fn generate_exact_len(&mut self, len: usize) -> Result<String, GenError> {
+        let walker = self.walker.as_ref().map(|w| Arc::new(nfa::Walker::new(w.nfa().clone(), len, len)));
+        let config = GeneratorConfig { min_len: len, max_len: len, ..self.config.clone() };
+        let own_config = std::mem::replace(&mut self.config, config);
+        let own_walker = std::mem::replace(&mut self.walker, walker);
+        let own_unit = std::mem::replace(&mut self.length_unit, LengthUnit::Chars);
+        let own_cap = self.max_output_bytes;
+        self.max_output_bytes = own_cap.max(len.saturating_mul(4));
+        // Relaxing would give up the length.
+        let own_relax = self.relax.take();
+        // The bare match: embedding it in filler would give up the length too.
+        let res = self.generate_tracked().map(|(s, _)| s);
+        self.relax = own_relax;
+        self.config = own_config;
+        self.walker = own_walker;
+        self.length_unit = own_unit;
+        self.max_output_bytes = own_cap;
+        res
+    }
+
+    /// Generate one match and embed it at a random position in filler text, returning the text
+    /// and the byte span of the match within it. The filler contains no match of the pattern,
+    /// which finds the embedded value at exactly that span. `generate_one` returns the text of
+    /// this for generators built with `anchored(false)`.
+    pub fn generate_embedded(&mut self) -> Result<(String, Range<usize>), GenError> {
+        let (value, _) = self.generate_tracked()?;
+        self.embed(&value)
+    }
+
+    /// Generate a multi-line document of filler with `config.matches` matches scattered over its
+    /// lines, and a manifest of their byte offsets. Filler lines contain no match of the pattern.
+    pub fn generate_document(&mut self, config: &DocumentConfig) -> Result<Document, GenError> {
+        if config.lines == 0 && config.matches > 0 {
+            return Err(GenError::InvalidConfig(format!("cannot place {} matches in a document with no lines", config.matches)));
+        }
+        let values = (0..config.matches).map(|_| self.generate_tracked().map(|(s, _)| s)).collect::<Result<Vec<_>, _>>()?;
+        let search = self.search_regex();
+        embed::document(&mut *self.rng, &values, search.as_ref(), config, self.config.max_attempts).ok_or(GenError::NoMatch)
+    }
+
+    /// The regex that finds embedded matches, built on first use.
+    fn search_regex(&self) -> Option<Regex> {
+        let tokens = &self.tokens;
+        self.search
+            .get_or_init(|| {
+                let tokens = tokens.as_deref()?;
+                embed::search_regex(&without_backrefs(tokens).unwrap_or_else(|| tokens.to_vec()))
+            })
+            .clone()
+    }
+
+    fn embed(&mut self, value: &str) -> Result<(String, Range<usize>), GenError> {
+        let search = self.search_regex();
+        embed::embed(&mut *self.rng, value, search.as_ref(), self.config.max_attempts).ok_or(GenError::NoMatch)
+    }
+
+    /// Generate one value together with a report of the work it took: attempts, rejections by
+    /// reason, elapsed time and the engine that produced it.
+    pub fn generate_one_with_report(&mut self) -> Result<(String, GenReport), GenError> {
+        let before = self.stats.clone();
+        let start = Instant::now();
+        let (mut s, engine) = self.generate_tracked()?;
+        if !self.anchored {
+            s = self.embed(&s)?.0;
+        }
+        let report = GenReport::new(engine, start.elapsed(), self.stats.since(&before));
+        Ok((s, report))
+    }
+
+    /// Generate one value together with the text of each capture group.
+    ///
+    /// Groups come from the token engine's own bookkeeping when it produced the value; for the
+    /// other engines the value is matched against the pattern to recover them.
+    pub fn generate_with_captures(&mut self) -> Result<(String, Captures), GenError> {
+        let (value, _) = self.generate_tracked()?;
+        let names: Vec<Option<String>> = self.re.capture_names().map(|n| n.map(str::to_string)).collect();
+        let values = match self.last_captures.take() {
+            Some(groups) => {
+                let mut values = Vec::with_capacity(names.len());
+                values.push(Some(value.clone()));
+                values.extend(groups);
+                values.resize(names.len(), None);
+                values
+            }
+            None => {
+                let caps = self.re.captures(&value).ok_or(GenError::NoMatch)?;
+                caps.iter().map(|m| m.map(|m| m.as_str().to_string())).collect()
+            }
+        };
+        Ok((value, Captures::new(values, names)))
+    }
+
+    /// Generate one value with a trace of how the token engine produced it: the span of the
+    /// output each token generated, the alternation branches taken and the repetition counts.
+    ///
+    /// Always uses the token engine, even when the NFA engine is enabled.
+    pub fn generate_explained(&mut self) -> Result<Explanation, GenError> {
+        if self.tokens.is_none() {
+            return Err(GenError::Unsupported("explanations need a lexed pattern".to_string()));
+        }
+        self.explain = true;
+        self.last_explanation = None;
+        let res = self.generate_tracked();
+        self.explain = false;
+        res?;
+        self.last_explanation.take().ok_or(GenError::NoMatch)
+    }
+
+    fn generate_tracked(&mut self) -> Result<(String, Engine), GenError> {
+        let mut value = String::new();
+        let engine = self.generate_tracked_into(&mut value)?;
+        Ok((value, engine))
+    }
+
+    fn generate_tracked_into(&mut self, buf: &mut String) -> Result<Engine, GenError> {
+        self.last_relaxations.clear();
+        let mut res = self.generate_candidate(buf);
+        if let (Err(GenError::NoMatch), Some(policy)) = (&res, self.relax.clone()) {
+            res = self.generate_relaxed(&policy, buf);
+        }
+        match res {
+            Ok(_) => self.stats.accepted += 1,
+            Err(_) => self.stats.failures += 1,
+        }
+        res
+    }
+
+    /// Retry a call that failed with `NoMatch`, each round of `policy` looser than the last,
+    /// then restore the configured settings.
+    fn generate_relaxed(&mut self, policy: &RelaxPolicy, buf: &mut String) -> Result<Engine, GenError> {
+        let own_config = self.config.clone();
+        let own_walker = self.walker.clone();
+        let own_cap = self.max_output_bytes;
+        let own_repeat = self.max_repeat;
+        let unit_bytes = if self.length_unit == LengthUnit::Bytes { 1 } else { 4 };
+        let mut res = Err(GenError::NoMatch);
+        for round in 1..=policy.rounds {
+            let (config, mut applied) = policy.relax(round, &own_config);
+            self.walker = match &own_walker {
+                Some(_) if policy.switch_engine => {
+                    applied.push(Relaxation::Engine(Engine::Tokens));
+                    None
+                }
+                Some(walker) if policy.widen_percent.is_some() => {
+                    let (min_len, max_len) = walker.bounds();
+                    let (min_len, max_len) = policy.widen(round, min_len, max_len);
+                    Some(Arc::new(nfa::Walker::new(walker.nfa().clone(), min_len, max_len)))
+                }
+                walker => walker.clone(),
+            };
+            if let Some(max_repeat) = policy.repeat(round, own_repeat) {
+                self.max_repeat = max_repeat;
+                applied.push(Relaxation::MaxRepeat(max_repeat));
+            }
+            let grown = config.max_len.saturating_sub(own_config.max_len);
+            self.max_output_bytes = own_cap.saturating_add(grown.saturating_mul(unit_bytes));
+            self.config = config;
+            self.last_relaxations = applied;
+            res = self.generate_candidate(buf);
+            if !matches!(res, Err(GenError::NoMatch)) {
+                break;
+            }
+        }
+        self.config = own_config;
+        self.walker = own_walker;
+        self.max_output_bytes = own_cap;
+        self.max_repeat = own_repeat;
+        res
+    }
+
+    /// The lexed token tree of the pattern, if it lexed.
+    pub fn tokens(&self) -> Option<&[Token]> {
+        self.tokens.as_deref()
+    }
+
+    /// The token tree as JSON, for tools outside Rust; see `genrex::to_json` for the layout.
+    pub fn to_json(&self) -> Option<String> {
+        self.tokens().map(to_json)
+    }
+
+    /// The token tree as a Graphviz DOT graph; see `genrex::to_dot`, and `Nfa::to_dot` for the
+    /// automaton.
+    pub fn to_dot(&self) -> Option<String> {
+        self.tokens().map(to_dot)
+    }
+
+    /// Analyze the language of the pattern: length bounds, finiteness and exact cardinality.
+    pub fn analyze(&self) -> Result<Analysis, GenError> {
+        Ok(analysis::analyze_dfa(self.dfa()?, self.config.max_len))
+    }
+
+    /// Entropy of the outputs whose length (in characters) is in `lengths` and within the
+    /// configured bounds: of a uniform choice among them, and of this generator's own sampling
+    /// when it generates by NFA walks with unweighted picks (no `realistic`, `char_weights` or
+    /// `humanize`). Filters and deny-lists are not taken into account.
+    ///
+    /// For auditing random tokens: `entropy(..)?.sampled_bits` is what an attacker guessing
+    /// outputs of this generator faces, which can be far below the uniform figure when short
+    /// alternatives are as likely as long ones.
+    pub fn entropy(&self, lengths: impl RangeBounds<usize>) -> Result<Entropy, GenError> {
+        let (lo, hi) = analysis::bounds(&lengths);
+        let (lo, hi) = (lo.max(self.config.min_len), hi.min(self.config.max_len));
+        let total = self.dfa()?.count_lengths(hi).into_iter().skip(lo).sum::<BigUint>();
+        let uniform_bits = analysis::log2(&total);
+        let sampled_bits = self
+            .walker
+            .as_ref()
+            .filter(|_| self.char_weights.is_none() && !self.humanize)
+            .and_then(|w| nfa::Walker::new(w.nfa().clone(), lo, hi).entropy_bits())
+            .map(|bits| bits.min(uniform_bits));
+        Ok(Entropy { uniform_bits, sampled_bits })
+    }
+
+    /// Exact number of distinct strings of `len` characters the pattern generates.
+    pub fn count_matches(&self, len: usize) -> Result<BigUint, GenError> {
+        Ok(self.dfa()?.count_len(len))
+    }
+
+    /// The `k`-th (0-based) string the pattern generates among those of at most `max_len`
+    /// characters, in lexicographic order by code point. `None` when `k` is out of range.
+    ///
+    /// Deterministic and seedless: distinct `k` give distinct strings, which makes this a
+    /// collision-free source of test identifiers.
+    pub fn nth_match(&self, k: &BigUint, max_len: usize) -> Result<Option<String>, GenError> {
+        Ok(self.dfa()?.nth(k, max_len))
+    }
+
+    /// Inverse of `nth_match`: the 0-based lexicographic index of `s` among the strings of at most
+    /// `max_len` characters, or `None` if the pattern does not generate `s` within that bound.
+    pub fn rank(&self, s: &str, max_len: usize) -> Result<Option<BigUint>, GenError> {
+        Ok(self.dfa()?.rank(s, max_len))
+    }
+
+    /// Strings smaller than `s` that this generator could also produce, for minimizing a failing
+    /// property-test input: `s` with spans dropped or replaced by shorter text (fewer repetitions,
+    /// shorter alternation branches) and chars replaced by smaller ones, most aggressive first.
+    ///
+    /// Every candidate matches the pattern in full, fits the length bounds, passes the deny-lists
+    /// and filters, and comes strictly before `s` in shortlex order (fewer chars, or as many and
+    /// earlier by code point), so repeatedly moving to the first candidate that still fails
+    /// terminates at a minimal input. Patterns whose outputs cannot be verified exactly
+    /// (backreferences under `allow_backrefs`) yield no candidates.
+    pub fn shrink<'a>(&'a self, s: &str) -> impl Iterator<Item = String> + 'a {
+        let exact = !self.warnings.iter().any(|w| matches!(w.kind, WarningKind::VerificationRelaxed | WarningKind::VerificationDisabled(_)));
+        let full = Regex::new(&format!(r"\A(?:{})\z", self.re.as_str())).ok().filter(|_| exact);
+        let original = (s.chars().count(), s.to_string());
+        let mut seen = HashSet::new();
+        shrink::candidates(s, self.dfa().ok(), self.config.min_len, self.config.max_len)
+            .filter(move |c| (c.chars().count(), c.as_str()) < (original.0, original.1.as_str()))
+            .filter(move |c| seen.insert(c.clone()))
+            .filter(move |c| {
+                (self.config.min_len..=self.config.max_len).contains(&self.measure(c))
+                    && self.denied(c).is_none()
+                    && full.as_ref().is_some_and(|re| re.is_match(c))
+            })
+    }
+
+    fn generate_extreme(&mut self, longest: bool) -> Result<String, GenError> {
+        let dfa = self.dfa()?;
+        let widest = dfa.widest_char();
+        let (min_len, max_len) = self.length_unit.char_bounds(self.config.min_len, self.config.max_len, widest);
+        let fits = |s: &String| (self.config.min_len..=self.config.max_len).contains(&self.measure(s));
+        let s = if widest > 1 && self.length_unit == LengthUnit::Bytes {
+            // Character counts map to byte lengths unevenly; take the first count that fits.
+            let mut counts = min_len..=max_len;
+            let mut fit = |len| dfa.extreme(len, len, longest).filter(fits);
+            if longest { counts.rev().find_map(&mut fit) } else { counts.find_map(&mut fit) }
+        } else {
+            dfa.extreme(min_len, max_len, longest).filter(fits)
+        };
+        let s = s.ok_or(GenError::NoMatch)?;
+        if self.denied(&s).is_some() || !self.re.is_match(&s) {
+            return Err(GenError::NoMatch);
+        }
+        Ok(s)
+    }
+
+    /// The pattern's DFA, built on first use.
+    fn dfa(&self) -> Result<&dfa::Dfa, GenError> {
+        self.dfa
+            .get_or_init(|| analysis::build_dfa(self.tokens.as_deref().unwrap_or_default()))
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// The top-level alternation branch that produced the most recent output, when branch
+    /// tagging is enabled on the builder.
+    pub fn last_branch(&self) -> Option<BranchTag> {
+        let labels = self.branch_labels.as_ref()?;
+        let index = self.last_branch?;
+        Some(BranchTag { index, label: labels.get(index).cloned() })
+    }
+
+    /// What a `RelaxPolicy` loosened to produce the last output, or to fail on the last round;
+    /// empty when the configured settings sufficed or no policy is set.
+    pub fn last_relaxations(&self) -> &[Relaxation] {
+        &self.last_relaxations
+    }
+
+    /// Non-fatal notices about constructs that were ignored or approximated while building.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Cumulative attempt/rejection counters since construction (or the last `reset_stats`).
+    pub fn stats(&self) -> &GenStats {
+        &self.stats
+    }
+
+    /// Reset the cumulative counters returned by `stats`.
+    pub fn reset_stats(&mut self) {
+        self.stats = GenStats::default();
+    }
+
+    /// Replace the RNG with a `StdRng` seeded from `seed`. The next outputs are the same as those
+    /// of a fresh generator built with `.rng(StdRng::seed_from_u64(seed))`.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = Box::new(StdRng::seed_from_u64(seed));
+        self.seed = Some(seed);
+    }
+
+    /// The seed of this generator's RNG, unless it was given a custom one with `rng`.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// An independent copy of this generator for sub-stream `stream`, seeded with
+    /// `batch::derive_seed(seed, stream)`. The same seed and stream id always give the same
+    /// outputs, so N workers can each fork their own stream from one base seed without
+    /// coordinating. The fork starts with fresh statistics and a copy of the current group
+    /// constraint state. Fails for generators given a custom RNG with `rng`, whose seed is
+    /// unknown.
+    pub fn fork(&self, stream: u64) -> Result<RegexGenerator, GenError> {
+        let seed = self
+            .seed
+            .map(|seed| batch::derive_seed(seed, stream))
+            .ok_or_else(|| GenError::InvalidConfig("cannot fork a generator with a custom RNG; build it with seed() instead".to_string()))?;
+        let mut fork = self.clone();
+        fork.reseed(seed);
+        fork.stats = GenStats::default();
+        fork.last_branch = None;
+        fork.last_captures = None;
+        fork.last_explanation = None;
+        fork.last_relaxations.clear();
+        Ok(fork)
+    }
+
+    fn generate_candidate(&mut self, buf: &mut String) -> Result<Engine, GenError> {
+        // Lend the RNG out so the engines can borrow the rest of the generator.
+        let mut rng = std::mem::replace(&mut self.rng, Box::new(StepRng::new(0, 0)));
+        let mut run = Run::new(&self.observer);
+        let res = self.candidate(&mut *rng, &mut run, buf);
+        self.rng = rng;
+        self.stats.merge(&run.stats);
+        if let (Ok(_), Some(captures)) = (&res, &run.captures) {
+            self.constraints.commit(captures);
+        }
+        self.last_branch = run.branch;
+        self.last_captures = run.captures;
+        if run.explanation.is_some() {
+            self.last_explanation = run.explanation;
+        }
+        res
+    }
+
+    /// One generation call: try the engines in turn, recording their work in `run`.
+    fn candidate(&self, rng: &mut dyn RngCore, run: &mut Run, buf: &mut String) -> Result<Engine, GenError> {
+        let base = buf.len();
+        #[cfg(feature = "metrics")]
+        let start = Instant::now();
+        let res = self.run_engines(rng, run, buf, base);
+        #[cfg(feature = "metrics")]
+        diag::generated(res.as_ref().ok().copied(), start.elapsed());
+        match (&res, &self.observer) {
+            (Err(_), _) => buf.truncate(base),
+            (Ok(_), Some(observer)) => observer.on_accept(self.measure(&buf[base..]), run.stats.attempts),
+            (Ok(_), None) => {}
+        }
+        res
+    }
+
+    /// The engines behind `candidate`, each attempt writing over `buf` from `base`.
+    fn run_engines(&self, rng: &mut dyn RngCore, run: &mut Run, buf: &mut String, base: usize) -> Result<Engine, GenError> {
+        // The timeout covers the whole call, across engines.
+        let deadline = self.config.timeout.map(|t| Instant::now() + t);
+        let timed_out = || deadline.is_some_and(|d| Instant::now() >= d);
+        // So does the fuel budget; each attempt's context draws on it and hands back the rest.
+        let mut fuel = self.fuel;
+        // 0) NFA walks: in bounds by construction, verified as a safety net.
+        if let Some(walker) = self.walker.as_ref().filter(|_| !self.explain) {
+            let mut length_only = true;
+            for attempt in 1..=self.config.max_attempts {
+                if timed_out() {
+                    return Err(GenError::Timeout);
+                }
+                if self.cancelled() {
+                    return Err(GenError::Cancelled);
+                }
+                if !burn(&mut fuel) {
+                    return Err(GenError::BudgetExhausted);
+                }
+                run.attempt();
+                let mut ctx = crate::traits::TokenContext::new_with_max_repeat(self.max_repeat);
+                ctx.forbidden.clone_from(&self.forbidden);
+                ctx.char_weights.clone_from(&self.char_weights);
+                ctx.humanize = self.humanize;
+                ctx.fuel = fuel;
+                ctx.cancel.clone_from(&self.cancel);
+                buf.truncate(base);
+                let walked = walker.walk_into(rng, &mut ctx, buf);
+                fuel = ctx.fuel;
+                if !walked {
+                    if ctx.cancelled() {
+                        return Err(GenError::Cancelled);
+                    }
+                    if ctx.out_of_fuel() {
+                        return Err(GenError::BudgetExhausted);
+                    }
+                    diag::rejected("nfa", attempt, RejectReason::Length, "");
+                    run.reject(RejectReason::Length);
+                    continue;
+                }
+                let out = &buf[base..];
+                let Some(reason) = self.rejection(out) else {
+                    return Ok(Engine::Nfa);
+                };
+                diag::rejected("nfa", attempt, reason, out);
+                run.reject(reason);
+                length_only &= reason == RejectReason::Length;
+            }
+            // Walks that only missed the length (byte bounds that only some characters fit)
+            // leave the tokens to try; other rejections would repeat there.
+            if !length_only {
+                return Err(GenError::NoMatch);
+            }
+        }
+        // 1) Token-based generation
         if let Some(tokens) = &self.tokens {
-            let start = Instant::now();
             let mut attempts = 0usize;
             while attempts < self.config.max_attempts {
-                if let Some(timeout) = self.config.timeout {
-                    if start.elapsed() >= timeout { break; }
+                if timed_out() {
+                    return Err(GenError::Timeout);
+                }
+                if self.cancelled() {
+                    return Err(GenError::Cancelled);
+                }
+                if !burn(&mut fuel) {
+                    return Err(GenError::BudgetExhausted);
                 }
                 attempts += 1;
-                let mut ctx = crate::traits::TokenContext::new();
+                run.attempt();
+                let mut ctx = crate::traits::TokenContext::new_with_max_repeat(self.max_repeat);
                 // Pre-size captures so backreferences referring to future groups are recorded
                 // as unresolved placeholders instead of causing immediate errors.
                 ctx.captures.resize(self.group_count, None);
-                let rng = &mut self.rng;
-                let mut out = String::new();
+                if !self.constraints.is_empty() {
+                    ctx.group_overrides = self.constraints.overrides(rng);
+                }
+                ctx.forbidden.clone_from(&self.forbidden);
+                ctx.char_weights.clone_from(&self.char_weights);
+                ctx.humanize = self.humanize;
+                ctx.output_limit = self.max_output_bytes;
+                ctx.fuel = fuel;
+                ctx.cancel.clone_from(&self.cancel);
+                if self.explain {
+                    ctx.start_trace();
+                }
+                buf.truncate(base);
                 let mut ok = true;
-                for t in tokens {
+                for (i, t) in tokens.iter().enumerate() {
                     // inform context of current output length so tokens (especially Backreference)
                     // can record unresolved placeholders relative to the current byte position.
-                    ctx.set_output_len(out.len());
-                    match t.generate(&mut *rng, &mut ctx) {
-                        Ok(s) => out.push_str(&s),
+                    ctx.set_output_len(buf.len() - base);
+                    let res = match self.program.as_ref().filter(|_| !self.explain) {
+                        Some(program) => program.run(i, &mut *rng, &mut ctx, buf),
+                        None => t.generate_into(&mut *rng, &mut ctx, buf),
+                    };
+                    match res {
+                        Ok(()) => {}
+                        Err(GenrexError::BudgetExhausted) => return Err(GenError::BudgetExhausted),
+                        Err(GenrexError::Cancelled) => return Err(GenError::Cancelled),
                         Err(_) => { ok = false; break; }
                     }
+                    if ctx.over_limit() {
+                        ok = false;
+                        break;
+                    }
+                }
+                fuel = ctx.fuel;
+                if ctx.over_limit() {
+                    diag::rejected("token", attempts, RejectReason::Length, "");
+                    run.reject(RejectReason::Length);
+                    continue;
+                }
+                if !ok {
+                    diag::rejected("token", attempts, RejectReason::TokenError, &buf[base..]);
+                    run.reject(RejectReason::TokenError);
+                    continue;
                 }
-                if !ok { continue; }
                 // If any unresolved backreferences were recorded, attempt to resolve them now.
                 if !ctx.unresolved_refs.is_empty() {
                     let mut unresolved_missing = false;
                     // Sort by position to insert in-order (they should already be in order but ensure correctness).
                     ctx.unresolved_refs.sort_by_key(|(pos, _)| *pos);
-                    let mut final_out = out.clone();
                     let mut offset = 0usize;
                     for (pos, gid) in &ctx.unresolved_refs {
                         if let Some(cap) = ctx.get_capture(*gid) {
-                            let insert_pos = (*pos).saturating_add(offset);
-                            if insert_pos <= final_out.len() {
-                                final_out.insert_str(insert_pos, &cap);
+                            let insert_pos = base + (*pos).saturating_add(offset);
+                            if insert_pos <= buf.len() {
+                                buf.insert_str(insert_pos, &cap);
                                 offset += cap.len();
                             } else {
                                 // Unexpected: recorded position out of bounds -> treat as unresolved.
@@ -448,86 +1564,184 @@ impl RegexGenerator {
                     }
                     if unresolved_missing {
                         // Unable to resolve forward refs for this candidate; try again.
-                        if VERBOSE.load(Ordering::Relaxed) {
-                            eprintln!("candidate rejected (unresolved backreference) during resolution: {}", out);
-                        }
+                        diag::rejected("token", attempts, RejectReason::UnresolvedBackref, &buf[base..]);
+                        run.reject(RejectReason::UnresolvedBackref);
                         continue;
-                    } else {
-                        out = final_out;
                     }
                 }
-                let len = out.len();
-                if len < self.config.min_len || len > self.config.max_len {
-                    if VERBOSE.load(Ordering::Relaxed) {
-                        eprintln!("candidate rejected (len {} not in {}..={}): {}", len, self.config.min_len, self.config.max_len, out);
-                    }
+                let out = &buf[base..];
+                if !self.in_bounds(out) {
+                    diag::rejected("token", attempts, RejectReason::Length, out);
+                    run.reject(RejectReason::Length);
                     continue;
                 }
-                if self.re.is_match(&out) {
-                    return Ok(out);
-                } else {
-                    if VERBOSE.load(Ordering::Relaxed) {
-                        eprintln!("candidate rejected (regex mismatch): {}", out);
+                if let Some(reason) = self.denied(out) {
+                    diag::rejected("token", attempts, reason, out);
+                    run.reject(reason);
+                    continue;
+                }
+                if !self.constraints.admits(&ctx.captures) {
+                    diag::rejected("token", attempts, RejectReason::Constraint, out);
+                    run.reject(RejectReason::Constraint);
+                    continue;
+                }
+                if self.verifies(out) {
+                    run.branch = ctx.first_branch;
+                    if let Some(trace) = ctx.take_trace() {
+                        run.explanation = Some(trace.finish(out.to_string(), |group| ctx.get_capture(group)));
                     }
+                    run.captures = Some(ctx.captures);
+                    return Ok(Engine::Tokens);
+                } else {
+                    diag::rejected("token", attempts, RejectReason::RegexMismatch, out);
+                    run.reject(RejectReason::RegexMismatch);
                     continue;
                 }
             }
             // If token-based attempts failed, fall through to AST or rejection sampling.
+            // Those engines cannot honor group constraints or be traced, so give up instead.
+            if !self.constraints.is_empty() || self.explain {
+                return Err(GenError::NoMatch);
+            }
         }
 
         // 2) AST-based single-generation (legacy behavior)
-        if let Some(ast) = &self.ast {
-            let rng = &mut self.rng;
-            let mut ctx = crate::traits::TokenContext::new();
-            let s = Self::generate_from_ast(ast, &mut *rng, &mut ctx)?;
-            let len = s.len();
-            if len < self.config.min_len || len > self.config.max_len {
-                if VERBOSE.load(Ordering::Relaxed) {
-                    eprintln!("AST candidate rejected (len {} not in {}..={}): {}", len, self.config.min_len, self.config.max_len, s);
+        let ast = self.ast.get_or_init(|| self.tokens.as_deref().and_then(|tokens| AstParser::new(tokens).parse()));
+        if let Some(ast) = ast {
+            let mut ctx = crate::traits::TokenContext::new_with_max_repeat(self.max_repeat);
+            ctx.output_limit = self.max_output_bytes;
+            if !burn(&mut fuel) {
+                return Err(GenError::BudgetExhausted);
+            }
+            ctx.fuel = fuel;
+            ctx.cancel.clone_from(&self.cancel);
+            run.attempt();
+            let s = Self::generate_from_ast(ast, &mut *rng, &mut ctx).inspect_err(|e| {
+                if !matches!(e, GenError::BudgetExhausted | GenError::Cancelled) {
+                    run.reject(RejectReason::TokenError);
                 }
+            })?;
+            if !self.in_bounds(&s) {
+                diag::rejected("ast", 1, RejectReason::Length, &s);
+                run.reject(RejectReason::Length);
+                return Err(GenError::NoMatch);
+            }
+            if let Some(reason) = self.denied(&s) {
+                diag::rejected("ast", 1, reason, &s);
+                run.reject(reason);
                 return Err(GenError::NoMatch);
             }
-            if self.re.is_match(&s) {
-                return Ok(s);
+            if self.verifies(&s) {
+                buf.truncate(base);
+                buf.push_str(&s);
+                return Ok(Engine::Ast);
             } else {
-                if VERBOSE.load(Ordering::Relaxed) {
-                    eprintln!("AST candidate rejected (regex mismatch): {}", s);
-                }
+                diag::rejected("ast", 1, RejectReason::RegexMismatch, &s);
+                run.reject(RejectReason::RegexMismatch);
                 return Err(GenError::NoMatch);
             }
         }
 
         // 3) Fallback: rejection sampling
-        let start = Instant::now();
         let mut attempts = 0;
         while attempts < self.config.max_attempts {
-            if let Some(timeout) = self.config.timeout {
-                if start.elapsed() >= timeout {
-                    break;
-                }
+            if timed_out() {
+                return Err(GenError::Timeout);
+            }
+            if self.cancelled() {
+                return Err(GenError::Cancelled);
+            }
+            if !burn(&mut fuel) {
+                return Err(GenError::BudgetExhausted);
             }
             attempts += 1;
+            run.attempt();
             let len = if self.config.max_len == self.config.min_len {
                 self.config.min_len
             } else {
-                self.rng.gen_range(self.config.min_len..=self.config.max_len)
+                rng.gen_range(self.config.min_len..=self.config.max_len)
             };
-            let s: String = (0..len).map(|_| self.rng.sample(Alphanumeric) as char).collect();
-            if self.re.is_match(&s) {
-                return Ok(s);
+            buf.truncate(base);
+            buf.extend((0..len).map(|_| rng.sample(Alphanumeric) as char));
+            let s = &buf[base..];
+            if let Some(reason) = self.denied(s) {
+                run.reject(reason);
+                continue;
             }
+            if self.verifies(s) {
+                return Ok(Engine::Rejection);
+            }
+            run.reject(RejectReason::RegexMismatch);
         }
         Err(GenError::NoMatch)
     }
 
+    /// Length of an output as bounded by `min_len` and `max_len`, in the configured unit, or in
+    /// byte mode the characters that stand for bytes.
+    fn measure(&self, s: &str) -> usize {
+        if self.bytes { s.chars().count() } else { self.length_unit.measure(s) }
+    }
+
+    /// Whether the cancel flag is set.
+    fn cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
+    /// Whether `s` is within the length bounds and the output size limit.
+    fn in_bounds(&self, s: &str) -> bool {
+        (self.config.min_len..=self.config.max_len).contains(&self.measure(s)) && s.len() <= self.max_output_bytes
+    }
+
+    /// Whether `s` matches the pattern, and in byte mode whether its bytes match the byte-level
+    /// pattern.
+    fn verifies(&self, s: &str) -> bool {
+        self.re.is_match(s) && self.bytes_re.as_ref().is_none_or(|re| latin1(s).is_some_and(|b| re.is_match(&b)))
+    }
+
+    /// Why `s` cannot be output: out of bounds, denied or not matching the pattern.
+    fn rejection(&self, s: &str) -> Option<RejectReason> {
+        if !self.in_bounds(s) {
+            Some(RejectReason::Length)
+        } else if let Some(reason) = self.denied(s) {
+            Some(reason)
+        } else if !self.verifies(s) {
+            Some(RejectReason::RegexMismatch)
+        } else {
+            None
+        }
+    }
+
+    /// Why `s` is ruled out by `must_not_contain`, `except` or `filter`, if it is.
+    fn denied(&self, s: &str) -> Option<RejectReason> {
+        if self.forbidden.iter().any(|f| s.contains(f.as_str())) {
+            Some(RejectReason::Forbidden)
+        } else if self.except.iter().any(|re| re.is_match(s)) {
+            Some(RejectReason::Excluded)
+        } else if !self.filters.iter().all(|accept| accept(s)) {
+            Some(RejectReason::Filtered)
+        } else {
+            None
+        }
+    }
+
     /// Recursively generate a string from the AST node.
     fn generate_from_ast<R: rand::Rng + ?Sized>(node: &AstNode, rng: &mut R, ctx: &mut crate::traits::TokenContext) -> Result<String, GenError> {
         use crate::ast::AstNode;
+        if ctx.cancelled() {
+            return Err(GenError::Cancelled);
+        }
+        if !ctx.burn() {
+            return Err(GenError::BudgetExhausted);
+        }
         match node {
             AstNode::Sequence(nodes) => {
                 let mut out = String::new();
                 for n in nodes {
                     out.push_str(&Self::generate_from_ast(n, rng, ctx)?);
+                    // Too long already: stop growing it and let the length check reject it.
+                    if out.len() > ctx.output_limit {
+                        break;
+                    }
                 }
                 Ok(out)
             }
@@ -559,6 +1773,10 @@ impl RegexGenerator {
                 let mut out = String::new();
                 for _ in 0..count {
                     out.push_str(&Self::generate_from_ast(node, rng, ctx)?);
+                    // Too long already: stop growing it and let the length check reject it.
+                    if out.len() > ctx.output_limit {
+                        break;
+                    }
                 }
                 Ok(out)
             }
@@ -583,6 +1801,78 @@ impl RegexGenerator {
         }
     }
 
+    /// Generate one value, possibly corrupted according to the configured `FaultConfig`.
+    ///
+    /// Valid outputs carry `fault: None`. A fault is only reported when the corrupted value
+    /// actually fails verification (regex or length bounds); if no configured fault kind can
+    /// break the value, it is returned unmodified and labeled valid.
+    pub fn generate_labeled(&mut self) -> Result<LabeledOutput, GenError> {
+        let value = self.generate_one()?;
+        let branch = self.last_branch();
+        let Some(faults) = &self.faults else {
+            return Ok(LabeledOutput { value, fault: None, branch });
+        };
+        if !self.rng.gen_bool(faults.rate.clamp(0.0, 1.0)) {
+            return Ok(LabeledOutput { value, fault: None, branch });
+        }
+        for kind in faults.shuffled_kinds(&mut self.rng) {
+            // In byte mode each char stands for a byte.
+            let unit = if self.bytes { LengthUnit::Chars } else { self.length_unit };
+            if let Some(bad) = faults::apply_fault(kind, &value, self.config.max_len, unit, &mut self.rng) {
+                let len = self.measure(&bad);
+                if len < self.config.min_len || len > self.config.max_len || !self.re.is_match(&bad) {
+                    return Ok(LabeledOutput { value: bad, fault: Some(kind), branch });
+                }
+            }
+        }
+        Ok(LabeledOutput { value, fault: None, branch })
+    }
+
+    /// Generate one value and encode it with `encoder`.
+    ///
+    /// The encoded bytes are decoded again and put through the checks every output passes
+    /// (pattern, length bounds, deny-lists and filters; for `anchored(false)` the bounds are
+    /// left out, as they apply to the match and not the filler around it); candidates that fail
+    /// to encode or are rejected after a lossy mapping are discarded and regenerated, up to
+    /// `max_attempts` times.
+    pub fn generate_encoded(&mut self, encoder: &dyn Encoder) -> Result<Vec<u8>, GenError> {
+        let mut last_err = GenError::NoMatch;
+        for attempt in 1..=self.config.max_attempts.max(1) {
+            let s = self.generate_one()?;
+            match encoder.encode(&s) {
+                Ok(bytes) => {
+                    let decoded = encoder.decode(&bytes);
+                    let reason = if self.anchored {
+                        self.rejection(&decoded)
+                    } else {
+                        self.denied(&decoded).or_else(|| (!self.verifies(&decoded)).then_some(RejectReason::RegexMismatch))
+                    };
+                    let Some(reason) = reason else {
+                        return Ok(bytes);
+                    };
+                    diag::encode_rejected(encoder.name(), attempt, &format_args!("{}: {:?}", reason, decoded));
+                    last_err = GenError::NoMatch;
+                }
+                Err(e) => {
+                    diag::encode_rejected(encoder.name(), attempt, &e);
+                    last_err = GenError::Encoding(e);
+                }
+            }
+        }
+        Err(last_err)
+    }
+
+    /// Generate one match as bytes. For a pattern read with `Dialect::Bytes` each generated
+    /// character stands for one byte and the bytes are verified against the byte-level pattern
+    /// with `regex::bytes::Regex`; otherwise this is the UTF-8 encoding of `generate_one`.
+    pub fn generate_bytes(&mut self) -> Result<Vec<u8>, GenError> {
+        let s = self.generate_one()?;
+        if !self.bytes {
+            return Ok(s.into_bytes());
+        }
+        latin1(&s).ok_or(GenError::NoMatch)
+    }
+
     /// Convenience: generate n matches (may return fewer if generator hit limits).
     pub fn generate_n(&mut self, n: usize) -> Result<Vec<String>, GenError> {
         let mut out = Vec::with_capacity(n);
@@ -594,18 +1884,295 @@ impl RegexGenerator {
         }
         Ok(out)
     }
+
+    /// Make `n` generation calls and keep whatever succeeds.
+    ///
+    /// Unlike `generate_n`, a failed call does not abort the batch. The returned stats cover just
+    /// this batch: `failures` counts the calls that produced nothing and the rejection counters
+    /// say why their candidates were discarded.
+    pub fn generate_n_lossy(&mut self, n: usize) -> (Vec<String>, GenStats) {
+        let before = self.stats.clone();
+        let out = (0..n).filter_map(|_| self.generate_one().ok()).collect();
+        (out, self.stats.since(&before))
+    }
+
+    /// Generate `n` distinct matches.
+    ///
+    /// Outputs are drawn as by `generate_one` and duplicates discarded. Once `max_attempts` draws
+    /// in a row produce nothing new, a finite language small enough to list is enumerated and the
+    /// missing values are taken from the strings not yet seen. Fails with `InvalidConfig` when the
+    /// pattern provably has fewer than `n` matches within the length bounds.
+    pub fn generate_n_unique(&mut self, n: usize) -> Result<Vec<String>, GenError> {
+        // Characters may join into fewer graphemes; enumerate as far as they could stretch.
+        let graphemes = !self.bytes && self.length_unit == LengthUnit::Graphemes;
+        let char_len = if graphemes { self.config.max_len.saturating_mul(length::MAX_GRAPHEME_CHARS) } else { self.config.max_len };
+        let dfa = self.dfa().ok().filter(|_| !graphemes);
+        if let Some(dfa) = dfa
+            && let Some(start) = dfa.start
+        {
+            // Char lengths never exceed byte lengths, so this bounds the matches from above.
+            // Grapheme lengths can be shorter than char lengths, so those are not checked.
+            let available = std::mem::take(&mut dfa.counts_up_to(self.config.max_len)[self.config.max_len][start]);
+            if available < BigUint::from(n) {
+                return Err(too_few_matches(&available, n));
+            }
+        }
+        let mut seen = HashSet::with_capacity(n);
+        let mut out = Vec::with_capacity(n);
+        let mut stale = 0;
+        while out.len() < n && stale < self.config.max_attempts {
+            match self.generate_one() {
+                Ok(s) if seen.insert(s.clone()) => {
+                    out.push(s);
+                    stale = 0;
+                }
+                Ok(_) => stale += 1,
+                // generate_one has already spent its own attempt budget.
+                Err(GenError::NoMatch) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        if out.len() == n {
+            return Ok(out);
+        }
+        let Some(all) = self.dfa().ok().and_then(|dfa| dfa.enumerate(char_len, MAX_ENUMERATED)) else {
+            return Err(GenError::NoMatch);
+        };
+        let mut rest: Vec<String> = all
+            .into_iter()
+            .filter(|s| (self.config.min_len..=self.config.max_len).contains(&self.measure(s)))
+            .filter(|s| !seen.contains(s) && self.denied(s).is_none() && self.re.is_match(s))
+            .collect();
+        if out.len() + rest.len() < n {
+            return Err(too_few_matches(&BigUint::from(seen.len() + rest.len()), n));
+        }
+        rest.shuffle(&mut self.rng);
+        out.extend(rest.into_iter().take(n - out.len()));
+        Ok(out)
+    }
+}
+
+/// Largest finite language `generate_n_unique` lists exhaustively.
+const MAX_ENUMERATED: usize = 100_000;
+
+fn too_few_matches(available: &BigUint, requested: usize) -> GenError {
+    let noun = if *available == BigUint::from(1u8) { "match" } else { "matches" };
+    GenError::InvalidConfig(format!("pattern has only {} distinct {} within the length bounds, {} requested", available, noun, requested))
+}
+
+/// Lex `pattern` (leniently) into the token tree generation works from.
+pub fn parse(pattern: &str) -> Result<Vec<Token>, GenError> {
+    Ok(lexer::lex(pattern, &mut LexState::new(ParseMode::Lenient))?)
+}
+
+/// Parse `pattern` with `regex-syntax` into the token tree generation works from.
+#[cfg(feature = "hir")]
+pub fn parse_hir(pattern: &str) -> Result<Vec<Token>, GenError> {
+    hir::lex(pattern, &mut LexState::new(ParseMode::Lenient))
+}
+
+/// Serialize a token sequence (as returned by `parse`) back into a pattern; see `Token::to_pattern`.
+pub fn to_pattern(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    tokens::write_sequence(tokens, &mut out);
+    out
+}
+
+/// Analyze `pattern` (lexed leniently) without building a generator.
+pub fn analyze(pattern: &str) -> Result<Analysis, GenError> {
+    Ok(analysis::analyze_dfa(&analysis::build_dfa(&parse(pattern)?)?, GeneratorConfig::default().max_len))
+}
+
+/// Replace each backreference with a non-capturing copy of the group it refers to, for patterns
+/// the `regex` crate cannot verify as written. None when a reference has no group or a group
+/// refers back into itself.
+fn without_backrefs(tokens: &[Token]) -> Option<Vec<Token>> {
+    struct Groups(HashMap<usize, Token>);
+    impl TokenVisitor for Groups {
+        fn visit_group(&mut self, inner: &Token, index: Option<usize>) {
+            if let Some(index) = index {
+                self.0.insert(index, inner.clone());
+            }
+            self.visit_token(inner);
+        }
+    }
+    struct Inline {
+        groups: HashMap<usize, Token>,
+        /// Groups being inlined, to detect self-reference.
+        active: Vec<usize>,
+        failed: bool,
+    }
+    impl TokenVisitorMut for Inline {
+        fn visit_token_mut(&mut self, token: &mut Token) {
+            let Token::Backreference(index) = *token else {
+                return visit::walk_token_mut(self, token);
+            };
+            match self.groups.get(&index) {
+                Some(body) if !self.active.contains(&index) => {
+                    let mut body = body.clone();
+                    self.active.push(index);
+                    self.visit_token_mut(&mut body);
+                    self.active.pop();
+                    // Copies must not capture, or later groups would be renumbered.
+                    Uncapture.visit_token_mut(&mut body);
+                    *token = Token::NonCapturingGroup(Box::new(body));
+                }
+                _ => self.failed = true,
+            }
+        }
+
+        fn visit_group_mut(&mut self, inner: &mut Token, index: Option<usize>) {
+            self.active.extend(index);
+            self.visit_token_mut(inner);
+            if index.is_some() {
+                self.active.pop();
+            }
+        }
+    }
+    struct Uncapture;
+    impl TokenVisitorMut for Uncapture {
+        fn visit_token_mut(&mut self, token: &mut Token) {
+            visit::walk_token_mut(self, token);
+            if let Token::Group(inner, _) = token {
+                *token = Token::NonCapturingGroup(std::mem::replace(inner, Box::new(Token::Concatenation(Vec::new()))));
+            }
+        }
+    }
+    let mut groups = Groups(HashMap::new());
+    visit::walk_tokens(&mut groups, tokens);
+    let mut inline = Inline { groups: groups.0, active: Vec::new(), failed: false };
+    let mut tokens = tokens.to_vec();
+    visit::walk_tokens_mut(&mut inline, &mut tokens);
+    (!inline.failed).then_some(tokens)
+}
+
+/// Number of branches of the pattern's top-level alternation, looking through anchors and a
+/// single enclosing group (so `^(?:A|B|C)$` has three). None when there is no such alternation.
+fn top_level_branch_count(tokens: &[Token]) -> Option<usize> {
+    let mut significant = tokens.iter().filter(|t| !matches!(t, Token::AnchorStart | Token::AnchorEnd));
+    let only = significant.next()?;
+    if significant.next().is_some() {
+        return None;
+    }
+    match only {
+        Token::Alternation(branches) => Some(branches.len()),
+        Token::Concatenation(inner) => top_level_branch_count(inner),
+        Token::Group(inner, _) | Token::NonCapturingGroup(inner) => top_level_branch_count(std::slice::from_ref(&**inner)),
+        _ => None,
+    }
+}
+
+/// The verification regex for `pattern`, allowing the same nesting as the lexer.
+fn verify_regex(pattern: &str, max_nesting_depth: usize) -> Result<Regex, regex::Error> {
+    regex::RegexBuilder::new(pattern).nest_limit(u32::try_from(max_nesting_depth).unwrap_or(u32::MAX)).build()
+}
+
+/// The bytes `s` stands for in byte mode, one per character; None past U+00FF.
+fn latin1(s: &str) -> Option<Vec<u8>> {
+    s.chars().map(|c| u8::try_from(c).ok()).collect()
+}
+
+impl Clone for RegexGenerator {
+    fn clone(&self) -> Self {
+        RegexGenerator {
+            re: self.re.clone(),
+            config: self.config.clone(),
+            rng: self.rng.clone_rng(),
+            seed: self.seed,
+            multiline: self.multiline,
+            ast: self.ast.clone(),
+            tokens: self.tokens.clone(),
+            program: self.program.clone(),
+            group_count: self.group_count,
+            faults: self.faults.clone(),
+            constraints: self.constraints.clone(),
+            stats: self.stats.clone(),
+            warnings: self.warnings.clone(),
+            branch_labels: self.branch_labels.clone(),
+            last_branch: self.last_branch,
+            last_captures: self.last_captures.clone(),
+            explain: self.explain,
+            last_explanation: self.last_explanation.clone(),
+            anchored: self.anchored,
+            search: self.search.clone(),
+            forbidden: self.forbidden.clone(),
+            char_weights: self.char_weights.clone(),
+            humanize: self.humanize,
+            except: self.except.clone(),
+            filters: self.filters.clone(),
+            dfa: self.dfa.clone(),
+            walker: self.walker.clone(),
+            length_unit: self.length_unit,
+            max_output_bytes: self.max_output_bytes,
+            fuel: self.fuel,
+            cancel: self.cancel.clone(),
+            observer: self.observer.clone(),
+            max_repeat: self.max_repeat,
+            relax: self.relax.clone(),
+            last_relaxations: self.last_relaxations.clone(),
+            bytes: self.bytes,
+            bytes_re: self.bytes_re.clone(),
+            scratch: String::new(),
+        }
+    }
+}
+
+/// Sampling with `rng.sample(&generator)` or `rng.sample_iter(&generator)` is `generate_one_ref`.
+///
+/// # Panics
+///
+/// When generation fails: no match within `max_attempts`, a timeout, an exhausted fuel budget,
+/// cancellation, or group constraints. Call `generate_one_ref` to handle those as errors.
+impl Distribution<String> for RegexGenerator {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.generate_one_ref(rng).unwrap_or_else(|e| panic!("genrex: sampling failed: {}", e))
+    }
 }
 
+/// A generator with no lexed pattern that falls back to rejection sampling against `.*`.
+/// Mostly useless on its own; `RegexGenerator::new(pattern)` is the short way to get a working
+/// generator.
 impl Default for RegexGenerator {
     fn default() -> Self {
         RegexGenerator {
             re: Regex::new(".*").unwrap(),
             config: GeneratorConfig::default(),
             rng: Box::new(StdRng::from_entropy()),
+            seed: None,
             multiline: false,
-            ast: None,
+            ast: Arc::default(),
             tokens: None,
+            program: None,
             group_count: 0,
+            faults: None,
+            constraints: ConstraintSet::default(),
+            stats: GenStats::default(),
+            warnings: Vec::new(),
+            branch_labels: None,
+            last_branch: None,
+            last_captures: None,
+            explain: false,
+            last_explanation: None,
+            anchored: true,
+            search: Arc::default(),
+            forbidden: Vec::new(),
+            char_weights: None,
+            humanize: false,
+            except: Vec::new(),
+            filters: Vec::new(),
+            dfa: Arc::default(),
+            walker: None,
+            length_unit: LengthUnit::default(),
+            max_output_bytes: GeneratorConfig::default().max_len,
+            fuel: None,
+            cancel: None,
+            observer: None,
+            max_repeat: lexer::MAX_REPEAT,
+            relax: None,
+            last_relaxations: Vec::new(),
+            bytes: false,
+            bytes_re: None,
+            scratch: String::new(),
         }
     }
 }
@@ -1,119 +1,691 @@
-use std::{env, process};
-use rand::rngs::StdRng;
-use rand::SeedableRng;
-use genrex::{RegexGeneratorBuilder, GeneratorConfig};
-use std::time::Duration;
+use std::process;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use genrex::{json_string, BoundedStream, Dialect, GenError, LengthUnit, Quota, RegexGenerator, RegexGeneratorBuilder, GeneratorConfig, GenStats, RecordGenerator, RejectReason, TokenTree};
+use genrex::nfa::Nfa;
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::time::{Duration, Instant};
 
-fn print_usage() {
-    eprintln!("Usage: genrex-cli <pattern> [--n N] [--seed S] [--min M] [--max M] [--attempts A] [--timeout-ms T] [--multiline] [--allow-backrefs] [-v]");
+/// Generate random strings matching a regular expression.
+///
+/// A bare pattern runs `gen`.
+#[derive(Parser)]
+#[command(name = "genrex-cli", version, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    generate: GenArgs,
 }
 
-fn main() {
-    let mut args = env::args().skip(1);
-    let pattern = match args.next() {
-        Some(p) => p,
-        None => {
-            print_usage();
-            process::exit(2);
+#[derive(Subcommand)]
+enum Command {
+    /// Print generated strings, or records with --field
+    Gen(GenArgs),
+    /// Print the token tree of a pattern
+    Explain(ExplainArgs),
+    /// Print length bounds and the number of distinct matches
+    Analyze(PatternArgs),
+    /// Generate strings without printing them and report throughput, rejections and latency
+    Bench(BenchArgs),
+    /// Print a shell completion script
+    #[command(hide = true)]
+    Completions {
+        shell: clap_complete::Shell,
+    },
+    /// Print the man page in roff format
+    #[command(hide = true)]
+    Man,
+}
+
+/// Output format for `--format`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+    Ndjson,
+}
+
+/// Graph printed by `explain --dot`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DotGraph {
+    Tree,
+    Nfa,
+}
+
+/// Pattern language for `--dialect`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DialectChoice {
+    Regex,
+    /// SQL LIKE: `%`, `_` and `[...]`
+    Like,
+    /// Shell glob: `*`, `?` and `[...]`
+    Glob,
+    /// POSIX basic regular expression (grep, sed)
+    Bre,
+    /// POSIX extended regular expression (grep -E, awk)
+    Ere,
+    /// JavaScript regular expression, optionally as `/.../flags`
+    Ecmascript,
+}
+
+impl DialectChoice {
+    fn dialect(self) -> Dialect {
+        match self {
+            DialectChoice::Regex => Dialect::Regex,
+            DialectChoice::Like => Dialect::SqlLike,
+            DialectChoice::Glob => Dialect::Glob,
+            DialectChoice::Bre => Dialect::PosixBre,
+            DialectChoice::Ere => Dialect::PosixEre,
+            DialectChoice::Ecmascript => Dialect::EcmaScript,
         }
-    };
+    }
+}
 
-    // Defaults
-    let mut n: usize = 1;
-    let mut seed: Option<u64> = None;
-    let mut min_len: Option<usize> = None;
-    let mut max_len: Option<usize> = None;
-    let mut max_attempts: Option<usize> = None;
-    let mut timeout_ms: Option<u64> = None;
-    let mut multiline = false;
-    let mut allow_backrefs = false;
-    let mut verbose = false;
-
-    while let Some(arg) = args.next() {
-        match arg.as_str() {
-            "--n" => {
-                if let Some(v) = args.next() { n = v.parse().unwrap_or(1); }
-            }
-            "--seed" => {
-                if let Some(v) = args.next() { seed = v.parse().ok(); }
-            }
-            "--min" => {
-                if let Some(v) = args.next() { min_len = v.parse().ok(); }
-            }
-            "--max" => {
-                if let Some(v) = args.next() { max_len = v.parse().ok(); }
-            }
-            "--attempts" => {
-                if let Some(v) = args.next() { max_attempts = v.parse().ok(); }
-            }
-            "--timeout-ms" => {
-                if let Some(v) = args.next() { timeout_ms = v.parse().ok(); }
-            }
-            "--multiline" => {
-                multiline = true;
-            }
-            "--allow-backrefs" => {
-                allow_backrefs = true;
+/// When to color tree output.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    /// When writing to a terminal and `NO_COLOR` is not set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(self, terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => terminal && std::env::var_os("NO_COLOR").is_none(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Unit of `--min` and `--max`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum UnitChoice {
+    /// UTF-8 bytes
+    Bytes,
+    /// Unicode characters
+    Chars,
+    /// User-perceived characters (a letter and its combining marks, an emoji sequence)
+    Graphemes,
+}
+
+impl UnitChoice {
+    fn unit(self) -> LengthUnit {
+        match self {
+            UnitChoice::Bytes => LengthUnit::Bytes,
+            UnitChoice::Chars => LengthUnit::Chars,
+            UnitChoice::Graphemes => LengthUnit::Graphemes,
+        }
+    }
+}
+
+/// Generator options shared by the subcommands.
+#[derive(Args)]
+struct Options {
+    /// Seed the RNG
+    #[arg(long, env = "GENREX_SEED")]
+    seed: Option<u64>,
+    /// Minimum string length, in --length-unit
+    #[arg(long = "min", value_name = "LEN")]
+    min_len: Option<usize>,
+    /// Maximum string length, in --length-unit
+    #[arg(long = "max", value_name = "LEN")]
+    max_len: Option<usize>,
+    /// Unit of --min and --max
+    #[arg(long, value_enum, default_value_t = UnitChoice::Bytes)]
+    length_unit: UnitChoice,
+    /// Maximum candidate attempts per output
+    #[arg(long = "attempts", value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    max_attempts: Option<u64>,
+    /// Generation timeout in milliseconds
+    #[arg(long, value_name = "MS")]
+    timeout_ms: Option<u64>,
+    /// Enable multiline mode
+    #[arg(long)]
+    multiline: bool,
+    /// Allow patterns that fail regex::Regex compilation (backreferences)
+    #[arg(long)]
+    allow_backrefs: bool,
+    /// Language the pattern is written in
+    #[arg(long, value_enum, default_value_t = DialectChoice::Regex)]
+    dialect: DialectChoice,
+    /// Print diagnostics to stderr
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[derive(Args)]
+struct PatternArgs {
+    pattern: String,
+    #[command(flatten)]
+    options: Options,
+}
+
+#[derive(Args)]
+struct ExplainArgs {
+    pattern: String,
+    /// Color the tree
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+    /// Print a Graphviz DOT graph of the token tree (default) or the NFA instead
+    #[arg(long, value_enum, value_name = "GRAPH", num_args = 0..=1, default_missing_value = "tree", conflicts_with = "color")]
+    dot: Option<DotGraph>,
+    #[command(flatten)]
+    options: Options,
+}
+
+#[derive(Args)]
+struct GenArgs {
+    /// Pattern to generate from
+    #[arg(required_unless_present = "fields", conflicts_with = "fields")]
+    pattern: Option<String>,
+    /// Number of outputs
+    #[arg(short, long, default_value_t = 1)]
+    n: usize,
+    /// Print accepted/rejected counts, rejection reasons and throughput after the run
+    #[arg(long)]
+    stats: bool,
+    /// Print the effective seed to stderr and prefix each output with its own seed and a tab;
+    /// `--seed <that seed> --n 1` reproduces the output
+    #[arg(long, conflicts_with_all = ["fields", "format", "jobs", "stream"])]
+    show_seed: bool,
+    /// Generate records with a field NAME from PATTERN (repeatable)
+    #[arg(long = "field", value_name = "NAME=PATTERN", value_parser = parse_field)]
+    fields: Vec<(String, String)>,
+    /// Print records as csv (the default for --field), json or ndjson
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// Print the token tree of the pattern to stderr before generating
+    #[arg(long, conflicts_with = "fields")]
+    explain: bool,
+    /// Color the --explain tree
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+    /// Write output to FILE instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Append to the --output file instead of truncating it
+    #[arg(long, requires = "output")]
+    append: bool,
+    /// Gzip-compress the output
+    #[arg(long)]
+    gzip: bool,
+    /// End each output with a NUL byte instead of a newline (for `xargs -0`)
+    #[arg(short = '0', long, conflicts_with_all = ["fields", "format"])]
+    print0: bool,
+    /// Write outputs back to back with no delimiter
+    #[arg(long, conflicts_with_all = ["fields", "format", "print0"])]
+    no_newline: bool,
+    /// Generate on N threads; output is reproducible for a given --seed and N
+    #[arg(short, long, value_name = "N", default_value = "1", conflicts_with_all = ["fields", "format"])]
+    jobs: NonZeroUsize,
+    /// Generate until interrupted (Ctrl-C) or --limit-bytes is reached, ignoring --n
+    #[arg(long, conflicts_with_all = ["fields", "format", "jobs"])]
+    stream: bool,
+    /// Stop --stream before the output (delimiters included, before compression) exceeds BYTES
+    #[arg(long, value_name = "BYTES", requires = "stream")]
+    limit_bytes: Option<u64>,
+    #[command(flatten)]
+    options: Options,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    pattern: String,
+    /// Number of strings to generate
+    #[arg(short, long, default_value_t = 10_000)]
+    n: usize,
+    /// Also print the full counters after the run
+    #[arg(long)]
+    stats: bool,
+    #[command(flatten)]
+    options: Options,
+}
+
+fn parse_field(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, pattern)) if !name.is_empty() => Ok((name.to_string(), pattern.to_string())),
+        _ => Err("expected NAME=PATTERN".to_string()),
+    }
+}
+
+impl Options {
+    /// A builder for `pattern` with the length, attempt, mode and seed options applied.
+    /// `@NAME` stands for the preset NAME, whose length bounds apply unless overridden.
+    fn builder(&self, pattern: &str, seed: Option<u64>) -> Result<RegexGeneratorBuilder, GenError> {
+        let (mut builder, defaults) = match pattern.strip_prefix('@') {
+            Some(name) => (genrex::preset(name)?, genrex::presets::get(name).map_or_else(GeneratorConfig::default, |p| p.config())),
+            None => (RegexGeneratorBuilder::new(pattern), GeneratorConfig::default()),
+        };
+        if self.min_len.is_some() || self.max_len.is_some() || self.max_attempts.is_some() || self.timeout_ms.is_some() {
+            builder = builder.config(GeneratorConfig {
+                min_len: self.min_len.unwrap_or(defaults.min_len),
+                max_len: self.max_len.unwrap_or(defaults.max_len),
+                max_attempts: self.max_attempts.map_or(defaults.max_attempts, |a| a as usize),
+                timeout: self.timeout_ms.map(Duration::from_millis),
+            });
+        }
+        if self.multiline {
+            builder = builder.multiline(true);
+        }
+        if let Some(s) = seed {
+            builder = builder.seed(s);
+        }
+        if self.allow_backrefs {
+            builder = builder.allow_backrefs();
+        }
+        if self.dialect != DialectChoice::Regex {
+            builder = builder.dialect(self.dialect.dialect());
+        }
+        if self.length_unit != UnitChoice::Bytes {
+            builder = builder.length_unit(self.length_unit.unit());
+        }
+        Ok(builder)
+    }
+
+    /// Build the generator for `pattern`, exiting on errors.
+    fn generator(&self, pattern: &str) -> RegexGenerator {
+        match self.builder(pattern, self.seed).and_then(|b| b.build()) {
+            Ok(g) => g,
+            Err(e) => {
+                eprintln!("Failed to build generator: {:?}", e);
+                process::exit(1);
             }
-            "-v" => {
-                verbose = true;
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Gen(cli.generate));
+    let verbose = match &command {
+        Command::Gen(args) => args.options.verbose,
+        Command::Explain(args) => args.options.verbose,
+        Command::Analyze(args) => args.options.verbose,
+        Command::Bench(args) => args.options.verbose,
+        Command::Completions { .. } | Command::Man => false,
+    };
+    if verbose {
+        genrex::set_verbose(true);
+    }
+    match command {
+        Command::Gen(args) => generate(args),
+        Command::Explain(args) => explain(&args),
+        Command::Analyze(args) => analyze(&args),
+        Command::Bench(args) => bench(&args),
+        Command::Completions { shell } => clap_complete::generate(shell, &mut Cli::command(), "genrex-cli", &mut io::stdout()),
+        Command::Man => clap_mangen::Man::new(Cli::command()).render(&mut io::stdout()).unwrap_or_else(|e| write_failed(e)),
+    }
+}
+
+/// Buffered destination for `gen` output.
+enum Output {
+    Plain(Box<dyn Write>),
+    Gzip(GzEncoder<Box<dyn Write>>),
+}
+
+impl Output {
+    /// Open the destination chosen by `--output`, `--append` and `--gzip`.
+    fn open(args: &GenArgs) -> io::Result<Output> {
+        let inner: Box<dyn Write> = match &args.output {
+            Some(path) => {
+                let file = OpenOptions::new().write(true).create(true).append(args.append).truncate(!args.append).open(path)?;
+                Box::new(BufWriter::new(file))
             }
-            _ => {
-                eprintln!("Unknown arg: {}", arg);
-                print_usage();
-                process::exit(2);
+            None => Box::new(BufWriter::new(io::stdout().lock())),
+        };
+        Ok(if args.gzip { Output::Gzip(GzEncoder::new(inner, Compression::default())) } else { Output::Plain(inner) })
+    }
+
+    /// Write any buffered output and, for gzip, the stream trailer.
+    fn finish(&mut self) -> io::Result<()> {
+        match self {
+            Output::Plain(w) => w.flush(),
+            Output::Gzip(gz) => {
+                gz.try_finish()?;
+                gz.get_mut().flush()
             }
         }
     }
+}
 
-    let mut builder = RegexGeneratorBuilder::new(&pattern);
-    if let Some(min) = min_len {
-        builder = builder.config(GeneratorConfig {
-            min_len: min,
-            max_len: max_len.unwrap_or(64),
-            max_attempts: max_attempts.unwrap_or(10_000),
-            timeout: timeout_ms.map(Duration::from_millis),
-        });
-    } else if max_len.is_some() || max_attempts.is_some() || timeout_ms.is_some() {
-        builder = builder.config(GeneratorConfig {
-            min_len: min_len.unwrap_or(0),
-            max_len: max_len.unwrap_or(64),
-            max_attempts: max_attempts.unwrap_or(10_000),
-            timeout: timeout_ms.map(Duration::from_millis),
-        });
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Plain(w) => w.write(buf),
+            Output::Gzip(gz) => gz.write(buf),
+        }
     }
 
-    if multiline {
-        builder = builder.multiline(true);
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Plain(w) => w.flush(),
+            Output::Gzip(gz) => gz.flush(),
+        }
     }
+}
 
-    if let Some(s) = seed {
-        builder = builder.rng(StdRng::seed_from_u64(s));
+/// Report a failed write and exit. A closed pipe (`genrex-cli ... | head`) ends the run quietly.
+fn write_failed(e: io::Error) -> ! {
+    if e.kind() == io::ErrorKind::BrokenPipe {
+        process::exit(0);
     }
-    
-    if allow_backrefs {
-        builder = builder.allow_backrefs();
+    eprintln!("Write error: {}", e);
+    process::exit(1);
+}
+
+/// `gen`: print generated strings, or records for `--field`/`--format`.
+fn generate(mut args: GenArgs) {
+    // With --format alone, the pattern becomes a single field named "value".
+    if args.fields.is_empty() && args.format.is_some() {
+        let value = args.pattern.take().map(|p| ("value".to_string(), p));
+        args.fields.extend(value);
     }
-    
-    if verbose {
-        genrex::set_verbose(true);
+    if args.show_seed {
+        let seed = *args.options.seed.get_or_insert_with(rand::random);
+        eprintln!("seed: {}", seed);
     }
-    
-    let mut generator = match builder.build() {
-        Ok(g) => g,
-        Err(e) => {
-            eprintln!("Failed to build generator: {:?}", e);
+    let opts = &args.options;
+    let mut out = Output::open(&args).unwrap_or_else(|e| {
+        eprintln!("Cannot open output: {}", e);
+        process::exit(1);
+    });
+    if !args.fields.is_empty() {
+        let seed = opts.seed.unwrap_or_else(rand::random);
+        let mut records = RecordGenerator::new(seed);
+        for (i, (name, field_pattern)) in args.fields.iter().enumerate() {
+            let built = opts.builder(field_pattern, Some(genrex::batch::derive_seed(seed, i as u64))).and_then(|b| b.build());
+            if let Err(e) = built.and_then(|g| records.add_generator(name, g).map(|_| ())) {
+                eprintln!("Failed to build field '{}': {:?}", name, e);
+                process::exit(1);
+            }
+        }
+        let res = write_records(&mut out, &mut records, args.n, args.format.unwrap_or(Format::Csv));
+        out.finish().unwrap_or_else(|e| write_failed(e));
+        if let Err(e) = res.unwrap_or_else(|e| write_failed(e)) {
+            eprintln!("Generation error: {:?}", e);
             process::exit(1);
         }
+        return;
+    }
+
+    let pattern = args.pattern.clone().expect("clap requires a pattern without --field");
+    let mut generator = opts.generator(&pattern);
+    if args.explain {
+        match generator.tokens() {
+            Some(tokens) => eprint!("{}", TokenTree::new(tokens).color(args.color.enabled(std::io::stderr().is_terminal()))),
+            None => eprintln!("Pattern has no token form; it is generated by rejection sampling"),
+        }
+    }
+    let delimiter: &[u8] = match (args.print0, args.no_newline) {
+        (true, _) => b"\0",
+        (_, true) => b"",
+        _ => b"\n",
+    };
+    let started = Instant::now();
+    let (stats, error) = if args.jobs.get() > 1 {
+        generate_parallel(&args, &generator, &mut out, delimiter)
+    } else if args.stream {
+        generate_stream(generator, args.limit_bytes, &mut out, delimiter)
+    } else {
+        let mut error = None;
+        for i in 0..args.n {
+            // With a seed, each output gets its own so it can be reproduced on its own, whether
+            // or not --show-seed prints it. The first uses the seed itself, so `--seed S --n 1`
+            // reproduces the output shown with seed S.
+            let item_seed = opts.seed.map(|seed| if i == 0 { seed } else { genrex::batch::derive_seed(seed, i as u64) });
+            if let Some(item_seed) = item_seed {
+                generator.reseed(item_seed);
+            }
+            match generator.generate_one() {
+                Ok(s) => item_seed
+                    .filter(|_| args.show_seed)
+                    .map_or(Ok(()), |item_seed| write!(out, "{}\t", item_seed))
+                    .and_then(|_| out.write_all(s.as_bytes()))
+                    .and_then(|_| out.write_all(delimiter))
+                    .unwrap_or_else(|e| write_failed(e)),
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
+        (generator.stats().clone(), error)
     };
- 
-    for _ in 0..n {
-        match generator.generate_one() {
-            Ok(s) => println!("{}", s),
+    out.finish().unwrap_or_else(|e| write_failed(e));
+    if let Some(e) = &error {
+        eprintln!("Generation error: {:?}", e);
+    }
+    if args.stats {
+        print_stats(&stats, started.elapsed());
+    }
+    if error.is_some() {
+        process::exit(1);
+    }
+}
+
+/// Generate until interrupted or until the next output would take the total past `limit_bytes`
+/// (delimiters included). Writes block when the reader falls behind, so a slow consumer slows
+/// generation instead of growing a buffer. Returns the counters and the generation error, if any.
+fn generate_stream(generator: RegexGenerator, limit_bytes: Option<u64>, out: &mut Output, delimiter: &[u8]) -> (GenStats, Option<GenError>) {
+    let interrupted = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    if let Err(e) = signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&interrupted)) {
+        eprintln!("Cannot handle SIGINT, output may be cut short: {}", e);
+    }
+    let quota = Quota { max_bytes: limit_bytes, max_items: None };
+    let mut stream = BoundedStream::new(generator, quota).delimiter(delimiter);
+    let mut error = None;
+    while !interrupted.load(Ordering::Relaxed) {
+        match stream.next() {
+            Some(Ok(s)) => out.write_all(s.as_bytes()).and_then(|_| out.write_all(delimiter)).unwrap_or_else(|e| write_failed(e)),
+            Some(Err(e)) => {
+                error = Some(e);
+                break;
+            }
+            None => break,
+        }
+    }
+    (stream.into_generator().stats().clone(), error)
+}
+
+/// Outputs per block handed from a worker thread to the writer in `--jobs` mode.
+const JOB_BLOCK: usize = 4096;
+
+/// Generate `args.n` outputs on `args.jobs` threads, each with a fork of `base` for its thread
+/// number. Blocks are dealt to the threads round-robin and written in order, so a given seed and
+/// job count always produce the same output. Returns the combined counters and the first
+/// generation error.
+fn generate_parallel(args: &GenArgs, base: &RegexGenerator, out: &mut Output, delimiter: &[u8]) -> (GenStats, Option<GenError>) {
+    let jobs = args.jobs.get();
+    let n = args.n;
+    let blocks = n.div_ceil(JOB_BLOCK);
+    thread::scope(|scope| {
+        let mut receivers = Vec::with_capacity(jobs);
+        let mut workers = Vec::with_capacity(jobs);
+        for job in 0..jobs {
+            // Two blocks in flight per thread bound memory when the writer is slow.
+            let (tx, rx) = mpsc::sync_channel::<Result<Vec<u8>, GenError>>(2);
+            receivers.push(rx);
+            let mut generator = match base.fork(job as u64) {
+                Ok(g) => g,
+                Err(e) => return (GenStats::default(), Some(e)),
+            };
+            workers.push(scope.spawn(move || {
+                for block in (job..blocks).step_by(jobs) {
+                    let count = JOB_BLOCK.min(n - block * JOB_BLOCK);
+                    let mut buf = Vec::new();
+                    for _ in 0..count {
+                        match generator.generate_one() {
+                            Ok(s) => {
+                                buf.extend_from_slice(s.as_bytes());
+                                buf.extend_from_slice(delimiter);
+                            }
+                            Err(e) => {
+                                let _ = tx.send(Err(e));
+                                return generator.stats().clone();
+                            }
+                        }
+                    }
+                    if tx.send(Ok(buf)).is_err() {
+                        break;
+                    }
+                }
+                generator.stats().clone()
+            }));
+        }
+        let mut error = None;
+        for block in 0..blocks {
+            match receivers[block % jobs].recv() {
+                Ok(Ok(buf)) => out.write_all(&buf).unwrap_or_else(|e| write_failed(e)),
+                Ok(Err(e)) => {
+                    error = Some(e);
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+        // Unblock workers still sending so they can finish.
+        drop(receivers);
+        let mut stats = GenStats::default();
+        for worker in workers {
+            stats.merge(&worker.join().expect("generation thread panicked"));
+        }
+        (stats, error)
+    })
+}
+
+/// `explain`: print the token tree with group numbers, repeat bounds and pattern text.
+fn explain(args: &ExplainArgs) {
+    let generator = args.options.generator(&args.pattern);
+    let Some(tokens) = generator.tokens() else {
+        eprintln!("Pattern has no token form; it is generated by rejection sampling");
+        process::exit(1);
+    };
+    match args.dot {
+        Some(DotGraph::Tree) => return print!("{}", genrex::to_dot(tokens)),
+        Some(DotGraph::Nfa) => match Nfa::compile(tokens) {
+            Ok(nfa) => return print!("{}", nfa.to_dot()),
             Err(e) => {
-                eprintln!("Generation error: {:?}", e);
+                eprintln!("No NFA for this pattern: {}", e);
                 process::exit(1);
             }
+        },
+        None => {}
+    }
+    print!("{}", TokenTree::new(tokens).color(args.color.enabled(std::io::stdout().is_terminal())));
+}
+
+/// `analyze`: print length bounds and the number of distinct matches.
+fn analyze(args: &PatternArgs) {
+    let generator = args.options.generator(&args.pattern);
+    let analysis = match generator.analyze() {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("Analysis error: {:?}", e);
+            process::exit(1);
+        }
+    };
+    println!("{:<14}{}", "min length:", analysis.min_len);
+    println!("{:<14}{}", "max length:", analysis.max_len.map_or("unbounded".to_string(), |n| n.to_string()));
+    println!("{:<14}{}", "finite:", analysis.finite);
+    println!("{:<14}{}", "cardinality:", analysis.cardinality.as_ref().map_or("infinite".to_string(), |n| n.to_string()));
+    if let Some(bits) = analysis.entropy_bits(..) {
+        println!("{:<14}{:.2}", "entropy bits:", bits);
+    }
+    // What the generator's own sampling yields within its length bounds.
+    if let Some(bits) = generator.entropy(..).ok().and_then(|e| e.sampled_bits) {
+        println!("{:<14}{:.2}", "sampled bits:", bits);
+    }
+}
+
+/// `bench`: generate `--n` strings without printing them and report throughput, rejections by
+/// reason, per-output latency and the engines that produced the outputs.
+fn bench(args: &BenchArgs) {
+    let mut generator = args.options.generator(&args.pattern);
+    let result = genrex::bench::measure_generator(&mut generator, args.n);
+    print!("{}", result);
+    if result.failed > 0 {
+        eprintln!("Generation error: {} of {} outputs failed", result.failed, result.samples);
+    }
+    if args.stats {
+        print_stats(&result.stats, result.elapsed);
+    }
+}
+
+/// Write `n` records from `records` to `out` in `format`. The inner result is the first
+/// generation error, which ends the output early.
+fn write_records(out: &mut impl Write, records: &mut RecordGenerator, n: usize, format: Format) -> io::Result<Result<(), genrex::GenError>> {
+    let names: Vec<String> = records.field_names().map(str::to_string).collect();
+    if format == Format::Csv {
+        writeln!(out, "{}", names.iter().map(|name| csv_field(name)).collect::<Vec<_>>().join(","))?;
+    } else if format == Format::Json {
+        writeln!(out, "[")?;
+    }
+    for i in 0..n {
+        let values = match records.generate_values() {
+            Ok(values) => values,
+            Err(e) => return Ok(Err(e)),
+        };
+        match format {
+            Format::Csv => {
+                writeln!(out, "{}", values.iter().map(|v| v.as_deref().map(csv_field).unwrap_or_default()).collect::<Vec<_>>().join(","))?;
+            }
+            Format::Json | Format::Ndjson => {
+                let members: Vec<String> = names
+                    .iter()
+                    .zip(&values)
+                    .map(|(name, v)| format!("{}:{}", json_string(name), v.as_deref().map(json_string).unwrap_or_else(|| "null".to_string())))
+                    .collect();
+                let object = format!("{{{}}}", members.join(","));
+                match format {
+                    Format::Json => writeln!(out, "  {}{}", object, if i + 1 < n { "," } else { "" })?,
+                    _ => writeln!(out, "{}", object)?,
+                }
+            }
+        }
+    }
+    if format == Format::Json {
+        writeln!(out, "]")?;
+    }
+    Ok(Ok(()))
+}
+
+/// A CSV field, quoted when it contains a delimiter, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Print a post-run summary of the generator's counters to stderr.
+fn print_stats(stats: &GenStats, elapsed: Duration) {
+    let secs = elapsed.as_secs_f64();
+    let throughput = if secs > 0.0 { stats.accepted as f64 / secs } else { 0.0 };
+    eprintln!("--- genrex stats ---");
+    eprintln!("{:<21}{}", "accepted:", stats.accepted);
+    eprintln!("{:<21}{}", "failed:", stats.failures);
+    eprintln!("{:<21}{}", "attempts:", stats.attempts);
+    eprintln!("{:<21}{}", "rejected:", stats.rejected());
+    for reason in RejectReason::ALL {
+        let count = stats.rejected_for(reason);
+        if count > 0 {
+            eprintln!("  {:<19}{}", format!("{}:", reason), count);
         }
     }
+    eprintln!("{:<21}{:.2}", "attempts per output:", stats.attempts_per_output());
+    eprintln!("{:<21}{:.3}s", "elapsed:", secs);
+    eprintln!("{:<21}{:.1}/s", "throughput:", throughput);
 }
\ No newline at end of file
@@ -0,0 +1,106 @@
+//! Labeled output from several patterns at once, for benchmarking multi-pattern matchers.
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::batch::derive_seed;
+use crate::{GenError, RegexGenerator};
+
+/// How a `MultiPatternGenerator` picks the pattern for each output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Interleave {
+    /// Pick at random with probability proportional to each pattern's weight.
+    #[default]
+    Weighted,
+    /// Cycle through the patterns in the order they were added, ignoring weights.
+    RoundRobin,
+}
+
+/// Generates strings from several patterns, each labeled with the id of the pattern that
+/// produced it. Ids are assigned in the order patterns are added, starting at 0.
+///
+/// The choice of pattern is driven by the seed given to `new`; patterns added with
+/// `add_pattern` get generators seeded from it too, so the whole stream is reproducible.
+pub struct MultiPatternGenerator {
+    generators: Vec<RegexGenerator>,
+    weights: Vec<f64>,
+    interleave: Interleave,
+    seed: u64,
+    rng: StdRng,
+    next: usize,
+}
+
+impl MultiPatternGenerator {
+    /// An empty generator; patterns are chosen with an RNG seeded from `seed`.
+    pub fn new(seed: u64) -> Self {
+        MultiPatternGenerator {
+            generators: Vec::new(),
+            weights: Vec::new(),
+            interleave: Interleave::default(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            next: 0,
+        }
+    }
+
+    /// Set how the pattern for each output is chosen.
+    pub fn interleave(mut self, interleave: Interleave) -> Self {
+        self.interleave = interleave;
+        self
+    }
+
+    /// Add a configured generator with a relative `weight`, returning its pattern id.
+    pub fn add(&mut self, generator: RegexGenerator, weight: f64) -> Result<usize, GenError> {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(GenError::InvalidConfig(format!("pattern weight must be finite and non-negative, got {}", weight)));
+        }
+        self.generators.push(generator);
+        self.weights.push(weight);
+        Ok(self.generators.len() - 1)
+    }
+
+    /// Add `pattern` with default settings and a derived seed, returning its pattern id.
+    pub fn add_pattern(&mut self, pattern: &str, weight: f64) -> Result<usize, GenError> {
+        let seed = derive_seed(self.seed, self.generators.len() as u64);
+        let generator = RegexGenerator::builder(pattern).rng(StdRng::seed_from_u64(seed)).build()?;
+        self.add(generator, weight)
+    }
+
+    /// Number of patterns added.
+    pub fn len(&self) -> usize {
+        self.generators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
+
+    /// The generator for pattern `id`.
+    pub fn generator(&mut self, id: usize) -> Option<&mut RegexGenerator> {
+        self.generators.get_mut(id)
+    }
+
+    /// Generate one string, labeled with the id of the pattern that produced it.
+    pub fn generate_one(&mut self) -> Result<(usize, String), GenError> {
+        if self.generators.is_empty() {
+            return Err(GenError::InvalidConfig("no patterns added".to_string()));
+        }
+        let id = match self.interleave {
+            Interleave::RoundRobin => {
+                let id = self.next % self.generators.len();
+                self.next = id + 1;
+                id
+            }
+            Interleave::Weighted => WeightedIndex::new(&self.weights)
+                .map_err(|_| GenError::InvalidConfig("pattern weights must not all be zero".to_string()))?
+                .sample(&mut self.rng),
+        };
+        Ok((id, self.generators[id].generate_one()?))
+    }
+
+    /// Generate `n` labeled strings.
+    pub fn generate_n(&mut self, n: usize) -> Result<Vec<(usize, String)>, GenError> {
+        (0..n).map(|_| self.generate_one()).collect()
+    }
+}
@@ -0,0 +1,390 @@
+//! Thompson NFA construction from the token tree, and generation by random walks over it.
+//!
+//! A `Walker` precomputes, for every NFA state, how few and how many characters can still be
+//! emitted before reaching the accepting state. Each step of a walk only takes moves that keep
+//! the final length inside `min_len..=max_len`, so outputs land in bounds by construction
+//! instead of being generated and then rejected.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use rand::Rng;
+
+use crate::tokens::{pick_allowed, Token, WILDCARD_CHARS};
+use crate::traits::TokenContext;
+use crate::GenError;
+
+/// Longest edge label `Nfa::to_dot` writes before eliding the rest of the set.
+const MAX_EDGE_LABEL: usize = 24;
+
+/// Most passes over one length's states when solving for walk entropy across epsilon cycles.
+const MAX_ENTROPY_SWEEPS: usize = 1000;
+
+/// Largest bounded repetition expanded into NFA states.
+const MAX_EXPANDED_REPEAT: usize = 1000;
+
+/// One NFA state: epsilon moves plus character-set edges.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NfaState {
+    pub(crate) eps: Vec<usize>,
+    /// Edges taken on any char of the (sorted, deduplicated) set.
+    pub(crate) edges: Vec<(Arc<[char]>, usize)>,
+}
+
+/// A Thompson NFA with a single start and a single accepting state.
+#[derive(Debug, Clone)]
+pub struct Nfa {
+    pub(crate) states: Vec<NfaState>,
+    pub(crate) start: usize,
+    pub(crate) accept: usize,
+}
+
+impl Nfa {
+    /// Compile a token sequence matched in full (anchors and word boundaries are treated as empty).
+    ///
+    /// Fails with `GenError::Unsupported` for constructs that are not regular or have no finite
+    /// alphabet here: backreferences and negated classes.
+    pub fn compile(tokens: &[Token]) -> Result<Nfa, GenError> {
+        let mut nfa = Nfa { states: Vec::new(), start: 0, accept: 0 };
+        let start = nfa.add();
+        let mut end = start;
+        for t in tokens {
+            end = nfa.fragment(t, end)?;
+        }
+        nfa.start = start;
+        nfa.accept = end;
+        Ok(nfa)
+    }
+
+    /// Lex `pattern` (leniently) and compile it.
+    pub fn from_pattern(pattern: &str) -> Result<Nfa, GenError> {
+        let tokens = crate::lexer::lex(pattern, &mut crate::lexer::LexState::new(crate::ParseMode::Lenient))?;
+        Nfa::compile(&tokens)
+    }
+
+    /// Number of states.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Most UTF-8 bytes any one character on an edge takes (1 for an NFA without edges).
+    pub(crate) fn widest_char(&self) -> usize {
+        self.states.iter().flat_map(|s| &s.edges).flat_map(|(set, _)| set.iter()).map(|c| c.len_utf8()).max().unwrap_or(1)
+    }
+
+    /// The NFA as a Graphviz DOT digraph. Epsilon moves are labeled `ε` and character edges
+    /// with their set in class syntax, shortened when long; the accepting state is doubly
+    /// circled.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph nfa {\n  rankdir=LR;\n  node [shape=circle, fontname=monospace];\n  start [shape=point];\n");
+        let _ = writeln!(out, "  s{} [shape=doublecircle];", self.accept);
+        let _ = writeln!(out, "  start -> s{};", self.start);
+        for (id, state) in self.states.iter().enumerate() {
+            for to in &state.eps {
+                let _ = writeln!(out, "  s{} -> s{} [label=\"ε\", style=dashed];", id, to);
+            }
+            for (chars, to) in &state.edges {
+                let _ = writeln!(out, "  s{} -> s{} [label={}];", id, to, crate::export::dot_string(&edge_label(chars)));
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn add(&mut self) -> usize {
+        self.states.push(NfaState::default());
+        self.states.len() - 1
+    }
+
+    fn edge(&mut self, from: usize, chars: &[char]) -> usize {
+        let mut set = chars.to_vec();
+        set.sort_unstable();
+        set.dedup();
+        let to = self.add();
+        self.states[from].edges.push((set.into(), to));
+        to
+    }
+
+    fn eps(&mut self, from: usize, to: usize) {
+        self.states[from].eps.push(to);
+    }
+
+    /// Append the fragment for `token` after state `from`; returns the fragment's end state.
+    fn fragment(&mut self, token: &Token, from: usize) -> Result<usize, GenError> {
+        match token {
+            Token::Literal(c) => Ok(self.edge(from, &[*c])),
+            Token::Class(chars) => Ok(self.edge(from, chars)),
+            Token::Wildcard => Ok(self.edge(from, WILDCARD_CHARS)),
+            Token::NegatedClass(_) => Err(GenError::Unsupported("negated class".to_string())),
+            Token::Backreference(_) => Err(GenError::Unsupported("backreference".to_string())),
+            Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary => Ok(from),
+            Token::Concatenation(tokens) => {
+                let mut end = from;
+                for t in tokens {
+                    end = self.fragment(t, end)?;
+                }
+                Ok(end)
+            }
+            Token::Alternation(choices) => {
+                let end = self.add();
+                for choice in choices {
+                    let start = self.add();
+                    self.eps(from, start);
+                    let branch_end = self.fragment(choice, start)?;
+                    self.eps(branch_end, end);
+                }
+                Ok(end)
+            }
+            Token::Group(inner, _) | Token::NonCapturingGroup(inner) => self.fragment(inner, from),
+            Token::Quantifier { token, min, max, .. } => {
+                let bounded = if *max == usize::MAX { *min } else { *max };
+                if bounded > MAX_EXPANDED_REPEAT {
+                    return Err(GenError::Unsupported(format!("repetition count above {}", MAX_EXPANDED_REPEAT)));
+                }
+                let mut end = from;
+                for _ in 0..*min {
+                    end = self.fragment(token, end)?;
+                }
+                if *max == usize::MAX {
+                    // Kleene star: loop through a hub state.
+                    let hub = self.add();
+                    self.eps(end, hub);
+                    let start = self.add();
+                    self.eps(hub, start);
+                    let body_end = self.fragment(token, start)?;
+                    self.eps(body_end, hub);
+                    return Ok(hub);
+                }
+                let exit = self.add();
+                for _ in *min..*max {
+                    self.eps(end, exit);
+                    end = self.fragment(token, end)?;
+                }
+                self.eps(end, exit);
+                Ok(exit)
+            }
+        }
+    }
+
+    /// Epsilon closure of `set`, sorted and deduplicated.
+    pub(crate) fn closure(&self, set: &mut Vec<usize>) {
+        let mut stack = set.clone();
+        let mut seen = vec![false; self.states.len()];
+        for &s in set.iter() {
+            seen[s] = true;
+        }
+        while let Some(s) = stack.pop() {
+            for &t in &self.states[s].eps {
+                if !seen[t] {
+                    seen[t] = true;
+                    set.push(t);
+                    stack.push(t);
+                }
+            }
+        }
+        set.sort_unstable();
+    }
+}
+
+/// Length-aware random walks over an `Nfa` (see the module docs).
+#[derive(Debug, Clone)]
+pub struct Walker {
+    nfa: Nfa,
+    min_len: usize,
+    max_len: usize,
+    /// Fewest characters from each state to acceptance (`usize::MAX` if unreachable).
+    min_rest: Vec<usize>,
+    /// Most characters from each state to acceptance, capped at `max_len + 1`.
+    max_rest: Vec<Option<usize>>,
+}
+
+/// One possible step of a walk.
+enum Step {
+    Stop,
+    Eps(usize),
+    Edge(usize, usize),
+}
+
+impl Walker {
+    /// Prepare walks producing between `min_len` and `max_len` characters.
+    pub fn new(nfa: Nfa, min_len: usize, max_len: usize) -> Walker {
+        let n = nfa.states.len();
+        let mut reverse: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        for (s, state) in nfa.states.iter().enumerate() {
+            for &t in &state.eps {
+                reverse[t].push((s, 0));
+            }
+            for (_, t) in &state.edges {
+                reverse[*t].push((s, 1));
+            }
+        }
+
+        // 0-1 BFS backwards from the accepting state.
+        let mut min_rest = vec![usize::MAX; n];
+        min_rest[nfa.accept] = 0;
+        let mut deque = VecDeque::from([nfa.accept]);
+        while let Some(t) = deque.pop_front() {
+            for &(s, w) in &reverse[t] {
+                let d = min_rest[t] + w;
+                if d < min_rest[s] {
+                    min_rest[s] = d;
+                    if w == 0 { deque.push_front(s) } else { deque.push_back(s) }
+                }
+            }
+        }
+
+        // Longest distance, capped so cycles converge: every value only grows and is bounded.
+        let cap = max_len.saturating_add(1);
+        let mut max_rest: Vec<Option<usize>> = vec![None; n];
+        max_rest[nfa.accept] = Some(0);
+        let mut work = vec![nfa.accept];
+        while let Some(t) = work.pop() {
+            let base = max_rest[t].unwrap_or(0);
+            for &(s, w) in &reverse[t] {
+                let d = (base + w).min(cap);
+                if max_rest[s].is_none_or(|m| d > m) {
+                    max_rest[s] = Some(d);
+                    work.push(s);
+                }
+            }
+        }
+        Walker { nfa, min_len, max_len, min_rest, max_rest }
+    }
+
+    pub fn nfa(&self) -> &Nfa {
+        &self.nfa
+    }
+
+    /// The fewest and most characters a walk produces.
+    pub(crate) fn bounds(&self) -> (usize, usize) {
+        (self.min_len, self.max_len)
+    }
+
+    /// True when `len` characters emitted so far plus the rest from `state` can land in bounds.
+    fn viable(&self, state: usize, len: usize) -> bool {
+        let Some(most) = self.max_rest[state] else { return false };
+        let least = self.min_rest[state];
+        least != usize::MAX && len + least <= self.max_len && len + most >= self.min_len
+    }
+
+    /// Shannon entropy, in bits, of the choices a walk makes: which move to take at each step
+    /// and which character of an edge to emit. This is the entropy of the outputs when every
+    /// output has a single walk, and overstates it when the NFA is ambiguous. Characters
+    /// steered away from by `must_not_contain` are counted as available. `None` if no walk
+    /// can land in bounds.
+    pub(crate) fn entropy_bits(&self) -> Option<f64> {
+        let states = &self.nfa.states;
+        if !self.viable(self.nfa.start, 0) {
+            return None;
+        }
+        // h[s] for walks that have emitted `len` characters, filled from the longest length down;
+        // `next` holds the layer for `len + 1`. Epsilon moves stay within a layer and may cycle,
+        // so each layer is solved by iterating to a fixed point.
+        let mut next = vec![0.0f64; states.len()];
+        let mut h = vec![0.0f64; states.len()];
+        for len in (0..=self.max_len).rev() {
+            h.iter_mut().for_each(|x| *x = 0.0);
+            for _ in 0..MAX_ENTROPY_SWEEPS {
+                let mut change = 0.0f64;
+                for s in 0..states.len() {
+                    if !self.viable(s, len) {
+                        continue;
+                    }
+                    let mut options = 0usize;
+                    let mut sum = 0.0;
+                    if s == self.nfa.accept && len >= self.min_len {
+                        options += 1;
+                    }
+                    for &t in states[s].eps.iter().filter(|&&t| self.viable(t, len)) {
+                        options += 1;
+                        sum += h[t];
+                    }
+                    for (chars, t) in states[s].edges.iter().filter(|(_, t)| self.viable(*t, len + 1)) {
+                        options += 1;
+                        sum += (chars.len() as f64).log2() + next[*t];
+                    }
+                    if options == 0 {
+                        continue;
+                    }
+                    let value = (options as f64).log2() + sum / options as f64;
+                    change = change.max((value - h[s]).abs());
+                    h[s] = value;
+                }
+                if change < 1e-9 {
+                    break;
+                }
+            }
+            std::mem::swap(&mut h, &mut next);
+        }
+        Some(next[self.nfa.start])
+    }
+
+    /// Walk from the start state to acceptance, choosing uniformly among the moves that keep the
+    /// output length feasible. Class edges pick a character as the token engine does (see
+    /// `ctx.char_weights` and `ctx.humanize`), steering away from `ctx.forbidden` substrings when
+    /// possible. `None` if the walk dead-ends, runs too long, uses up `ctx`'s fuel or is
+    /// cancelled.
+    pub fn walk<R: Rng + ?Sized>(&self, rng: &mut R, ctx: &mut TokenContext) -> Option<String> {
+        let mut out = String::new();
+        self.walk_into(rng, ctx, &mut out).then_some(out)
+    }
+
+    /// `walk`, appending the output to `out`; false where `walk` gives `None`, leaving partial
+    /// output in `out`.
+    pub fn walk_into<R: Rng + ?Sized>(&self, rng: &mut R, ctx: &mut TokenContext, out: &mut String) -> bool {
+        let states = &self.nfa.states;
+        if !self.viable(self.nfa.start, 0) {
+            return false;
+        }
+        let mut len = 0usize;
+        let mut state = self.nfa.start;
+        // Epsilon cycles make no progress; bound the walk so they cannot spin forever.
+        let step_limit = (self.max_len + 1).saturating_mul(states.len() + 1).saturating_mul(4);
+        let mut options = Vec::new();
+        for _ in 0..step_limit {
+            if ctx.cancelled() || !ctx.burn() {
+                return false;
+            }
+            options.clear();
+            if state == self.nfa.accept && len >= self.min_len {
+                options.push(Step::Stop);
+            }
+            options.extend(states[state].eps.iter().filter(|&&t| self.viable(t, len)).map(|&t| Step::Eps(t)));
+            options.extend(
+                states[state].edges.iter().enumerate().filter(|(_, (_, t))| self.viable(*t, len + 1)).map(|(i, (_, t))| Step::Edge(i, *t)),
+            );
+            if options.is_empty() {
+                return false;
+            }
+            match options[rng.gen_range(0..options.len())] {
+                Step::Stop => return true,
+                Step::Eps(t) => state = t,
+                Step::Edge(i, t) => {
+                    let c = pick_allowed(&states[state].edges[i].0, rng, ctx);
+                    ctx.emit(c.encode_utf8(&mut [0; 4]));
+                    out.push(c);
+                    len += 1;
+                    state = t;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// A character set in pattern syntax: the literal for one character, else a class.
+fn edge_label(chars: &[char]) -> String {
+    let label = match chars {
+        [c] => Token::Literal(*c).to_pattern(),
+        chars => Token::Class(chars.into()).to_pattern(),
+    };
+    match label.char_indices().nth(MAX_EDGE_LABEL) {
+        Some((at, _)) => format!("{}…", &label[..at]),
+        None => label,
+    }
+}
@@ -0,0 +1,21 @@
+//! Generation callbacks, for feeding metrics or tracing systems of the caller's choice.
+
+use crate::stats::RejectReason;
+
+/// Receives the events of each generation call on a generator it was installed on with
+/// `RegexGeneratorBuilder::observer`. Every method does nothing by default.
+///
+/// Callbacks run synchronously on the generating thread, in the middle of generation, so they
+/// should be cheap: bump a counter, record a histogram sample. Clones and forks of the
+/// generator report to the same observer.
+pub trait Observer: Send + Sync {
+    /// A candidate is about to be built.
+    fn on_attempt(&self) {}
+
+    /// The candidate just built was discarded for `reason`.
+    fn on_reject(&self, _reason: RejectReason) {}
+
+    /// An output was accepted. `len` is measured in the unit of `min_len` and `max_len`;
+    /// `attempts` counts the candidates the call built, the accepted one included.
+    fn on_accept(&self, _len: usize, _attempts: u64) {}
+}
@@ -0,0 +1,164 @@
+//! Generators for the string fields of OpenAPI (and JSON Schema) schemas.
+//!
+//! A field's `pattern`, `minLength`, `maxLength`, `enum` and `format` are reconciled into one
+//! configured generator, so a mocking tool needs a single call per field:
+//!
+//! ```
+//! use genrex::openapi::{string_generator, StringSchema};
+//!
+//! let schema = StringSchema::new().pattern("^[A-Z]{2}[0-9]+$").max_length(6);
+//! let mut g = string_generator(&schema).unwrap();
+//! let s = g.generate_one().unwrap();
+//! assert!((3..=6).contains(&s.len()));
+//! ```
+
+use regex::Regex;
+
+use crate::presets;
+use crate::tokens::{write_sequence, Token};
+use crate::{Dialect, GenError, GeneratorConfig, RegexGenerator, RegexGeneratorBuilder};
+
+/// Extra length allowed when a schema sets a minimum but no maximum length.
+const DEFAULT_SPAN: usize = 10;
+
+/// Patterns for the `format` values genrex can generate.
+const FORMATS: &[(&str, &str)] = &[
+    ("uuid", presets::UUID.pattern),
+    ("ipv4", presets::IPV4.pattern),
+    ("ipv6", presets::IPV6.pattern),
+    ("email", presets::EMAIL.pattern),
+    ("idn-email", presets::EMAIL.pattern),
+    ("date-time", presets::ISO8601.pattern),
+    ("date", r"^(19[7-9][0-9]|20[0-9]{2})-(0[1-9]|1[0-2])-(0[1-9]|1[0-9]|2[0-8])$"),
+    ("time", r"^([01][0-9]|2[0-3]):[0-5][0-9]:[0-5][0-9](Z|[+-](0[0-9]|1[0-4]):(00|30|45))$"),
+    ("hostname", r"^[a-z][a-z0-9]{0,9}(\.[a-z][a-z0-9]{0,9}){1,2}$"),
+    ("uri", r"^https://[a-z]{3,10}\.(com|org|net)(/[a-z0-9]{1,8}){0,3}$"),
+    ("byte", r"^([A-Za-z0-9+/]{4}){1,8}([A-Za-z0-9+/]{2}==|[A-Za-z0-9+/]{3}=)?$"),
+    ("password", r"^[!-~]{12,20}$"),
+];
+
+/// The string keywords of a schema object. Lengths are compared in bytes, which equals the
+/// specification's characters for ASCII output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StringSchema {
+    /// A JavaScript regex (ECMA-262), as OpenAPI and JSON Schema specify.
+    pub pattern: Option<String>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    /// Allowed values; when non-empty, outputs are drawn from those that satisfy the other
+    /// keywords.
+    pub enum_values: Vec<String>,
+    /// Used when there is no pattern; unknown formats are ignored.
+    pub format: Option<String>,
+}
+
+impl StringSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(pattern.to_string());
+        self
+    }
+
+    pub fn min_length(mut self, len: usize) -> Self {
+        self.min_length = Some(len);
+        self
+    }
+
+    pub fn max_length(mut self, len: usize) -> Self {
+        self.max_length = Some(len);
+        self
+    }
+
+    pub fn enum_values<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, values: I) -> Self {
+        self.enum_values = values.into_iter().map(|v| v.as_ref().to_string()).collect();
+        self
+    }
+
+    pub fn format(mut self, format: &str) -> Self {
+        self.format = Some(format.to_string());
+        self
+    }
+
+    /// A builder with the pattern and length bounds reconciled, for adding a seed or other
+    /// options. Fails with `InvalidConfig` when no string satisfies every keyword.
+    pub fn builder(&self) -> Result<RegexGeneratorBuilder, GenError> {
+        let min_len = self.min_length.unwrap_or(0);
+        if self.max_length.is_some_and(|max| min_len > max) {
+            return Err(GenError::InvalidConfig(format!("minLength {} exceeds maxLength {}", min_len, self.max_length.unwrap_or(0))));
+        }
+        if !self.enum_values.is_empty() {
+            return self.enum_builder(min_len);
+        }
+        let format = self.format.as_deref().and_then(|f| FORMATS.iter().find(|(name, _)| *name == f));
+        let (builder, pattern) = match (&self.pattern, format) {
+            (Some(pattern), _) => {
+                let translated = Dialect::EcmaScript.to_regex(pattern)?;
+                (RegexGenerator::builder(pattern).dialect(Dialect::EcmaScript), translated)
+            }
+            (None, Some((_, pattern))) => (RegexGenerator::builder(pattern), pattern.to_string()),
+            (None, None) => {
+                let lo = self.min_length.unwrap_or(1).min(self.max_length.unwrap_or(usize::MAX));
+                let hi = self.max_length.unwrap_or(lo + DEFAULT_SPAN);
+                let pattern = format!("[A-Za-z0-9]{{{},{}}}", lo, hi);
+                (RegexGenerator::builder(&pattern), pattern)
+            }
+        };
+        // Lengths the pattern can produce, when the analysis handles it (no backreferences).
+        let (shortest, longest) = match crate::analyze(&pattern) {
+            Ok(a) => (a.min_len, a.max_len),
+            Err(_) => (0, None),
+        };
+        let max_len = match (self.max_length, longest) {
+            (Some(max), _) => max,
+            (None, Some(longest)) => longest,
+            (None, None) => GeneratorConfig::default().max_len.max(shortest.max(min_len) + DEFAULT_SPAN),
+        };
+        if shortest > max_len || longest.is_some_and(|longest| longest < min_len) {
+            return Err(GenError::InvalidConfig(format!(
+                "pattern generates {} to {} characters, outside the length bounds {} to {}",
+                shortest,
+                longest.map_or("unbounded".to_string(), |n| n.to_string()),
+                min_len,
+                max_len
+            )));
+        }
+        Ok(builder.config(GeneratorConfig { min_len, max_len, ..GeneratorConfig::default() }))
+    }
+
+    /// A generator for this schema; see `builder`.
+    pub fn generator(&self) -> Result<RegexGenerator, GenError> {
+        self.builder()?.build()
+    }
+
+    /// A builder choosing uniformly among the enum values that satisfy the other keywords.
+    fn enum_builder(&self, min_len: usize) -> Result<RegexGeneratorBuilder, GenError> {
+        let pattern = match &self.pattern {
+            Some(pattern) => Some(
+                Regex::new(&Dialect::EcmaScript.to_regex(pattern)?).map_err(|e| GenError::InvalidRegex(e.to_string()))?,
+            ),
+            None => None,
+        };
+        let max_len = self.max_length.unwrap_or(usize::MAX);
+        let values: Vec<&String> = self
+            .enum_values
+            .iter()
+            .filter(|v| (min_len..=max_len).contains(&v.len()) && pattern.as_ref().is_none_or(|re| re.is_match(v)))
+            .collect();
+        if values.is_empty() {
+            return Err(GenError::InvalidConfig("no enum value satisfies the pattern and length bounds".to_string()));
+        }
+        let branches = values.iter().map(|v| Token::Concatenation(v.chars().map(Token::Literal).collect())).collect();
+        let mut alternation = String::new();
+        write_sequence(&[Token::Alternation(branches)], &mut alternation);
+        let longest = values.iter().map(|v| v.len()).max().unwrap_or(0);
+        Ok(RegexGenerator::builder(&alternation).config(GeneratorConfig { min_len: 0, max_len: longest, ..GeneratorConfig::default() }))
+    }
+}
+
+/// A generator for one string field; shorthand for `schema.generator()`.
+pub fn string_generator(schema: &StringSchema) -> Result<RegexGenerator, GenError> {
+    schema.generator()
+}
@@ -0,0 +1,62 @@
+//! Structured outputs: generated values plus metadata about how they were produced.
+
+use crate::faults::FaultKind;
+
+/// Identifies which top-level alternation branch produced an output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchTag {
+    /// Zero-based branch index in source order.
+    pub index: usize,
+    /// User-assigned label for the branch, if labels were configured.
+    pub label: Option<String>,
+}
+
+/// A generated value together with metadata about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabeledOutput {
+    pub value: String,
+    /// `None` for valid outputs, `Some(kind)` for deliberately malformed ones.
+    pub fault: Option<FaultKind>,
+    /// The top-level alternation branch that produced the value, when branch tagging is enabled.
+    pub branch: Option<BranchTag>,
+}
+
+/// Capture groups of a generated value, by index or by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Captures {
+    /// Slot 0 is the whole value; slot `i` is group `i`, `None` if it did not participate.
+    values: Vec<Option<String>>,
+    /// Group names, aligned with `values`.
+    names: Vec<Option<String>>,
+}
+
+impl Captures {
+    pub(crate) fn new(values: Vec<Option<String>>, names: Vec<Option<String>>) -> Self {
+        Captures { values, names }
+    }
+
+    /// Text of group `index` (0 is the whole value).
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.values.get(index)?.as_deref()
+    }
+
+    /// Text of the group called `name`.
+    pub fn name(&self, name: &str) -> Option<&str> {
+        let index = self.names.iter().position(|n| n.as_deref() == Some(name))?;
+        self.get(index)
+    }
+
+    /// Number of slots, including slot 0.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// `(index, name, text)` for every slot, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Option<&str>, Option<&str>)> {
+        self.values.iter().zip(&self.names).enumerate().map(|(i, (v, n))| (i, n.as_deref(), v.as_deref()))
+    }
+}
@@ -60,24 +60,16 @@ impl<'a> AstParser<'a> {
             Token::WordBoundary => AstNode::WordBoundary,
             Token::Wildcard => AstNode::Wildcard,
             Token::Backreference(_idx) => AstNode::Backreference,
-            Token::Group(inner, _idx) => AstNode::Group(Box::new(
-                AstParser::new(&[(**inner).clone()]).parse().unwrap_or(AstNode::Literal(' '))
-            )),
-            Token::NonCapturingGroup(inner) => AstNode::NonCapturingGroup(Box::new(
-                AstParser::new(&[(**inner).clone()]).parse().unwrap_or(AstNode::Literal(' '))
-            )),
+            Token::Group(inner, _idx) => AstNode::Group(Box::new(nested(inner))),
+            Token::NonCapturingGroup(inner) => AstNode::NonCapturingGroup(Box::new(nested(inner))),
             Token::Quantifier { token, min, max, greedy } => AstNode::Repeat {
-                node: Box::new(
-                    AstParser::new(&[(**token).clone()]).parse().unwrap_or(AstNode::Literal(' '))
-                ),
+                node: Box::new(nested(token)),
                 min: *min,
                 max: *max,
                 greedy: *greedy,
             },
-            Token::Concatenation(tokens) => AstParser::new(tokens).parse().unwrap_or(AstNode::Literal(' ')),
-            Token::Alternation(tokens) => AstNode::Alternation(
-                tokens.iter().map(|t| AstParser::new(&[t.clone()]).parse().unwrap_or(AstNode::Literal(' '))).collect()
-            ),
+            Token::Concatenation(tokens) => AstParser::new(tokens).parse().unwrap_or(AstNode::Sequence(Vec::new())),
+            Token::Alternation(tokens) => AstNode::Alternation(tokens.iter().map(nested).collect()),
         };
         self.pos += 1;
         Some(node)
@@ -91,3 +83,9 @@ impl<'a> AstParser<'a> {
         self.pos >= self.tokens.len()
     }
 }
+
+/// Parse a nested token. An empty body, such as the empty branch of `(a|)` or the group `()`,
+/// is the empty sequence.
+fn nested(token: &Token) -> AstNode {
+    AstParser::new(std::slice::from_ref(token)).parse().unwrap_or(AstNode::Sequence(Vec::new()))
+}
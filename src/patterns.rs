@@ -0,0 +1,93 @@
+//! Curated corpus of canonical benchmark patterns.
+//!
+//! These patterns cover the shapes genrex is most often pointed at (structured identifiers,
+//! log lines, nested alternations, backreferences) and serve as realistic defaults when
+//! evaluating the effect of configuration changes on throughput and rejection rate.
+
+use crate::GeneratorConfig;
+
+/// A named, canonical pattern together with the length bounds it is tuned for.
+#[derive(Debug, Clone, Copy)]
+pub struct CanonicalPattern {
+    /// Short stable identifier (e.g. "uuid").
+    pub name: &'static str,
+    /// The regex source.
+    pub pattern: &'static str,
+    /// Human-readable summary of what the pattern stresses.
+    pub description: &'static str,
+    /// Minimum output length the pattern can produce.
+    pub min_len: usize,
+    /// Maximum output length the pattern can produce.
+    pub max_len: usize,
+    /// True when the pattern needs `allow_backrefs` to build.
+    pub needs_backrefs: bool,
+}
+
+impl CanonicalPattern {
+    /// A `GeneratorConfig` whose length bounds fit this pattern.
+    pub fn config(&self) -> GeneratorConfig {
+        GeneratorConfig {
+            min_len: self.min_len,
+            max_len: self.max_len,
+            ..GeneratorConfig::default()
+        }
+    }
+}
+
+/// A bounded email address with one of four TLDs.
+pub const EMAIL: CanonicalPattern = CanonicalPattern {
+    name: "email",
+    pattern: r"^[a-z0-9._]{1,16}@[a-z0-9]{1,12}\.(com|net|org|io)$",
+    description: "simple email address: bounded classes and a TLD alternation",
+    min_len: 6,
+    max_len: 33,
+    needs_backrefs: false,
+};
+
+/// A timestamped log line with a level, a component and a message.
+pub const LOG_LINE: CanonicalPattern = CanonicalPattern {
+    name: "log_line",
+    pattern: r"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2} (INFO|WARN|ERROR|DEBUG) [a-z]{3,10}: [a-zA-Z ]{5,40}$",
+    description: "timestamped log line: long concatenation of fixed-width fields",
+    min_len: 35,
+    max_len: 78,
+    needs_backrefs: false,
+};
+
+/// A lowercase version-4 UUID.
+pub const UUID: CanonicalPattern = CanonicalPattern {
+    name: "uuid",
+    pattern: r"^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$",
+    description: "UUIDv4: fixed length, repeated hex classes",
+    min_len: 36,
+    max_len: 36,
+    needs_backrefs: false,
+};
+
+/// A bounded repetition of nested alternations.
+pub const NESTED_ALTERNATION: CanonicalPattern = CanonicalPattern {
+    name: "nested_alternation",
+    pattern: r"^((a|b)(c|d)|(e|f)(g|h)|((i|j)|(k|l))m){1,8}$",
+    description: "deeply nested alternations under a bounded quantifier",
+    min_len: 2,
+    max_len: 16,
+    needs_backrefs: false,
+};
+
+/// Two alternation groups echoed back in reverse order by backreferences.
+pub const BACKREF_STRESS: CanonicalPattern = CanonicalPattern {
+    name: "backref_stress",
+    pattern: r"^(ab|cd)(ef|gh)[0-9]{2}\2\1$",
+    description: "multiple backreferences to alternation-bearing groups",
+    min_len: 10,
+    max_len: 10,
+    needs_backrefs: true,
+};
+
+/// All canonical patterns in a stable order.
+pub const ALL: &[CanonicalPattern] = &[EMAIL, LOG_LINE, UUID, NESTED_ALTERNATION, BACKREF_STRESS];
+
+/// Look up a canonical pattern by name.
+pub fn get(name: &str) -> Option<&'static CanonicalPattern> {
+    ALL.iter().find(|p| p.name == name)
+}
@@ -0,0 +1,201 @@
+//! Password-policy generation: strings over a charset that satisfy every requirement pattern,
+//! avoid banned substrings and fit length bounds, drawn uniformly from all such strings.
+//!
+//! The policy is compiled to one automaton by intersecting a DFA per rule, so generation never
+//! rejects a candidate, however unlikely a random string would be to pass every rule.
+
+use num_bigint::BigUint;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::analysis::{self, Entropy};
+use crate::dfa::Dfa;
+use crate::tokens::{Token, WILDCARD_CHARS};
+use crate::visit::{walk_token_mut, TokenVisitorMut};
+use crate::GenError;
+
+/// Printable ASCII without the space.
+const DEFAULT_CHARSET: &str = "[!-~]";
+
+/// Builder for a `PolicyGenerator`.
+///
+/// ```
+/// let mut g = genrex::PolicyBuilder::new()
+///     .length(12, 16)
+///     .require("[A-Z]")
+///     .require("[0-9].*[0-9]")
+///     .require("[!@#$%]")
+///     .ban("password")
+///     .seed(7)
+///     .build()
+///     .unwrap();
+/// let password = g.generate_one().unwrap();
+/// assert!((12..=16).contains(&password.len()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PolicyBuilder {
+    charset: String,
+    required: Vec<String>,
+    banned: Vec<String>,
+    min_len: usize,
+    max_len: usize,
+    seed: Option<u64>,
+}
+
+impl Default for PolicyBuilder {
+    fn default() -> Self {
+        PolicyBuilder {
+            charset: DEFAULT_CHARSET.to_string(),
+            required: Vec::new(),
+            banned: Vec::new(),
+            min_len: 12,
+            max_len: 20,
+            seed: None,
+        }
+    }
+}
+
+impl PolicyBuilder {
+    /// Printable ASCII without the space, 12 to 20 characters, no requirements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The characters outputs are made of, as a character class such as `[a-zA-Z0-9_]`.
+    pub fn charset(mut self, class: &str) -> Self {
+        self.charset = class.to_string();
+        self
+    }
+
+    /// Require a match of `pattern` somewhere in every output. Within it, `.` and negated
+    /// classes range over the charset.
+    pub fn require(mut self, pattern: &str) -> Self {
+        self.required.push(pattern.to_string());
+        self
+    }
+
+    /// Ban `substring` from every output (case-sensitive).
+    pub fn ban(mut self, substring: &str) -> Self {
+        self.banned.push(substring.to_string());
+        self
+    }
+
+    /// Ban each of `substrings`, e.g. a list of common passwords.
+    pub fn ban_all<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, substrings: I) -> Self {
+        self.banned.extend(substrings.into_iter().map(|s| s.as_ref().to_string()));
+        self
+    }
+
+    /// Output length bounds, in characters.
+    pub fn length(mut self, min_len: usize, max_len: usize) -> Self {
+        self.min_len = min_len;
+        self.max_len = max_len;
+        self
+    }
+
+    /// Seed the generator's RNG, for reproducible outputs.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Compile the policy. Fails with `InvalidConfig` when no string satisfies it, and with
+    /// `Unsupported` when a requirement cannot be compiled to an automaton (backreferences) or
+    /// the combined automaton grows too large.
+    pub fn build(self) -> Result<PolicyGenerator, GenError> {
+        if self.min_len > self.max_len {
+            return Err(GenError::InvalidConfig(format!("min_len {} exceeds max_len {}", self.min_len, self.max_len)));
+        }
+        let alphabet = charset(&self.charset)?;
+        let any = Token::Quantifier { token: Box::new(Token::Class(alphabet.as_slice().into())), min: 0, max: usize::MAX, greedy: true };
+
+        let mut dfas = vec![analysis::build_dfa(std::slice::from_ref(&any))?];
+        for pattern in &self.required {
+            let mut required = Token::Concatenation(crate::parse(pattern)?);
+            OverCharset(&alphabet).visit_token_mut(&mut required);
+            dfas.push(analysis::build_dfa(&[any.clone(), required, any.clone()])?);
+        }
+        for banned in &self.banned {
+            if banned.is_empty() {
+                return Err(GenError::InvalidConfig("banned substrings must not be empty".to_string()));
+            }
+            let mut tokens = vec![any.clone()];
+            tokens.extend(banned.chars().map(Token::Literal));
+            tokens.push(any.clone());
+            dfas.push(analysis::build_dfa(&tokens)?.complement(&alphabet));
+        }
+        let dfa = Dfa::intersect(&dfas)?;
+
+        let table = dfa.exact_counts(self.max_len);
+        let count: BigUint = dfa.start.map_or_else(BigUint::default, |s| table[self.min_len..].iter().map(|row| &row[s]).sum());
+        if count.bits() == 0 {
+            return Err(GenError::InvalidConfig(format!(
+                "no string of {} to {} characters satisfies the policy",
+                self.min_len, self.max_len
+            )));
+        }
+        let seed = self.seed.unwrap_or_else(rand::random);
+        Ok(PolicyGenerator { dfa, table, count, min_len: self.min_len, max_len: self.max_len, rng: StdRng::seed_from_u64(seed) })
+    }
+}
+
+/// Generates strings satisfying a password policy; see `PolicyBuilder`.
+///
+/// Every output is drawn uniformly from all strings that satisfy the policy, so `entropy()`
+/// is exactly the strength of a generated password.
+pub struct PolicyGenerator {
+    dfa: Dfa,
+    /// `dfa.exact_counts(max_len)`.
+    table: Vec<Vec<BigUint>>,
+    count: BigUint,
+    min_len: usize,
+    max_len: usize,
+    rng: StdRng,
+}
+
+impl PolicyGenerator {
+    pub fn generate_one(&mut self) -> Result<String, GenError> {
+        self.dfa.sample(&mut self.rng, &self.table, self.min_len, self.max_len).ok_or(GenError::NoMatch)
+    }
+
+    pub fn generate_n(&mut self, n: usize) -> Result<Vec<String>, GenError> {
+        (0..n).map(|_| self.generate_one()).collect()
+    }
+
+    /// Number of distinct strings that satisfy the policy.
+    pub fn count(&self) -> &BigUint {
+        &self.count
+    }
+
+    /// Entropy of an output. Sampling is uniform, so both figures are `log2(count())`.
+    pub fn entropy(&self) -> Entropy {
+        let bits = analysis::log2(&self.count);
+        Entropy { uniform_bits: bits, sampled_bits: Some(bits) }
+    }
+}
+
+/// The characters, sorted, of a charset given as a class, a single literal or `.`.
+fn charset(class: &str) -> Result<Vec<char>, GenError> {
+    let mut chars = match crate::parse(class)?.as_slice() {
+        [Token::Class(chars)] if !chars.is_empty() => chars.to_vec(),
+        [Token::Literal(c)] => vec![*c],
+        [Token::Wildcard] => WILDCARD_CHARS.to_vec(),
+        _ => return Err(GenError::InvalidConfig(format!("charset must be a non-empty character class, got '{}'", class))),
+    };
+    chars.sort_unstable();
+    chars.dedup();
+    Ok(chars)
+}
+
+/// Rewrites `.` and negated classes in a requirement to range over the charset.
+struct OverCharset<'a>(&'a [char]);
+
+impl TokenVisitorMut for OverCharset<'_> {
+    fn visit_token_mut(&mut self, token: &mut Token) {
+        match token {
+            Token::Wildcard => *token = Token::Class(self.0.into()),
+            Token::NegatedClass(excluded) => *token = Token::Class(self.0.iter().copied().filter(|c| !excluded.contains(c)).collect()),
+            _ => walk_token_mut(self, token),
+        }
+    }
+}
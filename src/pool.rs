@@ -0,0 +1,134 @@
+//! A pool of pre-generated values kept topped up by a background thread.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::{GenError, RegexGenerator};
+
+struct State {
+    ready: VecDeque<String>,
+    /// Set when the generator fails; the refill thread stops and the error is reported by `take`.
+    error: Option<GenError>,
+    shutdown: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Signalled when a value is added or the refill thread stops.
+    filled: Condvar,
+    /// Signalled when a value is taken or shutdown is requested.
+    drained: Condvar,
+}
+
+/// Keeps up to `capacity` generated strings ready so callers can take one in O(1) without
+/// paying for generation (and possible retries) inline.
+///
+/// The generator runs on a background thread that refills the pool whenever it drops below
+/// capacity. If generation fails, refilling stops and the error is returned once the remaining
+/// values are used up. Dropping the pool stops and joins the thread.
+pub struct PrefetchPool {
+    shared: Arc<Shared>,
+    capacity: usize,
+    worker: Option<JoinHandle<RegexGenerator>>,
+}
+
+impl PrefetchPool {
+    pub fn new(mut generator: RegexGenerator, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State { ready: VecDeque::with_capacity(capacity), error: None, shutdown: false }),
+            filled: Condvar::new(),
+            drained: Condvar::new(),
+        });
+        let worker_shared = Arc::clone(&shared);
+        let worker = thread::spawn(move || {
+            loop {
+                {
+                    let mut state = worker_shared.state.lock().unwrap();
+                    while !state.shutdown && state.ready.len() >= capacity {
+                        state = worker_shared.drained.wait(state).unwrap();
+                    }
+                    if state.shutdown {
+                        break;
+                    }
+                }
+                // Generate without holding the lock so `take` never waits on generation.
+                let result = generator.generate_one();
+                let mut state = worker_shared.state.lock().unwrap();
+                match result {
+                    Ok(s) => state.ready.push_back(s),
+                    Err(e) => {
+                        state.error = Some(e);
+                        state.shutdown = true;
+                        worker_shared.filled.notify_all();
+                        break;
+                    }
+                }
+                worker_shared.filled.notify_one();
+            }
+            generator
+        });
+        PrefetchPool { shared, capacity, worker: Some(worker) }
+    }
+
+    /// Take a ready value without blocking. `None` when the pool is momentarily empty.
+    pub fn try_take(&self) -> Option<String> {
+        let value = self.shared.state.lock().unwrap().ready.pop_front();
+        if value.is_some() {
+            self.shared.drained.notify_one();
+        }
+        value
+    }
+
+    /// Take a ready value, waiting for the refill thread if the pool is empty.
+    ///
+    /// Returns the generator's error once refilling has failed and the pool is exhausted.
+    pub fn take(&self) -> Result<String, GenError> {
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            if let Some(s) = state.ready.pop_front() {
+                self.shared.drained.notify_one();
+                return Ok(s);
+            }
+            if let Some(e) = &state.error {
+                return Err(e.clone());
+            }
+            if state.shutdown {
+                return Err(GenError::NoMatch);
+            }
+            state = self.shared.filled.wait(state).unwrap();
+        }
+    }
+
+    /// Number of values ready right now.
+    pub fn len(&self) -> usize {
+        self.shared.state.lock().unwrap().ready.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Stop refilling and recover the generator (for example to inspect its stats).
+    /// Values still in the pool are discarded.
+    pub fn into_generator(mut self) -> RegexGenerator {
+        self.stop().unwrap_or_default()
+    }
+
+    fn stop(&mut self) -> Option<RegexGenerator> {
+        self.shared.state.lock().unwrap().shutdown = true;
+        self.shared.drained.notify_all();
+        self.worker.take().and_then(|w| w.join().ok())
+    }
+}
+
+impl Drop for PrefetchPool {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
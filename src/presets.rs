@@ -0,0 +1,117 @@
+//! Vetted patterns for common data types, with length bounds tuned to each.
+//!
+//! Every output of a preset is valid for its type, not just well-formed: IPv4 octets stay within
+//! 0-255, dates only use days that exist in every month, and so on.
+
+use crate::{GenError, GeneratorConfig, RegexGeneratorBuilder};
+
+/// A named pattern for one data type together with the length bounds of its outputs.
+#[derive(Debug, Clone, Copy)]
+pub struct Preset {
+    /// Short stable identifier (e.g. "uuid"), used as `@uuid` on the command line.
+    pub name: &'static str,
+    /// The regex source.
+    pub pattern: &'static str,
+    /// Human-readable summary of what the outputs look like.
+    pub description: &'static str,
+    /// Minimum output length in bytes.
+    pub min_len: usize,
+    /// Maximum output length in bytes.
+    pub max_len: usize,
+}
+
+impl Preset {
+    /// A `GeneratorConfig` whose length bounds fit this preset.
+    pub fn config(&self) -> GeneratorConfig {
+        GeneratorConfig {
+            min_len: self.min_len,
+            max_len: self.max_len,
+            ..GeneratorConfig::default()
+        }
+    }
+
+    /// A builder for this preset's pattern with its config applied.
+    pub fn builder(&self) -> RegexGeneratorBuilder {
+        RegexGeneratorBuilder::new(self.pattern).config(self.config())
+    }
+}
+
+pub const UUID: Preset = Preset {
+    name: "uuid",
+    pattern: r"^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$",
+    description: "random (version 4) UUID in lowercase hex",
+    min_len: 36,
+    max_len: 36,
+};
+
+pub const IPV4: Preset = Preset {
+    name: "ipv4",
+    pattern: r"^(25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])(\.(25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])){3}$",
+    description: "dotted-quad IPv4 address without leading zeros",
+    min_len: 7,
+    max_len: 15,
+};
+
+pub const IPV6: Preset = Preset {
+    name: "ipv6",
+    pattern: r"^[0-9a-f]{1,4}(:[0-9a-f]{1,4}){7}$",
+    description: "IPv6 address in full (uncompressed) form, lowercase hex",
+    min_len: 15,
+    max_len: 39,
+};
+
+pub const EMAIL: Preset = Preset {
+    name: "email",
+    pattern: r"^[a-z0-9]{1,8}([._-][a-z0-9]{1,8}){0,2}@[a-z0-9]{1,12}(-[a-z0-9]{1,8})?\.(com|net|org|io|dev)$",
+    description: "email address with dot, underscore or dash separated local parts",
+    min_len: 6,
+    max_len: 52,
+};
+
+pub const E164: Preset = Preset {
+    name: "e164",
+    pattern: r"^\+[1-9][0-9]{6,14}$",
+    description: "E.164 phone number: a plus sign and 7 to 15 digits",
+    min_len: 8,
+    max_len: 16,
+};
+
+pub const ISO8601: Preset = Preset {
+    name: "iso8601",
+    pattern: r"^(19[7-9][0-9]|20[0-9]{2})-(0[1-9]|1[0-2])-(0[1-9]|1[0-9]|2[0-8])T([01][0-9]|2[0-3]):[0-5][0-9]:[0-5][0-9](Z|[+-](0[0-9]|1[0-4]):(00|30|45))$",
+    description: "ISO-8601 datetime from 1970-2099 with a UTC or offset zone; days stop at 28",
+    min_len: 20,
+    max_len: 25,
+};
+
+pub const MAC: Preset = Preset {
+    name: "mac",
+    pattern: r"^[0-9a-f]{2}(:[0-9a-f]{2}){5}$",
+    description: "colon-separated MAC address, lowercase hex",
+    min_len: 17,
+    max_len: 17,
+};
+
+pub const SEMVER: Preset = Preset {
+    name: "semver",
+    pattern: r"^(0|[1-9][0-9]{0,2})\.(0|[1-9][0-9]{0,2})\.(0|[1-9][0-9]{0,2})(-(alpha|beta|rc)\.(0|[1-9][0-9]?))?$",
+    description: "semantic version with an optional alpha, beta or rc pre-release",
+    min_len: 5,
+    max_len: 20,
+};
+
+/// All presets in a stable order.
+pub const ALL: &[Preset] = &[UUID, IPV4, IPV6, EMAIL, E164, ISO8601, MAC, SEMVER];
+
+/// Look up a preset by name.
+pub fn get(name: &str) -> Option<&'static Preset> {
+    ALL.iter().find(|p| p.name == name)
+}
+
+/// A builder for the preset called `name`, with its length bounds applied.
+pub fn preset(name: &str) -> Result<RegexGeneratorBuilder, GenError> {
+    get(name).map(Preset::builder).ok_or_else(|| {
+        let names: Vec<&str> = ALL.iter().map(|p| p.name).collect();
+        GenError::InvalidConfig(format!("unknown preset '{}', expected one of: {}", name, names.join(", ")))
+    })
+}
@@ -0,0 +1,100 @@
+//! Indented tree rendering of token sequences, for debugging what a pattern parsed into.
+
+use std::fmt;
+
+use crate::tokens::Token;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BLUE: &str = "\x1b[34m";
+const MAGENTA: &str = "\x1b[35m";
+const CYAN: &str = "\x1b[36m";
+
+/// Displays tokens as a tree, one node per line: the kind of node, its group number or repeat
+/// bounds, and the pattern text it came from.
+///
+/// ```text
+/// concat
+/// ├── anchor ^
+/// ├── group #1  (a|bc)
+/// │   └── alternation  a|bc
+/// ...
+/// ```
+pub struct TokenTree<'a> {
+    tokens: &'a [Token],
+    color: bool,
+}
+
+impl<'a> TokenTree<'a> {
+    pub fn new(tokens: &'a [Token]) -> Self {
+        TokenTree { tokens, color: false }
+    }
+
+    /// Highlight node kinds with ANSI colors.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.color { format!("{}{}{}", color, text, RESET) } else { text.to_string() }
+    }
+
+    /// The label of one node, without its children.
+    fn label(&self, token: &Token) -> String {
+        let text = token.to_pattern();
+        match token {
+            Token::Literal(_) => format!("{} {}", self.paint(GREEN, "literal"), text),
+            Token::Class(_) => format!("{} {}", self.paint(CYAN, "class"), text),
+            Token::NegatedClass(_) => format!("{} {}", self.paint(CYAN, "negated class"), text),
+            Token::Wildcard => format!("{} {}", self.paint(CYAN, "any"), text),
+            Token::Concatenation(_) => format!("{}  {}", self.paint(BOLD, "concat"), text),
+            Token::Alternation(branches) => format!("{} of {}  {}", self.paint(BOLD, "alternation"), branches.len(), text),
+            Token::Quantifier { min, max, greedy, .. } => {
+                let bounds = match *max {
+                    usize::MAX => format!("{{{},}}", min),
+                    max if max == *min => format!("{{{}}}", min),
+                    max => format!("{{{},{}}}", min, max),
+                };
+                let lazy = if *greedy { "" } else { " lazy" };
+                format!("{} {}{}  {}", self.paint(YELLOW, "repeat"), self.paint(YELLOW, &bounds), lazy, text)
+            }
+            Token::Group(_, index) => format!("{}  {}", self.paint(MAGENTA, &format!("group #{}", index)), text),
+            Token::NonCapturingGroup(_) => format!("{}  {}", self.paint(MAGENTA, "group (non-capturing)"), text),
+            Token::Backreference(index) => format!("{} {}", self.paint(MAGENTA, &format!("backreference #{}", index)), text),
+            Token::AnchorStart | Token::AnchorEnd => format!("{} {}", self.paint(BLUE, "anchor"), text),
+            Token::WordBoundary => format!("{} {}", self.paint(BLUE, "word boundary"), text),
+        }
+    }
+
+    fn write_node(&self, f: &mut fmt::Formatter<'_>, token: &Token, prefix: &str, last: bool, root: bool) -> fmt::Result {
+        let (branch, indent) = match (root, last) {
+            (true, _) => ("", ""),
+            (false, false) => ("├── ", "│   "),
+            (false, true) => ("└── ", "    "),
+        };
+        writeln!(f, "{}{}{}", prefix, branch, self.label(token))?;
+        let prefix = format!("{}{}", prefix, indent);
+        let children: &[Token] = match token {
+            Token::Concatenation(children) | Token::Alternation(children) => children,
+            Token::Quantifier { token: inner, .. } | Token::Group(inner, _) | Token::NonCapturingGroup(inner) => std::slice::from_ref(&**inner),
+            _ => &[],
+        };
+        for (i, child) in children.iter().enumerate() {
+            self.write_node(f, child, &prefix, i + 1 == children.len(), false)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TokenTree<'_> {
+    /// A top-level sequence of several tokens is shown under one `concat` root.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tokens {
+            [token] => self.write_node(f, token, "", true, true),
+            tokens => self.write_node(f, &Token::Concatenation(tokens.to_vec()), "", true, true),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! The token tree compiled to a flat generation program.
+//!
+//! Tokens are laid out in pre-order, each node followed by its children and recording where its
+//! subtree ends. `Program::run` executes one top-level token with an explicit stack of open
+//! concatenations, repetitions and groups instead of recursing through `Token::generate`, making
+//! the same random draws in the same order, so seeded outputs do not depend on which is used.
+//!
+//! Runs of literals inside a concatenation are fused into one instruction appended with a single
+//! `push_str`, and classes of up to `INLINE_CLASS` characters are stored inline.
+
+use std::fmt::Write;
+
+use rand::Rng;
+use smallvec::SmallVec;
+
+use crate::constraints::GroupOverride;
+use crate::error::GenrexError;
+use crate::explain::Choice;
+use crate::tokens::{pick_allowed, Token, WILDCARD_CHARS};
+use crate::traits::TokenContext;
+
+/// Classes up to this size (digits, hex, most hand-written sets) need no allocation.
+const INLINE_CLASS: usize = 16;
+
+/// One node of the program; children start at the next index.
+#[derive(Debug, Clone)]
+enum Inst {
+    Literal(char),
+    /// Two or more consecutive literals.
+    Literals(Box<str>),
+    Class(SmallVec<[char; INLINE_CLASS]>),
+    NegatedClass,
+    Wildcard,
+    /// Anchors and word boundaries, which generate nothing.
+    Empty,
+    Backreference(usize),
+    Concat,
+    /// Start of each branch.
+    Alternation(Vec<usize>),
+    Repeat { min: usize, max: usize, greedy: bool },
+    Group(usize),
+    NonCapturingGroup,
+}
+
+/// Work left in a node whose children are running.
+enum Frame {
+    /// `child` is running; the concatenation's children end at `end`.
+    Concat { start: usize, child: usize, end: usize },
+    /// `body` is running, with `left` more repetitions after it.
+    Repeat { start: usize, left: usize, body: usize },
+    /// Capture group `idx`, replaced by `counter` once its contents are known.
+    Group { idx: usize, start: usize, counter: Option<u64> },
+}
+
+/// Compiled form of a token sequence, for the token engine.
+#[derive(Debug, Clone)]
+pub(crate) struct Program {
+    insts: Vec<Inst>,
+    /// Index one past the subtree of each node.
+    ends: Vec<usize>,
+    /// Entry point of each top-level token.
+    roots: Vec<usize>,
+}
+
+impl Program {
+    pub(crate) fn compile(tokens: &[Token]) -> Program {
+        let mut program = Program { insts: Vec::new(), ends: Vec::new(), roots: Vec::new() };
+        for token in tokens {
+            let root = program.insts.len();
+            program.roots.push(root);
+            program.emit(token);
+        }
+        program
+    }
+
+    fn emit(&mut self, token: &Token) {
+        let at = self.insts.len();
+        let (inst, children): (Inst, &[Token]) = match token {
+            Token::Literal(c) => (Inst::Literal(*c), &[]),
+            Token::Class(chars) => (Inst::Class(SmallVec::from_slice(chars)), &[]),
+            Token::NegatedClass(_) => (Inst::NegatedClass, &[]),
+            Token::Wildcard => (Inst::Wildcard, &[]),
+            Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary => (Inst::Empty, &[]),
+            Token::Backreference(idx) => (Inst::Backreference(*idx), &[]),
+            Token::Concatenation(tokens) => (Inst::Concat, tokens),
+            // Branch starts are filled in below, once known.
+            Token::Alternation(choices) => (Inst::Alternation(Vec::new()), choices),
+            Token::Quantifier { token, min, max, greedy } => {
+                (Inst::Repeat { min: *min, max: *max, greedy: *greedy }, std::slice::from_ref(&**token))
+            }
+            Token::Group(inner, idx) => (Inst::Group(*idx), std::slice::from_ref(&**inner)),
+            Token::NonCapturingGroup(inner) => (Inst::NonCapturingGroup, std::slice::from_ref(&**inner)),
+        };
+        self.insts.push(inst);
+        self.ends.push(at);
+        let mut starts = Vec::with_capacity(children.len());
+        let mut rest = children;
+        while let Some(child) = rest.first() {
+            starts.push(self.insts.len());
+            let run = rest.iter().take_while(|t| matches!(t, Token::Literal(_))).count();
+            if run > 1 && matches!(token, Token::Concatenation(_)) {
+                let literals = rest[..run].iter().filter_map(|t| if let Token::Literal(c) = t { Some(*c) } else { None });
+                self.insts.push(Inst::Literals(literals.collect()));
+                self.ends.push(self.insts.len());
+                rest = &rest[run..];
+            } else {
+                self.emit(child);
+                rest = &rest[1..];
+            }
+        }
+        if let Inst::Alternation(branches) = &mut self.insts[at] {
+            *branches = starts;
+        }
+        self.ends[at] = self.insts.len();
+    }
+
+    /// Append the output of top-level token `index` to `out`, as `Token::generate_into` would.
+    /// Not traced; traced generation uses the tokens themselves.
+    pub(crate) fn run<R: Rng + ?Sized>(&self, index: usize, rng: &mut R, ctx: &mut TokenContext, out: &mut String) -> Result<(), GenrexError> {
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut pc = self.roots[index];
+        'nodes: loop {
+            if ctx.cancelled() {
+                return Err(GenrexError::Cancelled);
+            }
+            if !ctx.burn() {
+                return Err(GenrexError::BudgetExhausted);
+            }
+            // Start node `pc`: leaves finish at once, other nodes continue with a child.
+            match &self.insts[pc] {
+                Inst::Literal(c) => {
+                    ctx.emit(c.encode_utf8(&mut [0; 4]));
+                    out.push(*c);
+                }
+                Inst::Literals(s) => {
+                    // Fuel as for the literals one by one.
+                    if !ctx.burn_n(s.chars().count() as u64 - 1) {
+                        return Err(GenrexError::BudgetExhausted);
+                    }
+                    ctx.emit(s);
+                    out.push_str(s);
+                }
+                Inst::Class(chars) => {
+                    if chars.is_empty() {
+                        return Err(GenrexError::Internal("Empty class".to_string()));
+                    }
+                    let c = pick_allowed(chars, rng, ctx);
+                    ctx.emit(c.encode_utf8(&mut [0; 4]));
+                    out.push(c);
+                }
+                Inst::NegatedClass => return Err(GenrexError::UnsupportedFeature("Negated class generation".to_string())),
+                Inst::Wildcard => {
+                    let c = pick_allowed(WILDCARD_CHARS, rng, ctx);
+                    ctx.emit(c.encode_utf8(&mut [0; 4]));
+                    out.push(c);
+                }
+                Inst::Empty => {}
+                Inst::Backreference(idx) => {
+                    if *idx == 0 {
+                        return Err(GenrexError::BackreferenceError("backreference index 0 is invalid".to_string()));
+                    }
+                    if ctx.captures.is_empty() {
+                        return Err(GenrexError::BackreferenceError(format!("no capture available for backreference \\{}", idx)));
+                    }
+                    match ctx.get_capture(*idx) {
+                        Some(s) => {
+                            ctx.emit(&s);
+                            out.push_str(&s);
+                        }
+                        None => ctx.add_unresolved(*idx),
+                    }
+                }
+                Inst::Concat => {
+                    let end = self.ends[pc];
+                    if pc + 1 < end {
+                        ctx.set_output_len(0);
+                        stack.push(Frame::Concat { start: out.len(), child: pc + 1, end });
+                        pc += 1;
+                        continue 'nodes;
+                    }
+                }
+                Inst::Alternation(branches) => {
+                    if branches.is_empty() {
+                        return Err(GenrexError::Internal("Empty alternation".to_string()));
+                    }
+                    let idx = rng.gen_range(0..branches.len());
+                    ctx.first_branch.get_or_insert(idx);
+                    ctx.trace_choice(Choice::Branch(idx));
+                    ctx.set_output_len(0);
+                    pc = branches[idx];
+                    continue 'nodes;
+                }
+                Inst::Repeat { min, max, greedy } => {
+                    // Same bounds and bias as `Token::Quantifier`.
+                    if min > max {
+                        return Err(GenrexError::Internal("Quantifier min > max".to_string()));
+                    }
+                    let effective_max = if *max == usize::MAX { (*min).saturating_add(ctx.max_repeat) } else { *max };
+                    let count = if min == max {
+                        *min
+                    } else {
+                        let a = rng.gen_range(*min..=effective_max);
+                        let b = rng.gen_range(*min..=effective_max);
+                        if *greedy { a.max(b) } else { a.min(b) }
+                    };
+                    ctx.trace_choice(Choice::Repeat(count));
+                    if count > 0 {
+                        ctx.set_output_len(0);
+                        stack.push(Frame::Repeat { start: out.len(), left: count - 1, body: pc + 1 });
+                        pc += 1;
+                        continue 'nodes;
+                    }
+                }
+                Inst::Group(idx) => {
+                    ctx.set_output_len(0);
+                    if ctx.group_overrides.contains_key(idx) {
+                        ctx.trace_choice(Choice::Override);
+                    }
+                    let start = out.len();
+                    match ctx.group_overrides.get(idx) {
+                        Some(GroupOverride::Fixed(v)) => {
+                            let v = v.clone();
+                            ctx.emit(&v);
+                            out.push_str(&v);
+                            ctx.record_capture(*idx, v);
+                        }
+                        Some(GroupOverride::Counter(n)) => {
+                            stack.push(Frame::Group { idx: *idx, start, counter: Some(*n) });
+                            pc += 1;
+                            continue 'nodes;
+                        }
+                        None => {
+                            stack.push(Frame::Group { idx: *idx, start, counter: None });
+                            pc += 1;
+                            continue 'nodes;
+                        }
+                    }
+                }
+                Inst::NonCapturingGroup => {
+                    ctx.set_output_len(0);
+                    pc += 1;
+                    continue 'nodes;
+                }
+            }
+            // Node finished: resume the innermost node with children still to run.
+            while let Some(frame) = stack.last_mut() {
+                match frame {
+                    Frame::Concat { start, child, end } => {
+                        if ctx.over_limit() {
+                            return Err(GenrexError::NoMatch);
+                        }
+                        let next = self.ends[*child];
+                        if next < *end {
+                            *child = next;
+                            ctx.set_output_len(out.len() - *start);
+                            pc = next;
+                            continue 'nodes;
+                        }
+                    }
+                    Frame::Repeat { start, left, body } => {
+                        if ctx.over_limit() {
+                            return Err(GenrexError::NoMatch);
+                        }
+                        if *left > 0 {
+                            *left -= 1;
+                            ctx.set_output_len(out.len() - *start);
+                            pc = *body;
+                            continue 'nodes;
+                        }
+                    }
+                    Frame::Group { idx, start, counter } => {
+                        if let Some(n) = *counter {
+                            let width = out[*start..].chars().count();
+                            out.truncate(*start);
+                            let _ = write!(out, "{:0width$}", n, width = width);
+                        }
+                        ctx.record_capture(*idx, out[*start..].to_string());
+                    }
+                }
+                stack.pop();
+            }
+            return Ok(());
+        }
+    }
+}
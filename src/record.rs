@@ -0,0 +1,185 @@
+//! Table generation: rows of named fields, each generated from its own pattern.
+
+use std::collections::{HashMap, HashSet};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::batch::derive_seed;
+use crate::{GenError, RegexGenerator};
+
+/// Draws per value of a unique field before giving up on finding an unused one.
+const MAX_UNIQUE_DRAWS: usize = 1000;
+
+/// Options for one field of a `RecordGenerator`.
+pub struct FieldSpec {
+    name: String,
+    generator: RegexGenerator,
+    unique: bool,
+    null_rate: f64,
+    seen: HashSet<String>,
+}
+
+impl FieldSpec {
+    /// Never repeat a value of this field across the rows of the generator.
+    pub fn unique(&mut self) -> &mut Self {
+        self.unique = true;
+        self
+    }
+
+    /// Leave this field null in roughly `rate` (0.0..=1.0) of the rows.
+    pub fn nullable(&mut self, rate: f64) -> &mut Self {
+        self.null_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Generates rows whose fields each come from their own pattern, for seeding databases and
+/// fixtures. Fields keep the order they were added in.
+///
+/// Nulls are decided with an RNG seeded from the seed given to `new`; fields added with
+/// `add_field` get generators seeded from it too, so the rows are reproducible.
+pub struct RecordGenerator {
+    fields: Vec<FieldSpec>,
+    seed: u64,
+    rng: StdRng,
+}
+
+impl RecordGenerator {
+    pub fn new(seed: u64) -> Self {
+        RecordGenerator { fields: Vec::new(), seed, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    /// Add field `name` generated from `pattern` with default settings and a derived seed.
+    pub fn add_field(&mut self, name: &str, pattern: &str) -> Result<&mut FieldSpec, GenError> {
+        let seed = derive_seed(self.seed, self.fields.len() as u64);
+        let generator = RegexGenerator::builder(pattern).rng(StdRng::seed_from_u64(seed)).build()?;
+        self.add_generator(name, generator)
+    }
+
+    /// Add field `name` generated by a configured generator.
+    pub fn add_generator(&mut self, name: &str, generator: RegexGenerator) -> Result<&mut FieldSpec, GenError> {
+        if self.fields.iter().any(|f| f.name == name) {
+            return Err(GenError::InvalidConfig(format!("duplicate field '{}'", name)));
+        }
+        self.fields.push(FieldSpec { name: name.to_string(), generator, unique: false, null_rate: 0.0, seen: HashSet::new() });
+        Ok(self.fields.last_mut().expect("field just added"))
+    }
+
+    /// Field names in column order.
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|f| f.name.as_str())
+    }
+
+    /// Generate one row as values in column order, `None` for nulls.
+    pub fn generate_values(&mut self) -> Result<Vec<Option<String>>, GenError> {
+        let mut values = Vec::with_capacity(self.fields.len());
+        for field in &mut self.fields {
+            if field.null_rate > 0.0 && self.rng.gen_bool(field.null_rate) {
+                values.push(None);
+                continue;
+            }
+            values.push(Some(next_value(field)?));
+        }
+        Ok(values)
+    }
+
+    /// Generate one row keyed by field name; null fields are left out.
+    pub fn generate_row(&mut self) -> Result<HashMap<String, String>, GenError> {
+        let values = self.generate_values()?;
+        Ok(self.fields.iter().zip(values).filter_map(|(f, v)| Some((f.name.clone(), v?))).collect())
+    }
+
+    /// Generate `n` rows keyed by field name.
+    pub fn generate_rows(&mut self, n: usize) -> Result<Vec<HashMap<String, String>>, GenError> {
+        (0..n).map(|_| self.generate_row()).collect()
+    }
+
+    /// Generate one row and deserialize it into `T`. Fields deserialize as strings, or as numbers
+    /// and booleans by parsing the generated text; null fields are absent, so they suit `Option`
+    /// members.
+    #[cfg(feature = "serde")]
+    pub fn generate_as<T: serde::de::DeserializeOwned>(&mut self) -> Result<T, GenError> {
+        use serde::de::value::{Error, MapDeserializer};
+        let row = self.generate_row()?.into_iter().map(|(name, value)| (name, de::FieldValue(value)));
+        T::deserialize(MapDeserializer::<_, Error>::new(row))
+            .map_err(|e| GenError::InvalidConfig(format!("row does not deserialize: {}", e)))
+    }
+}
+
+/// The next value of `field`, drawing until an unused one turns up for unique fields.
+fn next_value(field: &mut FieldSpec) -> Result<String, GenError> {
+    if !field.unique {
+        return field.generator.generate_one();
+    }
+    for _ in 0..MAX_UNIQUE_DRAWS {
+        let value = field.generator.generate_one()?;
+        if field.seen.insert(value.clone()) {
+            return Ok(value);
+        }
+    }
+    Err(GenError::InvalidConfig(format!("no unused value found for unique field '{}' after {} draws", field.name, MAX_UNIQUE_DRAWS)))
+}
+
+#[cfg(feature = "serde")]
+mod de {
+    use serde::de::value::Error;
+    use serde::de::{self, Deserializer, IntoDeserializer, Visitor};
+
+    /// A generated field value: a string that also parses into numbers and booleans.
+    pub(super) struct FieldValue(pub(super) String);
+
+    impl<'de> IntoDeserializer<'de, Error> for FieldValue {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self {
+            self
+        }
+    }
+
+    macro_rules! parse_as {
+        ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {$(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                let value: $ty = self.0.parse().map_err(|_| {
+                    de::Error::invalid_value(de::Unexpected::Str(&self.0), &stringify!($ty))
+                })?;
+                visitor.$visit(value)
+            }
+        )*};
+    }
+
+    impl<'de> Deserializer<'de> for FieldValue {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_string(self.0)
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_some(self)
+        }
+
+        parse_as! {
+            deserialize_bool => visit_bool: bool,
+            deserialize_i8 => visit_i8: i8,
+            deserialize_i16 => visit_i16: i16,
+            deserialize_i32 => visit_i32: i32,
+            deserialize_i64 => visit_i64: i64,
+            deserialize_u8 => visit_u8: u8,
+            deserialize_u16 => visit_u16: u16,
+            deserialize_u32 => visit_u32: u32,
+            deserialize_u64 => visit_u64: u64,
+            deserialize_f32 => visit_f32: f32,
+            deserialize_f64 => visit_f64: f64,
+        }
+
+        serde::forward_to_deserialize_any! {
+            i128 u128 char str string bytes byte_buf unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+}
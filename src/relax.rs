@@ -0,0 +1,95 @@
+//! Automatic retries with looser settings when a generation call finds no match.
+
+use crate::stats::Engine;
+use crate::GeneratorConfig;
+
+/// How to retry a call that failed with `GenError::NoMatch`, set with
+/// `RegexGeneratorBuilder::relax`. Each round starts over from the configured settings and
+/// loosens them further than the round before; the first output found is returned, and
+/// `RegexGenerator::last_relaxations` reports what it took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelaxPolicy {
+    /// Rounds tried after the first failure.
+    pub rounds: usize,
+    /// Percent by which `min_len` shrinks and `max_len` grows per round (by at least one).
+    pub widen_percent: Option<usize>,
+    /// Factor by which `max_attempts` grows per round.
+    pub attempts_factor: Option<usize>,
+    /// Factor by which `max_repeat` grows per round.
+    pub repeat_factor: Option<usize>,
+    /// Generate from the tokens instead of by NFA walks when retrying.
+    pub switch_engine: bool,
+}
+
+/// A loosened setting in effect for the last output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relaxation {
+    /// Lengths were widened to these bounds.
+    Lengths { min_len: usize, max_len: usize },
+    /// Up to this many candidates were tried.
+    Attempts(usize),
+    /// Open-ended quantifiers repeated up to this many times beyond their minimum.
+    MaxRepeat(usize),
+    /// This engine was used instead of the configured one.
+    Engine(Engine),
+}
+
+impl RelaxPolicy {
+    /// Retry up to `rounds` times. Nothing is loosened until a relaxation is added.
+    pub fn new(rounds: usize) -> Self {
+        RelaxPolicy { rounds, widen_percent: None, attempts_factor: None, repeat_factor: None, switch_engine: false }
+    }
+
+    /// Widen the length bounds by `percent` of their value per round.
+    pub fn widen_lengths(mut self, percent: usize) -> Self {
+        self.widen_percent = Some(percent);
+        self
+    }
+
+    /// Multiply `max_attempts` by `factor` per round.
+    pub fn more_attempts(mut self, factor: usize) -> Self {
+        self.attempts_factor = Some(factor);
+        self
+    }
+
+    /// Multiply `max_repeat` by `factor` per round. Only token generation counts repetitions,
+    /// so pair this with `switch_engine` for patterns generated by NFA walks.
+    pub fn raise_max_repeat(mut self, factor: usize) -> Self {
+        self.repeat_factor = Some(factor);
+        self
+    }
+
+    /// Fall back from NFA walks to token generation, for patterns generated by NFA walks.
+    pub fn switch_engine(mut self) -> Self {
+        self.switch_engine = true;
+        self
+    }
+
+    /// `config` loosened for `round` (from 1), and the relaxations that makes.
+    pub(crate) fn relax(&self, round: usize, config: &GeneratorConfig) -> (GeneratorConfig, Vec<Relaxation>) {
+        let mut relaxed = config.clone();
+        let mut applied = Vec::new();
+        if self.widen_percent.is_some() {
+            (relaxed.min_len, relaxed.max_len) = self.widen(round, config.min_len, config.max_len);
+            applied.push(Relaxation::Lengths { min_len: relaxed.min_len, max_len: relaxed.max_len });
+        }
+        if let Some(factor) = self.attempts_factor {
+            relaxed.max_attempts = (0..round).fold(config.max_attempts, |n, _| n.saturating_mul(factor));
+            applied.push(Relaxation::Attempts(relaxed.max_attempts));
+        }
+        (relaxed, applied)
+    }
+
+    /// `max_repeat` raised for `round`, or `None` without `raise_max_repeat`.
+    pub(crate) fn repeat(&self, round: usize, max_repeat: usize) -> Option<usize> {
+        let factor = self.repeat_factor?;
+        Some((0..round).fold(max_repeat.max(1), |n, _| n.saturating_mul(factor)))
+    }
+
+    /// Length bounds `min_len..=max_len` widened for `round`; unchanged without `widen_lengths`.
+    pub(crate) fn widen(&self, round: usize, min_len: usize, max_len: usize) -> (usize, usize) {
+        let Some(percent) = self.widen_percent else { return (min_len, max_len) };
+        let step = |len: usize| (len.saturating_mul(percent.saturating_mul(round)) / 100).max(round);
+        (min_len.saturating_sub(step(min_len)), max_len.saturating_add(step(max_len)))
+    }
+}
@@ -0,0 +1,128 @@
+//! Build-time detection of patterns that no string matches.
+//!
+//! A pass over the tokens tracks, for every way through the pattern, whether characters have
+//! been produced and whether an end anchor has been passed. A way that produces a character
+//! after `$`, reaches `^` after a character, or crosses an empty class or an inverted
+//! repetition is dropped; when no way is left the pattern is unsatisfiable. Backreferences and
+//! word boundaries are assumed satisfiable, so the pass never rejects a pattern that matches.
+
+use crate::tokens::Token;
+
+/// Set of path states, one bit per (characters produced, end anchor passed) pair.
+type States = u8;
+
+const START: States = 1 << state(false, false);
+
+const fn state(produced: bool, ended: bool) -> u8 {
+    produced as u8 | (ended as u8) << 1
+}
+
+/// Ok if some string could match `tokens`, else why none can. With `multiline`, `^` and `$`
+/// match at line boundaries and are not checked.
+pub(crate) fn check(tokens: &[Token], multiline: bool) -> Result<(), String> {
+    let mut pass = Pass { multiline, reason: None };
+    let end = tokens.iter().fold(START, |states, t| pass.run(t, states));
+    if end != 0 {
+        return Ok(());
+    }
+    Err(pass.reason.unwrap_or("no string matches the pattern").to_string())
+}
+
+/// Whether `pattern` turns on multiline mode with an inline flag such as `(?m)` or `(?im:...)`.
+pub(crate) fn has_multiline_flag(pattern: &str) -> bool {
+    pattern.match_indices("(?").any(|(i, _)| {
+        let flags = &pattern[i + 2..];
+        let flags = &flags[..flags.find(|c: char| !(c.is_ascii_alphabetic() || c == '-')).unwrap_or(flags.len())];
+        flags.split('-').next().is_some_and(|on| on.contains('m'))
+    })
+}
+
+struct Pass {
+    multiline: bool,
+    /// Cause of the last step that left no way through.
+    reason: Option<&'static str>,
+}
+
+impl Pass {
+    /// The states reachable after `token` from `input`.
+    fn run(&mut self, token: &Token, input: States) -> States {
+        if input == 0 {
+            return 0;
+        }
+        match token {
+            Token::Class(chars) if chars.is_empty() => self.dead("a character class matches nothing"),
+            Token::Literal(_) | Token::Class(_) | Token::NegatedClass(_) | Token::Wildcard => self.produce(input),
+            Token::AnchorStart if !self.multiline => {
+                let out = self.map(input, |produced, ended| (!produced).then_some((produced, ended)));
+                self.or_dead(input, out, "characters are required before the start of the text")
+            }
+            Token::AnchorEnd if !self.multiline => self.map(input, |produced, _| Some((produced, true))),
+            Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary => input,
+            // Unknown length: possibly empty, possibly not.
+            Token::Backreference(_) => input | self.map(input, |_, ended| (!ended).then_some((true, false))),
+            Token::Group(inner, _) | Token::NonCapturingGroup(inner) => self.run(inner, input),
+            Token::Concatenation(tokens) => tokens.iter().fold(input, |states, t| self.run(t, states)),
+            Token::Alternation(branches) => branches.iter().fold(0, |states, b| states | self.run(b, input)),
+            Token::Quantifier { token, min, max, .. } => {
+                if min > max {
+                    return self.dead("a repetition minimum exceeds its maximum");
+                }
+                let required = self.repeat(token, *min, input);
+                // Each optional repetition may stop, so the result is the union over the
+                // counts; it settles within a few rounds as there are only four states.
+                let mut states = required;
+                for _ in *min..*max {
+                    let next = required | self.run(token, states);
+                    if next == states {
+                        break;
+                    }
+                    states = next;
+                }
+                states
+            }
+        }
+    }
+
+    /// `token` applied exactly `count` times, skipping ahead once the states cycle.
+    fn repeat(&mut self, token: &Token, count: usize, input: States) -> States {
+        let mut history = Vec::new();
+        let mut states = input;
+        for i in 0..count {
+            if let Some(first) = history.iter().position(|&s| s == states) {
+                return history[first + (count - first) % (i - first)];
+            }
+            history.push(states);
+            states = self.run(token, states);
+        }
+        states
+    }
+
+    /// The states after one character.
+    fn produce(&mut self, input: States) -> States {
+        let out = self.map(input, |_, ended| (!ended).then_some((true, false)));
+        self.or_dead(input, out, "characters are required after the end of the text")
+    }
+
+    fn map(&self, input: States, f: impl Fn(bool, bool) -> Option<(bool, bool)>) -> States {
+        let mut out = 0;
+        for (produced, ended) in [(false, false), (true, false), (false, true), (true, true)] {
+            if input & 1 << state(produced, ended) != 0
+                && let Some((produced, ended)) = f(produced, ended) {
+                out |= 1 << state(produced, ended);
+            }
+        }
+        out
+    }
+
+    fn or_dead(&mut self, input: States, out: States, reason: &'static str) -> States {
+        if input != 0 && out == 0 {
+            self.reason = Some(reason);
+        }
+        out
+    }
+
+    fn dead(&mut self, reason: &'static str) -> States {
+        self.reason = Some(reason);
+        0
+    }
+}
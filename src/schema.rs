@@ -0,0 +1,307 @@
+//! Sample documents for a JSON Schema, with `pattern` and `format` strings generated by genrex.
+//!
+//! Covers the keywords that shape a value: `type`, `const`, `enum`, `$ref` (within the
+//! document), `allOf`, `anyOf` and `oneOf`, `properties` and `required`, `items` and
+//! `prefixItems` with `minItems`/`maxItems`/`uniqueItems`, numeric bounds and `multipleOf`, and
+//! for strings `pattern` (read as a JavaScript regex, as the specification requires),
+//! `format` and `minLength`/`maxLength`, reconciled by [`crate::openapi`].
+
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use serde_json::{Map, Number, Value};
+
+use crate::openapi::StringSchema;
+use crate::{GenError, RegexGenerator};
+
+/// How many `$ref`s deep a document may go before optional properties and items are left out.
+const MAX_DEPTH: usize = 16;
+
+/// Extra length, items or numeric range allowed when the schema sets no upper bound.
+const DEFAULT_SPAN: u64 = 10;
+
+/// The schema that accepts any value.
+static ANY: Value = Value::Bool(true);
+
+/// Generates JSON documents that validate against a schema.
+///
+/// ```
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "type": "object",
+///     "required": ["id", "sku"],
+///     "properties": {
+///         "id": {"type": "string", "format": "uuid"},
+///         "sku": {"type": "string", "pattern": "^[A-Z]{3}-\\d{4}$"},
+///         "qty": {"type": "integer", "minimum": 1, "maximum": 9}
+///     }
+/// });
+/// let mut sampler = genrex::SchemaSampler::new(schema).seed(1);
+/// let doc = sampler.generate_one().unwrap();
+/// assert_eq!(doc["sku"].as_str().unwrap().len(), 8);
+/// ```
+pub struct SchemaSampler {
+    schema: Value,
+    rng: StdRng,
+    /// String generators by the JSON text of their schema, built on first use.
+    strings: HashMap<String, RegexGenerator>,
+}
+
+impl SchemaSampler {
+    pub fn new(schema: Value) -> Self {
+        SchemaSampler { schema, rng: StdRng::from_entropy(), strings: HashMap::new() }
+    }
+
+    /// A sampler for the schema in the JSON text `schema`.
+    pub fn from_json(schema: &str) -> Result<Self, GenError> {
+        let schema = serde_json::from_str(schema).map_err(|e| GenError::InvalidConfig(format!("schema is not JSON: {}", e)))?;
+        Ok(Self::new(schema))
+    }
+
+    /// Seed the RNG, for reproducible documents.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Generate one document. Fails with `InvalidConfig` for a schema no value satisfies (as
+    /// far as the sampler can tell) and with `Unsupported` for references outside the document.
+    pub fn generate_one(&mut self) -> Result<Value, GenError> {
+        Walk { root: &self.schema, rng: &mut self.rng, strings: &mut self.strings }.sample(&self.schema, 0)
+    }
+
+    pub fn generate_n(&mut self, n: usize) -> Result<Vec<Value>, GenError> {
+        (0..n).map(|_| self.generate_one()).collect()
+    }
+}
+
+/// One document's generation.
+struct Walk<'a> {
+    root: &'a Value,
+    rng: &'a mut StdRng,
+    strings: &'a mut HashMap<String, RegexGenerator>,
+}
+
+impl<'a> Walk<'a> {
+    fn sample(&mut self, schema: &Value, depth: usize) -> Result<Value, GenError> {
+        let schema = match schema {
+            Value::Object(schema) => schema,
+            Value::Bool(true) => return Ok(Value::Null),
+            Value::Bool(false) => return Err(GenError::InvalidConfig("schema `false` accepts no value".to_string())),
+            other => return Err(GenError::InvalidConfig(format!("schema must be an object or a boolean, got {}", other))),
+        };
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let target = self.follow(reference, depth)?;
+            return self.sample(target, depth + 1);
+        }
+        if let Some(value) = schema.get("const") {
+            return Ok(value.clone());
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            return values.choose(self.rng).cloned().ok_or_else(|| GenError::InvalidConfig("empty enum".to_string()));
+        }
+        for key in ["allOf", "anyOf", "oneOf"] {
+            let Some(parts) = schema.get(key).and_then(Value::as_array) else {
+                continue;
+            };
+            let mut merged = schema.clone();
+            merged.remove(key);
+            let parts: Vec<&Value> = match key {
+                "allOf" => parts.iter().collect(),
+                _ => parts.choose(self.rng).into_iter().collect(),
+            };
+            // A referenced part counts as a level, as a plain `$ref` does: it may bring the
+            // combinator back.
+            let mut next = depth;
+            for part in parts {
+                let part = match part.get("$ref").and_then(Value::as_str) {
+                    Some(reference) => {
+                        next = depth + 1;
+                        self.follow(reference, depth)?
+                    }
+                    None => part,
+                };
+                if let Value::Object(part) = part {
+                    merge(&mut merged, part);
+                }
+            }
+            return self.sample(&Value::Object(merged), next);
+        }
+        let kind = match schema.get("type") {
+            Some(Value::String(kind)) => kind.as_str(),
+            Some(Value::Array(kinds)) => kinds.choose(self.rng).and_then(Value::as_str).unwrap_or("null"),
+            _ => inferred_type(schema),
+        };
+        match kind {
+            "object" => self.object(schema, depth),
+            "array" => self.array(schema, depth),
+            "string" => self.string(schema).map(Value::String),
+            "integer" => self.integer(schema),
+            "number" => self.number(schema),
+            "boolean" => Ok(Value::Bool(self.rng.r#gen())),
+            "null" => Ok(Value::Null),
+            other => Err(GenError::InvalidConfig(format!("unknown type '{}'", other))),
+        }
+    }
+
+    /// Resolve a `$ref` met at `depth`, failing once references nest past `MAX_DEPTH`.
+    fn follow(&self, reference: &str, depth: usize) -> Result<&'a Value, GenError> {
+        if depth >= MAX_DEPTH {
+            return Err(GenError::InvalidConfig(format!("$ref nesting exceeds {} levels", MAX_DEPTH)));
+        }
+        let pointer = reference
+            .strip_prefix('#')
+            .ok_or_else(|| GenError::Unsupported(format!("$ref outside the document: {}", reference)))?;
+        self.root.pointer(pointer).ok_or_else(|| GenError::InvalidConfig(format!("unresolved $ref: {}", reference)))
+    }
+
+    fn object(&mut self, schema: &Map<String, Value>, depth: usize) -> Result<Value, GenError> {
+        let required: Vec<&str> = schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str).collect();
+        let mut out = Map::new();
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, property) in properties {
+                // Optional properties appear half the time, and never past the depth limit.
+                if required.contains(&name.as_str()) || (depth < MAX_DEPTH && self.rng.gen_bool(0.5)) {
+                    out.insert(name.clone(), self.sample(property, depth)?);
+                }
+            }
+        }
+        if let Some(missing) = required.iter().find(|name| !out.contains_key(**name)) {
+            return Err(GenError::InvalidConfig(format!("required property '{}' has no schema", missing)));
+        }
+        Ok(Value::Object(out))
+    }
+
+    fn array(&mut self, schema: &Map<String, Value>, depth: usize) -> Result<Value, GenError> {
+        let min = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0);
+        let max = schema.get("maxItems").and_then(Value::as_u64).unwrap_or(min + DEFAULT_SPAN / 2);
+        if min > max {
+            return Err(GenError::InvalidConfig(format!("minItems {} exceeds maxItems {}", min, max)));
+        }
+        let len = if depth >= MAX_DEPTH { min } else { self.rng.gen_range(min..=max) } as usize;
+        let prefix = schema.get("prefixItems").and_then(Value::as_array).map_or(&[][..], Vec::as_slice);
+        let items = schema.get("items").unwrap_or(&ANY);
+        let unique = schema.get("uniqueItems").and_then(Value::as_bool).unwrap_or(false);
+        let mut out: Vec<Value> = Vec::with_capacity(len);
+        let mut attempts = 0;
+        while out.len() < len {
+            let item = self.sample(prefix.get(out.len()).unwrap_or(items), depth)?;
+            if unique && out.contains(&item) {
+                attempts += 1;
+                if attempts > 100 * len {
+                    return Err(GenError::InvalidConfig(format!("could not find {} unique items", len)));
+                }
+                continue;
+            }
+            out.push(item);
+        }
+        Ok(Value::Array(out))
+    }
+
+    fn string(&mut self, schema: &Map<String, Value>) -> Result<String, GenError> {
+        let key = Value::Object(schema.clone()).to_string();
+        if !self.strings.contains_key(&key) {
+            self.strings.insert(key.clone(), string_schema(schema).generator()?);
+        }
+        let generator = self.strings.get_mut(&key).expect("inserted above");
+        generator.reseed(self.rng.next_u64());
+        generator.generate_one()
+    }
+
+    fn integer(&mut self, schema: &Map<String, Value>) -> Result<Value, GenError> {
+        let (lo, hi) = bounds(schema, true);
+        let step = schema.get("multipleOf").and_then(Value::as_f64).filter(|m| *m > 0.0).unwrap_or(1.0);
+        let (k_lo, k_hi) = ((lo / step).ceil(), (hi / step).floor());
+        if k_lo > k_hi {
+            return Err(GenError::InvalidConfig(format!("no integer multiple of {} between {} and {}", step, lo, hi)));
+        }
+        let k = self.rng.gen_range(k_lo as i64..=k_hi as i64);
+        Ok(Value::Number(Number::from((k as f64 * step) as i64)))
+    }
+
+    fn number(&mut self, schema: &Map<String, Value>) -> Result<Value, GenError> {
+        let (lo, hi) = bounds(schema, false);
+        let exclusive = schema.contains_key("exclusiveMinimum") || schema.contains_key("exclusiveMaximum");
+        if lo > hi || (lo == hi && exclusive) {
+            return Err(GenError::InvalidConfig(format!("no number between {} and {}", lo, hi)));
+        }
+        let value = match schema.get("multipleOf").and_then(Value::as_f64).filter(|m| *m > 0.0) {
+            Some(step) => {
+                let (k_lo, k_hi) = ((lo / step).ceil(), (hi / step).floor());
+                if k_lo > k_hi {
+                    return Err(GenError::InvalidConfig(format!("no multiple of {} between {} and {}", step, lo, hi)));
+                }
+                self.rng.gen_range(k_lo as i64..=k_hi as i64) as f64 * step
+            }
+            None if lo == hi => lo,
+            // `gen_range` never returns `hi`, so only an exclusive minimum needs avoiding.
+            None => match self.rng.gen_range(lo..hi) {
+                v if Some(v) == schema.get("exclusiveMinimum").and_then(Value::as_f64) => (lo + hi) / 2.0,
+                v => v,
+            },
+        };
+        Number::from_f64(value).map(Value::Number).ok_or_else(|| GenError::InvalidConfig(format!("{} is not a JSON number", value)))
+    }
+}
+
+/// The type a schema without `type` describes, judging by its keywords.
+fn inferred_type(schema: &Map<String, Value>) -> &'static str {
+    let has = |keys: &[&str]| keys.iter().any(|k| schema.contains_key(*k));
+    if has(&["properties", "required"]) {
+        "object"
+    } else if has(&["items", "prefixItems", "minItems", "maxItems"]) {
+        "array"
+    } else if has(&["pattern", "format", "minLength", "maxLength"]) {
+        "string"
+    } else if has(&["minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum", "multipleOf"]) {
+        "number"
+    } else {
+        "null"
+    }
+}
+
+/// Merge `part` into `into`: `properties` are combined, `required` lists concatenated, and
+/// other keywords overwritten.
+fn merge(into: &mut Map<String, Value>, part: &Map<String, Value>) {
+    for (key, value) in part {
+        match (key.as_str(), into.get_mut(key), value) {
+            ("properties", Some(Value::Object(ours)), Value::Object(theirs)) => {
+                ours.extend(theirs.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+            ("required", Some(Value::Array(ours)), Value::Array(theirs)) => ours.extend(theirs.iter().cloned()),
+            _ => {
+                into.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// The range allowed by a schema's numeric bounds. For integers it is inclusive, with exclusive
+/// bounds stepped in by one; for numbers the caller keeps clear of exclusive bounds.
+fn bounds(schema: &Map<String, Value>, integer: bool) -> (f64, f64) {
+    let get = |key| schema.get(key).and_then(Value::as_f64);
+    let step = if integer { 1.0 } else { 0.0 };
+    let lo = [get("minimum"), get("exclusiveMinimum").map(|b| b + step)].into_iter().flatten().reduce(f64::max);
+    let hi = [get("maximum"), get("exclusiveMaximum").map(|b| b - step)].into_iter().flatten().reduce(f64::min);
+    let span = (DEFAULT_SPAN * DEFAULT_SPAN) as f64;
+    match (lo, hi) {
+        (None, None) => (0.0, span),
+        (None, Some(hi)) => (hi - span, hi),
+        (Some(lo), None) => (lo, lo + span),
+        (Some(lo), Some(hi)) => (lo, hi),
+    }
+}
+
+/// The string keywords of a schema, for `openapi` to reconcile into a generator.
+fn string_schema(schema: &Map<String, Value>) -> StringSchema {
+    StringSchema {
+        pattern: schema.get("pattern").and_then(Value::as_str).map(str::to_string),
+        min_length: schema.get("minLength").and_then(Value::as_u64).map(|n| n as usize),
+        max_length: schema.get("maxLength").and_then(Value::as_u64).map(|n| n as usize),
+        enum_values: Vec::new(),
+        format: schema.get("format").and_then(Value::as_str).map(str::to_string),
+    }
+}
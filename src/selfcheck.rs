@@ -0,0 +1,103 @@
+//! Differential self-check: generate from a pattern and confirm every output is a full match of
+//! the pattern as the `regex` crate compiles it, independently of the generator's own checks.
+
+use std::fmt;
+
+use regex::Regex;
+
+use crate::batch::derive_seed;
+use crate::{Engine, Explanation, GenError, RegexGenerator};
+
+/// An output that the compiled pattern does not match.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    /// Position of the sample in the run.
+    pub index: usize,
+    /// Seed that reproduces the sample: `reseed(seed)` on a generator for the same pattern.
+    pub seed: u64,
+    pub output: String,
+    /// Engine that produced the output.
+    pub engine: Engine,
+    /// Trace of the output, when the token engine produced it.
+    pub trace: Option<Explanation>,
+}
+
+/// The outcome of `verify_self`.
+#[derive(Debug, Clone)]
+pub struct SelfCheckReport {
+    pub pattern: String,
+    /// Samples requested.
+    pub samples: usize,
+    /// Samples for which generation itself failed (e.g. `NoMatch`).
+    pub failed: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl SelfCheckReport {
+    /// True when every sample was generated and matched the pattern.
+    pub fn is_ok(&self) -> bool {
+        self.failed == 0 && self.mismatches.is_empty()
+    }
+}
+
+impl fmt::Display for SelfCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "self-check of {:?}: {} samples, {} failed, {} mismatched",
+            self.pattern,
+            self.samples,
+            self.failed,
+            self.mismatches.len()
+        )?;
+        for m in &self.mismatches {
+            writeln!(f, "sample {} (seed {}, {} engine): {:?}", m.index, m.seed, m.engine, m.output)?;
+            if let Some(trace) = &m.trace {
+                write!(f, "{}", trace)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Generate `samples` values from `pattern` and check that each is a full match of the pattern
+/// compiled with the `regex` crate. Each sample uses its own seed derived from `seed`, so any
+/// mismatch in the report can be reproduced on its own.
+///
+/// Fails if the pattern does not build, or if the `regex` crate cannot compile it (for example
+/// because it uses backreferences), since there is then nothing to check against.
+pub fn verify_self(pattern: &str, samples: usize, seed: u64) -> Result<SelfCheckReport, GenError> {
+    let mut generator = RegexGenerator::builder(pattern).seed(seed).build()?;
+    let source = crate::constraints::strip_annotations(pattern);
+    let source = crate::lexer::normalize_repetitions(&crate::lexer::normalize_escapes(&source));
+    let full = Regex::new(&format!(r"\A(?:{})\z", source)).map_err(|e| GenError::InvalidRegex(e.to_string()))?;
+
+    let mut report = SelfCheckReport { pattern: pattern.to_string(), samples, failed: 0, mismatches: Vec::new() };
+    for index in 0..samples {
+        let sample_seed = derive_seed(seed, index as u64);
+        generator.reseed(sample_seed);
+        let (output, gen_report) = match generator.generate_one_with_report() {
+            Ok(res) => res,
+            Err(_) => {
+                report.failed += 1;
+                continue;
+            }
+        };
+        if full.is_match(&output) {
+            continue;
+        }
+        let engine = gen_report.engine;
+        let trace = trace(&mut generator, sample_seed, &output, engine);
+        report.mismatches.push(Mismatch { index, seed: sample_seed, output, engine, trace });
+    }
+    Ok(report)
+}
+
+/// Replay a token-engine sample with tracing on; the trace is kept only if it reproduces `output`.
+fn trace(generator: &mut RegexGenerator, seed: u64, output: &str, engine: Engine) -> Option<Explanation> {
+    if engine != Engine::Tokens {
+        return None;
+    }
+    generator.reseed(seed);
+    generator.generate_explained().ok().filter(|e| e.value == output)
+}
@@ -0,0 +1,48 @@
+//! Shrinking: smaller strings that still match, for minimizing property-test failures.
+//!
+//! With the pattern's DFA, each span of the input is replaced by the shortest string that keeps
+//! the whole accepted, which drops repetitions and switches to shorter alternation branches, and
+//! each char by the smallest one that fits. Without it (patterns with backreferences), spans are
+//! deleted and chars replaced by a few simple ones. Candidates are checked by the caller.
+
+use crate::dfa::Dfa;
+
+/// Replacement chars tried when there is no DFA to pick one.
+const SIMPLE_CHARS: [char; 4] = [' ', '0', 'A', 'a'];
+
+/// Candidate shrinks of `s`, most aggressive first: the shortest string the DFA accepts within
+/// `min_len..=max_len`, then span replacements from the whole string down to single chars, then
+/// single-char simplifications.
+pub(crate) fn candidates<'a>(s: &str, dfa: Option<&'a Dfa>, min_len: usize, max_len: usize) -> impl Iterator<Item = String> + 'a {
+    let chars: Vec<char> = s.chars().collect();
+    let n = chars.len();
+    let text = move |range: std::ops::Range<usize>| -> String { chars[range].iter().collect() };
+    let shortest = dfa.and_then(|d| d.extreme(min_len, max_len, false));
+    let sizes = std::iter::successors(Some(n), |&size| (size > 1).then_some(size / 2)).filter(|&size| size > 0);
+    let spans = {
+        let text = text.clone();
+        sizes.flat_map(move |size| {
+            let text = text.clone();
+            (0..=n - size).filter_map(move |start| {
+                let (prefix, suffix) = (text(0..start), text(start + size..n));
+                let middle = match dfa {
+                    Some(d) => d.bridge(&prefix, &suffix).filter(|m| m.chars().count() < size)?,
+                    None => String::new(),
+                };
+                Some(prefix + &middle + &suffix)
+            })
+        })
+    };
+    let simplified = (0..n).flat_map(move |i| {
+        let (prefix, current, suffix) = (text(0..i), text(i..i + 1), text(i + 1..n));
+        let replacements: Vec<char> = match dfa {
+            Some(d) => d.smallest_char(&prefix, &suffix).into_iter().collect(),
+            None => SIMPLE_CHARS.to_vec(),
+        };
+        replacements
+            .into_iter()
+            .filter(move |&c| current.starts_with(|d: char| c < d))
+            .map(move |c| format!("{}{}{}", prefix, c, suffix))
+    });
+    shortest.into_iter().chain(spans).chain(simplified)
+}
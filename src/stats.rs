@@ -0,0 +1,173 @@
+//! Generation statistics: attempt and rejection accounting.
+
+use std::fmt;
+use std::time::Duration;
+
+/// Why a candidate string was discarded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RejectReason {
+    /// A token failed to generate (e.g. unsupported construct).
+    TokenError,
+    /// A backreference could not be resolved.
+    UnresolvedBackref,
+    /// The candidate length fell outside `min_len..=max_len`.
+    Length,
+    /// The candidate contained a substring banned with `must_not_contain`.
+    Forbidden,
+    /// The candidate matched a deny-list pattern given with `except`.
+    Excluded,
+    /// The candidate failed a predicate given with `filter`.
+    Filtered,
+    /// A group constraint (e.g. uniqueness) was violated.
+    Constraint,
+    /// The candidate did not match the verification regex.
+    RegexMismatch,
+}
+
+impl RejectReason {
+    /// All reasons, in reporting order.
+    pub const ALL: [RejectReason; 8] = [
+        RejectReason::TokenError,
+        RejectReason::UnresolvedBackref,
+        RejectReason::Length,
+        RejectReason::Forbidden,
+        RejectReason::Excluded,
+        RejectReason::Filtered,
+        RejectReason::Constraint,
+        RejectReason::RegexMismatch,
+    ];
+
+    fn slot(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            RejectReason::TokenError => "token error",
+            RejectReason::UnresolvedBackref => "unresolved backreference",
+            RejectReason::Length => "length",
+            RejectReason::Forbidden => "forbidden substring",
+            RejectReason::Excluded => "excluded pattern",
+            RejectReason::Filtered => "filter",
+            RejectReason::Constraint => "group constraint",
+            RejectReason::RegexMismatch => "regex mismatch",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Cumulative counters over the lifetime of a generator.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenStats {
+    /// Outputs returned successfully.
+    pub accepted: u64,
+    /// Candidate strings constructed (accepted or rejected).
+    pub attempts: u64,
+    /// Calls that gave up without producing an output.
+    pub failures: u64,
+    rejections: [u64; RejectReason::ALL.len()],
+}
+
+impl GenStats {
+    /// Number of candidates rejected for `reason`.
+    pub fn rejected_for(&self, reason: RejectReason) -> u64 {
+        self.rejections[reason.slot()]
+    }
+
+    /// Total number of rejected candidates.
+    pub fn rejected(&self) -> u64 {
+        self.rejections.iter().sum()
+    }
+
+    /// Average candidates constructed per accepted output.
+    pub fn attempts_per_output(&self) -> f64 {
+        if self.accepted == 0 {
+            0.0
+        } else {
+            self.attempts as f64 / self.accepted as f64
+        }
+    }
+
+    pub(crate) fn record_reject(&mut self, reason: RejectReason) {
+        self.rejections[reason.slot()] += 1;
+    }
+
+    /// Counters accumulated since the `earlier` snapshot of the same generator.
+    pub(crate) fn since(&self, earlier: &GenStats) -> GenStats {
+        let mut out = GenStats {
+            accepted: self.accepted - earlier.accepted,
+            attempts: self.attempts - earlier.attempts,
+            failures: self.failures - earlier.failures,
+            rejections: self.rejections,
+        };
+        for (a, b) in out.rejections.iter_mut().zip(earlier.rejections.iter()) {
+            *a -= b;
+        }
+        out
+    }
+
+    /// Add another set of counters into this one.
+    pub fn merge(&mut self, other: &GenStats) {
+        self.accepted += other.accepted;
+        self.attempts += other.attempts;
+        self.failures += other.failures;
+        for (a, b) in self.rejections.iter_mut().zip(other.rejections.iter()) {
+            *a += b;
+        }
+    }
+}
+
+/// The generation strategy that produced an output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Engine {
+    /// Length-aware random walk over the pattern's NFA.
+    Nfa,
+    /// Token-based generation from the lexed pattern.
+    Tokens,
+    /// Single-shot generation from the parsed AST.
+    Ast,
+    /// Random alphanumeric candidates filtered by the pattern.
+    Rejection,
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Engine::Nfa => "nfa",
+            Engine::Tokens => "tokens",
+            Engine::Ast => "ast",
+            Engine::Rejection => "rejection",
+        };
+        f.write_str(s)
+    }
+}
+
+/// What it took to produce a single output (see `RegexGenerator::generate_one_with_report`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenReport {
+    /// Engine that produced the accepted output.
+    pub engine: Engine,
+    /// Candidates constructed, including the accepted one.
+    pub attempts: u64,
+    /// Wall-clock time spent generating.
+    pub elapsed: Duration,
+    stats: GenStats,
+}
+
+impl GenReport {
+    pub(crate) fn new(engine: Engine, elapsed: Duration, stats: GenStats) -> Self {
+        GenReport { engine, attempts: stats.attempts, elapsed, stats }
+    }
+
+    /// Number of candidates rejected for `reason` before the output was accepted.
+    pub fn rejected_for(&self, reason: RejectReason) -> u64 {
+        self.stats.rejected_for(reason)
+    }
+
+    /// Total number of rejected candidates.
+    pub fn rejected(&self) -> u64 {
+        self.stats.rejected()
+    }
+}
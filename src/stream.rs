@@ -0,0 +1,151 @@
+//! Quota-bounded streaming over a generator.
+
+use std::io::{self, Write};
+
+use crate::{GenError, RegexGenerator};
+
+/// Global limits for a `BoundedStream`. Whichever limit is reached first ends the stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Quota {
+    /// Maximum total bytes emitted, counting one delimiter per item.
+    pub max_bytes: Option<u64>,
+    /// Maximum number of items emitted.
+    pub max_items: Option<u64>,
+}
+
+impl Quota {
+    pub fn bytes(max_bytes: u64) -> Self {
+        Quota { max_bytes: Some(max_bytes), max_items: None }
+    }
+
+    pub fn items(max_items: u64) -> Self {
+        Quota { max_bytes: None, max_items: Some(max_items) }
+    }
+
+    /// Additionally cap the number of items.
+    pub fn and_items(mut self, max_items: u64) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Additionally cap the number of bytes.
+    pub fn and_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+}
+
+/// Totals reported when a stream is finished.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamSummary {
+    pub items: u64,
+    pub bytes: u64,
+}
+
+/// An iterator of generated strings that stops at a global byte/item quota.
+///
+/// Items can optionally be mirrored into a sink (file, socket, buffer), each followed by the
+/// delimiter. An item is only emitted if it fits the remaining byte budget in full, so the
+/// quota is never exceeded. The sink is flushed when the stream is dropped; use `finish` to
+/// observe flush errors.
+pub struct BoundedStream {
+    generator: RegexGenerator,
+    quota: Quota,
+    delimiter: Vec<u8>,
+    sink: Option<Box<dyn Write + Send>>,
+    summary: StreamSummary,
+    sink_error: Option<io::Error>,
+    done: bool,
+}
+
+impl BoundedStream {
+    pub fn new(generator: RegexGenerator, quota: Quota) -> Self {
+        BoundedStream {
+            generator,
+            quota,
+            delimiter: b"\n".to_vec(),
+            sink: None,
+            summary: StreamSummary::default(),
+            sink_error: None,
+            done: false,
+        }
+    }
+
+    /// Write every emitted item (plus delimiter) to `sink`.
+    pub fn sink<W: Write + Send + 'static>(mut self, sink: W) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Delimiter written after each item and counted against the byte quota (default "\n").
+    pub fn delimiter(mut self, delimiter: &[u8]) -> Self {
+        self.delimiter = delimiter.to_vec();
+        self
+    }
+
+    /// Totals emitted so far.
+    pub fn summary(&self) -> StreamSummary {
+        self.summary
+    }
+
+    /// Stop the stream, flush the sink and report totals or the first sink error.
+    pub fn finish(mut self) -> io::Result<StreamSummary> {
+        self.done = true;
+        if let Some(e) = self.sink_error.take() {
+            return Err(e);
+        }
+        if let Some(sink) = self.sink.as_mut() {
+            sink.flush()?;
+        }
+        Ok(self.summary)
+    }
+
+    /// Recover the generator (for example to inspect its stats). Flushes the sink first.
+    pub fn into_generator(mut self) -> RegexGenerator {
+        if let Some(sink) = self.sink.as_mut() {
+            let _ = sink.flush();
+        }
+        self.sink = None;
+        std::mem::take(&mut self.generator)
+    }
+}
+
+impl Iterator for BoundedStream {
+    type Item = Result<String, GenError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.quota.max_items.is_some_and(|max| self.summary.items >= max) {
+            self.done = true;
+            return None;
+        }
+        let s = match self.generator.generate_one() {
+            Ok(s) => s,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+        let cost = (s.len() + self.delimiter.len()) as u64;
+        if self.quota.max_bytes.is_some_and(|max| self.summary.bytes + cost > max) {
+            self.done = true;
+            return None;
+        }
+        if let Some(sink) = self.sink.as_mut()
+            && let Err(e) = sink.write_all(s.as_bytes()).and_then(|_| sink.write_all(&self.delimiter)) {
+            self.sink_error = Some(e);
+            self.done = true;
+            return None;
+        }
+        self.summary.items += 1;
+        self.summary.bytes += cost;
+        Some(Ok(s))
+    }
+}
+
+impl Drop for BoundedStream {
+    fn drop(&mut self) {
+        if let Some(sink) = self.sink.as_mut() {
+            let _ = sink.flush();
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Templates: literal text with embedded patterns, e.g. `user-{[0-9]{6}}@{(gmail|corp)}.com`.
+
+use crate::batch::derive_seed;
+use crate::{GenError, RegexGenerator};
+
+enum Segment {
+    Literal(String),
+    Pattern(Box<RegexGenerator>),
+}
+
+/// Literal text interleaved with `{...}` patterns, generating one string per call from the
+/// literal text and one match of each pattern in turn.
+///
+/// Everything outside braces is taken literally, so it needs no regex escaping; `{{` and `}}`
+/// stand for literal braces. A pattern ends at the brace that balances its opening one, so
+/// repetition counts such as `{[0-9]{6}}` work as written. Braces inside a character class or
+/// after a backslash do not count.
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parse `template`, building a generator with default settings for each pattern.
+    pub fn parse(template: &str) -> Result<Self, GenError> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.char_indices().peekable();
+        while let Some((at, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|&(_, c)| c == '{').is_some() => literal.push('{'),
+                '}' if chars.next_if(|&(_, c)| c == '}').is_some() => literal.push('}'),
+                '{' => {
+                    let start = at + 1;
+                    let end = pattern_end(template, start)
+                        .ok_or_else(|| GenError::InvalidConfig(format!("unclosed '{{' at byte {} of template", at)))?;
+                    let pattern = &template[start..end];
+                    if pattern.is_empty() {
+                        return Err(GenError::InvalidConfig(format!("empty pattern at byte {} of template", at)));
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Pattern(Box::new(RegexGenerator::builder(pattern).build()?)));
+                    while chars.next_if(|&(i, _)| i <= end).is_some() {}
+                }
+                '}' => return Err(GenError::InvalidConfig(format!("unmatched '}}' at byte {} of template (use '}}}}' for a literal brace)", at))),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Template { segments })
+    }
+
+    /// Reseed the pattern generators from `seed`, each with its own derived seed, so the outputs
+    /// are reproducible.
+    pub fn seed(mut self, seed: u64) -> Self {
+        for (i, generator) in self.generators().enumerate() {
+            generator.reseed(derive_seed(seed, i as u64));
+        }
+        self
+    }
+
+    /// The generators of the patterns, in template order, for further configuration.
+    pub fn generators(&mut self) -> impl Iterator<Item = &mut RegexGenerator> {
+        self.segments.iter_mut().filter_map(|s| match s {
+            Segment::Pattern(generator) => Some(&mut **generator),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// Generate one string.
+    pub fn generate_one(&mut self) -> Result<String, GenError> {
+        let mut out = String::new();
+        for segment in &mut self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Pattern(generator) => out.push_str(&generator.generate_one()?),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Generate `n` strings.
+    pub fn generate_n(&mut self, n: usize) -> Result<Vec<String>, GenError> {
+        (0..n).map(|_| self.generate_one()).collect()
+    }
+}
+
+/// The byte offset of the `}` closing a pattern that starts at `start`, skipping escaped
+/// characters and the contents of character classes.
+fn pattern_end(template: &str, start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_class = false;
+    let mut chars = template[start..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' if !in_class => {
+                in_class = true;
+                // A `]` right after `[` or `[^` is a literal member of the class.
+                let rest = &template[start + i + 1..];
+                let skip = if rest.starts_with("^]") { 2 } else { usize::from(rest.starts_with(']')) };
+                for _ in 0..skip {
+                    chars.next();
+                }
+            }
+            ']' if in_class => in_class = false,
+            '{' if !in_class => depth += 1,
+            '}' if !in_class => {
+                if depth == 0 {
+                    return Some(start + i);
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
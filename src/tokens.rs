@@ -1,13 +1,20 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
 use rand::Rng;
 use crate::traits::{RegexToken, TokenContext};
+use crate::constraints::GroupOverride;
 use crate::error::GenrexError;
+use crate::explain::Choice;
 
 /// Enum representing all possible regex AST token types.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Token {
 	Literal(char),
-	Class(Vec<char>),
-	NegatedClass(Vec<char>),
+	/// Character class; equal classes from one lexed pattern share their set.
+	Class(Arc<[char]>),
+	NegatedClass(Arc<[char]>),
 	Concatenation(Vec<Token>),
 	Alternation(Vec<Token>),
 	Quantifier {
@@ -27,16 +34,287 @@ pub enum Token {
 	Wildcard,
 }
 
+/// Characters a wildcard (`.`) generates.
+pub(crate) const WILDCARD_CHARS: &[char] = &[
+	'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+	'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+	'0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+];
+
+impl Token {
+	/// Lower bound, in bytes, on the length of any string this token generates.
+	/// Backreferences count as empty since their target may be optional.
+	pub fn min_len(&self) -> usize {
+		self.min_width(char::len_utf8)
+	}
+
+	/// Lower bound on the length of any string this token generates, in characters.
+	pub(crate) fn min_chars(&self) -> usize {
+		self.min_width(|_| 1)
+	}
+
+	/// Upper bound, in bytes, on the length of any string this token generates; `None` when
+	/// unbounded. Backreferences count as unbounded.
+	pub fn max_len(&self) -> Option<usize> {
+		self.max_width(char::len_utf8, char::MAX_LEN_UTF8)
+	}
+
+	/// Upper bound on the length of any string this token generates, in characters.
+	pub(crate) fn max_chars(&self) -> Option<usize> {
+		self.max_width(|_| 1, 1)
+	}
+
+	/// `any` is the width of a character that is not known in advance.
+	fn max_width(&self, width: fn(char) -> usize, any: usize) -> Option<usize> {
+		match self {
+			Token::Literal(c) => Some(width(*c)),
+			Token::Class(chars) => Some(chars.iter().map(|&c| width(c)).max().unwrap_or(0)),
+			Token::NegatedClass(_) | Token::Wildcard => Some(any),
+			Token::Concatenation(tokens) => tokens.iter().try_fold(0usize, |sum, t| sum.checked_add(t.max_width(width, any)?)),
+			Token::Alternation(choices) => choices.iter().try_fold(0, |most, t| Some(most.max(t.max_width(width, any)?))),
+			Token::Quantifier { token, max, .. } => match token.max_width(width, any)? {
+				0 => Some(0),
+				_ if *max == usize::MAX => None,
+				each => each.checked_mul(*max),
+			},
+			Token::Group(inner, _) | Token::NonCapturingGroup(inner) => inner.max_width(width, any),
+			Token::Backreference(_) => None,
+			Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary => Some(0),
+		}
+	}
+
+	fn min_width(&self, width: fn(char) -> usize) -> usize {
+		match self {
+			Token::Literal(c) => width(*c),
+			Token::Class(chars) => chars.iter().map(|&c| width(c)).min().unwrap_or(0),
+			Token::NegatedClass(_) | Token::Wildcard => 1,
+			Token::Concatenation(tokens) => tokens.iter().map(|t| t.min_width(width)).sum(),
+			Token::Alternation(choices) => choices.iter().map(|t| t.min_width(width)).min().unwrap_or(0),
+			Token::Quantifier { token, min, .. } => token.min_width(width).saturating_mul(*min),
+			Token::Group(inner, _) | Token::NonCapturingGroup(inner) => inner.min_width(width),
+			Token::Backreference(_) | Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary => 0,
+		}
+	}
+}
+
+impl Token {
+	/// Serialize the token back into regex syntax.
+	///
+	/// Lexing a pattern, serializing its tokens and lexing the result gives the same tokens.
+	/// Group names, inline flags and lookarounds are not represented in the token tree and do
+	/// not survive the round trip; character classes come back as explicit members and ranges.
+	pub fn to_pattern(&self) -> String {
+		let mut out = String::new();
+		self.write_pattern(&mut out);
+		out
+	}
+
+	fn write_pattern(&self, out: &mut String) {
+		match self {
+			Token::Literal(c) => write_literal(*c, out),
+			Token::Class(chars) => write_class(chars, false, out),
+			Token::NegatedClass(chars) => write_class(chars, true, out),
+			// The lexer's only standalone empty concatenation is `\B`.
+			Token::Concatenation(tokens) if tokens.is_empty() => out.push_str("\\B"),
+			Token::Concatenation(tokens) => write_sequence(tokens, out),
+			Token::Alternation(branches) => {
+				for (i, branch) in branches.iter().enumerate() {
+					if i > 0 {
+						out.push('|');
+					}
+					write_body(branch, out);
+				}
+			}
+			Token::Quantifier { token, min, max, greedy } => {
+				match **token {
+					Token::Alternation(_) => write_wrapped(token, out),
+					Token::Concatenation(ref tokens) if !tokens.is_empty() => write_wrapped(token, out),
+					_ => token.write_pattern(out),
+				}
+				match (*min, *max) {
+					(0, 1) => out.push('?'),
+					(0, usize::MAX) => out.push('*'),
+					(1, usize::MAX) => out.push('+'),
+					(n, usize::MAX) => out.push_str(&format!("{{{},}}", n)),
+					(n, m) if n == m => out.push_str(&format!("{{{}}}", n)),
+					(n, m) => out.push_str(&format!("{{{},{}}}", n, m)),
+				}
+				if !greedy {
+					out.push('?');
+				}
+			}
+			Token::Group(inner, _) => {
+				out.push('(');
+				write_body(inner, out);
+				out.push(')');
+			}
+			Token::NonCapturingGroup(inner) => write_wrapped(inner, out),
+			Token::Backreference(idx) => out.push_str(&format!("\\{}", idx)),
+			Token::AnchorStart => out.push('^'),
+			Token::AnchorEnd => out.push('$'),
+			Token::WordBoundary => out.push_str("\\b"),
+			Token::Wildcard => out.push('.'),
+		}
+	}
+}
+
+/// Serialize a token sequence; an alternation sharing the sequence with other tokens is wrapped
+/// in a non-capturing group.
+pub(crate) fn write_sequence(tokens: &[Token], out: &mut String) {
+	if let [only @ Token::Alternation(_)] = tokens {
+		only.write_pattern(out);
+		return;
+	}
+	for t in tokens {
+		match t {
+			Token::Alternation(_) => write_wrapped(t, out),
+			_ => t.write_pattern(out),
+		}
+	}
+}
+
+/// The contents of a group or branch: a concatenation is written without the `\B` special case.
+fn write_body(token: &Token, out: &mut String) {
+	match token {
+		Token::Concatenation(tokens) => write_sequence(tokens, out),
+		_ => token.write_pattern(out),
+	}
+}
+
+fn write_wrapped(token: &Token, out: &mut String) {
+	out.push_str("(?:");
+	write_body(token, out);
+	out.push(')');
+}
+
+/// Escape for a character the lexer reads back as itself, if it needs one.
+fn escape_of(c: char) -> Option<&'static str> {
+	Some(match c {
+		'\n' => "\\n",
+		'\t' => "\\t",
+		'\r' => "\\r",
+		'\x0C' => "\\f",
+		'\x0B' => "\\v",
+		'\x07' => "\\a",
+		_ => return None,
+	})
+}
+
+fn write_literal(c: char, out: &mut String) {
+	if let Some(esc) = escape_of(c) {
+		out.push_str(esc);
+		return;
+	}
+	if "\\.^$|?*+()[]{}".contains(c) {
+		out.push('\\');
+	}
+	out.push(c);
+}
+
+pub(crate) fn write_class_char(c: char, out: &mut String) {
+	if let Some(esc) = escape_of(c) {
+		out.push_str(esc);
+		return;
+	}
+	if "\\[]^-&~".contains(c) {
+		out.push('\\');
+	}
+	out.push(c);
+}
+
+/// Write class members in order, collapsing runs of three or more consecutive code points into
+/// ranges. The lexer reads a range's upper bound unescaped, so runs end before any character
+/// that would need an escape there.
+fn write_class(chars: &[char], negated: bool, out: &mut String) {
+	out.push('[');
+	if negated {
+		out.push('^');
+	}
+	let plain = |c: char| escape_of(c).is_none() && !"\\[]^-&~".contains(c);
+	let mut i = 0;
+	while i < chars.len() {
+		let mut j = i;
+		while j + 1 < chars.len() && chars[j + 1] as u32 == chars[j] as u32 + 1 && plain(chars[j + 1]) {
+			j += 1;
+		}
+		write_class_char(chars[i], out);
+		if j >= i + 2 {
+			out.push('-');
+			out.push(chars[j]);
+			i = j + 1;
+		} else {
+			i += 1;
+		}
+	}
+	out.push(']');
+}
+
 impl RegexToken for Token {
 	fn generate<R: Rng + ?Sized>(&self, rng: &mut R, ctx: &mut TokenContext) -> Result<String, GenrexError> {
+		let mut out = String::new();
+		self.generate_into(rng, ctx, &mut out)?;
+		Ok(out)
+	}
+
+	fn generate_into<R: Rng + ?Sized>(&self, rng: &mut R, ctx: &mut TokenContext, out: &mut String) -> Result<(), GenrexError> {
+		if ctx.cancelled() {
+			return Err(GenrexError::Cancelled);
+		}
+		if !ctx.burn() {
+			return Err(GenrexError::BudgetExhausted);
+		}
+		if !ctx.tracing() {
+			return self.write_untraced(rng, ctx, out);
+		}
+		ctx.trace_open(self);
+		let start = out.len();
+		let res = self.write_untraced(rng, ctx, out);
+		// An empty backreference to a group not generated yet is filled in once the output is complete.
+		let pending = match (self, &res) {
+			(Token::Backreference(idx), Ok(())) if out.len() == start && ctx.get_capture(*idx).is_none() => Some(*idx),
+			_ => None,
+		};
+		ctx.trace_close(res.is_ok().then(|| &out[start..]), pending);
+		res
+	}
+
+	fn describe(&self) -> String {
 		match self {
-			Token::Literal(c) => Ok(c.to_string()),
+			Token::Literal(c) => format!("Literal('{}')", c),
+			Token::Class(chars) => format!("Class[{}]", chars.iter().collect::<String>()),
+			Token::NegatedClass(chars) => format!("NegatedClass[{}]", chars.iter().collect::<String>()),
+			Token::Concatenation(tokens) => format!("Concat({})", tokens.len()),
+			Token::Alternation(choices) => format!("Alt({})", choices.len()),
+			Token::Quantifier { min, max: usize::MAX, .. } => format!("Quantifier{{{},}}", min),
+			Token::Quantifier { min, max, .. } => format!("Quantifier{{{},{}}}", min, max),
+			Token::Group(_, idx) => format!("Group({})", idx),
+			Token::NonCapturingGroup(_) => "NonCapturingGroup".to_string(),
+			Token::Backreference(idx) => format!("Backreference({})", idx),
+			Token::AnchorStart => "AnchorStart".to_string(),
+			Token::AnchorEnd => "AnchorEnd".to_string(),
+			Token::WordBoundary => "WordBoundary".to_string(),
+			Token::Wildcard => "Wildcard".to_string(),
+		}
+	}
+}
+
+impl Token {
+	/// Append this token's output to `out`, without tracing it.
+	fn write_untraced<R: Rng + ?Sized>(&self, rng: &mut R, ctx: &mut TokenContext, out: &mut String) -> Result<(), GenrexError> {
+		match self {
+			Token::Literal(c) => {
+				ctx.emit(c.encode_utf8(&mut [0; 4]));
+				out.push(*c);
+				Ok(())
+			}
 			Token::Class(chars) => {
 				if chars.is_empty() {
 					Err(GenrexError::Internal("Empty class".to_string()))
 				} else {
-					let idx = rng.gen_range(0..chars.len());
-					Ok(chars[idx].to_string())
+					let c = pick_allowed(chars, rng, ctx);
+					ctx.emit(c.encode_utf8(&mut [0; 4]));
+					out.push(c);
+					Ok(())
 				}
 			}
 			Token::NegatedClass(_chars) => {
@@ -44,27 +322,31 @@ impl RegexToken for Token {
 				Err(GenrexError::UnsupportedFeature("Negated class generation".to_string()))
 			}
 			Token::Concatenation(tokens) => {
-				let mut out = String::new();
+				let start = out.len();
 				for t in tokens {
-					ctx.set_output_len(out.len());
-					out.push_str(&t.generate(rng, ctx)?);
+					ctx.set_output_len(out.len() - start);
+					t.generate_into(rng, ctx, out)?;
+					if ctx.over_limit() {
+						return Err(GenrexError::NoMatch);
+					}
 				}
-				Ok(out)
+				Ok(())
 			}
 			Token::Alternation(choices) => {
 				if choices.is_empty() {
 					Err(GenrexError::Internal("Empty alternation".to_string()))
 				} else {
 					let idx = rng.gen_range(0..choices.len());
+					ctx.first_branch.get_or_insert(idx);
+					ctx.trace_choice(Choice::Branch(idx));
 					ctx.set_output_len(0); // caller will set top-level, but ensure child sees a sane baseline
-					choices[idx].generate(rng, ctx)
+					choices[idx].generate_into(rng, ctx, out)
 				}
 			}
 			Token::Quantifier { token, min, max, greedy } => {
 				// Avoid unbounded quantifiers producing enormous ranges (e.g., max == usize::MAX).
-				const MAX_REPEAT: usize = 32;
 				if min > max { return Err(GenrexError::Internal("Quantifier min > max".to_string())); }
-				let effective_max = if *max == usize::MAX { (*min).saturating_add(MAX_REPEAT) } else { *max };
+				let effective_max = if *max == usize::MAX { (*min).saturating_add(ctx.max_repeat) } else { *max };
 				let count = if *min == *max {
 					*min
 				} else {
@@ -73,24 +355,48 @@ impl RegexToken for Token {
 					let b = rng.gen_range(*min..=effective_max);
 					if *greedy { a.max(b) } else { a.min(b) }
 				};
-				let mut out = String::new();
+				ctx.trace_choice(Choice::Repeat(count));
+				let start = out.len();
 				for _ in 0..count {
-					ctx.set_output_len(out.len());
-					out.push_str(&token.generate(rng, ctx)?);
+					ctx.set_output_len(out.len() - start);
+					token.generate_into(rng, ctx, out)?;
+					if ctx.over_limit() {
+						return Err(GenrexError::NoMatch);
+					}
 				}
-				Ok(out)
+				Ok(())
 			}
 			Token::Group(inner, idx) => {
 				// Ensure nested generation sees the current output length.
 				ctx.set_output_len(0); // caller for top-level tokens sets position; nested groups start from caller's last set position.
-				let s = inner.generate(rng, ctx)?;
+				// Apply any constraint-driven override for this group. Fixed values replace the
+				// group wholesale, so its inner tokens (which may not be generatable) are skipped.
+				if ctx.group_overrides.contains_key(idx) {
+					ctx.trace_choice(Choice::Override);
+				}
+				let start = out.len();
+				match ctx.group_overrides.get(idx) {
+					Some(GroupOverride::Fixed(v)) => {
+						let v = v.clone();
+						ctx.emit(&v);
+						out.push_str(&v);
+					}
+					Some(GroupOverride::Counter(n)) => {
+						let n = *n;
+						inner.generate_into(rng, ctx, out)?;
+						let width = out[start..].chars().count();
+						out.truncate(start);
+						let _ = write!(out, "{:0width$}", n, width = width);
+					}
+					None => inner.generate_into(rng, ctx, out)?,
+				}
 				// Record capture into context at the specified index.
-				ctx.record_capture(*idx, s.clone());
-				Ok(s)
+				ctx.record_capture(*idx, out[start..].to_string());
+				Ok(())
 			}
 			Token::NonCapturingGroup(inner) => {
 				ctx.set_output_len(0);
-				inner.generate(rng, ctx)
+				inner.generate_into(rng, ctx, out)
 			}
 			Token::Backreference(idx) => {
 				// Backreference support: lookup previously recorded capture by group index (1-based).
@@ -103,39 +409,84 @@ impl RegexToken for Token {
 					return Err(GenrexError::BackreferenceError(format!("no capture available for backreference \\{}", idx)));
 				}
 				if let Some(s) = ctx.get_capture(*idx) {
-					Ok(s)
+					ctx.emit(&s);
+					out.push_str(&s);
 				} else {
-					// Record unresolved forward backreference for later resolution.
+					// Record unresolved forward backreference for later resolution; the resolver
+					// inserts the actual text at the recorded position.
 					ctx.add_unresolved(*idx);
-					// Return empty for now; resolver may insert the actual text at the recorded position.
-					Ok(String::new())
 				}
+				Ok(())
 			}
-			Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary => Ok(String::new()),
+			Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary => Ok(()),
 			Token::Wildcard => {
 				// For MVP, use ASCII alphanumeric
-				const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
-				let idx = rng.gen_range(0..ALPHABET.len());
-				Ok((ALPHABET[idx] as char).to_string())
+				let c = pick_allowed(WILDCARD_CHARS, rng, ctx);
+				ctx.emit(c.encode_utf8(&mut [0; 4]));
+				out.push(c);
+				Ok(())
 			}
 		}
 	}
+}
 
-	fn describe(&self) -> String {
-		match self {
-			Token::Literal(c) => format!("Literal('{}')", c),
-			Token::Class(chars) => format!("Class[{}]", chars.iter().collect::<String>()),
-			Token::NegatedClass(chars) => format!("NegatedClass[{}]", chars.iter().collect::<String>()),
-			Token::Concatenation(tokens) => format!("Concat({})", tokens.len()),
-			Token::Alternation(choices) => format!("Alt({})", choices.len()),
-			Token::Quantifier { min, max, .. } => format!("Quantifier{{{},{}}}", min, max),
-			Token::Group(_, idx) => format!("Group({})", idx),
-			Token::NonCapturingGroup(_) => "NonCapturingGroup".to_string(),
-			Token::Backreference(idx) => format!("Backreference({})", idx),
-			Token::AnchorStart => "AnchorStart".to_string(),
-			Token::AnchorEnd => "AnchorEnd".to_string(),
-			Token::WordBoundary => "WordBoundary".to_string(),
-			Token::Wildcard => "Wildcard".to_string(),
+/// Characters easily mistaken for one another (`0`/`O`, `1`/`l`/`I`), avoided by `humanize`.
+const CONFUSABLE: &[char] = &['0', 'O', 'o', '1', 'l', 'I', '|'];
+
+fn is_vowel(c: char) -> bool {
+	matches!(c.to_ascii_lowercase(), 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// The members of `chars` a human reads most easily after `last`: no confusables, and a vowel
+/// after a consonant or a consonant after a vowel. Each preference is dropped when `chars` has
+/// nothing that satisfies it, so the result is never empty.
+fn readable_choices(chars: &[char], last: Option<char>) -> Vec<char> {
+	let mut clear: Vec<char> = chars.iter().copied().filter(|c| !CONFUSABLE.contains(c)).collect();
+	if clear.is_empty() {
+		clear = chars.to_vec();
+	}
+	let want_vowel = match last {
+		Some(c) if c.is_ascii_alphabetic() => !is_vowel(c),
+		_ => return clear,
+	};
+	let alternating: Vec<char> = clear.iter().copied().filter(|&c| c.is_ascii_alphabetic() && is_vowel(c) == want_vowel).collect();
+	if alternating.is_empty() { clear } else { alternating }
+}
+
+/// Pick a random char from `chars`, preferring ones that do not complete a forbidden substring.
+/// Falls back to an unconstrained pick when every choice is forbidden; the caller's final check
+/// then rejects the candidate. Picks follow `ctx.char_weights` when set and narrow to readable
+/// characters under `ctx.humanize`.
+pub(crate) fn pick_allowed<R: Rng + ?Sized>(chars: &[char], rng: &mut R, ctx: &TokenContext) -> char {
+	let allowed: Vec<char>;
+	let readable: Vec<char>;
+	let mut pool = chars;
+	if !ctx.forbidden.is_empty() {
+		allowed = chars.iter().copied().filter(|&c| ctx.allows(c)).collect();
+		if !allowed.is_empty() {
+			pool = &allowed;
+		}
+	}
+	if ctx.humanize {
+		readable = readable_choices(pool, ctx.last_char());
+		pool = &readable;
+	}
+	if let Some(weights) = &ctx.char_weights {
+		let total: f64 = pool.iter().map(|&c| weights.weight(c)).sum();
+		if total > 0.0 {
+			let mut r = rng.gen_range(0.0..total);
+			for &c in pool {
+				let w = weights.weight(c);
+				if r < w {
+					return c;
+				}
+				r -= w;
+			}
+			// Rounding left `r` just past the last weight.
+			if let Some(&c) = pool.iter().rev().find(|&&c| weights.weight(c) > 0.0) {
+				return c;
+			}
 		}
 	}
+	pool[rng.gen_range(0..pool.len())]
 }
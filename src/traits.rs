@@ -1,12 +1,27 @@
 use rand::Rng;
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::constraints::GroupOverride;
 use crate::error::GenrexError;
+use crate::explain::{Choice, Recorder};
+use crate::tokens::Token;
+use crate::weights::CharWeights;
 
 /// Trait for a regex AST token node.
 pub trait RegexToken {
     /// Generate a string matching this token, using the provided PRNG and context.
     fn generate<R: Rng + ?Sized>(&self, rng: &mut R, ctx: &mut TokenContext) -> Result<String, GenrexError>;
 
+    /// Append a string matching this token to `out`. Implementations that can write in place
+    /// override this to avoid a `String` per token. On error, `out` may hold partial output.
+    fn generate_into<R: Rng + ?Sized>(&self, rng: &mut R, ctx: &mut TokenContext, out: &mut String) -> Result<(), GenrexError> {
+        out.push_str(&self.generate(rng, ctx)?);
+        Ok(())
+    }
+
     /// Returns a human-readable description of the token.
     fn describe(&self) -> String;
 }
@@ -23,14 +38,44 @@ pub struct TokenContext {
     /// Unresolved backreference placeholders recorded during first pass:
     /// (byte_pos_in_output, group_id)
     pub unresolved_refs: Vec<(usize, usize)>,
+    /// Values forced onto specific groups (by 1-based id) for this generation, from group constraints.
+    pub group_overrides: HashMap<usize, GroupOverride>,
+    /// Branch index chosen by the first alternation generated in this context.
+    pub first_branch: Option<usize>,
+    /// Substrings that must not appear in the output; leaf tokens steer away from completing them.
+    pub forbidden: Vec<String>,
+    /// Relative weights for picking characters from classes and `.`; uniform when `None`.
+    pub char_weights: Option<Arc<CharWeights>>,
+    /// Prefer readable characters: alternate consonants and vowels, avoid confusables like `0`/`O`.
+    pub humanize: bool,
+    /// Last character emitted, for `humanize`.
+    last_char: Option<char>,
+    /// Tail of the text emitted so far, long enough to detect any forbidden substring.
+    emitted_tail: String,
     /// Current output byte length (updated by the caller before generating each token).
     current_output_len: usize,
+    /// Bytes emitted so far.
+    emitted: usize,
+    /// Bytes a candidate may emit before its generation is abandoned.
+    pub(crate) output_limit: usize,
+    /// Work units left for this call, handed in and back by the engine; `None` when unbudgeted.
+    pub(crate) fuel: Option<u64>,
+    /// Flag that stops generation when set.
+    pub(crate) cancel: Option<Arc<AtomicBool>>,
+    /// Generation trace, when one was requested.
+    trace: Option<Recorder>,
+}
+
+impl Default for TokenContext {
+    fn default() -> Self {
+        TokenContext::new()
+    }
 }
  
 impl TokenContext {
     /// Create a TokenContext with the default max_repeat.
     pub fn new() -> Self {
-        TokenContext::new_with_max_repeat(32)
+        TokenContext::new_with_max_repeat(crate::lexer::MAX_REPEAT)
     }
  
     /// Create a TokenContext with a caller-provided max_repeat.
@@ -39,7 +84,19 @@ impl TokenContext {
             max_repeat,
             captures: Vec::new(),
             unresolved_refs: Vec::new(),
+            group_overrides: HashMap::new(),
+            first_branch: None,
+            forbidden: Vec::new(),
+            char_weights: None,
+            humanize: false,
+            last_char: None,
+            emitted_tail: String::new(),
             current_output_len: 0,
+            emitted: 0,
+            output_limit: usize::MAX,
+            fuel: None,
+            cancel: None,
+            trace: None,
         }
     }
  
@@ -48,6 +105,107 @@ impl TokenContext {
         self.current_output_len = len;
     }
  
+    /// True when emitting `c` next would not complete a forbidden substring.
+    pub fn allows(&self, c: char) -> bool {
+        if self.forbidden.is_empty() {
+            return true;
+        }
+        let mut probe = self.emitted_tail.clone();
+        probe.push(c);
+        !self.forbidden.iter().any(|f| probe.ends_with(f.as_str()))
+    }
+
+    /// Note text emitted by a leaf token, in output order.
+    pub fn emit(&mut self, s: &str) {
+        self.emitted = self.emitted.saturating_add(s.len());
+        if let Some(c) = s.chars().next_back() {
+            self.last_char = Some(c);
+        }
+        let keep = self.forbidden.iter().map(|f| f.len()).max().unwrap_or(0);
+        if keep == 0 {
+            return;
+        }
+        self.emitted_tail.push_str(s);
+        if self.emitted_tail.len() > keep {
+            let mut cut = self.emitted_tail.len() - keep;
+            while !self.emitted_tail.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            self.emitted_tail.drain(..cut);
+        }
+    }
+
+    /// True once more than `output_limit` bytes have been emitted.
+    pub(crate) fn over_limit(&self) -> bool {
+        self.emitted > self.output_limit
+    }
+
+    /// Spend one unit of fuel; false when none is left.
+    pub(crate) fn burn(&mut self) -> bool {
+        burn(&mut self.fuel)
+    }
+
+    /// Spend `units` of fuel at once; false when fewer are left.
+    pub(crate) fn burn_n(&mut self, units: u64) -> bool {
+        match &mut self.fuel {
+            Some(n) if *n < units => {
+                *n = 0;
+                false
+            }
+            Some(n) => {
+                *n -= units;
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// True when a fuel budget is set and spent.
+    pub(crate) fn out_of_fuel(&self) -> bool {
+        self.fuel == Some(0)
+    }
+
+    /// True once the cancel flag is set.
+    pub(crate) fn cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
+    /// The last character emitted so far.
+    pub(crate) fn last_char(&self) -> Option<char> {
+        self.last_char
+    }
+
+    /// Record a trace of the tokens generated from now on.
+    pub(crate) fn start_trace(&mut self) {
+        self.trace = Some(Recorder::default());
+    }
+
+    pub(crate) fn take_trace(&mut self) -> Option<Recorder> {
+        self.trace.take()
+    }
+
+    pub(crate) fn tracing(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    pub(crate) fn trace_open(&mut self, token: &Token) {
+        if let Some(trace) = &mut self.trace {
+            trace.open(token);
+        }
+    }
+
+    pub(crate) fn trace_choice(&mut self, choice: Choice) {
+        if let Some(trace) = &mut self.trace {
+            trace.choose(choice);
+        }
+    }
+
+    pub(crate) fn trace_close(&mut self, text: Option<&str>, pending: Option<usize>) {
+        if let Some(trace) = &mut self.trace {
+            trace.close(text, pending);
+        }
+    }
+
     /// Record an unresolved backreference for the current output position.
     pub fn add_unresolved(&mut self, group_id: usize) {
         self.unresolved_refs.push((self.current_output_len, group_id));
@@ -126,3 +284,15 @@ pub trait GenerationAgent {
     /// Returns `GenrexError` if generation fails or the strategy is unsupported.
     fn generate_with_strategy(&mut self, strategy: &str) -> Result<String, GenrexError>;
 }
+
+/// Spend one unit from an optional budget; false when it is spent, leaving it at zero.
+pub(crate) fn burn(fuel: &mut Option<u64>) -> bool {
+    match fuel {
+        None => true,
+        Some(0) => false,
+        Some(n) => {
+            *n -= 1;
+            true
+        }
+    }
+}
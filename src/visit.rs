@@ -0,0 +1,136 @@
+//! Visitors over the token tree.
+//!
+//! `TokenVisitor` reads a tree and `TokenVisitorMut` rewrites one in place. Each has one method
+//! per kind of token; the defaults descend into children through the matching `walk_*` function,
+//! so an implementation only overrides the kinds it cares about. An override that still wants
+//! the children visited calls the `walk_*` function itself.
+
+use std::sync::Arc;
+
+use crate::tokens::Token;
+
+/// Read-only traversal of a token tree.
+pub trait TokenVisitor {
+    /// Entry point for every token; dispatches on its kind.
+    fn visit_token(&mut self, token: &Token) {
+        walk_token(self, token);
+    }
+
+    fn visit_literal(&mut self, _c: char) {}
+
+    /// Character classes; `negated` for `[^...]`.
+    fn visit_class(&mut self, _chars: &[char], _negated: bool) {}
+
+    fn visit_concatenation(&mut self, tokens: &[Token]) {
+        walk_tokens(self, tokens);
+    }
+
+    fn visit_alternation(&mut self, branches: &[Token]) {
+        walk_tokens(self, branches);
+    }
+
+    fn visit_quantifier(&mut self, token: &Token, _min: usize, _max: usize, _greedy: bool) {
+        self.visit_token(token);
+    }
+
+    /// Groups; `index` is the 1-based group number, `None` for non-capturing groups.
+    fn visit_group(&mut self, inner: &Token, _index: Option<usize>) {
+        self.visit_token(inner);
+    }
+
+    fn visit_backreference(&mut self, _index: usize) {}
+
+    /// Zero-width tokens: `^`, `$` and `\b`.
+    fn visit_assertion(&mut self, _token: &Token) {}
+
+    fn visit_wildcard(&mut self) {}
+}
+
+/// Dispatch `token` to the visitor method for its kind.
+pub fn walk_token<V: TokenVisitor + ?Sized>(visitor: &mut V, token: &Token) {
+    match token {
+        Token::Literal(c) => visitor.visit_literal(*c),
+        Token::Class(chars) => visitor.visit_class(chars, false),
+        Token::NegatedClass(chars) => visitor.visit_class(chars, true),
+        Token::Concatenation(tokens) => visitor.visit_concatenation(tokens),
+        Token::Alternation(branches) => visitor.visit_alternation(branches),
+        Token::Quantifier { token, min, max, greedy } => visitor.visit_quantifier(token, *min, *max, *greedy),
+        Token::Group(inner, index) => visitor.visit_group(inner, Some(*index)),
+        Token::NonCapturingGroup(inner) => visitor.visit_group(inner, None),
+        Token::Backreference(index) => visitor.visit_backreference(*index),
+        Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary => visitor.visit_assertion(token),
+        Token::Wildcard => visitor.visit_wildcard(),
+    }
+}
+
+/// Visit each token of a sequence in order.
+pub fn walk_tokens<V: TokenVisitor + ?Sized>(visitor: &mut V, tokens: &[Token]) {
+    for token in tokens {
+        visitor.visit_token(token);
+    }
+}
+
+/// In-place rewriting of a token tree.
+///
+/// `visit_token` may replace a token wholesale (for example a class with a literal); the
+/// per-kind methods edit the parts of a token of that kind.
+pub trait TokenVisitorMut {
+    fn visit_token_mut(&mut self, token: &mut Token) {
+        walk_token_mut(self, token);
+    }
+
+    fn visit_literal_mut(&mut self, _c: &mut char) {}
+
+    fn visit_class_mut(&mut self, _chars: &mut Vec<char>, _negated: bool) {}
+
+    fn visit_concatenation_mut(&mut self, tokens: &mut Vec<Token>) {
+        walk_tokens_mut(self, tokens);
+    }
+
+    fn visit_alternation_mut(&mut self, branches: &mut Vec<Token>) {
+        walk_tokens_mut(self, branches);
+    }
+
+    fn visit_quantifier_mut(&mut self, token: &mut Token, _min: &mut usize, _max: &mut usize, _greedy: &mut bool) {
+        self.visit_token_mut(token);
+    }
+
+    fn visit_group_mut(&mut self, inner: &mut Token, _index: Option<usize>) {
+        self.visit_token_mut(inner);
+    }
+
+    fn visit_backreference_mut(&mut self, _index: &mut usize) {}
+}
+
+/// Dispatch `token` to the mutable visitor method for its kind.
+pub fn walk_token_mut<V: TokenVisitorMut + ?Sized>(visitor: &mut V, token: &mut Token) {
+    match token {
+        Token::Literal(c) => visitor.visit_literal_mut(c),
+        Token::Class(chars) => visit_class_mut(visitor, chars, false),
+        Token::NegatedClass(chars) => visit_class_mut(visitor, chars, true),
+        Token::Concatenation(tokens) => visitor.visit_concatenation_mut(tokens),
+        Token::Alternation(branches) => visitor.visit_alternation_mut(branches),
+        Token::Quantifier { token, min, max, greedy } => visitor.visit_quantifier_mut(token, min, max, greedy),
+        Token::Group(inner, index) => visitor.visit_group_mut(inner, Some(*index)),
+        Token::NonCapturingGroup(inner) => visitor.visit_group_mut(inner, None),
+        Token::Backreference(index) => visitor.visit_backreference_mut(index),
+        Token::AnchorStart | Token::AnchorEnd | Token::WordBoundary | Token::Wildcard => {}
+    }
+}
+
+/// Hand a class set to the visitor as a `Vec`, replacing the (possibly shared) set only if the
+/// visitor changed it.
+fn visit_class_mut<V: TokenVisitorMut + ?Sized>(visitor: &mut V, chars: &mut Arc<[char]>, negated: bool) {
+    let mut edited = chars.to_vec();
+    visitor.visit_class_mut(&mut edited, negated);
+    if edited[..] != chars[..] {
+        *chars = edited.into();
+    }
+}
+
+/// Visit each token of a sequence in order, mutably.
+pub fn walk_tokens_mut<V: TokenVisitorMut + ?Sized>(visitor: &mut V, tokens: &mut [Token]) {
+    for token in tokens {
+        visitor.visit_token_mut(token);
+    }
+}
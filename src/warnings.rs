@@ -0,0 +1,51 @@
+//! Non-fatal notices collected while lexing and building a generator.
+
+use std::fmt;
+use std::ops::Range;
+
+/// What kind of degradation a warning describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A construct was ignored or approximated in lenient mode (e.g. a lookahead).
+    Degraded(String),
+    /// The `regex` crate rejected the pattern; candidates are not verified against it.
+    VerificationDisabled(String),
+    /// The `regex` crate rejected the pattern's backreferences; candidates are verified against
+    /// the pattern with each backreference replaced by a copy of its group, so a repeat is
+    /// checked for shape but not for being the same text.
+    VerificationRelaxed,
+    /// An open-ended quantifier (`*`, `+`, `{n,}`) is capped at this many extra repetitions.
+    UnboundedRepeatCapped(usize),
+    /// `.` is generated from ASCII alphanumerics only. Reported once, at the first `.`.
+    WildcardApproximated,
+}
+
+/// A non-fatal notice about how faithfully a pattern will be generated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    /// Byte span in the pattern, when the notice concerns a specific construct.
+    pub span: Option<Range<usize>>,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, span: Option<Range<usize>>) -> Self {
+        Warning { kind, span }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            WarningKind::Degraded(what) => write!(f, "{} ignored or approximated", what)?,
+            WarningKind::VerificationDisabled(e) => write!(f, "pattern not verifiable with the regex crate ({})", e)?,
+            WarningKind::VerificationRelaxed => f.write_str("backreferences verified against their group's pattern, not its captured text")?,
+            WarningKind::UnboundedRepeatCapped(n) => write!(f, "unbounded repetition capped at {} extra repeats", n)?,
+            WarningKind::WildcardApproximated => f.write_str("wildcard generated from ASCII alphanumerics only")?,
+        }
+        if let Some(span) = &self.span {
+            write!(f, " at {}..{}", span.start, span.end)?;
+        }
+        Ok(())
+    }
+}
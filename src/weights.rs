@@ -0,0 +1,74 @@
+//! Character weights for sampling from classes and `.`, so outputs resemble real text instead of
+//! uniformly random characters.
+
+use std::collections::HashMap;
+
+/// English letter frequencies, in percent of letters.
+const ENGLISH_LETTERS: &[(char, f64)] = &[
+    ('e', 12.7), ('t', 9.06), ('a', 8.17), ('o', 7.51), ('i', 6.97), ('n', 6.75), ('s', 6.33), ('h', 6.09),
+    ('r', 5.99), ('d', 4.25), ('l', 4.03), ('c', 2.78), ('u', 2.76), ('m', 2.41), ('w', 2.36), ('f', 2.23),
+    ('g', 2.02), ('y', 1.97), ('p', 1.93), ('b', 1.29), ('v', 0.98), ('k', 0.77), ('j', 0.15), ('x', 0.15),
+    ('q', 0.095), ('z', 0.074),
+];
+
+/// Capitals are this much rarer than the same letter in lowercase.
+const UPPERCASE_FACTOR: f64 = 0.1;
+
+/// Digits, biased toward small leading digits as in Benford's law.
+const DIGITS: &[(char, f64)] =
+    &[('0', 1.0), ('1', 3.01), ('2', 1.76), ('3', 1.25), ('4', 0.97), ('5', 0.79), ('6', 0.67), ('7', 0.58), ('8', 0.51), ('9', 0.46)];
+
+/// Spaces and common punctuation.
+const OTHERS: &[(char, f64)] = &[(' ', 18.0), ('.', 0.65), (',', 0.61), ('-', 0.2), ('\'', 0.24), ('_', 0.1)];
+
+/// Relative weights for picking characters from a class or `.`; see
+/// `RegexGeneratorBuilder::char_weights`.
+///
+/// Each member of a class is picked with probability proportional to its weight among the
+/// members. Characters without an entry take the default weight. When every member weighs zero
+/// the pick is uniform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharWeights {
+    weights: HashMap<char, f64>,
+    default: f64,
+}
+
+impl Default for CharWeights {
+    fn default() -> Self {
+        CharWeights { weights: HashMap::new(), default: 1.0 }
+    }
+}
+
+impl CharWeights {
+    /// Every character weighs 1: uniform picks, until weights are set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// English text: letter frequencies (capitals ten times rarer), digits favoring 1, and
+    /// frequent spaces; anything else weighs 0.1.
+    pub fn english() -> Self {
+        let mut weights: HashMap<char, f64> = ENGLISH_LETTERS.iter().copied().collect();
+        weights.extend(ENGLISH_LETTERS.iter().map(|&(c, w)| (c.to_ascii_uppercase(), w * UPPERCASE_FACTOR)));
+        weights.extend(DIGITS.iter().copied());
+        weights.extend(OTHERS.iter().copied());
+        CharWeights { weights, default: 0.1 }
+    }
+
+    /// Set the weight of `c`. Negative weights count as zero.
+    pub fn set(mut self, c: char, weight: f64) -> Self {
+        self.weights.insert(c, weight.max(0.0));
+        self
+    }
+
+    /// Set the weight of characters without an entry.
+    pub fn default_weight(mut self, weight: f64) -> Self {
+        self.default = weight.max(0.0);
+        self
+    }
+
+    /// The weight of `c`.
+    pub fn weight(&self, c: char) -> f64 {
+        self.weights.get(&c).copied().unwrap_or(self.default)
+    }
+}
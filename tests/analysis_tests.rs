@@ -0,0 +1,173 @@
+//! Tests for static pattern analysis.
+
+use std::collections::HashSet;
+
+use genrex::{analyze, BigUint, GeneratorConfig, LengthUnit, RegexGenerator};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[test]
+fn test_fixed_width_id_cardinality() {
+    let a = analyze("^[0-9a-f]{8}$").unwrap();
+    assert_eq!((a.min_len, a.max_len, a.finite), (8, Some(8), true));
+    assert_eq!(a.cardinality, Some(BigUint::from(16u64.pow(8))));
+    assert_eq!(a.entropy_bits(..), Some(32.0));
+}
+
+#[test]
+fn test_cardinality_counts_distinct_strings() {
+    let a = analyze("^(a|a)b?$").unwrap();
+    assert_eq!(a.cardinality, Some(BigUint::from(2u32)));
+    let a = analyze("^(foo|bar){1,2}$").unwrap();
+    assert_eq!((a.min_len, a.max_len), (3, Some(6)));
+    assert_eq!(a.cardinality, Some(BigUint::from(6u32)));
+    let a = analyze("[a-z]{30}").unwrap();
+    assert_eq!(a.cardinality, Some(BigUint::from(26u32).pow(30)));
+}
+
+#[test]
+fn test_infinite_language() {
+    let a = analyze("^x[ab]+y*$").unwrap();
+    assert_eq!((a.min_len, a.max_len, a.finite), (2, None, false));
+    assert_eq!(a.cardinality, None);
+    assert_eq!(a.entropy_bits(..), None);
+}
+
+#[test]
+fn test_generator_analyze_and_unsupported() {
+    let g = RegexGenerator::builder("^id-[0-9]{4}$").build().unwrap();
+    assert_eq!(g.analyze().unwrap(), analyze("^id-[0-9]{4}$").unwrap());
+    assert!(matches!(analyze("^(a)\\1$"), Err(genrex::GenError::Unsupported(_))));
+}
+
+#[test]
+fn test_count_matches_per_length() {
+    let g = RegexGenerator::builder("^[a-z]{2,4}[0-9]?$").build().unwrap();
+    assert_eq!(g.count_matches(1).unwrap(), BigUint::from(0u32));
+    assert_eq!(g.count_matches(2).unwrap(), BigUint::from(26u32 * 26));
+    // "aaa" alone or "aa" plus a digit.
+    assert_eq!(g.count_matches(3).unwrap(), BigUint::from(26u32.pow(3) + 26 * 26 * 10));
+    assert_eq!(g.count_matches(6).unwrap(), BigUint::from(0u32));
+    let total: BigUint = (0..=5).map(|n| g.count_matches(n).unwrap()).sum();
+    assert_eq!(Some(total), g.analyze().unwrap().cardinality);
+
+    let g = RegexGenerator::builder("^a*b*$").build().unwrap();
+    assert_eq!(g.count_matches(10).unwrap(), BigUint::from(11u32));
+}
+
+#[test]
+fn test_nth_match_enumerates_in_order() {
+    let g = RegexGenerator::builder("^(ab?|c)[01]$").build().unwrap();
+    let all: Vec<String> = (0u32..)
+        .map_while(|k| g.nth_match(&BigUint::from(k), 8).unwrap())
+        .collect();
+    assert_eq!(all, ["a0", "a1", "ab0", "ab1", "c0", "c1"]);
+
+    // Infinite language: the bound decides the order's extent.
+    let g = RegexGenerator::builder("^a*$").build().unwrap();
+    assert_eq!(g.nth_match(&BigUint::from(3u32), 5).unwrap().as_deref(), Some("aaa"));
+    assert_eq!(g.nth_match(&BigUint::from(6u32), 5).unwrap(), None);
+
+    let g = RegexGenerator::builder("^[0-9a-f]{16}$").build().unwrap();
+    let k = BigUint::from(16u32).pow(16) - 1u32;
+    assert_eq!(g.nth_match(&k, 16).unwrap().as_deref(), Some("ffffffffffffffff"));
+    assert_eq!(g.nth_match(&BigUint::from(255u32), 16).unwrap().as_deref(), Some("00000000000000ff"));
+}
+
+#[test]
+fn test_rank_inverts_nth_match() {
+    let g = RegexGenerator::builder("^(ab?|c)[01]$").build().unwrap();
+    assert_eq!(g.rank("ab0", 8).unwrap(), Some(BigUint::from(2u32)));
+    assert_eq!(g.rank("c1", 8).unwrap(), Some(BigUint::from(5u32)));
+    assert_eq!(g.rank("ab", 8).unwrap(), None);
+    assert_eq!(g.rank("x0", 8).unwrap(), None);
+
+    let g = RegexGenerator::builder("^[a-c]{0,3}(x|yz)?$").build().unwrap();
+    let total = (0u32..).take_while(|&k| g.nth_match(&BigUint::from(k), 5).unwrap().is_some()).count();
+    for k in 0..total as u32 {
+        let s = g.nth_match(&BigUint::from(k), 5).unwrap().unwrap();
+        assert_eq!(g.rank(&s, 5).unwrap(), Some(BigUint::from(k)), "{}", s);
+    }
+    assert_eq!(g.rank("abcyz", 4).unwrap(), None);
+}
+
+#[test]
+fn test_generate_n_unique() {
+    let mut g = RegexGenerator::builder("^[a-z]{3}$").rng(StdRng::seed_from_u64(3)).build().unwrap();
+    let out = g.generate_n_unique(500).unwrap();
+    assert_eq!(out.len(), 500);
+    assert_eq!(out.iter().collect::<HashSet<_>>().len(), 500);
+
+    // Asking for the whole language works; asking for more fails up front.
+    let mut g = RegexGenerator::builder("^(red|green|blue)-[12]$").rng(StdRng::seed_from_u64(3)).build().unwrap();
+    let mut all = g.generate_n_unique(6).unwrap();
+    all.sort();
+    assert_eq!(all, ["blue-1", "blue-2", "green-1", "green-2", "red-1", "red-2"]);
+    let err = g.generate_n_unique(7).unwrap_err();
+    assert!(err.to_string().contains("only 6 distinct matches"), "{}", err);
+    let mut g = RegexGenerator::builder("^ok$").build().unwrap();
+    let err = g.generate_n_unique(2).unwrap_err();
+    assert!(err.to_string().contains("only 1 distinct match within"), "{}", err);
+
+    // Two chars per grapheme: the char-length count would say only one fits.
+    let config = GeneratorConfig { min_len: 1, max_len: 2, ..GeneratorConfig::default() };
+    let mut g = RegexGenerator::builder("^(e\u{301}){1,3}$")
+        .config(config)
+        .length_unit(LengthUnit::Graphemes)
+        .rng(StdRng::seed_from_u64(3))
+        .build()
+        .unwrap();
+    let mut all = g.generate_n_unique(2).unwrap();
+    all.sort();
+    assert_eq!(all, ["e\u{301}", "e\u{301}e\u{301}"]);
+}
+
+#[test]
+fn test_generate_n_unique_fills_from_enumeration() {
+    // A skewed language: random draws rarely reach the long tail, enumeration completes it.
+    let config = GeneratorConfig { max_attempts: 20, ..GeneratorConfig::default() };
+    let mut g = RegexGenerator::builder("^(a|b[0-9]{2})$").config(config).rng(StdRng::seed_from_u64(1)).build().unwrap();
+    let out = g.generate_n_unique(101).unwrap();
+    assert_eq!(out.iter().collect::<HashSet<_>>().len(), 101);
+}
+
+#[test]
+fn test_entropy_within_length_range() {
+    let a = analyze("^x[ab]+$").unwrap();
+    assert_eq!(a.entropy_bits(..), None);
+    assert_eq!(a.length_counts[..4], [0u32, 0, 2, 4].map(BigUint::from));
+    // 2 + 4 + ... + 512 strings of lengths 2 through 10.
+    let bits = a.entropy_bits(..=10).unwrap();
+    assert!((bits - 1022f64.log2()).abs() < 1e-9, "{}", bits);
+    assert_eq!(a.entropy_bits(3..3), Some(0.0));
+
+    let a = analyze("^[a-z]{1,10}$").unwrap();
+    assert_eq!(a.entropy_bits(3..=3), Some(3.0 * 26f64.log2()));
+    assert_eq!(a.entropy_bits(..), a.entropy_bits(1..=10));
+}
+
+#[test]
+fn test_sampled_entropy() {
+    let g = RegexGenerator::builder("^[0-9a-f]{8}$").build().unwrap();
+    let e = g.entropy(..).unwrap();
+    assert_eq!(e.uniform_bits, 32.0);
+    assert!((e.sampled_bits.unwrap() - 32.0).abs() < 1e-9, "{:?}", e);
+
+    // Half of all outputs are "a", so sampling yields about half the uniform entropy.
+    let g = RegexGenerator::builder("^(a|[a-z]{20})$").build().unwrap();
+    let e = g.entropy(..).unwrap();
+    let long = 20.0 * 26f64.log2();
+    assert!((e.uniform_bits - long).abs() < 1e-6, "{:?}", e);
+    assert!((e.sampled_bits.unwrap() - (1.0 + long / 2.0)).abs() < 1e-6, "{:?}", e);
+    // Restricting lengths restricts the walk too.
+    let e = g.entropy(20..).unwrap();
+    assert!((e.sampled_bits.unwrap() - long).abs() < 1e-6, "{:?}", e);
+
+    // An ambiguous pattern is capped at the uniform entropy.
+    let e = RegexGenerator::builder("^(a|a)$").build().unwrap().entropy(..).unwrap();
+    assert_eq!((e.uniform_bits, e.sampled_bits), (0.0, Some(0.0)));
+
+    // Token-engine generators have no sampled figure.
+    let e = RegexGenerator::builder("^[a-z]{4}$").nfa(false).build().unwrap().entropy(..).unwrap();
+    assert_eq!(e.sampled_bits, None);
+}
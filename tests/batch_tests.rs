@@ -0,0 +1,190 @@
+//! Tests for deterministic batch composition.
+
+use genrex::batch::{self, derive_seed};
+use std::sync::Arc;
+use std::thread;
+
+use genrex::{GenError, GeneratorConfig, GroupConstraint, RegexGenerator};
+use rand::distributions::Distribution;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn batch(pattern: &str, seed: u64) -> Vec<String> {
+    RegexGenerator::builder(pattern)
+        .config(GeneratorConfig { min_len: 0, max_len: 32, max_attempts: 1000, timeout: None })
+        .rng(StdRng::seed_from_u64(seed))
+        .build()
+        .unwrap()
+        .generate_n(10)
+        .unwrap()
+}
+
+fn dataset(seed: u64) -> Vec<String> {
+    let users = batch("^user:[a-z]{4}$", derive_seed(seed, 0));
+    let orders = batch("^order:[0-9]{6}$", derive_seed(seed, 1));
+    let mut mixed = batch::interleave(&[users, orders], derive_seed(seed, 2));
+    batch::shuffle(&mut mixed[..5], derive_seed(seed, 3));
+    mixed
+}
+
+#[test]
+fn test_dataset_reproducible_from_one_seed() {
+    assert_eq!(dataset(7), dataset(7));
+    assert_ne!(dataset(7), dataset(8));
+    assert_ne!(derive_seed(7, 0), derive_seed(7, 1));
+}
+
+#[test]
+fn test_interleave_preserves_batch_order() {
+    let a: Vec<u32> = (0..20).collect();
+    let b: Vec<u32> = (100..110).collect();
+    let merged = batch::interleave(&[a.clone(), b.clone()], 3);
+    assert_eq!(merged.len(), 30);
+    assert_eq!(merged.iter().copied().filter(|&x| x < 100).collect::<Vec<_>>(), a);
+    assert_eq!(merged.iter().copied().filter(|&x| x >= 100).collect::<Vec<_>>(), b);
+    assert_ne!(merged, [a, b].concat());
+}
+
+#[test]
+fn test_round_robin_and_shuffle() {
+    let merged = batch::round_robin(&[vec![1, 2, 3], vec![10], vec![20, 21]]);
+    assert_eq!(merged, vec![1, 10, 20, 2, 21, 3]);
+    let mut items: Vec<u32> = (0..50).collect();
+    batch::shuffle(&mut items, 9);
+    let mut again: Vec<u32> = (0..50).collect();
+    batch::shuffle(&mut again, 9);
+    assert_eq!(items, again);
+    again.sort();
+    assert_eq!(again, (0..50).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_reseed_matches_fresh_generator() {
+    let mut g = RegexGenerator::builder("^[a-z]{3}-(x|yz)+$").rng(StdRng::seed_from_u64(1)).build().unwrap();
+    g.generate_n(5).unwrap();
+    g.reseed(derive_seed(42, 3));
+    let after = g.generate_n(10).unwrap();
+    let fresh = RegexGenerator::builder("^[a-z]{3}-(x|yz)+$").rng(StdRng::seed_from_u64(derive_seed(42, 3))).build().unwrap().generate_n(10).unwrap();
+    assert_eq!(after, fresh);
+}
+
+#[test]
+fn test_fork_derives_reproducible_independent_streams() {
+    let base = RegexGenerator::builder("^[a-z]{8}$").seed(42).build().unwrap();
+    assert_eq!(base.seed(), Some(42));
+    let a = base.fork(0).unwrap().generate_n(10).unwrap();
+    let b = base.fork(1).unwrap().generate_n(10).unwrap();
+    assert_ne!(a, b);
+    assert_eq!(a, base.fork(0).unwrap().generate_n(10).unwrap());
+
+    // A fork is a generator built with the derived seed, and can be forked again.
+    let fresh = RegexGenerator::builder("^[a-z]{8}$").seed(derive_seed(42, 1)).build().unwrap().generate_n(10).unwrap();
+    assert_eq!(b, fresh);
+    let nested = base.fork(1).unwrap().fork(3).unwrap();
+    assert_eq!(nested.seed(), Some(derive_seed(derive_seed(42, 1), 3)));
+
+    // Forking does not disturb the base generator's own stream.
+    let mut base = base;
+    let own = base.generate_n(10).unwrap();
+    assert_eq!(own, RegexGenerator::builder("^[a-z]{8}$").seed(42).build().unwrap().generate_n(10).unwrap());
+
+    let custom = RegexGenerator::builder("^a$").rng(StdRng::seed_from_u64(1)).build().unwrap();
+    assert_eq!(custom.seed(), None);
+    assert!(custom.fork(0).is_err());
+}
+
+#[test]
+fn test_clone_continues_the_same_stream() {
+    // Custom RNGs clone too, unlike fork.
+    let mut original = RegexGenerator::builder("^[a-z]{3}-(x|yz)+$").rng(StdRng::seed_from_u64(5)).build().unwrap();
+    original.generate_n(3).unwrap();
+    let mut copy = original.clone();
+    assert_eq!(copy.stats(), original.stats());
+    assert_eq!(copy.generate_n(10).unwrap(), original.generate_n(10).unwrap());
+
+    // Reseeding a clone leaves the original untouched.
+    let mut reseeded = original.clone();
+    reseeded.reseed(7);
+    let fresh = RegexGenerator::builder("^[a-z]{3}-(x|yz)+$").rng(StdRng::seed_from_u64(7)).build().unwrap().generate_n(5).unwrap();
+    assert_eq!(reseeded.generate_n(5).unwrap(), fresh);
+    assert_eq!(original.generate_n(5).unwrap(), copy.generate_n(5).unwrap());
+}
+
+#[test]
+fn test_generate_with_seed_is_per_item() {
+    let mut g = RegexGenerator::builder("^[a-z]{6}$").seed(1).build().unwrap();
+    let rows: Vec<String> = (0..20).map(|row| g.generate_with_seed(row).unwrap()).collect();
+    // Any order, any generator of the same pattern: row n always gets the same value.
+    let mut other = RegexGenerator::builder("^[a-z]{6}$").seed(99).build().unwrap();
+    for row in (0..20).rev() {
+        assert_eq!(other.generate_with_seed(row).unwrap(), rows[row as usize]);
+    }
+    let mut reseeded = RegexGenerator::builder("^[a-z]{6}$").build().unwrap();
+    reseeded.reseed(7);
+    assert_eq!(reseeded.generate_one().unwrap(), rows[7]);
+    // The generator's own stream is unaffected.
+    let own = RegexGenerator::builder("^[a-z]{6}$").seed(1).build().unwrap().generate_n(5).unwrap();
+    assert_eq!(g.generate_n(5).unwrap(), own);
+}
+
+#[test]
+fn test_generate_one_ref_shares_across_threads() {
+    let g = Arc::new(RegexGenerator::builder("^[a-z]{3}-[0-9]{2}$").seed(1).build().unwrap());
+    let handles: Vec<_> = (0..4u64)
+        .map(|t| {
+            let g = Arc::clone(&g);
+            thread::spawn(move || {
+                let mut rng = StdRng::seed_from_u64(t);
+                (0..10).map(|_| g.generate_one_ref(&mut rng).unwrap()).collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let outputs: Vec<Vec<String>> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    // The caller's RNG alone decides the value.
+    let mut g = Arc::try_unwrap(g).ok().unwrap();
+    for (t, values) in outputs.iter().enumerate() {
+        assert_eq!(values[0], g.generate_with_seed(t as u64).unwrap());
+    }
+    assert_eq!(g.stats().accepted, 4);
+
+    let constrained = RegexGenerator::builder("^(?P<id>[a-z]{2})$").group_constraint("id", GroupConstraint::Unique).build().unwrap();
+    assert!(matches!(constrained.generate_one_ref(&mut StdRng::seed_from_u64(0)), Err(GenError::Unsupported(_))));
+}
+
+#[test]
+fn test_sample_as_distribution() {
+    let g = RegexGenerator::builder("^[a-f]{4}$").build().unwrap();
+    let mut rng = StdRng::seed_from_u64(3);
+    let one: String = rng.sample(&g);
+    let many: Vec<String> = (&mut rng).sample_iter(&g).take(20).collect();
+    assert!(many.iter().chain([&one]).all(|s| s.len() == 4 && s.chars().all(|c| ('a'..='f').contains(&c))));
+    // Sampling is generate_one_ref.
+    assert_eq!(g.sample(&mut StdRng::seed_from_u64(3)), one);
+    assert_eq!(g.generate_one_ref(&mut StdRng::seed_from_u64(3)).unwrap(), one);
+}
+
+#[test]
+fn test_generate_into_reuses_buffer() {
+    // NFA, token (backreference) and unanchored generation all write the values generate_one gives.
+    for (pattern, backrefs) in [("^[a-z]{3}-(x|yz)+$", false), (r"^(ab|cd)x{1,4}-\1$", true)] {
+        let build = || {
+            let b = RegexGenerator::builder(pattern).seed(8);
+            if backrefs { b.allow_backrefs() } else { b }.build().unwrap()
+        };
+        let expected = build().generate_n(10).unwrap();
+        let mut g = build();
+        let mut buf = String::new();
+        for value in &expected {
+            buf.clear();
+            g.generate_into(&mut buf).unwrap();
+            assert_eq!(&buf, value);
+        }
+        let mut written = String::from(">");
+        build().generate_write(&mut written).unwrap();
+        assert_eq!(written, format!(">{}", expected[0]));
+    }
+    // On failure the buffer is untouched.
+    let mut starved = RegexGenerator::builder("^[a-z]{8}$").fuel(3).build().unwrap();
+    let mut buf = String::from("keep");
+    assert!(matches!(starved.generate_into(&mut buf), Err(GenError::BudgetExhausted)));
+    assert_eq!(buf, "keep");
+}
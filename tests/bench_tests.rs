@@ -0,0 +1,62 @@
+//! Tests for the benchmark API.
+
+use genrex::bench::{measure, measure_corpus, measure_generator, BenchConfig};
+use genrex::{Engine, GeneratorConfig, RegexGenerator, RejectReason};
+
+#[test]
+fn test_measure_counts() {
+    let config = BenchConfig { samples: 200, seed: 7, ..BenchConfig::default() };
+    let result = measure(r"^[a-f0-9]{8}$", &config).unwrap();
+    assert_eq!(result.samples, 200);
+    assert_eq!(result.failed, 0);
+    assert_eq!(result.stats.accepted, 200);
+    assert_eq!(result.histogram.values().sum::<u64>(), 200);
+    assert_eq!(result.engines, vec![(Engine::Nfa, 200)]);
+    assert_eq!(result.rejection_rate(), 0.0);
+    assert!(result.throughput() > 0.0);
+    assert!(result.latency(50) <= result.latency(99));
+    assert!(result.to_string().starts_with("outputs:         200\n"), "{}", result);
+}
+
+#[test]
+fn test_measure_rejections_repeat() {
+    // Only outputs of 30 or more characters pass, so many candidates are thrown away.
+    let run = || {
+        let config = GeneratorConfig { min_len: 30, max_len: 40, ..GeneratorConfig::default() };
+        let mut generator = RegexGenerator::builder(r"^([a-z]{1,20})-\1$").allow_backrefs().config(config).seed(1).build().unwrap();
+        measure_generator(&mut generator, 100)
+    };
+    let (first, second) = (run(), run());
+    assert_eq!(first.histogram, second.histogram);
+    assert_eq!(first.stats, second.stats);
+    assert_eq!(first.failed, second.failed);
+    assert!(first.rejection_rate() > 0.0);
+    assert!(first.stats.rejected_for(RejectReason::Length) > 0);
+    let taken: u64 = first.histogram.iter().map(|(attempts, n)| attempts * n).sum();
+    assert!(first.stats.attempts >= taken);
+}
+
+#[test]
+fn test_measure_counts_only_this_run() {
+    let mut generator = RegexGenerator::builder("^[0-9]{4}$").seed(3).build().unwrap();
+    generator.generate_n(10).unwrap();
+    let result = measure_generator(&mut generator, 5);
+    assert_eq!(result.stats.accepted, 5);
+    assert_eq!(generator.stats().accepted, 15);
+}
+
+#[test]
+fn test_measure_bad_pattern() {
+    assert!(measure("(", &BenchConfig::default()).is_err());
+}
+
+#[test]
+fn test_measure_corpus() {
+    let config = BenchConfig { samples: 20, seed: 5, ..BenchConfig::default() };
+    let results = measure_corpus(&config).unwrap();
+    let names: Vec<&str> = results.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, genrex::patterns::ALL.iter().map(|p| p.name).collect::<Vec<_>>());
+    for (name, result) in &results {
+        assert_eq!((result.failed, result.stats.accepted), (0, 20), "{}", name);
+    }
+}
@@ -0,0 +1,84 @@
+//! Tests for the fuel budget and cancellation.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use genrex::{GenError, GenrexError, GeneratorConfig, RegexGenerator};
+
+#[test]
+fn test_fuel_exhaustion_nfa() {
+    // Twenty characters take at least twenty NFA steps.
+    let mut starved = RegexGenerator::builder("[a-z]{20}").fuel(10).seed(1).build().unwrap();
+    assert!(matches!(starved.generate_one(), Err(GenError::BudgetExhausted)));
+    let mut fed = RegexGenerator::builder("[a-z]{20}").fuel(10_000).seed(1).build().unwrap();
+    assert_eq!(fed.generate_one().unwrap().len(), 20);
+}
+
+#[test]
+fn test_fuel_exhaustion_tokens() {
+    // Branch labels use the token engine.
+    let builder = || RegexGenerator::builder("red[0-9]{5}|blue[0-9]{5}").branch_labels(&["r", "b"]).seed(2);
+    assert!(matches!(builder().fuel(3).build().unwrap().generate_one(), Err(GenError::BudgetExhausted)));
+    let s = builder().fuel(10_000).build().unwrap().generate_one().unwrap();
+    assert!(s.starts_with("red") || s.starts_with("blue"), "{}", s);
+}
+
+#[test]
+fn test_fuel_is_per_call() {
+    let mut g = RegexGenerator::builder("[0-9]{4}").fuel(100).seed(3).build().unwrap();
+    // Many calls together spend far more than 100 units.
+    assert_eq!(g.generate_n(50).unwrap().len(), 50);
+}
+
+#[test]
+fn test_fuel_outcomes_repeat() {
+    // Lengths are often rejected, so some calls run out and others do not; the same seed and
+    // budget must give the same mix every run.
+    let run = || {
+        let mut g = RegexGenerator::builder("[a-z]{1,40}")
+            .config(GeneratorConfig { min_len: 35, max_len: 40, ..GeneratorConfig::default() })
+            .fuel(60)
+            .seed(4)
+            .build()
+            .unwrap();
+        (0..30).map(|_| g.generate_one().map_err(|e| e.to_string())).collect::<Vec<_>>()
+    };
+    let first = run();
+    assert_eq!(first, run());
+    assert!(first.iter().any(Result::is_ok));
+}
+
+#[test]
+fn test_budget_error_converts() {
+    let e: GenrexError = GenError::BudgetExhausted.into();
+    assert!(matches!(e, GenrexError::BudgetExhausted));
+}
+
+#[test]
+fn test_cancel_flag_set() {
+    let flag = Arc::new(AtomicBool::new(true));
+    let mut g = RegexGenerator::builder("[a-z]{8}").cancel_flag(flag.clone()).seed(5).build().unwrap();
+    assert!(matches!(g.generate_one(), Err(GenError::Cancelled)));
+    flag.store(false, Ordering::Relaxed);
+    assert_eq!(g.generate_one().unwrap().len(), 8);
+}
+
+#[test]
+fn test_cancel_from_another_thread() {
+    // Every candidate is filtered out, so without cancellation this would run ~forever.
+    let flag = Arc::new(AtomicBool::new(false));
+    let mut g = RegexGenerator::builder("[a-z]{8}")
+        .config(GeneratorConfig { max_attempts: usize::MAX, ..GeneratorConfig::default() })
+        .filter(|_| false)
+        .cancel_flag(flag.clone())
+        .build()
+        .unwrap();
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        flag.store(true, Ordering::Relaxed);
+    });
+    assert!(matches!(g.generate_one(), Err(GenError::Cancelled)));
+    canceller.join().unwrap();
+}
@@ -0,0 +1,45 @@
+//! Tests for the process-wide compiled pattern cache.
+
+use genrex::{cache, GenError, GeneratorConfig, ParseMode, RegexGenerator};
+
+// The cache is global, so everything runs in one test.
+#[test]
+fn test_pattern_cache() {
+    let outputs = |pattern: &str| RegexGenerator::builder(pattern).seed(3).build().unwrap().generate_n(10).unwrap();
+    let uncached = outputs("^[a-z]{4}-(x|yz)+$");
+    assert_eq!(cache::len(), 0);
+
+    cache::set_capacity(2);
+    // Cached generators behave exactly like freshly compiled ones, on a miss and on a hit.
+    assert_eq!(outputs("^[a-z]{4}-(x|yz)+$"), uncached);
+    assert_eq!(outputs("^[a-z]{4}-(x|yz)+$"), uncached);
+    assert_eq!(cache::len(), 1);
+
+    // Per-build options still apply to a cached pattern.
+    let config = GeneratorConfig { min_len: 9, max_len: 9, ..GeneratorConfig::default() };
+    let mut fixed = RegexGenerator::builder("^[a-z]{4}-(x|yz)+$").config(config).build().unwrap();
+    assert!(fixed.generate_n(10).unwrap().iter().all(|s| s.len() == 9));
+    assert_eq!(cache::len(), 1);
+
+    // Options that change compilation get their own entry.
+    RegexGenerator::builder("^[a-z]{4}-(x|yz)+$").parse_mode(ParseMode::Strict).build().unwrap();
+    assert_eq!(cache::len(), 2);
+
+    // Full: the least recently used entry (the strict one) goes.
+    outputs("^[a-z]{4}-(x|yz)+$");
+    outputs("^[0-9]{3}$");
+    assert_eq!(cache::len(), 2);
+    RegexGenerator::builder("^[a-z]{4}-(x|yz)+$").build().unwrap();
+    assert_eq!(cache::len(), 2);
+
+    // Failures are not cached.
+    assert!(matches!(RegexGenerator::builder("(").build(), Err(GenError::Parse(_))));
+    assert_eq!(cache::len(), 2);
+
+    cache::clear();
+    assert_eq!(cache::len(), 0);
+    outputs("^[0-9]{3}$");
+    cache::set_capacity(0);
+    assert_eq!(cache::len(), 0);
+    assert_eq!(outputs("^[a-z]{4}-(x|yz)+$"), uncached);
+}
@@ -0,0 +1,250 @@
+//! Tests running the `genrex-cli` binary.
+#![cfg(feature = "cli")]
+
+use std::process::Command;
+
+fn run(args: &[&str]) -> (bool, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_genrex-cli")).args(args).output().expect("binary runs");
+    (out.status.success(), String::from_utf8(out.stdout).expect("utf-8 output"))
+}
+
+#[test]
+fn test_field_rows_as_csv_and_json() {
+    let (ok, csv) = run(&["--field", r"id=\d{3}", "--field", "name=[a-z]{2}, [a-z]{2}", "--n", "4", "--seed", "1"]);
+    assert!(ok);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "id,name");
+    assert_eq!(lines.len(), 5);
+    assert!(lines[1..].iter().all(|l| l.len() == 12 && l[3..].starts_with(",\"") && l.ends_with('"')), "{}", csv);
+
+    let (ok, ndjson) = run(&["--field", r#"q="\d""#, "--format", "ndjson", "--n", "2", "--seed", "1"]);
+    assert!(ok);
+    assert!(ndjson.lines().all(|l| l.starts_with(r#"{"q":"\""#) && l.ends_with(r#"\""}"#)), "{}", ndjson);
+
+    let (ok, json) = run(&["^[a-z]{3}$", "--format", "json", "--n", "2", "--seed", "3"]);
+    assert!(ok);
+    let lines: Vec<&str> = json.lines().collect();
+    assert_eq!((lines[0], lines[3]), ("[", "]"));
+    assert!(lines[1].starts_with(r#"  {"value":""#) && lines[1].ends_with(','));
+
+    assert!(!run(&["--field", "missing-equals"]).0);
+    assert!(!run(&["^a$", "--format", "xml"]).0);
+}
+
+#[test]
+fn test_subcommands() {
+    let (ok, out) = run(&["gen", "^x[0-9]$", "--n", "3", "--seed", "2"]);
+    assert!(ok);
+    assert_eq!(out, run(&["^x[0-9]$", "--n", "3", "--seed", "2"]).1);
+
+    let (ok, tree) = run(&["explain", "^a(b|cd)+$"]);
+    assert!(ok);
+    assert!(tree.lines().any(|l| l == "├── repeat {1,}  (b|cd)+"), "{}", tree);
+    assert!(tree.lines().any(|l| l == "│   └── group #1  (b|cd)"), "{}", tree);
+    assert!(!tree.contains('\x1b'), "{}", tree);
+
+    let (ok, dot) = run(&["explain", "^a(b|cd)+$", "--dot"]);
+    assert!(ok);
+    assert!(dot.starts_with("digraph tokens {") && dot.contains("group #1"), "{}", dot);
+    let (ok, dot) = run(&["explain", "^a(b|cd)+$", "--dot", "nfa"]);
+    assert!(ok);
+    assert!(dot.starts_with("digraph nfa {") && dot.contains("[label=\"c\"]"), "{}", dot);
+
+    let (ok, analysis) = run(&["analyze", r"^[a-c]{2}-\d?$"]);
+    assert!(ok);
+    assert!(analysis.contains("min length:   3\n") && analysis.contains("cardinality:  99\n"), "{}", analysis);
+
+    let (ok, bench) = run(&["bench", "^[a-z]{3}$", "--n", "100", "--seed", "1"]);
+    assert!(ok);
+    assert!(bench.starts_with("outputs:         100\n") && bench.contains("strings/sec:"), "{}", bench);
+
+    let (ok, bench) = run(&["bench", r"^([a-z]+)-\1$", "--allow-backrefs", "--min", "5", "--max", "7", "--n", "50", "--seed", "1"]);
+    assert!(ok);
+    for label in ["rejection rate:", "  length:", "attempts/output:", "latency p50:", "latency p99:", "engine:          tokens (100.00%)"] {
+        assert!(bench.lines().any(|l| l.starts_with(label)), "{}: {}", label, bench);
+    }
+
+    assert!(!run(&["analyze", "--field", "a=b"]).0);
+}
+
+#[test]
+fn test_argument_validation() {
+    assert_eq!(run(&["^x[0-9]$", "--n=3", "--seed=2"]), run(&["^x[0-9]$", "-n", "3", "--seed", "2"]));
+    let status = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_genrex-cli")).args(args).output().unwrap().status.code();
+    assert_eq!(status(&["^a$", "--n", "abc"]), Some(2));
+    assert_eq!(status(&["^a$", "--attempts", "0"]), Some(2));
+    assert_eq!(status(&["^a$", "--field", "a=b"]), Some(2));
+    assert_eq!(status(&["^a$", "--bogus"]), Some(2));
+    assert_eq!(status(&["^a$", "--min", "5", "--max", "2"]), Some(1));
+}
+
+#[test]
+fn test_explain_flag_prints_tree_to_stderr() {
+    let out = Command::new(env!("CARGO_BIN_EXE_genrex-cli")).args(["^x[0-9]$", "--explain", "--color", "never", "--seed", "1"]).output().unwrap();
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout).unwrap().len(), 3);
+    let tree = String::from_utf8(out.stderr).unwrap();
+    assert!(tree.starts_with("concat  ^x[0-9]$\n") && tree.contains("├── class [0-9]"), "{}", tree);
+}
+
+#[test]
+fn test_output_file_append_and_gzip() {
+    use std::io::Read;
+
+    let dir = std::env::temp_dir().join(format!("genrex-cli-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let plain = dir.join("out.txt");
+    let path = plain.to_str().unwrap();
+    assert!(run(&["^a[0-9]$", "--n", "2", "--seed", "1", "--output", path]).0);
+    assert!(run(&["^b[0-9]$", "--n", "1", "--seed", "1", "--output", path, "--append"]).0);
+    let text = std::fs::read_to_string(&plain).unwrap();
+    assert_eq!(text.lines().map(|l| &l[..1]).collect::<String>(), "aab");
+
+    let gz = dir.join("out.gz");
+    let path = gz.to_str().unwrap();
+    assert!(run(&["--field", "id=[0-9]{3}", "--n", "50", "--seed", "1", "--gzip", "-o", path]).0);
+    assert!(run(&["--field", "id=[0-9]{3}", "--n", "1", "--seed", "2", "--gzip", "-o", path, "--append"]).0);
+    let mut csv = String::new();
+    flate2::read::MultiGzDecoder::new(std::fs::File::open(&gz).unwrap()).read_to_string(&mut csv).unwrap();
+    assert_eq!(csv.lines().filter(|l| *l == "id").count(), 2);
+    assert_eq!(csv.lines().count(), 53);
+
+    assert!(!run(&["^a$", "--append"]).0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_nul_and_no_delimiter() {
+    let (ok, nul) = run(&["^a[0-9]$", "--n", "3", "--seed", "1", "--print0"]);
+    assert!(ok);
+    let lines = run(&["^a[0-9]$", "--n", "3", "--seed", "1"]).1;
+    assert_eq!(nul, lines.replace('\n', "\0"));
+    let (ok, joined) = run(&["^a[0-9]$", "--n", "3", "--seed", "1", "--no-newline"]);
+    assert!(ok);
+    assert_eq!(joined, lines.replace('\n', ""));
+    assert!(!run(&["^a$", "-0", "--no-newline"]).0);
+    assert!(!run(&["--field", "a=b", "-0"]).0);
+}
+
+#[test]
+fn test_jobs_are_reproducible() {
+    let args = ["^[a-z]{4}[0-9]$", "--n", "10000", "--seed", "9", "--jobs", "3"];
+    let (ok, first) = run(&args);
+    assert!(ok);
+    assert_eq!(first, run(&args).1);
+    let re = regex::Regex::new("^[a-z]{4}[0-9]$").unwrap();
+    assert_eq!(first.lines().count(), 10000);
+    assert!(first.lines().all(|l| re.is_match(l)));
+    assert_ne!(first, run(&["^[a-z]{4}[0-9]$", "--n", "10000", "--seed", "9", "--jobs", "2"]).1);
+
+    assert!(!run(&["^a$", "--jobs", "0"]).0);
+    assert!(!run(&["--field", "a=b", "--jobs", "2"]).0);
+}
+
+#[test]
+fn test_stream_stops_at_byte_limit() {
+    let (ok, out) = run(&["^[a-z]{4}$", "--stream", "--limit-bytes", "23", "--seed", "1"]);
+    assert!(ok);
+    assert_eq!(out.len(), 20);
+    assert_eq!(out.lines().count(), 4);
+    assert!(!run(&["^a$", "--limit-bytes", "5"]).0);
+    assert!(!run(&["^a$", "--stream", "--jobs", "2"]).0);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_stream_finishes_output_on_sigint() {
+    use std::io::Read;
+
+    let path = std::env::temp_dir().join(format!("genrex-cli-stream-{}.gz", std::process::id()));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_genrex-cli"))
+        .args(["^[a-z]{4}$", "--stream", "--gzip", "-o", path.to_str().unwrap()])
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    assert!(Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap().success());
+    assert!(child.wait().unwrap().success());
+    let mut text = String::new();
+    flate2::read::GzDecoder::new(std::fs::File::open(&path).unwrap()).read_to_string(&mut text).unwrap();
+    assert!(!text.is_empty() && text.lines().all(|l| l.len() == 4));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_completions_and_man_page() {
+    let (ok, bash) = run(&["completions", "bash"]);
+    assert!(ok);
+    assert!(bash.contains("complete -F _genrex__cli") && bash.contains("--limit-bytes"));
+    let (ok, zsh) = run(&["completions", "zsh"]);
+    assert!(ok && zsh.starts_with("#compdef genrex-cli"));
+    let (ok, man) = run(&["man"]);
+    assert!(ok);
+    assert!(man.contains(".TH genrex-cli 1") && man.contains("explain"));
+    assert!(!run(&["completions", "tcsh"]).0);
+    assert!(!run(&["--help"]).1.contains("completions"));
+}
+
+#[test]
+fn test_env_seed_and_show_seed() {
+    let seeded = run(&["^[a-z]{6}$", "--n", "3", "--seed", "9"]).1;
+    let out = Command::new(env!("CARGO_BIN_EXE_genrex-cli")).args(["^[a-z]{6}$", "--n", "3"]).env("GENREX_SEED", "9").output().unwrap();
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), seeded);
+
+    let out = Command::new(env!("CARGO_BIN_EXE_genrex-cli")).args(["^[a-z]{6}$", "--n", "3", "--show-seed"]).output().unwrap();
+    assert!(out.status.success());
+    assert!(String::from_utf8(out.stderr).unwrap().starts_with("seed: "));
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3);
+    for line in lines {
+        let (seed, value) = line.split_once('\t').expect("seed prefix");
+        assert_eq!(run(&["^[a-z]{6}$", "--seed", seed]).1, format!("{}\n", value));
+    }
+
+    // The flag only adds the prefix: seeded values are the same with and without it.
+    let plain = run(&["^[a-z]{6}$", "--n", "4", "--seed", "1"]).1;
+    let shown = run(&["^[a-z]{6}$", "--n", "4", "--seed", "1", "--show-seed"]).1;
+    let values: Vec<&str> = shown.lines().map(|l| l.split_once('\t').expect("seed prefix").1).collect();
+    assert_eq!(plain.lines().collect::<Vec<_>>(), values);
+
+    // Paths that cannot prefix per-item seeds reject the flag rather than drop them.
+    for extra in [&["--jobs", "2"][..], &["--stream"], &["--format", "json"]] {
+        let (ok, _) = run(&[&["^[a-z]{6}$", "--show-seed"][..], extra].concat());
+        assert!(!ok, "{:?}", extra);
+    }
+    assert!(!run(&["--field", "a=[a-z]", "--show-seed"]).0);
+}
+
+#[test]
+fn test_presets() {
+    let (ok, out) = run(&["gen", "@uuid", "--n", "2", "--seed", "1"]);
+    assert!(ok);
+    assert!(out.lines().all(|l| l.len() == 36 && l.as_bytes()[14] == b'4'), "{}", out);
+
+    let (ok, out) = run(&["--field", "host=@ipv4", "--field", "mac=@mac", "--n", "1", "--seed", "1"]);
+    assert!(ok);
+    assert_eq!(out.lines().next(), Some("host,mac"));
+
+    assert!(!run(&["@nope"]).0);
+}
+
+#[test]
+fn test_dialect_option() {
+    let (ok, out) = run(&["gen", "--dialect", "glob", "file-??.txt", "--n", "3", "--seed", "1"]);
+    assert!(ok);
+    assert!(out.lines().all(|l| l.len() == 11 && l.starts_with("file-") && l.ends_with(".txt")), "{}", out);
+
+    let (ok, out) = run(&["--dialect", "bre", r"a\{3\}(", "--seed", "1"]);
+    assert!(ok);
+    assert_eq!(out, "aaa(\n");
+
+    assert!(!run(&["--dialect", "perl", "a"]).0);
+}
+
+#[test]
+fn test_length_unit_option() {
+    let (ok, out) = run(&["[éü]+", "--min", "6", "--max", "6", "--length-unit", "chars", "--n", "3", "--seed", "1"]);
+    assert!(ok);
+    assert!(out.lines().all(|l| l.chars().count() == 6 && l.len() == 12), "{}", out);
+    assert!(!run(&["a", "--length-unit", "words"]).0);
+}
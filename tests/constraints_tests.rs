@@ -0,0 +1,156 @@
+//! Tests for per-group constraints declared inline or out-of-band.
+
+use std::collections::HashSet;
+
+use genrex::{GeneratorConfig, GroupConstraint, RegexGenerator};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn config() -> GeneratorConfig {
+    GeneratorConfig { min_len: 0, max_len: 64, max_attempts: 10_000, timeout: None }
+}
+
+#[test]
+fn test_inline_unique_annotation() {
+    let mut generator = RegexGenerator::builder("^id-(?P<id>[0-9]){genrex:unique}$")
+        .config(config())
+        .rng(StdRng::seed_from_u64(1))
+        .build()
+        .unwrap();
+    let values = generator.generate_n(10).unwrap();
+    let distinct: HashSet<_> = values.iter().collect();
+    assert_eq!(distinct.len(), 10);
+    // The language has only 10 members, so the next attempt must exhaust.
+    assert!(generator.generate_one().is_err());
+}
+
+#[test]
+fn test_inline_sequential_annotation() {
+    let mut generator = RegexGenerator::builder("^row(?P<n>[0-9]{4}){genrex:seq=7}$")
+        .config(config())
+        .rng(StdRng::seed_from_u64(2))
+        .build()
+        .unwrap();
+    assert_eq!(generator.generate_n(3).unwrap(), vec!["row0007", "row0008", "row0009"]);
+}
+
+#[test]
+fn test_out_of_band_values_constraint() {
+    let mut generator = RegexGenerator::builder("^(?P<color>[a-z]+)-[0-9]$")
+        .config(config())
+        .rng(StdRng::seed_from_u64(3))
+        .group_constraint("color", GroupConstraint::Values(vec!["red".into(), "blue".into()]))
+        .build()
+        .unwrap();
+    for s in generator.generate_n(20).unwrap() {
+        assert!(s.starts_with("red-") || s.starts_with("blue-"), "{}", s);
+    }
+}
+
+#[test]
+fn test_unknown_group_name_rejected() {
+    let res = RegexGenerator::builder("^(?P<a>x)$")
+        .group_constraint("b", GroupConstraint::Unique)
+        .build();
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_unknown_annotation_rejected() {
+    assert!(RegexGenerator::builder("(a){genrex:bogus}").build().is_err());
+}
+
+#[test]
+fn test_unclosed_annotation_rejected() {
+    match RegexGenerator::builder("^(a){genrex:unique").build() {
+        Err(genrex::GenError::Parse(e)) => {
+            assert_eq!(e.kind, genrex::ParseErrorKind::UnclosedAnnotation);
+            assert_eq!(e.span, 4..5);
+        }
+        other => panic!("expected an unclosed annotation error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_detached_annotation_rejected() {
+    // A quantified group, a non-capturing group and a literal cannot carry a constraint.
+    for (pattern, span) in [("^(x){3}{genrex:unique}$", 7..22), ("(?:x){genrex:unique}", 5..20), ("a{genrex:seq=1}", 1..15)] {
+        match RegexGenerator::builder(pattern).build() {
+            Err(genrex::GenError::Parse(e)) => {
+                assert_eq!(e.kind, genrex::ParseErrorKind::MisplacedAnnotation, "{}", pattern);
+                assert_eq!(e.span, span, "{}", pattern);
+            }
+            other => panic!("{}: expected a misplaced annotation error, got {:?}", pattern, other.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn test_must_not_contain_steers_generation() {
+    let mut g = RegexGenerator::builder("^[ab]{20}$")
+        .config(GeneratorConfig { min_len: 20, max_len: 20, max_attempts: 1000, timeout: None })
+        .rng(StdRng::seed_from_u64(12))
+        .must_not_contain("aa")
+        .must_not_contain("bbb")
+        .build()
+        .unwrap();
+    for s in g.generate_n(100).unwrap() {
+        assert!(!s.contains("aa") && !s.contains("bbb"), "{}", s);
+    }
+    // Steering means no candidate has to be thrown away.
+    assert_eq!(g.stats().attempts, g.stats().accepted);
+}
+
+#[test]
+fn test_must_not_contain_rejects_unavoidable_literals() {
+    let mut g = RegexGenerator::builder("^(?:foo|bar)$")
+        .rng(StdRng::seed_from_u64(13))
+        .must_not_contain("oo")
+        .build()
+        .unwrap();
+    assert!(g.generate_n(20).unwrap().iter().all(|s| s == "bar"));
+    assert!(g.stats().rejected_for(genrex::RejectReason::Forbidden) > 0);
+    assert!(RegexGenerator::builder("a").must_not_contain("").build().is_err());
+}
+
+#[test]
+fn test_except_deny_list() {
+    let reserved = "^(admin|root|sys[a-z]*)$";
+    let mut g = RegexGenerator::builder("^(admin|root|sys|[a-z]{3,5})$")
+        .config(config())
+        .rng(StdRng::seed_from_u64(14))
+        .except(reserved)
+        .build()
+        .unwrap();
+    let deny = regex::Regex::new(reserved).unwrap();
+    for s in g.generate_n(200).unwrap() {
+        assert!(!deny.is_match(&s), "{}", s);
+    }
+    assert!(g.stats().rejected_for(genrex::RejectReason::Excluded) > 0);
+    assert!(RegexGenerator::builder("a").except("(").build().is_err());
+}
+
+#[test]
+fn test_filter_predicate() {
+    // Even numbers only, with a second predicate banning a digit.
+    let mut g = RegexGenerator::builder(r"^[0-9]{4}$")
+        .config(config())
+        .rng(StdRng::seed_from_u64(15))
+        .filter(|s| s.parse::<u32>().unwrap() % 2 == 0)
+        .filter(|s| !s.contains('7'))
+        .build()
+        .unwrap();
+    for s in g.generate_n(100).unwrap() {
+        assert!(s.ends_with(['0', '2', '4', '6', '8']) && !s.contains('7'), "{}", s);
+    }
+    assert!(g.stats().rejected_for(genrex::RejectReason::Filtered) > 0);
+    assert_eq!(g.stats().attempts, g.stats().accepted + g.stats().rejected());
+
+    // A predicate nothing passes exhausts the attempt budget instead of looping forever.
+    let mut g = RegexGenerator::builder("^[a-z]{3}$")
+        .config(GeneratorConfig { max_attempts: 50, ..config() })
+        .filter(|_| false)
+        .build()
+        .unwrap();
+    assert!(matches!(g.generate_one(), Err(genrex::GenError::NoMatch)));
+    assert_eq!(g.stats().rejected_for(genrex::RejectReason::Filtered), 50);
+}
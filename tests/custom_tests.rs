@@ -0,0 +1,207 @@
+//! Tests for custom tokens plugged into capturing groups.
+
+use genrex::custom::{Balanced, Escape, QuotedString};
+use genrex::{CustomToken, GeneratorConfig, GroupConstraint, RegexGenerator};
+use std::sync::Arc;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use regex::Regex;
+
+fn config() -> GeneratorConfig {
+    GeneratorConfig { min_len: 0, max_len: 256, max_attempts: 1000, timeout: None }
+}
+
+/// Decode a JSON string literal, failing on malformed escapes or unescaped quotes.
+fn parse_json_string(s: &str) -> Option<String> {
+    let body = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::new();
+    // UTF-16 code units from consecutive `\u` escapes, decoded together for surrogate pairs.
+    let mut units = Vec::new();
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.clone().next() == Some('u') {
+            chars.next();
+            let hex: String = chars.by_ref().take(4).collect();
+            units.push(u16::from_str_radix(&hex, 16).ok()?);
+            continue;
+        }
+        for decoded in char::decode_utf16(units.drain(..)) {
+            out.push(decoded.ok()?);
+        }
+        match c {
+            '"' | '\n' => return None,
+            '\\' => match chars.next()? {
+                '"' => out.push('"'),
+                '\\' => out.push('\\'),
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                _ => return None,
+            },
+            c => out.push(c),
+        }
+    }
+    for decoded in char::decode_utf16(units) {
+        out.push(decoded.ok()?);
+    }
+    Some(out)
+}
+
+#[test]
+fn test_inline_json_string_token() {
+    let mut g = RegexGenerator::builder(r#"^\{"name": ("(?:[^"\\]|\\.)*"){genrex:token=json_string}\}$"#)
+        .config(config())
+        .rng(StdRng::seed_from_u64(30))
+        .build()
+        .unwrap();
+    let mut escaped = 0;
+    for s in g.generate_n(100).unwrap() {
+        let literal = s.strip_prefix("{\"name\": ").unwrap().strip_suffix('}').unwrap();
+        assert!(parse_json_string(literal).is_some(), "{}", literal);
+        escaped += literal.contains('\\') as usize;
+    }
+    assert!(escaped > 0);
+}
+
+#[test]
+fn test_json_escapes_non_bmp_as_surrogate_pairs() {
+    let token = QuotedString::json().alphabet(&['😀']).content_len(3, 3);
+    let mut rng = StdRng::seed_from_u64(33);
+    for _ in 0..50 {
+        let s = token.generate(&mut rng);
+        assert!(s.is_ascii(), "{}", s);
+        let decoded = parse_json_string(&s).expect("valid JSON string");
+        assert_eq!(decoded.chars().count(), 3, "{}", s);
+        assert_eq!(s.matches("\\ud83d\\ude00").count(), decoded.matches('😀').count(), "{}", s);
+    }
+}
+
+#[test]
+fn test_backslash_escapes_round_trip_through_serde_json() {
+    let mut rng = StdRng::seed_from_u64(34);
+    let token = QuotedString::json().alphabet(&['a', '"', '\r', '\u{1}']).content_len(4, 12);
+    for _ in 0..100 {
+        let s = token.generate(&mut rng);
+        let decoded: String = serde_json::from_str(&s).unwrap_or_else(|e| panic!("{}: {}", s, e));
+        assert!((4..=12).contains(&decoded.chars().count()), "{}", s);
+    }
+
+    // A single-quoted literal escapes its own quote; as JSON, only that escape differs.
+    let token = QuotedString::new('\'', Escape::Backslash).alphabet(&['a', '\'', '\r']).content_len(4, 12);
+    let mut quoted = 0;
+    for _ in 0..100 {
+        let s = token.generate(&mut rng);
+        let body = &s[1..s.len() - 1];
+        assert!(!body.replace("\\\\", "").replace("\\'", "").contains('\''), "{}", s);
+        let decoded: String = serde_json::from_str(&format!("\"{}\"", body.replace("\\'", "'"))).unwrap_or_else(|e| panic!("{}: {}", s, e));
+        quoted += decoded.contains('\'') as usize;
+    }
+    assert!(quoted > 0);
+}
+
+#[test]
+fn test_csv_field_round_trips() {
+    let field = QuotedString::csv().content_len(5, 20);
+    let mut rng = StdRng::seed_from_u64(31);
+    for _ in 0..100 {
+        let s = field.generate(&mut rng);
+        let body = &s[1..s.len() - 1];
+        // Every quote inside the field is doubled.
+        assert_eq!(body.replace("\"\"", "").find('"'), None, "{}", s);
+    }
+}
+
+#[test]
+fn test_balanced_brackets() {
+    let mut rng = StdRng::seed_from_u64(32);
+    let token = Balanced::new('[', ']').max_depth(4);
+    for _ in 0..100 {
+        let s = token.generate(&mut rng);
+        let mut depth = 0i32;
+        for c in s.chars() {
+            depth += match c {
+                '[' => 1,
+                ']' => -1,
+                _ => 0,
+            };
+            assert!(depth >= 0, "{}", s);
+        }
+        assert_eq!(depth, 0, "{}", s);
+    }
+}
+
+#[derive(Debug)]
+struct Hex;
+
+impl CustomToken for Hex {
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
+        format!("{:08x}", rng.next_u32())
+    }
+}
+
+#[test]
+fn test_registered_token_by_group_name() {
+    let mut g = RegexGenerator::builder("^id=(?P<id>[0-9a-f]{8})$")
+        .config(config())
+        .rng(StdRng::seed_from_u64(33))
+        .register_token("hex", Hex)
+        .group_constraint("id", GroupConstraint::Token("hex".into()))
+        .build()
+        .unwrap();
+    assert!(g.generate_n(10).unwrap().iter().all(|s| s.len() == 11));
+    assert!(RegexGenerator::builder("(a){genrex:token=nope}").build().is_err());
+}
+
+/// An account number followed by its Luhn check digit, with the number of digits as argument.
+#[derive(Debug)]
+struct Luhn(usize);
+
+impl CustomToken for Luhn {
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
+        let number: String = (0..self.0).map(|_| char::from(b'0' + (rng.next_u32() % 10) as u8)).collect();
+        let check = (0..10).find(|d| luhn_valid(&format!("{}{}", number, d))).unwrap();
+        format!("{}{}", number, check)
+    }
+
+    fn with_args(&self, args: &str) -> Result<Arc<dyn CustomToken>, String> {
+        args.parse().map(|n| Arc::new(Luhn(n)) as Arc<dyn CustomToken>).map_err(|_| format!("expected a digit count, got '{}'", args))
+    }
+}
+
+fn luhn_valid(number: &str) -> bool {
+    let sum: u32 = number.chars().rev().map(|c| c.to_digit(10).unwrap()).enumerate()
+        .map(|(i, d)| if i % 2 == 1 { if d * 2 > 9 { d * 2 - 9 } else { d * 2 } } else { d })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+#[test]
+fn test_placeholders_with_arguments() {
+    let mut g = RegexGenerator::builder(r"^card-\T{luhn:15} issued \T{date:%Y/%j}$")
+        .config(config())
+        .rng(StdRng::seed_from_u64(34))
+        .register_token("luhn", Luhn(9))
+        .build()
+        .unwrap();
+    let re = Regex::new(r"^card-([0-9]{16}) issued (19[7-9][0-9]|20[0-9]{2})/([0-9]{3})$").unwrap();
+    for s in g.generate_n(50).unwrap() {
+        let caps = re.captures(&s).unwrap_or_else(|| panic!("{}", s));
+        assert!(luhn_valid(&caps[1]), "{}", s);
+        assert!((1..=366).contains(&caps[3].parse::<u32>().unwrap()), "{}", s);
+    }
+    let (_, captures) = g.generate_with_captures().unwrap();
+    assert_eq!(captures.get(1).map(str::len), Some(16));
+}
+
+#[test]
+fn test_date_token_and_placeholder_errors() {
+    let mut g = RegexGenerator::builder(r"^\T{date}$").config(config()).rng(StdRng::seed_from_u64(35)).build().unwrap();
+    let re = Regex::new(r"^\d{4}-(0[1-9]|1[0-2])-(0[1-9]|[12][0-9]|3[01])$").unwrap();
+    assert!(g.generate_n(50).unwrap().iter().all(|s| re.is_match(s)));
+
+    for bad in [r"\T{nope}", r"\T{date:%Q}", r"\T{parens:3}", r"\T{date", r"\T{}", r"\T"] {
+        assert!(RegexGenerator::builder(bad).build().is_err(), "{}", bad);
+    }
+    // An escaped backslash before `T{` is not a placeholder.
+    let mut g = RegexGenerator::builder(r"^a\\T\{b\}$").build().unwrap();
+    assert_eq!(g.generate_one().unwrap(), r"a\T{b}");
+}
@@ -0,0 +1,77 @@
+//! Tests for `#[derive(Generate)]`.
+#![cfg(feature = "derive")]
+
+use genrex::{GenError, Generate};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[derive(Debug, PartialEq, Generate)]
+struct Address {
+    #[genrex(pattern = "[1-9][0-9]{0,3} (Main|Oak|Elm) St")]
+    street: String,
+    #[genrex(pattern = "[0-9]{5}")]
+    zip: String,
+}
+
+#[derive(Debug, PartialEq, Generate)]
+struct User {
+    #[genrex(preset = "uuid")]
+    id: String,
+    #[genrex(pattern = "[a-z]{3,8}")]
+    name: String,
+    #[genrex(pattern = "1[89]|[2-9][0-9]")]
+    age: u8,
+    address: Address,
+}
+
+#[derive(Debug, Generate)]
+struct Code(#[genrex(pattern = "[A-Z]{2}-[0-9]{3}")] String);
+
+#[derive(Debug, Generate)]
+struct Unit;
+
+#[derive(Debug, Generate)]
+struct BadPreset {
+    #[genrex(preset = "nope")]
+    _value: String,
+}
+
+#[derive(Debug, Generate)]
+struct Unparseable {
+    #[genrex(pattern = "[a-z]{3}")]
+    _value: u32,
+}
+
+#[test]
+fn test_derive_generates_every_field() {
+    let uuid = regex::Regex::new(genrex::presets::UUID.pattern).unwrap();
+    let street = regex::Regex::new("^[1-9][0-9]{0,3} (Main|Oak|Elm) St$").unwrap();
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..50 {
+        let user = User::generate(&mut rng).unwrap();
+        assert!(uuid.is_match(&user.id), "{:?}", user);
+        assert!((3..=8).contains(&user.name.len()) && user.name.chars().all(|c| c.is_ascii_lowercase()));
+        assert!((18..=99).contains(&user.age));
+        assert!(street.is_match(&user.address.street));
+        assert_eq!(user.address.zip.len(), 5);
+    }
+    let code = Code::generate(&mut rng).unwrap();
+    assert!(regex::Regex::new("^[A-Z]{2}-[0-9]{3}$").unwrap().is_match(&code.0));
+    Unit::generate(&mut rng).unwrap();
+}
+
+#[test]
+fn test_derive_is_reproducible_from_the_rng() {
+    let a = User::generate(&mut StdRng::seed_from_u64(9)).unwrap();
+    let b = User::generate(&mut StdRng::seed_from_u64(9)).unwrap();
+    let c = User::generate(&mut StdRng::seed_from_u64(10)).unwrap();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn test_derive_errors() {
+    let mut rng = StdRng::seed_from_u64(1);
+    assert!(matches!(BadPreset::generate(&mut rng), Err(GenError::InvalidConfig(msg)) if msg.contains("nope")));
+    assert!(matches!(Unparseable::generate(&mut rng), Err(GenError::InvalidConfig(msg)) if msg.contains("_value")));
+}
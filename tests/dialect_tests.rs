@@ -0,0 +1,167 @@
+//! Tests for pattern dialects other than regex.
+
+use genrex::{Dialect, GenError, PatternDialect, RegexGenerator};
+use regex::Regex;
+
+#[test]
+fn test_sql_like_to_regex() {
+    assert_eq!(Dialect::SqlLike.to_regex("abc%").unwrap(), "abc.*");
+    assert_eq!(Dialect::SqlLike.to_regex("a_c").unwrap(), "a.c");
+    assert_eq!(Dialect::SqlLike.to_regex(r"100\%").unwrap(), r"100%");
+    assert_eq!(Dialect::SqlLike.to_regex("[a-c]x[^0-9]").unwrap(), "[a-c]x[^0-9]");
+    // `*`, `?` and `.` are plain characters in LIKE.
+    assert_eq!(Dialect::SqlLike.to_regex("a*?.b").unwrap(), r"a\*\?\.b");
+}
+
+#[test]
+fn test_glob_to_regex() {
+    assert_eq!(Dialect::Glob.to_regex("*.txt").unwrap(), r".*\.txt");
+    assert_eq!(Dialect::Glob.to_regex("file?.log").unwrap(), r"file.\.log");
+    assert_eq!(Dialect::Glob.to_regex("[!ab]_[]x]").unwrap(), r"[^ab]_[\]x]");
+    assert_eq!(Dialect::Glob.to_regex(r"\*").unwrap(), r"\*");
+    // `%` and `_` are plain characters in a glob.
+    assert_eq!(Dialect::Glob.to_regex("%_").unwrap(), "%_");
+}
+
+#[test]
+fn test_unterminated_class_is_literal() {
+    assert_eq!(Dialect::Glob.to_regex("a[b").unwrap(), r"a\[b");
+    assert_eq!(Dialect::SqlLike.to_regex("[z-a]").unwrap(), r"\[z-a\]");
+}
+
+#[test]
+fn test_regex_dialect_is_unchanged() {
+    assert_eq!(Dialect::Regex.to_regex("a%_*").unwrap(), "a%_*");
+    assert_eq!(Dialect::default(), Dialect::Regex);
+}
+
+#[test]
+fn test_generate_sql_like_values() {
+    let mut g = RegexGenerator::builder("INV-____-%").dialect(Dialect::SqlLike).seed(1).build().unwrap();
+    let re = Regex::new(r"^INV-.{4}-.*$").unwrap();
+    for s in g.generate_n(30).unwrap() {
+        assert!(re.is_match(&s), "{}", s);
+    }
+}
+
+#[test]
+fn test_generate_glob_values() {
+    let mut g = RegexGenerator::builder("log-[0-9][0-9]-*.txt").dialect(Dialect::Glob).seed(2).build().unwrap();
+    let re = Regex::new(r"^log-[0-9]{2}-.*\.txt$").unwrap();
+    for s in g.generate_n(30).unwrap() {
+        assert!(re.is_match(&s), "{}", s);
+    }
+}
+
+#[test]
+fn test_posix_basic_to_regex() {
+    assert_eq!(Dialect::PosixBre.to_regex(r"\(ab\)\{2,3\}c*").unwrap(), "(ab){2,3}c*");
+    // Unescaped ERE operators are literals.
+    assert_eq!(Dialect::PosixBre.to_regex("a+b?(c){d}|e").unwrap(), r"a\+b\?\(c\)\{d\}\|e");
+    assert_eq!(Dialect::PosixBre.to_regex(r"a\+\|b\?").unwrap(), "a+|b?");
+    // `*` first and `^`/`$` in the middle are literals.
+    assert_eq!(Dialect::PosixBre.to_regex("*a^b$c$").unwrap(), r"\*a\^b\$c$");
+    assert_eq!(Dialect::PosixBre.to_regex(r"^*x\(^y$\)").unwrap(), r"^\*x(^y$)");
+    assert_eq!(Dialect::PosixBre.to_regex(r"\(a\)\1").unwrap(), r"(a)\1");
+}
+
+#[test]
+fn test_posix_brackets() {
+    assert_eq!(Dialect::PosixEre.to_regex("[[:digit:]]+").unwrap(), "[0-9]+");
+    assert_eq!(Dialect::PosixEre.to_regex("[[:upper:]_]{2}").unwrap(), "[A-Z_]{2}");
+    // A backslash is a member, and `]` first is a literal member.
+    assert_eq!(Dialect::PosixEre.to_regex(r"[\n]").unwrap(), r"[\\n]");
+    assert_eq!(Dialect::PosixEre.to_regex("[]a]").unwrap(), r"[\]a]");
+    assert_eq!(Dialect::PosixEre.to_regex("[^[:space:]]").unwrap(), r"[^\t\n\v\f\r ]");
+    assert_eq!(Dialect::PosixBre.to_regex("[[:xdigit:]]").unwrap(), "[0-9A-Fa-f]");
+    assert!(matches!(Dialect::PosixEre.to_regex("[[:nope:]]"), Err(GenError::InvalidRegex(_))));
+    // An unterminated bracket is a literal.
+    assert_eq!(Dialect::PosixEre.to_regex("a[b").unwrap(), r"a\[b");
+}
+
+#[test]
+fn test_posix_extended_is_regex_outside_brackets() {
+    assert_eq!(Dialect::PosixEre.to_regex(r"(ab|c)+\.d{2}").unwrap(), r"(ab|c)+\.d{2}");
+}
+
+#[test]
+fn test_ecmascript_to_regex() {
+    assert_eq!(Dialect::EcmaScript.to_regex(r"/^\d{3}$/").unwrap(), r"^\d{3}$");
+    assert_eq!(Dialect::EcmaScript.to_regex(r"/^[a-z]+$/gi").unwrap(), r"(?i)^[a-z]+$");
+    assert_eq!(Dialect::EcmaScript.to_regex(r"a\/b").unwrap(), "a/b");
+    assert_eq!(Dialect::EcmaScript.to_regex(r"A\u{1F600}\x2e\cJ").unwrap(), "A\u{1F600}\\.\\n");
+    assert_eq!(Dialect::EcmaScript.to_regex(r"[\b][&]").unwrap(), "[\x08][\\&]");
+    assert_eq!(Dialect::EcmaScript.to_regex("[^]").unwrap(), "(?s:.)");
+    assert_eq!(Dialect::EcmaScript.to_regex(r"(?<y>\d{4})-(x)\k<y>").unwrap(), r"(?<y>\d{4})-(x)\1");
+    assert_eq!(Dialect::EcmaScript.to_regex(r"\0").unwrap(), "\0");
+}
+
+#[test]
+fn test_generate_from_js_validator() {
+    let mut g = RegexGenerator::builder(r"/^[A-Z]{2}\d{6}$/").dialect(Dialect::EcmaScript).seed(3).build().unwrap();
+    let re = Regex::new(r"^[A-Z]{2}\d{6}$").unwrap();
+    for s in g.generate_n(20).unwrap() {
+        assert!(re.is_match(&s), "{}", s);
+    }
+}
+
+#[test]
+fn test_generate_posix_basic() {
+    let mut g = RegexGenerator::builder(r"^\([[:lower:]]\{3\}\)-\1$").dialect(Dialect::PosixBre).allow_backrefs().seed(4).build().unwrap();
+    for s in g.generate_n(20).unwrap() {
+        assert_eq!(s.len(), 7, "{}", s);
+        assert_eq!(s[..3], s[4..], "{}", s);
+    }
+}
+
+/// Hex digits spelled `H`, everything else literal.
+struct HexShorthand;
+
+impl PatternDialect for HexShorthand {
+    fn translate(&self, source: &str) -> Result<String, GenError> {
+        if source.contains('\\') {
+            return Err(GenError::InvalidConfig("no escapes in hex shorthand".to_string()));
+        }
+        Ok(source.replace('H', "[0-9a-f]"))
+    }
+}
+
+#[test]
+fn test_custom_dialect() {
+    let mut g = RegexGenerator::builder("0xHHHH").dialect(HexShorthand).seed(5).build().unwrap();
+    let re = Regex::new("^0x[0-9a-f]{4}$").unwrap();
+    assert!(re.is_match(&g.generate_one().unwrap()));
+    assert!(matches!(RegexGenerator::builder(r"\H").dialect(HexShorthand).build(), Err(GenError::InvalidConfig(_))));
+}
+
+#[test]
+fn test_bytes_to_regex() {
+    assert_eq!(Dialect::Bytes.to_regex(r"(?-u)\x41(?i-u:b)(?-u:c)").unwrap(), "A(?i:b)(?:c)");
+    assert_eq!(Dialect::Bytes.to_regex(r"\x{e9}é").unwrap(), "\u{e9}(?:\u{c3}\u{a9})");
+    assert!(matches!(Dialect::Bytes.to_regex(r"\x{100}"), Err(GenError::InvalidRegex(_))));
+    assert!(matches!(Dialect::Bytes.to_regex(r"\xZ1"), Err(GenError::InvalidRegex(_))));
+    assert!(matches!(Dialect::Bytes.to_regex("[é]"), Err(GenError::InvalidRegex(_))));
+}
+
+#[test]
+fn test_generate_bytes() {
+    let pattern = r"\x89PNG\r\n\x1A\n[\x00-\xFF]{4}[^\x00-\x7F]{2}[[:digit:]\W]";
+    let mut g = RegexGenerator::builder(pattern).dialect(Dialect::Bytes).seed(6).build().unwrap();
+    let re = regex::bytes::Regex::new(&format!("(?-u)^{}$", pattern)).unwrap();
+    for _ in 0..50 {
+        let bytes = g.generate_bytes().unwrap();
+        assert_eq!(bytes.len(), 15, "{:?}", bytes);
+        assert_eq!(bytes[..8], *b"\x89PNG\r\n\x1A\n");
+        assert!(bytes[12..14].iter().all(|&b| b >= 0x80), "{:?}", bytes);
+        assert!(re.is_match(&bytes), "{:?}", bytes);
+    }
+}
+
+#[test]
+fn test_bytes_lengths_count_bytes() {
+    // 40 high bytes are 80 bytes of UTF-8, but within the default max_len of 64 bytes.
+    let mut g = RegexGenerator::builder(r"[\x80-\xFF]{40}").dialect(Dialect::Bytes).seed(7).build().unwrap();
+    assert_eq!(g.generate_bytes().unwrap().len(), 40);
+    let mut text = RegexGenerator::builder("é{2}").seed(8).build().unwrap();
+    assert_eq!(text.generate_bytes().unwrap(), "éé".as_bytes());
+}
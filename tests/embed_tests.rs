@@ -0,0 +1,97 @@
+//! Tests for `anchored(false)`: matches embedded in filler text.
+
+use genrex::{DocumentConfig, GenError, GeneratorConfig, RegexGenerator};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+
+fn unanchored(pattern: &str, seed: u64) -> RegexGenerator {
+    RegexGenerator::builder(pattern)
+        .anchored(false)
+        .config(GeneratorConfig { min_len: 0, max_len: 16, max_attempts: 1000, timeout: None })
+        .rng(StdRng::seed_from_u64(seed))
+        .build()
+        .expect("pattern builds")
+}
+
+#[test]
+fn test_embedded_match_is_the_only_match() {
+    let full = Regex::new(r"^\d{3}$").unwrap();
+    let search = Regex::new(r"\d{3}").unwrap();
+    let mut g = unanchored(r"\d{3}", 7);
+    let mut padded = false;
+    for _ in 0..50 {
+        let (text, span) = g.generate_embedded().unwrap();
+        assert!(full.is_match(&text[span.clone()]), "{:?} at {:?}", text, span);
+        let found: Vec<_> = search.find_iter(&text).map(|m| m.range()).collect();
+        assert_eq!(found, vec![span.clone()], "{:?}", text);
+        padded |= text.len() > 3;
+    }
+    assert!(padded);
+
+    let word = Regex::new(r"cat|dog").unwrap();
+    for s in unanchored(r"cat|dog", 1).generate_n(20).unwrap() {
+        assert_eq!(word.find_iter(&s).count(), 1, "{:?}", s);
+    }
+}
+
+#[test]
+fn test_embedded_match_keeps_assertions() {
+    // Filler never glues word characters onto a `\b` edge.
+    let word = Regex::new(r"\bword\b").unwrap();
+    let mut g = unanchored(r"\bword\b", 3);
+    let mut padded = false;
+    for _ in 0..200 {
+        let (text, span) = g.generate_embedded().unwrap();
+        let found: Vec<_> = word.find_iter(&text).map(|m| m.range()).collect();
+        assert_eq!(found, vec![span], "{:?}", text);
+        padded |= text.len() > 4;
+    }
+    assert!(padded);
+
+    // Anchors pin the match to the edges of the text: no filler on an anchored side.
+    let full = Regex::new(r"^foo$").unwrap();
+    for s in unanchored(r"^foo$", 4).generate_n(200).unwrap() {
+        assert!(full.is_match(&s), "{:?}", s);
+    }
+    let start = Regex::new(r"^id\d").unwrap();
+    for (text, span) in (0..50).map(|_| unanchored(r"^id\d", 5).generate_embedded().unwrap()) {
+        assert!(start.is_match(&text) && span.start == 0, "{:?}", text);
+    }
+
+    // Documents hold the same guarantee line by line.
+    let mut g = unanchored(r"\bword\b", 6);
+    let doc = g.generate_document(&DocumentConfig::new(40, 8)).unwrap();
+    let found: Vec<_> = word.find_iter(&doc.text).map(|m| m.range()).collect();
+    assert_eq!(found, doc.matches.iter().map(|m| m.span.clone()).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_anchored_by_default() {
+    let re = Regex::new(r"^[a-z]{4}$").unwrap();
+    let mut g = RegexGenerator::builder(r"^[a-z]{4}$").rng(StdRng::seed_from_u64(2)).build().unwrap();
+    for s in g.generate_n(20).unwrap() {
+        assert!(re.is_match(&s), "{:?}", s);
+    }
+    let (text, span) = g.generate_embedded().unwrap();
+    assert!(re.is_match(&text[span]));
+}
+
+#[test]
+fn test_document_manifest() {
+    let search = Regex::new(r"ERR-\d{4}").unwrap();
+    let mut g = unanchored(r"ERR-\d{4}", 11);
+    let doc = g.generate_document(&DocumentConfig::new(40, 25).line_width(60)).unwrap();
+    assert_eq!(doc.text.lines().count(), 40);
+    assert_eq!(doc.matches.len(), 25);
+    let found: Vec<_> = search.find_iter(&doc.text).map(|m| m.range()).collect();
+    let manifest: Vec<_> = doc.matches.iter().map(|m| m.span.clone()).collect();
+    assert_eq!(found, manifest);
+    for m in &doc.matches {
+        assert_eq!(doc.text[..m.span.start].matches('\n').count(), m.line);
+    }
+    assert!(doc.values().all(|v| v.starts_with("ERR-")));
+
+    let empty = g.generate_document(&DocumentConfig::new(3, 0)).unwrap();
+    assert!(empty.matches.is_empty() && !search.is_match(&empty.text));
+    assert!(matches!(g.generate_document(&DocumentConfig::new(0, 1)), Err(GenError::InvalidConfig(_))));
+}
@@ -0,0 +1,67 @@
+//! Tests for output encoders.
+
+use genrex::encoding::{Ebcdic037, Latin1, Unmappable, Utf16};
+use genrex::{Encoder, GenError, GeneratorConfig, RegexGenerator};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn generator(pattern: &str) -> RegexGenerator {
+    RegexGenerator::builder(pattern)
+        .config(GeneratorConfig { min_len: 0, max_len: 64, max_attempts: 200, timeout: None })
+        .rng(StdRng::seed_from_u64(17))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_utf16_round_trip() {
+    let enc = Utf16::be().with_bom();
+    let bytes = enc.encode("Aé").unwrap();
+    assert_eq!(bytes, vec![0xFE, 0xFF, 0x00, 0x41, 0x00, 0xE9]);
+    assert_eq!(enc.decode(&bytes), "Aé");
+    assert_eq!(Utf16::le().encode("A").unwrap(), vec![0x41, 0x00]);
+}
+
+#[test]
+fn test_latin1_policies() {
+    assert_eq!(Latin1::new(Unmappable::Error).encode("é").unwrap(), vec![0xE9]);
+    let err = Latin1::new(Unmappable::Error).encode("a€").unwrap_err();
+    assert_eq!((err.ch, err.position), ('€', 1));
+    assert_eq!(Latin1::new(Unmappable::Replace('?')).encode("a€").unwrap(), b"a?".to_vec());
+    assert_eq!(Latin1::new(Unmappable::Skip).encode("a€b").unwrap(), b"ab".to_vec());
+}
+
+#[test]
+fn test_ebcdic_known_bytes() {
+    let enc = Ebcdic037::new(Unmappable::Error);
+    assert_eq!(enc.encode("A1 a").unwrap(), vec![0xC1, 0xF1, 0x40, 0x81]);
+    assert_eq!(enc.decode(&[0xC1, 0xF1, 0x40, 0x81]), "A1 a");
+}
+
+#[test]
+fn test_generate_encoded_verifies() {
+    let mut g = generator("^[A-Z]{3}[0-9]{2}$");
+    let enc = Ebcdic037::new(Unmappable::Error);
+    let bytes = g.generate_encoded(&enc).unwrap();
+    assert_eq!(bytes.len(), 5);
+    assert!(regex::Regex::new("^[A-Z]{3}[0-9]{2}$").unwrap().is_match(&enc.decode(&bytes)));
+}
+
+#[test]
+fn test_generate_encoded_rejects_unencodable() {
+    let mut g = generator("^€$");
+    let res = g.generate_encoded(&Latin1::new(Unmappable::Error));
+    assert!(matches!(res, Err(GenError::Encoding(_))));
+    // A lossy replacement no longer matches the pattern, so nothing is returned either.
+    let res = g.generate_encoded(&Latin1::new(Unmappable::Replace('?')));
+    assert!(matches!(res, Err(GenError::NoMatch)));
+}
+
+#[test]
+fn test_generate_encoded_applies_deny_lists() {
+    // `€` becomes `?`, which still matches but is forbidden.
+    let mut g = RegexGenerator::builder("^[b€?]$").must_not_contain("?").rng(StdRng::seed_from_u64(18)).build().unwrap();
+    let enc = Latin1::new(Unmappable::Replace('?'));
+    for _ in 0..20 {
+        assert_eq!(g.generate_encoded(&enc).unwrap(), b"b".to_vec());
+    }
+}
@@ -0,0 +1,63 @@
+//! Tests for the JSON and DOT exports of token trees.
+
+use genrex::RegexGenerator;
+use serde_json::{Value, json};
+
+fn export(pattern: &str) -> Value {
+    let json = RegexGenerator::builder(pattern).allow_backrefs().build().unwrap().to_json().unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn test_json_layout() {
+    let value = export(r#"^(a|"\d)+?[^x]\1.$"#);
+    assert_eq!(value["version"], 1);
+    let tokens = value["tokens"].as_array().unwrap();
+    assert_eq!(tokens[0], json!({"kind": "anchor_start", "pattern": "^"}));
+    let repeat = &tokens[1];
+    assert_eq!((repeat["kind"].as_str(), repeat["min"].as_u64(), repeat["max"].is_null(), repeat["greedy"].as_bool()), (Some("repeat"), Some(1), true, Some(false)));
+    assert_eq!(repeat["child"]["kind"], "group");
+    assert_eq!(repeat["child"]["index"], 1);
+    let branches = repeat["child"]["child"]["children"][0]["branches"].as_array().expect("alternation under the group");
+    assert_eq!(branches.len(), 2);
+    assert!(branches[1].to_string().contains(r#""char":"\"""#));
+    assert_eq!(tokens[2]["kind"], "negated_class");
+    assert_eq!(tokens[2]["chars"], json!(["x"]));
+    assert_eq!(tokens[3], json!({"kind": "backreference", "pattern": r"\1", "index": 1}));
+    assert_eq!(tokens[4]["kind"], "any");
+    assert_eq!(tokens[5]["kind"], "anchor_end");
+}
+
+#[test]
+fn test_json_matches_free_function() {
+    let tokens = genrex::parse("ab{2,3}").unwrap();
+    let json = genrex::to_json(&tokens);
+    assert!(json.starts_with(r#"{"version":1,"tokens":["#));
+    assert!(!json.contains('\n'));
+    assert_eq!(export("ab{2,3}"), serde_json::from_str::<Value>(&json).unwrap());
+}
+
+#[test]
+fn test_dot_token_tree() {
+    let dot = RegexGenerator::builder(r#"^(a|"b)*?$"#).build().unwrap().to_dot().unwrap();
+    assert!(dot.starts_with("digraph tokens {\n") && dot.ends_with("}\n"), "{}", dot);
+    assert!(dot.contains(r#"n0 [label="concat", shape=ellipse];"#), "{}", dot);
+    assert!(dot.contains(r#"[label="repeat {0,} lazy", shape=hexagon];"#), "{}", dot);
+    assert!(dot.contains(r#"[label="alternation of 2", shape=diamond];"#), "{}", dot);
+    assert!(dot.contains(r#"[label="literal \""];"#), "{}", dot);
+    assert_eq!(dot.matches(r#"[label="1"]"#).count(), 1, "{}", dot);
+    // Every node but the root has exactly one incoming edge.
+    let nodes = dot.lines().filter(|l| l.contains("[label=") && !l.contains("->")).count();
+    assert_eq!(dot.matches(" -> ").count(), nodes - 1, "{}", dot);
+}
+
+#[test]
+fn test_dot_nfa() {
+    let nfa = genrex::nfa::Nfa::from_pattern("a[0-9]+|[acegikmoqsuwyACEGIKMOQSU]").unwrap();
+    let dot = nfa.to_dot();
+    assert!(dot.starts_with("digraph nfa {\n"), "{}", dot);
+    assert_eq!(dot.matches("shape=doublecircle").count(), 1);
+    assert!(dot.contains(r#"[label="a"]"#) && dot.contains(r#"[label="[0-9]"]"#), "{}", dot);
+    assert!(dot.contains("…"), "long sets are shortened: {}", dot);
+    assert!(dot.contains("style=dashed"), "{}", dot);
+}
@@ -0,0 +1,74 @@
+//! Tests for failure injection.
+
+use genrex::{FaultConfig, FaultKind, GeneratorConfig, RegexGenerator};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+
+fn generator(faults: FaultConfig) -> RegexGenerator {
+    RegexGenerator::builder("^[a-z]{4,8}$")
+        .config(GeneratorConfig { min_len: 4, max_len: 8, max_attempts: 1000, timeout: None })
+        .rng(StdRng::seed_from_u64(99))
+        .inject_faults(faults)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_fault_labels_are_honest() {
+    let re = Regex::new("^[a-z]{4,8}$").unwrap();
+    let mut generator = generator(FaultConfig::new(0.5));
+    let mut faulted = 0;
+    for _ in 0..200 {
+        let out = generator.generate_labeled().unwrap();
+        match out.fault {
+            Some(_) => {
+                faulted += 1;
+                assert!(!re.is_match(&out.value), "faulted value still valid: {:?}", out);
+            }
+            None => assert!(re.is_match(&out.value)),
+        }
+    }
+    assert!((50..150).contains(&faulted), "faulted {} of 200", faulted);
+}
+
+#[test]
+fn test_fault_kinds_restricted() {
+    let mut generator = generator(FaultConfig::new(1.0).kinds(&[FaultKind::Overlong]));
+    for _ in 0..20 {
+        let out = generator.generate_labeled().unwrap();
+        assert_eq!(out.fault, Some(FaultKind::Overlong));
+        assert!(out.value.len() > 8);
+    }
+}
+
+#[test]
+fn test_no_faults_without_config() {
+    let mut generator = RegexGenerator::builder("^a+$").rng(StdRng::seed_from_u64(1)).build().unwrap();
+    assert_eq!(generator.generate_labeled().unwrap().fault, None);
+}
+
+#[test]
+fn test_fault_rate_validated() {
+    for rate in [f64::NAN, f64::INFINITY, -0.1, 1.5] {
+        let built = RegexGenerator::builder("^[a-z]{4}$").inject_faults(FaultConfig::new(rate)).build();
+        assert!(matches!(built, Err(genrex::GenError::InvalidConfig(_))), "rate {}", rate);
+    }
+    assert!(RegexGenerator::builder("^[a-z]{4}$").inject_faults(FaultConfig::new(1.0)).build().is_ok());
+}
+
+#[test]
+fn test_overlong_counts_in_the_length_unit() {
+    let config = GeneratorConfig { min_len: 1, max_len: 8, max_attempts: 100, timeout: None };
+    let mut g = RegexGenerator::builder("^é{1,8}$")
+        .config(config)
+        .length_unit(genrex::LengthUnit::Chars)
+        .rng(StdRng::seed_from_u64(5))
+        .inject_faults(FaultConfig::new(1.0).kinds(&[FaultKind::Overlong]))
+        .build()
+        .unwrap();
+    for _ in 0..20 {
+        let out = g.generate_labeled().unwrap();
+        assert_eq!(out.fault, Some(FaultKind::Overlong));
+        assert_eq!(out.value.chars().count(), 9, "{}", out.value);
+    }
+}
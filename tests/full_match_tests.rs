@@ -0,0 +1,55 @@
+//! Tests for whole-string verification with `full_match`.
+
+use genrex::{EncodeError, Encoder, GenError, GeneratorConfig, RegexGenerator};
+
+/// Appends a NUL terminator and keeps it when decoding, so decoded values carry a byte the
+/// pattern does not produce.
+struct CString;
+
+impl Encoder for CString {
+    fn name(&self) -> &'static str {
+        "c-string"
+    }
+
+    fn encode(&self, s: &str) -> Result<Vec<u8>, EncodeError> {
+        Ok([s.as_bytes(), b"\0"].concat())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+fn digits(full_match: bool) -> RegexGenerator {
+    RegexGenerator::builder("[0-9]{3}")
+        .config(GeneratorConfig { max_attempts: 5, ..GeneratorConfig::default() })
+        .full_match(full_match)
+        .seed(2)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_substring_match_accepted_by_default() {
+    // "123\0" contains a match, which is all the default verification asks for.
+    let bytes = digits(false).generate_encoded(&CString).unwrap();
+    assert_eq!(bytes.len(), 4);
+}
+
+#[test]
+fn test_full_match_rejects_surrounding_text() {
+    assert!(matches!(digits(true).generate_encoded(&CString), Err(GenError::NoMatch)));
+
+    let mut g = RegexGenerator::builder("[0-9]{3}|x").full_match(true).seed(2).build().unwrap();
+    for s in g.generate_n(50).unwrap() {
+        assert!(s == "x" || (s.len() == 3 && s.chars().all(|c| c.is_ascii_digit())), "{}", s);
+    }
+}
+
+#[test]
+fn test_full_match_keeps_group_numbers() {
+    let mut g = RegexGenerator::builder("(?<year>[0-9]{4})-([0-9]{2})").full_match(true).seed(4).build().unwrap();
+    let (_, captures) = g.generate_with_captures().unwrap();
+    assert_eq!(captures.name("year").unwrap().len(), 4);
+    assert_eq!(captures.get(2).unwrap().len(), 2);
+}
@@ -0,0 +1,45 @@
+//! Tests for the `arbitrary` support used by fuzz harnesses.
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use genrex::{FuzzPattern, GeneratorConfig, RegexGenerator, Token};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use regex::Regex;
+
+/// Fuzzer-like input: `len` pseudo-random bytes.
+fn input(seed: u64, len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    StdRng::seed_from_u64(seed).fill_bytes(&mut bytes);
+    bytes
+}
+
+#[test]
+fn test_patterns_compile_with_regex() {
+    for seed in 0..500 {
+        let bytes = input(seed, 128);
+        let pattern = FuzzPattern::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert!(Regex::new(&pattern.pattern).is_ok(), "{}", pattern.pattern);
+        let token = Token::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert!(Regex::new(&token.to_pattern()).is_ok(), "{}", token.to_pattern());
+    }
+    // Exhausted input still produces a (minimal) pattern.
+    assert!(FuzzPattern::arbitrary(&mut Unstructured::new(&[])).is_ok());
+}
+
+#[test]
+fn test_differential_against_regex() {
+    let mut generated = 0;
+    for seed in 0..300 {
+        let bytes = input(seed, 128);
+        let FuzzPattern { pattern, .. } = FuzzPattern::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        let re = Regex::new(&pattern).unwrap();
+        // Patterns genrex cannot satisfy (e.g. `a^`) only need to fail quickly.
+        let config = GeneratorConfig { max_attempts: 50, ..GeneratorConfig::default() };
+        let Ok(mut generator) = RegexGenerator::builder(&pattern).config(config).seed(seed).build() else { continue };
+        for s in generator.generate_n_lossy(5).0 {
+            assert!(re.is_match(&s), "{:?} does not match {}", s, pattern);
+            generated += 1;
+        }
+    }
+    assert!(generated > 300, "{}", generated);
+}
@@ -0,0 +1,68 @@
+//! Tests for the `regex-syntax` front-end.
+#![cfg(feature = "hir")]
+
+use genrex::{parse_hir, GeneratorConfig, GroupConstraint, RegexGenerator, Token, WarningKind};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+
+fn generate(pattern: &str, seed: u64) -> Vec<String> {
+    RegexGenerator::builder(pattern)
+        .hir(true)
+        .config(GeneratorConfig { min_len: 0, max_len: 64, max_attempts: 1000, timeout: None })
+        .rng(StdRng::seed_from_u64(seed))
+        .build()
+        .expect("pattern builds")
+        .generate_n(30)
+        .expect("pattern generates")
+}
+
+#[test]
+fn test_constructs_the_lexer_cannot_generate() {
+    for pattern in [
+        r"^(?i)abc$",
+        r"^[^a-z]{3}$",
+        r"^\p{Greek}{4}$",
+        r"^[a-z&&[^aeiou]]{5}$",
+        r"^\x41\u{e9}[[:digit:]]\D\W$",
+        r"^.{2}-\w+$",
+    ] {
+        let re = Regex::new(pattern).unwrap();
+        for s in generate(pattern, 1) {
+            assert!(re.is_match(&s), "{}: {:?}", pattern, s);
+        }
+    }
+    assert!(generate(r"^(?i)abc$", 2).iter().any(|s| s != "abc" && s.eq_ignore_ascii_case("abc")));
+}
+
+#[test]
+fn test_tokens_and_group_names() {
+    let tokens = parse_hir(r"a(?P<x>bc|de)*").unwrap();
+    let branch = |a, b| Token::Concatenation(vec![Token::Literal(a), Token::Literal(b)]);
+    let branches = Token::Alternation(vec![branch('b', 'c'), branch('d', 'e')]);
+    let group = Token::Group(Box::new(Token::Concatenation(vec![branches])), 1);
+    assert_eq!(tokens, [Token::Literal('a'), Token::Quantifier { token: Box::new(group), min: 0, max: usize::MAX, greedy: true }]);
+
+    let mut g = RegexGenerator::builder(r"^id-(?P<n>[0-9]{4})$")
+        .hir(true)
+        .group_constraint("n", GroupConstraint::Values(vec!["0042".to_string()]))
+        .build()
+        .unwrap();
+    assert_eq!(g.generate_one().unwrap(), "id-0042");
+}
+
+#[test]
+fn test_backrefs_fall_back_to_lexer() {
+    let mut g = RegexGenerator::builder(r"^(ab|cd)-\1$").hir(true).allow_backrefs().rng(StdRng::seed_from_u64(3)).build().unwrap();
+    let s = g.generate_one().unwrap();
+    assert_eq!(s[..2], s[3..]);
+
+    let g = RegexGenerator::builder(r"^[\s\S]$").hir(true).build().unwrap();
+    assert!(g.warnings().iter().any(|w| matches!(&w.kind, WarningKind::Degraded(what) if what.starts_with("class of"))));
+}
+
+#[test]
+fn test_empty_class_is_unsatisfiable() {
+    let built = RegexGenerator::builder(r"^x[a&&b]$").hir(true).build();
+    assert!(matches!(built, Err(genrex::GenError::Unsatisfiable(reason)) if reason == "a character class matches nothing"));
+    assert!(RegexGenerator::builder(r"^x[a&&b]?$").hir(true).build().is_ok());
+}
@@ -0,0 +1,52 @@
+//! Tests for internationalized domain generation.
+
+use genrex::idn::{self, IdnGenerator, Script};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+
+#[test]
+fn test_punycode_vectors() {
+    let cases = [
+        ("münchen", "mnchen-3ya"),
+        ("bücher", "bcher-kva"),
+        ("пример", "e1afmkfd"),
+        ("españa", "espaa-rta"),
+        ("中国", "fiqs8s"),
+        ("ドメイン名例", "eckwd4c7cu47r2wf"),
+        ("ελληνικά", "hxargifdar"),
+    ];
+    for (unicode, puny) in cases {
+        assert_eq!(idn::punycode_encode(unicode), puny, "{}", unicode);
+    }
+    assert_eq!(idn::to_ascii("bücher.example.рф"), "xn--bcher-kva.example.xn--p1ai");
+}
+
+#[test]
+fn test_generated_forms_agree() {
+    let re = Regex::new(idn::ASCII_DOMAIN_PATTERN).unwrap();
+    let mut g = IdnGenerator::new().rng(StdRng::seed_from_u64(21)).labels(1, 3);
+    for d in g.generate_n(200).unwrap() {
+        assert_eq!(idn::to_ascii(&d.unicode), d.ascii);
+        assert!(re.is_match(&d.ascii), "{}", d.ascii);
+        assert!(d.ascii.len() <= 253);
+        let first = d.unicode.split('.').next().unwrap();
+        assert!(!first.is_ascii(), "{}", d.unicode);
+    }
+}
+
+#[test]
+fn test_script_and_tld_selection() {
+    let mut g = IdnGenerator::new()
+        .rng(StdRng::seed_from_u64(22))
+        .scripts(&[Script::Cyrillic])
+        .tlds(&["рф"])
+        .labels(1, 1);
+    for d in g.generate_n(20).unwrap() {
+        let (label, tld) = d.unicode.split_once('.').unwrap();
+        assert!(label.chars().all(|c| ('а'..='я').contains(&c)), "{}", label);
+        assert_eq!(tld, "рф");
+        assert!(d.ascii.starts_with("xn--") && d.ascii.ends_with(".xn--p1ai"));
+    }
+    assert!(matches!(IdnGenerator::new().scripts(&[]).generate_one(), Err(genrex::GenError::InvalidConfig(_))));
+    assert!(matches!(IdnGenerator::new().tlds(&[]).generate_one(), Err(genrex::GenError::InvalidConfig(_))));
+}
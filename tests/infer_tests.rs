@@ -0,0 +1,64 @@
+//! Tests for inferring patterns and generators from example values.
+
+use genrex::infer::{from_examples, infer_pattern};
+use genrex::GenError;
+use regex::Regex;
+
+#[test]
+fn test_infer_runs_and_separators() {
+    assert_eq!(infer_pattern(&["555-1234", "555-98765"]).unwrap(), "555-[0-9]{4,5}");
+    assert_eq!(infer_pattern(&["Alice Smith", "Bob Jones"]).unwrap(), "[A-Z][a-z]{2,4} [A-Z][a-z]{4}");
+    assert_eq!(infer_pattern(&["2024-01-05"]).unwrap(), "[0-9]{4}-[0-9]{2}-[0-9]{2}");
+}
+
+#[test]
+fn test_infer_merges_kinds_when_shapes_differ() {
+    // Case differs between examples: letters merge.
+    assert_eq!(infer_pattern(&["abc-1", "ABC-2"]).unwrap(), "[A-Za-z]{3}-[0-9]");
+    // Letters and digits interleave differently: alphanumerics merge.
+    assert_eq!(infer_pattern(&["a1b2", "12ab"]).unwrap(), "[0-9A-Za-z]{4}");
+    // No common shape at all: one class of every character seen.
+    assert_eq!(infer_pattern(&["a.b", "ab"]).unwrap(), "[.ab]{2,3}");
+}
+
+#[test]
+fn test_infer_escapes_literals() {
+    assert_eq!(infer_pattern(&["v1.2", "v3.4"]).unwrap(), r"v[0-9]\.[0-9]");
+}
+
+#[test]
+fn test_infer_no_examples() {
+    assert!(matches!(infer_pattern(&[]), Err(GenError::InvalidConfig(_))));
+    assert!(from_examples(&[]).is_err());
+}
+
+#[test]
+fn test_from_examples_generates_look_alikes() {
+    let examples = ["ACCT-00012345-X", "ACCT-99887766-Q", "ACCT-31415926-Z"];
+    let mut g = from_examples(&examples).unwrap();
+    let re = Regex::new("^ACCT-[0-9]{8}-[A-Z]$").unwrap();
+    for s in g.generate_n(50).unwrap() {
+        assert!(re.is_match(&s), "{}", s);
+    }
+}
+
+#[test]
+fn test_from_examples_fits_long_values() {
+    let long = "x".repeat(100);
+    let mut g = from_examples(&[&long, "abc"]).unwrap();
+    let s = g.generate_one().unwrap();
+    assert!((3..=100).contains(&s.len()), "{}", s);
+}
+
+#[test]
+fn test_from_examples_outputs_match_in_full() {
+    let mut empty = from_examples(&[""]).unwrap();
+    assert!(empty.generate_n(20).unwrap().iter().all(String::is_empty));
+
+    let examples = ["ab-12", "cd-3", "ef-456"];
+    let shape = Regex::new(&format!("^(?:{})$", infer_pattern(&examples).unwrap())).unwrap();
+    let mut g = from_examples(&examples).unwrap();
+    for s in g.generate_n(100).unwrap() {
+        assert!(shape.is_match(&s), "{}", s);
+    }
+}
@@ -0,0 +1,111 @@
+//! Tests for length bounds in bytes, characters or graphemes, and the output size limit.
+
+use genrex::{GeneratorConfig, LengthUnit, RegexGenerator};
+
+#[test]
+fn test_measure() {
+    let accented = "cafe\u{301}";
+    assert_eq!(LengthUnit::Bytes.measure(accented), 6);
+    assert_eq!(LengthUnit::Chars.measure(accented), 5);
+    assert_eq!(LengthUnit::Graphemes.measure(accented), 4);
+    // Flags pair regional indicators; ZWJ sequences, skin tones and CRLF are one grapheme each.
+    assert_eq!(LengthUnit::Graphemes.measure("\u{1F1FA}\u{1F1F8}\u{1F1EB}\u{1F1F7}\u{1F1E9}"), 3);
+    assert_eq!(LengthUnit::Graphemes.measure("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"), 1);
+    assert_eq!(LengthUnit::Graphemes.measure("\u{1F44D}\u{1F3FD}!"), 2);
+    assert_eq!(LengthUnit::Graphemes.measure("a\r\nb\n\u{301}"), 5);
+    assert_eq!(LengthUnit::default(), LengthUnit::Bytes);
+}
+
+fn config(min_len: usize, max_len: usize) -> GeneratorConfig {
+    GeneratorConfig { min_len, max_len, ..GeneratorConfig::default() }
+}
+
+#[test]
+fn test_char_lengths() {
+    // Ten two-byte characters: 20 bytes, over a max of 10 unless lengths count characters.
+    assert!(RegexGenerator::builder("[éñ]{10}").config(config(10, 10)).build().is_err());
+    let mut g = RegexGenerator::builder("[éñ]{10}").config(config(10, 10)).length_unit(LengthUnit::Chars).seed(1).build().unwrap();
+    assert_eq!(g.generate_one().unwrap().chars().count(), 10);
+}
+
+#[test]
+fn test_grapheme_lengths() {
+    let mut g = RegexGenerator::builder("(?:[ae]\u{301}?){2,9}")
+        .config(config(5, 5))
+        .length_unit(LengthUnit::Graphemes)
+        .seed(2)
+        .build()
+        .unwrap();
+    let outputs = g.generate_n(50).unwrap();
+    for s in &outputs {
+        assert_eq!(LengthUnit::Graphemes.measure(s), 5, "{:?}", s);
+    }
+    assert!(outputs.iter().any(|s| s.chars().count() > 5));
+}
+
+#[test]
+fn test_max_output_bytes() {
+    // The backreference needs the token engine, which builds candidates piece by piece.
+    let mut g = RegexGenerator::builder(r"(a)(?:.{100}){0,20}\1")
+        .config(config(0, 1_000_000))
+        .allow_backrefs()
+        .max_output_bytes(1000)
+        .seed(3)
+        .build()
+        .unwrap();
+    for s in g.generate_n(20).unwrap() {
+        assert!(s.len() <= 1000, "{}", s.len());
+    }
+    assert!(g.stats().rejected_for(genrex::RejectReason::Length) > 0);
+    let short = RegexGenerator::builder("a{20}").max_output_bytes(10).build();
+    assert!(matches!(short, Err(genrex::GenError::InvalidConfig(_))));
+}
+
+#[test]
+fn test_generate_exact_len() {
+    let mut g = RegexGenerator::builder(r"^[a-z]{1,100}(-[0-9]{1,3})*$").config(config(0, 20)).seed(1).build().unwrap();
+    for len in [1, 7, 20, 37, 200] {
+        let s = g.generate_exact_len(len).unwrap();
+        assert_eq!(s.chars().count(), len, "{}", s);
+    }
+    // Steered, not rejected.
+    assert_eq!(g.stats().rejected(), 0);
+    // The generator's own bounds are back in force afterwards.
+    assert!(g.generate_one().unwrap().len() <= 20);
+
+    let mut accented = RegexGenerator::builder("^[éñ]{1,50}$").seed(2).build().unwrap();
+    assert_eq!(accented.generate_exact_len(40).unwrap().chars().count(), 40);
+
+    // Unanchored generators give the bare match, not the match in filler.
+    let mut embedded = RegexGenerator::builder("[a-z]{1,10}").anchored(false).seed(4).build().unwrap();
+    for _ in 0..20 {
+        assert_eq!(embedded.generate_exact_len(4).unwrap().chars().count(), 4);
+    }
+}
+
+#[test]
+fn test_generate_exact_len_backrefs() {
+    // Not regular, so candidates are tried until one fits: only odd lengths can.
+    let mut g = RegexGenerator::builder(r"^([a-z]{1,5})-\1$").allow_backrefs().seed(3).build().unwrap();
+    let s = g.generate_exact_len(7).unwrap();
+    assert_eq!(s.len(), 7);
+    assert_eq!(s[..3], s[4..]);
+    assert!(g.generate_exact_len(8).is_err());
+}
+
+#[test]
+fn test_lengths_fitted_without_config() {
+    let mut long = RegexGenerator::builder("^[a-z]{100}$").seed(1).build().unwrap();
+    assert_eq!(long.generate_one().unwrap().len(), 100);
+    let mut ranged = RegexGenerator::builder("^[a-z]{80,90}(-[éñ]{2})?$").seed(2).build().unwrap();
+    for s in ranged.generate_n(50).unwrap() {
+        assert!((80..=95).contains(&s.len()), "{}", s);
+    }
+    // Unbounded: the default allowance past the shortest match.
+    let mut open = RegexGenerator::builder("^x{70,}$").seed(3).build().unwrap();
+    assert!(open.generate_n(50).unwrap().iter().all(|s| (70..=134).contains(&s.len())));
+    let mut any = RegexGenerator::builder("^.*$").seed(4).build().unwrap();
+    assert!(any.generate_n(50).unwrap().iter().all(|s| s.len() <= 64));
+    // Lengths given explicitly are kept, even when the pattern cannot fit them.
+    assert!(RegexGenerator::builder("^[a-z]{100}$").config(GeneratorConfig::default()).build().is_err());
+}
@@ -0,0 +1,301 @@
+//! Tests for pattern lexing behavior observed through generation.
+
+use genrex::{GeneratorConfig, RegexGenerator};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn generate(pattern: &str, seed: u64) -> Vec<String> {
+    let cfg = GeneratorConfig { min_len: 0, max_len: 64, max_attempts: 1000, timeout: None };
+    RegexGenerator::builder(pattern)
+        .config(cfg)
+        .rng(StdRng::seed_from_u64(seed))
+        .build()
+        .expect("pattern builds")
+        .generate_n(20)
+        .expect("pattern generates")
+}
+
+#[test]
+fn test_open_lower_bound_repetition() {
+    let out = generate("^a{,3}$", 1);
+    assert!(out.iter().all(|s| s.len() <= 3 && s.chars().all(|c| c == 'a')));
+    assert!(out.iter().any(|s| !s.is_empty()));
+}
+
+#[test]
+fn test_malformed_brace_is_literal() {
+    assert!(generate("^a{foo}$", 2).iter().all(|s| s == "a{foo}"));
+    assert!(generate("^x{$", 3).iter().all(|s| s == "x{"));
+    assert!(generate("{1}", 4).iter().all(|s| s == "{1}"));
+}
+
+#[test]
+fn test_well_formed_repetitions() {
+    assert!(generate("^b{3}$", 5).iter().all(|s| s == "bbb"));
+    assert!(generate("^b{2,}$", 6).iter().all(|s| s.len() >= 2));
+    assert!(generate("^b{1,2}$", 7).iter().all(|s| (1..=2).contains(&s.len())));
+}
+
+fn parse_error(pattern: &str) -> genrex::ParseError {
+    match RegexGenerator::builder(pattern).build() {
+        Err(genrex::GenError::Parse(e)) => e,
+        Err(e) => panic!("{}: expected parse error, got {:?}", pattern, e),
+        Ok(_) => panic!("{}: expected parse error", pattern),
+    }
+}
+
+#[test]
+fn test_parse_error_positions() {
+    use genrex::ParseErrorKind::*;
+    let cases: &[(&str, genrex::ParseErrorKind, std::ops::Range<usize>)] = &[
+        ("ab[cd", UnclosedClass, 2..3),
+        ("a(b(c)", UnclosedGroup, 1..2),
+        ("ab)c", UnopenedGroup, 2..3),
+        ("*a", DanglingQuantifier, 0..1),
+        ("(a|+)", DanglingQuantifier, 3..4),
+        ("x{3,1}", InvalidRepetitionRange, 1..6),
+        ("ab\\", TrailingBackslash, 2..3),
+        ("a\\qb", InvalidEscape('q'), 1..3),
+        ("a\\c", InvalidEscape('c'), 1..3),
+        ("[\\c\u{e9}]", InvalidEscape('c'), 1..3),
+        ("(?:x)(y\\", UnclosedGroup, 5..6),
+        ("a(?%b)", InvalidGroup, 2..3),
+        ("(?i-:x)(?P=n)", InvalidGroup, 8..9),
+    ];
+    for (pattern, kind, span) in cases {
+        let err = parse_error(pattern);
+        assert_eq!(&err.kind, kind, "{}", pattern);
+        assert_eq!(&err.span, span, "{}", pattern);
+    }
+}
+
+#[test]
+fn test_parse_error_converts_to_genrex_error() {
+    let err = RegexGenerator::builder("(a").build().err().unwrap();
+    let err: genrex::GenrexError = err.into();
+    assert!(matches!(err, genrex::GenrexError::Parse(_)));
+    assert_eq!(err.to_string(), "parse error: unclosed group at 0..1");
+}
+
+#[test]
+fn test_octal_and_control_escapes() {
+    assert!(generate(r"^a\0b$", 15).iter().all(|s| s == "a\0b"));
+    // `\0` takes at most two more octal digits.
+    assert!(generate(r"^\012\0101\08$", 16).iter().all(|s| s == "\n\x081\08"));
+    assert!(generate(r"^\cJ\cj\c@\c?$", 17).iter().all(|s| s == "\n\n\0\x7F"));
+    assert!(generate(r"^[\0-\02\cZ]{4}$", 18).iter().all(|s| s.chars().all(|c| ['\0', '\x01', '\x02', '\x1A'].contains(&c))));
+    // Escaped backslashes are not escapes.
+    assert!(generate(r"^\\0\\cJ$", 19).iter().all(|s| s == "\\0\\cJ"));
+}
+
+#[test]
+fn test_class_and_group_edge_cases() {
+    assert!(generate("^[]a]$", 8).iter().all(|s| s == "]" || s == "a"));
+    assert!(generate("^(a[)]b)$", 9).iter().all(|s| s == "a)b"));
+    assert!(generate("^\\(x\\)$", 10).iter().all(|s| s == "(x)"));
+    assert!(generate("^a\\tb$", 11).iter().all(|s| s == "a\tb"));
+}
+
+#[test]
+fn test_empty_alternation_branches() {
+    for (pattern, expected) in [("^x(foo|)y$", &["xy", "xfooy"][..]), ("^x(|a)y$", &["xy", "xay"]), ("^x(a||b)y$", &["xy", "xay", "xby"])] {
+        for nfa in [true, false] {
+            let mut g = RegexGenerator::builder(pattern).nfa(nfa).rng(StdRng::seed_from_u64(12)).build().unwrap();
+            let out = g.generate_n(60).unwrap();
+            assert!(out.iter().all(|s| expected.contains(&s.as_str())), "{}: {:?}", pattern, out);
+            assert!(expected.iter().all(|e| out.contains(&e.to_string())), "{}: {:?}", pattern, out);
+        }
+    }
+    let mut g = RegexGenerator::builder("^(a|)$").rng(StdRng::seed_from_u64(13)).build().unwrap();
+    let (value, captures) = (0..20).map(|_| g.generate_with_captures().unwrap()).find(|(v, _)| v.is_empty()).unwrap();
+    assert_eq!((value.as_str(), captures.get(1)), ("", Some("")));
+
+    // The single-shot fallback after the token engine gives up also takes the empty branch.
+    let mut engines = Vec::new();
+    for seed in 0..40 {
+        let mut g = RegexGenerator::builder("^x(ab|)$")
+            .config(GeneratorConfig { min_len: 0, max_len: 1, max_attempts: 1, timeout: None })
+            .nfa(false)
+            .rng(StdRng::seed_from_u64(seed))
+            .build()
+            .unwrap();
+        if let Ok((value, report)) = g.generate_one_with_report() {
+            assert_eq!(value, "x");
+            engines.push(report.engine);
+        }
+    }
+    assert!(engines.contains(&genrex::Engine::Ast), "{:?}", engines);
+}
+
+#[test]
+fn test_operator_precedence() {
+    use genrex::Token;
+    let lit = Token::Literal;
+    let cat = Token::Concatenation;
+    let alt = Token::Alternation;
+    let repeat = |t: Token, min, max| Token::Quantifier { token: Box::new(t), min, max, greedy: true };
+    let group = |t: Token, i| Token::Group(Box::new(cat(vec![t])), i);
+    let tokens = |pattern: &str| RegexGenerator::builder(pattern).build().unwrap().tokens().unwrap().to_vec();
+
+    assert_eq!(tokens("a|b*"), [alt(vec![cat(vec![lit('a')]), cat(vec![repeat(lit('b'), 0, usize::MAX)])])]);
+    assert_eq!(tokens("ab+|c"), [alt(vec![cat(vec![lit('a'), repeat(lit('b'), 1, usize::MAX)]), cat(vec![lit('c')])])]);
+    assert_eq!(tokens("a|b{2}c"), [alt(vec![cat(vec![lit('a')]), cat(vec![repeat(lit('b'), 2, 2), lit('c')])])]);
+    assert_eq!(
+        tokens("a|b|c?d"),
+        [alt(vec![cat(vec![lit('a')]), cat(vec![lit('b')]), cat(vec![repeat(lit('c'), 0, 1), lit('d')])])]
+    );
+    // A quantifier after a group repeats the whole group, alternation included.
+    let ab = alt(vec![cat(vec![lit('a')]), cat(vec![lit('b')])]);
+    assert_eq!(tokens("(a|b)*c|d"), [alt(vec![cat(vec![repeat(group(ab.clone(), 1), 0, usize::MAX), lit('c')]), cat(vec![lit('d')])])]);
+    assert_eq!(tokens("x(a|b)?"), [lit('x'), repeat(group(ab, 1), 0, 1)]);
+
+    // Generated values agree with the regex crate's reading.
+    for pattern in ["^(?:a|b*)$", "^(?:ab+|c)$", "^(?:a|b{2}c)$", "^(?:(a|b)*c|d)$"] {
+        let re = regex::Regex::new(pattern).unwrap();
+        assert!(generate(pattern, 14).iter().all(|s| re.is_match(s)), "{}", pattern);
+    }
+}
+
+#[test]
+fn test_strict_mode_rejects_unsupported() {
+    use genrex::{ParseErrorKind, ParseMode};
+    for (pattern, span) in [("a(?=b)", 1..6), ("x\\p{L}", 1..3), ("[^a]", 0..1), ("(?x)a b", 0..4), ("[[:alpha:]]", 1..3)] {
+        match RegexGenerator::builder(pattern).parse_mode(ParseMode::Strict).build() {
+            Err(genrex::GenError::Parse(e)) => {
+                assert!(matches!(e.kind, ParseErrorKind::Unsupported(_)), "{}: {:?}", pattern, e);
+                assert_eq!(e.span, span, "{}", pattern);
+            }
+            _ => panic!("{}: expected unsupported construct error", pattern),
+        }
+    }
+}
+
+#[test]
+fn test_lenient_mode_records_degradations() {
+    use genrex::WarningKind;
+    let g = RegexGenerator::builder("^a(?i)b\\D?$").build().unwrap();
+    let degraded: Vec<_> = g.warnings().iter().filter(|w| matches!(w.kind, WarningKind::Degraded(_))).collect();
+    assert_eq!(degraded.len(), 1);
+    assert_eq!(degraded[0].span, Some(7..9));
+    let g = RegexGenerator::builder("^[a-c]{2}(?:x|y)$").parse_mode(genrex::ParseMode::Strict).build().unwrap();
+    assert!(g.warnings().is_empty());
+}
+
+#[test]
+fn test_build_warnings() {
+    use genrex::WarningKind;
+    let g = RegexGenerator::builder("^(a).+\\1$").allow_backrefs().build().unwrap();
+    let kinds: Vec<_> = g.warnings().iter().map(|w| w.kind.clone()).collect();
+    assert!(kinds.contains(&WarningKind::WildcardApproximated));
+    assert!(kinds.contains(&WarningKind::UnboundedRepeatCapped(32)));
+    assert!(kinds.contains(&WarningKind::VerificationRelaxed));
+    assert_eq!(g.warnings()[0].to_string(), "wildcard generated from ASCII alphanumerics only at 4..5");
+    let g = RegexGenerator::builder("^.{3}(.*|x).+$").build().unwrap();
+    let wildcards: Vec<_> = g.warnings().iter().filter(|w| w.kind == WarningKind::WildcardApproximated).collect();
+    assert_eq!(wildcards.len(), 1);
+    assert_eq!(wildcards[0].span, Some(1..2));
+    // A group referring to itself cannot be inlined; verification is off.
+    let g = RegexGenerator::builder("^(a\\1)$").allow_backrefs().build().unwrap();
+    assert!(g.warnings().iter().any(|w| matches!(w.kind, WarningKind::VerificationDisabled(_))));
+}
+
+#[test]
+fn test_build_validates_config() {
+    let build = |pattern: &str, min_len, max_len, max_attempts| {
+        RegexGenerator::builder(pattern)
+            .config(GeneratorConfig { min_len, max_len, max_attempts, timeout: None })
+            .build()
+            .map(|_| ())
+    };
+    let invalid = |r: Result<(), genrex::GenError>| matches!(r, Err(genrex::GenError::InvalidConfig(_)));
+    assert!(invalid(build("a", 5, 2, 10)));
+    assert!(invalid(build("a", 0, 2, 0)));
+    assert!(matches!(build("^[0-9]{4}-(x|yz)$", 0, 5, 10), Err(genrex::GenError::Unsatisfiable(_))));
+    assert!(build("^[0-9]{4}-(x|yz)$", 0, 6, 10).is_ok());
+    assert!(build("^(ab)?c*$", 0, 0, 10).is_ok());
+    let err = build("é{3}", 0, 5, 10).unwrap_err();
+    assert_eq!(err.to_string(), "pattern cannot match: pattern needs at least 6 bytes but max_len is 5");
+    let err = RegexGenerator::builder("é{3}")
+        .config(GeneratorConfig { min_len: 0, max_len: 2, max_attempts: 10, timeout: None })
+        .length_unit(genrex::LengthUnit::Chars)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "pattern cannot match: pattern needs at least 3 chars but max_len is 2");
+}
+
+#[test]
+fn test_unsatisfiable_patterns() {
+    let reason = |pattern: &str| match RegexGenerator::builder(pattern).build() {
+        Err(genrex::GenError::Unsatisfiable(reason)) => reason,
+        other => panic!("{}: {:?}", pattern, other.map(|_| ())),
+    };
+    assert_eq!(reason("^a$b"), "characters are required after the end of the text");
+    assert_eq!(reason(r"ab\z(c|d)+"), "characters are required after the end of the text");
+    assert_eq!(reason("a^b"), "characters are required before the start of the text");
+    assert_eq!(reason("(x$|y$)z"), "characters are required after the end of the text");
+    assert_eq!(reason("x(a$b)+"), "characters are required after the end of the text");
+    // Some way through avoids the contradiction.
+    for pattern in ["(a$|b)c", "a$(b)?", "a$(b{0,3})", "^(a$)*$", "(^|x)a", "(?m)a$\nb", "(?sm:a$.b)"] {
+        assert!(RegexGenerator::builder(pattern).build().is_ok(), "{}", pattern);
+    }
+    assert!(RegexGenerator::builder("a$\nb").multiline(true).build().is_ok());
+    assert!(RegexGenerator::builder("(a)$\\1").allow_backrefs().build().is_ok());
+    let err = RegexGenerator::builder("^a$b").build().err().unwrap();
+    assert_eq!(genrex::GenrexError::from(err).to_string(), "pattern cannot match: characters are required after the end of the text");
+}
+
+#[test]
+fn test_new_uses_defaults() {
+    let mut g = RegexGenerator::new(r"^[a-z]{3}-\d{4}$").unwrap();
+    let out = g.generate_one().unwrap();
+    assert!(regex::Regex::new(r"^[a-z]{3}-\d{4}$").unwrap().is_match(&out), "{}", out);
+    // A fresh random seed each time.
+    assert_ne!(g.seed(), RegexGenerator::new(r"^[a-z]{3}-\d{4}$").unwrap().seed());
+    assert!(matches!(RegexGenerator::new("("), Err(genrex::GenError::Parse(_))));
+}
+
+#[test]
+fn test_nesting_depth_limit() {
+    use genrex::ParseErrorKind::NestingTooDeep;
+    let nested = |depth: usize| format!("{}a{}", "(".repeat(depth), ")".repeat(depth));
+    // Thousands of levels are an error, not a stack overflow.
+    assert_eq!(parse_error(&nested(5000)).kind, NestingTooDeep(250));
+    assert_eq!(parse_error(&format!("a{}", "{2}".repeat(300))).kind, NestingTooDeep(250));
+    let err = RegexGenerator::builder(&nested(11)).max_nesting_depth(10).build().err().unwrap();
+    assert!(matches!(err, genrex::GenError::Parse(e) if e.kind == NestingTooDeep(10) && e.span == (10..13)));
+    // Up to the limit, the pattern generates.
+    let mut g = RegexGenerator::builder(&nested(250)).build().unwrap();
+    assert_eq!(g.generate_one().unwrap(), "a");
+}
+
+#[test]
+fn test_long_alternation() {
+    let words: Vec<String> = (0..20_000).map(|i| format!("w{}", i)).collect();
+    let mut g = RegexGenerator::builder(&format!("^(?:{})$", words.join("|"))).seed(9).build().unwrap();
+    let out = g.generate_one().unwrap();
+    assert!(words.contains(&out), "{}", out);
+}
+
+#[test]
+fn test_repeated_classes_share_one_set() {
+    use genrex::visit::walk_tokens;
+    use genrex::{parse, TokenVisitor};
+
+    // Start of each class set seen, and its contents.
+    #[derive(Default)]
+    struct Sets(Vec<(*const char, Vec<char>)>);
+    impl TokenVisitor for Sets {
+        fn visit_class(&mut self, chars: &[char], _negated: bool) {
+            self.0.push((chars.as_ptr(), chars.to_vec()));
+        }
+    }
+
+    let mut sets = Sets::default();
+    walk_tokens(&mut sets, &parse(r"[0-9A-Fa-f]{2}(:[0-9A-Fa-f]{2}){5}-\d\d[0-9]").unwrap());
+    let [hex1, hex2, digit1, digit2, digit3] = &sets.0[..] else { panic!("{:?}", sets.0) };
+    assert_eq!(hex1, hex2);
+    assert_eq!(digit1.0, digit2.0);
+    // Same members as `\d` in the same order, so `[0-9]` shares it too.
+    assert_eq!(digit1, digit3);
+    assert_ne!(hex1.0, digit1.0);
+}
@@ -0,0 +1,73 @@
+//! Tests for the `metrics` facade integration.
+#![cfg(feature = "metrics")]
+
+use genrex::{GeneratorConfig, RegexGenerator};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+/// Run `f` with a recorder local to this thread and return what it recorded, as (name, labels,
+/// value) with labels rendered `key=value`.
+fn record(f: impl FnOnce()) -> Vec<(String, Vec<String>, DebugValue)> {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, f);
+    snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let key = key.key();
+            let labels = key.labels().map(|l| format!("{}={}", l.key(), l.value())).collect();
+            (key.name().to_string(), labels, value)
+        })
+        .collect()
+}
+
+fn counter(recorded: &[(String, Vec<String>, DebugValue)], name: &str, labels: &[&str]) -> u64 {
+    recorded
+        .iter()
+        .find(|(n, l, _)| n == name && l == labels)
+        .map(|(_, _, value)| match value {
+            DebugValue::Counter(n) => *n,
+            other => panic!("{} is not a counter: {:?}", name, other),
+        })
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_metrics_match_stats() {
+    let mut stats = None;
+    let recorded = record(|| {
+        let mut g = RegexGenerator::builder("^[a-z]{1,12}$")
+            .config(GeneratorConfig { min_len: 10, max_len: 12, ..GeneratorConfig::default() })
+            .filter(|s| s.len() % 2 == 0)
+            .seed(1)
+            .build()
+            .unwrap();
+        g.generate_n(40).unwrap();
+        stats = Some(g.stats().clone());
+    });
+    let stats = stats.unwrap();
+    assert_eq!(counter(&recorded, "genrex_attempts_total", &[]), stats.attempts);
+    assert_eq!(counter(&recorded, "genrex_outputs_total", &["engine=nfa"]), 40);
+    let rejected: u64 = ["reason=length", "reason=filtered"].iter().map(|l| counter(&recorded, "genrex_rejections_total", &[l])).sum();
+    assert_eq!(rejected, stats.rejected());
+    assert!(counter(&recorded, "genrex_rejections_total", &["reason=filtered"]) > 0);
+    let latencies = recorded.iter().find(|(n, l, _)| n == "genrex_generation_seconds" && l == &["outcome=ok"]);
+    assert!(matches!(latencies, Some((_, _, DebugValue::Histogram(samples))) if samples.len() == 40), "{:?}", latencies);
+}
+
+#[test]
+fn test_metrics_failures() {
+    let recorded = record(|| {
+        let mut g = RegexGenerator::builder("^[a-z]{8}$")
+            .config(GeneratorConfig { max_attempts: 3, ..GeneratorConfig::default() })
+            .filter(|_| false)
+            .build()
+            .unwrap();
+        assert!(g.generate_one().is_err());
+    });
+    assert_eq!(counter(&recorded, "genrex_failures_total", &[]), 1);
+    assert_eq!(counter(&recorded, "genrex_attempts_total", &[]), 3);
+    assert_eq!(counter(&recorded, "genrex_rejections_total", &["reason=filtered"]), 3);
+    assert!(recorded.iter().any(|(n, l, _)| n == "genrex_generation_seconds" && l == &["outcome=error"]));
+}
@@ -0,0 +1,41 @@
+//! Tests for `MultiPatternGenerator`.
+
+use genrex::{GenError, Interleave, MultiPatternGenerator, RegexGenerator};
+use regex::Regex;
+
+#[test]
+fn test_labels_match_their_patterns() {
+    let patterns = [r"^[a-z]{3}$", r"^\d{2,4}$", r"^(GET|POST) /[a-z]+$"];
+    let mut m = MultiPatternGenerator::new(5);
+    for (i, p) in patterns.iter().enumerate() {
+        assert_eq!(m.add_pattern(p, (i + 1) as f64).unwrap(), i);
+    }
+    let out = m.generate_n(300).unwrap();
+    let mut counts = [0; 3];
+    for (id, s) in &out {
+        assert!(Regex::new(patterns[*id]).unwrap().is_match(s), "{} {:?}", id, s);
+        counts[*id] += 1;
+    }
+    assert!(counts[0] < counts[1] && counts[1] < counts[2], "{:?}", counts);
+
+    let mut again = MultiPatternGenerator::new(5);
+    for (i, p) in patterns.iter().enumerate() {
+        again.add_pattern(p, (i + 1) as f64).unwrap();
+    }
+    assert_eq!(again.generate_n(300).unwrap(), out);
+}
+
+#[test]
+fn test_round_robin_and_invalid_weights() {
+    let mut m = MultiPatternGenerator::new(1).interleave(Interleave::RoundRobin);
+    m.add_pattern("^a$", 0.0).unwrap();
+    m.add(RegexGenerator::builder("^b$").build().unwrap(), 0.0).unwrap();
+    let ids: Vec<usize> = m.generate_n(5).unwrap().into_iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, [0, 1, 0, 1, 0]);
+
+    let mut weighted = MultiPatternGenerator::new(1);
+    assert!(matches!(weighted.generate_one(), Err(GenError::InvalidConfig(_))));
+    assert!(weighted.add_pattern("^a$", -1.0).is_err());
+    weighted.add_pattern("^a$", 0.0).unwrap();
+    assert!(matches!(weighted.generate_one(), Err(GenError::InvalidConfig(_))));
+}
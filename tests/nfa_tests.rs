@@ -0,0 +1,50 @@
+//! Tests for NFA compilation and length-aware walks.
+
+use genrex::nfa::{Nfa, Walker};
+use genrex::{Engine, GeneratorConfig, RegexGenerator, TokenContext};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+
+#[test]
+fn test_walker_respects_bounds() {
+    let re = Regex::new("^(ab|c)*d?$").unwrap();
+    let walker = Walker::new(Nfa::from_pattern("^(ab|c)*d?$").unwrap(), 5, 7);
+    let mut rng = StdRng::seed_from_u64(49);
+    for _ in 0..200 {
+        let s = walker.walk(&mut rng, &mut TokenContext::new()).expect("walk succeeds");
+        assert!((5..=7).contains(&s.len()) && re.is_match(&s), "{}", s);
+    }
+    let impossible = Walker::new(Nfa::from_pattern("^a{3}$").unwrap(), 4, 9);
+    assert_eq!(impossible.walk(&mut rng, &mut TokenContext::new()), None);
+    assert!(matches!(Nfa::from_pattern("(a)\\1"), Err(genrex::GenError::Unsupported(_))));
+}
+
+#[test]
+fn test_generator_walks_without_rejections() {
+    let cfg = GeneratorConfig { min_len: 38, max_len: 40, max_attempts: 100, timeout: None };
+    let mut g = RegexGenerator::builder("^[a-z]{1,40}(-[0-9]+)?$")
+        .config(cfg)
+        .rng(StdRng::seed_from_u64(50))
+        .build()
+        .unwrap();
+    let re = Regex::new("^[a-z]{1,40}(-[0-9]+)?$").unwrap();
+    for s in g.generate_n(200).unwrap() {
+        assert!((38..=40).contains(&s.len()) && re.is_match(&s), "{}", s);
+    }
+    assert_eq!(g.stats().rejected(), 0);
+    assert_eq!(g.generate_one_with_report().unwrap().1.engine, Engine::Nfa);
+
+    let mut g = RegexGenerator::builder("^[a-z]{1,40}$").nfa(false).build().unwrap();
+    assert_eq!(g.generate_one_with_report().unwrap().1.engine, Engine::Tokens);
+}
+
+#[test]
+fn test_walks_fit_byte_bounds_with_multibyte_chars() {
+    let cfg = GeneratorConfig { min_len: 4, max_len: 6, ..GeneratorConfig::default() };
+    let mut g = RegexGenerator::builder("^é{1,3}$").config(cfg).rng(StdRng::seed_from_u64(51)).build().unwrap();
+    for _ in 0..50 {
+        let (s, report) = g.generate_one_with_report().unwrap();
+        assert!(s == "éé" || s == "ééé", "{}", s);
+        assert_eq!(report.engine, Engine::Nfa);
+    }
+}
@@ -0,0 +1,78 @@
+//! Tests for generation observers.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use genrex::{GeneratorConfig, Observer, RegexGenerator, RejectReason};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[derive(Default)]
+struct Counts {
+    attempts: AtomicU64,
+    rejects: Mutex<Vec<RejectReason>>,
+    accepts: Mutex<Vec<(usize, u64)>>,
+}
+
+impl Observer for Counts {
+    fn on_attempt(&self) {
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_reject(&self, reason: RejectReason) {
+        self.rejects.lock().unwrap().push(reason);
+    }
+
+    fn on_accept(&self, len: usize, attempts: u64) {
+        self.accepts.lock().unwrap().push((len, attempts));
+    }
+}
+
+#[test]
+fn test_observer_matches_stats() {
+    let counts = Arc::new(Counts::default());
+    let mut g = RegexGenerator::builder("^[a-z]{1,12}$")
+        .config(GeneratorConfig { min_len: 10, max_len: 12, ..GeneratorConfig::default() })
+        .filter(|s| !s.starts_with('a'))
+        .observer(counts.clone())
+        .seed(1)
+        .build()
+        .unwrap();
+    let outputs = g.generate_n(50).unwrap();
+    let stats = g.stats();
+    assert_eq!(counts.attempts.load(Ordering::Relaxed), stats.attempts);
+    let rejects = counts.rejects.lock().unwrap();
+    assert_eq!(rejects.len() as u64, stats.rejected());
+    for reason in RejectReason::ALL {
+        assert_eq!(rejects.iter().filter(|&&r| r == reason).count() as u64, stats.rejected_for(reason));
+    }
+    let accepts = counts.accepts.lock().unwrap();
+    assert_eq!(accepts.iter().map(|&(len, _)| len).collect::<Vec<_>>(), outputs.iter().map(String::len).collect::<Vec<_>>());
+    assert_eq!(accepts.iter().map(|&(_, attempts)| attempts).sum::<u64>(), stats.attempts);
+}
+
+#[test]
+fn test_observer_reports_token_engine_rejections() {
+    let counts = Arc::new(Counts::default());
+    let mut g = RegexGenerator::builder(r"^([a-z]{1,6})-\1$")
+        .allow_backrefs()
+        .config(GeneratorConfig { min_len: 9, max_len: 13, ..GeneratorConfig::default() })
+        .observer(counts.clone())
+        .seed(2)
+        .build()
+        .unwrap();
+    g.generate_n(20).unwrap();
+    assert!(counts.rejects.lock().unwrap().contains(&RejectReason::Length));
+    assert_eq!(counts.accepts.lock().unwrap().len(), 20);
+}
+
+#[test]
+fn test_observer_shared_by_clones_and_ref_generation() {
+    let counts = Arc::new(Counts::default());
+    let g = RegexGenerator::builder("^[0-9]{4}$").observer(counts.clone()).seed(3).build().unwrap();
+    let mut clone = g.clone();
+    clone.generate_one().unwrap();
+    g.generate_one_ref(&mut StdRng::seed_from_u64(4)).unwrap();
+    assert_eq!(*counts.accepts.lock().unwrap(), vec![(4, 1), (4, 1)]);
+    assert_eq!(counts.attempts.load(Ordering::Relaxed), 2);
+}
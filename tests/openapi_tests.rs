@@ -0,0 +1,62 @@
+//! Tests for generators built from OpenAPI string schemas.
+
+use std::collections::HashSet;
+
+use genrex::openapi::{string_generator, StringSchema};
+use genrex::GenError;
+use regex::Regex;
+
+#[test]
+fn test_pattern_with_lengths() {
+    let schema = StringSchema::new().pattern("^[a-f0-9]+$").min_length(4).max_length(6);
+    let mut g = schema.builder().unwrap().seed(1).build().unwrap();
+    let re = Regex::new("^[a-f0-9]{4,6}$").unwrap();
+    for s in g.generate_n(50).unwrap() {
+        assert!(re.is_match(&s), "{}", s);
+    }
+}
+
+#[test]
+fn test_bounded_pattern_sets_max_length() {
+    // Without maxLength, the pattern's own bound is used rather than the default of 64.
+    let schema = StringSchema::new().pattern(r"^\d{3}-\d{4}$");
+    let mut g = string_generator(&schema).unwrap();
+    for s in g.generate_n(20).unwrap() {
+        assert_eq!(s.len(), 8, "{}", s);
+    }
+}
+
+#[test]
+fn test_enum_filtered_by_pattern_and_length() {
+    let schema = StringSchema::new().enum_values(["red", "green", "blue", "a.b", "teal"]).pattern("^[a-z.]+$").max_length(4);
+    let mut g = schema.builder().unwrap().seed(2).build().unwrap();
+    let seen: HashSet<String> = g.generate_n(200).unwrap().into_iter().collect();
+    let expected: HashSet<String> = ["red", "blue", "a.b", "teal"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(seen, expected);
+}
+
+#[test]
+fn test_formats_and_plain_strings() {
+    let mut uuid = string_generator(&StringSchema::new().format("uuid")).unwrap();
+    let re = Regex::new("^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$").unwrap();
+    assert!(re.is_match(&uuid.generate_one().unwrap()));
+    let mut byte = string_generator(&StringSchema::new().format("byte")).unwrap();
+    assert_eq!(byte.generate_one().unwrap().len() % 4, 0);
+    // Unknown formats fall back to alphanumerics.
+    let mut plain = StringSchema::new().format("x-custom").min_length(3).max_length(5).builder().unwrap().seed(3).build().unwrap();
+    for s in plain.generate_n(30).unwrap() {
+        assert!((3..=5).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()), "{}", s);
+    }
+}
+
+#[test]
+fn test_unsatisfiable_schemas() {
+    let lengths = StringSchema::new().min_length(5).max_length(4);
+    assert!(matches!(lengths.generator(), Err(GenError::InvalidConfig(_))));
+    let too_short = StringSchema::new().pattern("^[a-z]{2}$").min_length(3);
+    assert!(matches!(too_short.generator(), Err(GenError::InvalidConfig(_))));
+    let too_long = StringSchema::new().pattern("^[a-z]{5,}$").max_length(4);
+    assert!(matches!(too_long.generator(), Err(GenError::InvalidConfig(_))));
+    let no_enum = StringSchema::new().enum_values(["abc"]).pattern("^[0-9]+$");
+    assert!(matches!(no_enum.generator(), Err(GenError::InvalidConfig(_))));
+}
@@ -0,0 +1,148 @@
+//! Tests for structured output metadata.
+
+use genrex::{Choice, GeneratorConfig, RegexGenerator};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn config() -> GeneratorConfig {
+    GeneratorConfig { min_len: 0, max_len: 32, max_attempts: 1000, timeout: None }
+}
+
+#[test]
+fn test_branch_index_matches_output() {
+    let mut g = RegexGenerator::builder("^(?:cat|dog|(b|c)ird)$")
+        .config(config())
+        .rng(StdRng::seed_from_u64(3))
+        .tag_branches()
+        .build()
+        .unwrap();
+    let mut seen = [false; 3];
+    for _ in 0..100 {
+        let out = g.generate_labeled().unwrap();
+        let branch = out.branch.expect("branch recorded");
+        let expected = match out.value.as_str() {
+            "cat" => 0,
+            "dog" => 1,
+            _ => 2,
+        };
+        assert_eq!(branch.index, expected, "{}", out.value);
+        assert_eq!(branch.label, None);
+        seen[branch.index] = true;
+    }
+    assert!(seen.iter().all(|&s| s));
+}
+
+#[test]
+fn test_branch_labels() {
+    let mut g = RegexGenerator::builder("a+|[0-9]+")
+        .config(config())
+        .rng(StdRng::seed_from_u64(4))
+        .branch_labels(&["letters", "digits"])
+        .build()
+        .unwrap();
+    for _ in 0..50 {
+        let value = g.generate_one().unwrap();
+        let tag = g.last_branch().unwrap();
+        let want = if value.starts_with('a') { "letters" } else { "digits" };
+        assert_eq!(tag.label.as_deref(), Some(want), "{}", value);
+    }
+}
+
+#[test]
+fn test_branch_tagging_validation() {
+    assert!(RegexGenerator::builder("^abc$").tag_branches().build().is_err());
+    assert!(RegexGenerator::builder("^(a|b)c$").tag_branches().build().is_err());
+    assert!(RegexGenerator::builder("x|y").branch_labels(&["only-one"]).build().is_err());
+    let mut g = RegexGenerator::builder("x|y").build().unwrap();
+    assert_eq!(g.generate_labeled().unwrap().branch, None);
+}
+
+#[test]
+fn test_captures_by_index_and_name() {
+    for nfa in [true, false] {
+        let mut g = RegexGenerator::builder(r"^\[(?P<level>INFO|WARN)\] user=(?P<user>u[0-9]{4}) (ok|fail)( retry)?$")
+            .config(config())
+            .nfa(nfa)
+            .rng(StdRng::seed_from_u64(4))
+            .build()
+            .unwrap();
+        for _ in 0..20 {
+            let (line, caps) = g.generate_with_captures().unwrap();
+            assert_eq!(caps.len(), 5);
+            assert_eq!(caps.get(0), Some(line.as_str()));
+            let user = caps.name("user").unwrap();
+            assert!(user.len() == 5 && user.starts_with('u'), "{}", user);
+            assert_eq!(caps.get(2), Some(user));
+            let level = caps.name("level").unwrap();
+            assert_eq!(line, format!("[{}] user={} {}{}", level, user, caps.get(3).unwrap(), caps.get(4).unwrap_or("")));
+            assert_eq!(caps.iter().nth(1).map(|(i, n, _)| (i, n)), Some((1, Some("level"))));
+        }
+    }
+}
+
+#[test]
+fn test_capture_numbers_match_regex_crate() {
+    // Groups are numbered by opening parenthesis across branches and non-capturing groups.
+    let patterns = [
+        r"^(a)|(b)c$",
+        r"^(?:(a)|(b))(c)$",
+        r"^((a)|(?:x(b)))(d)$",
+        r"^(?P<n>a)|(b)(c)$",
+        r"^(a(b)|c(d))(e)$",
+        r"^((?:(a)|b)(c))$",
+        r"^[(](a)\((b)$",
+    ];
+    for pattern in patterns {
+        let re = regex::Regex::new(pattern).unwrap();
+        for nfa in [true, false] {
+            let mut g = RegexGenerator::builder(pattern).nfa(nfa).rng(StdRng::seed_from_u64(6)).build().unwrap();
+            for _ in 0..20 {
+                let (value, caps) = g.generate_with_captures().unwrap();
+                let expected = re.captures(&value).unwrap();
+                for i in 0..expected.len() {
+                    assert_eq!(caps.get(i), expected.get(i).map(|m| m.as_str()), "{} on {:?}, group {}", pattern, value, i);
+                }
+            }
+        }
+    }
+    // `\2` names the group in the second branch, so outputs are "a" or "bb".
+    let mut g = RegexGenerator::builder(r"^(a)|(b)\2$").allow_backrefs().rng(StdRng::seed_from_u64(6)).build().unwrap();
+    for value in g.generate_n(20).unwrap() {
+        assert!(value == "a" || value == "bb", "{}", value);
+    }
+}
+
+#[test]
+fn test_explained_spans_and_choices() {
+    let mut g = RegexGenerator::builder(r"^(ab|cd)x{1,4}-\1$").allow_backrefs().config(config()).rng(StdRng::seed_from_u64(9)).build().unwrap();
+    for _ in 0..10 {
+        let e = g.generate_explained().unwrap();
+        for node in e.iter() {
+            assert_eq!(&e.value[node.span.clone()], node.text, "{}", node.token);
+        }
+        let alt = e.iter().find(|n| n.token == "Alt(2)").unwrap();
+        let Some(Choice::Branch(branch)) = alt.choice else { panic!("no branch recorded") };
+        assert_eq!(alt.text, ["ab", "cd"][branch]);
+        let repeat = e.iter().find(|n| n.token == "Quantifier{1,4}").unwrap();
+        let Some(Choice::Repeat(count)) = repeat.choice else { panic!("no count recorded") };
+        assert_eq!((repeat.text.len(), repeat.children.len()), (count, count));
+        assert_eq!(e.iter().find(|n| n.token == "Backreference(1)").unwrap().text, alt.text);
+        assert!(e.to_string().contains(&format!("(branch {})", branch)));
+    }
+}
+
+#[test]
+fn test_explained_forward_reference_is_filled_in() {
+    let mut g = RegexGenerator::builder(r"^(?:\1-)?([a-c]{2})$").allow_backrefs().config(config()).rng(StdRng::seed_from_u64(2)).build().unwrap();
+    let mut forward = 0;
+    for _ in 0..20 {
+        let e = g.generate_explained().unwrap();
+        for node in e.iter() {
+            assert_eq!(&e.value[node.span.clone()], node.text, "{}", node.token);
+        }
+        if e.value.contains('-') {
+            forward += 1;
+            assert_eq!(e.iter().find(|n| n.token == "Backreference(1)").unwrap().span, 0..2);
+        }
+    }
+    assert!(forward > 0);
+}
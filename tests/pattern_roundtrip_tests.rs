@@ -0,0 +1,57 @@
+//! Tests for serializing token trees back into patterns.
+
+use genrex::{parse, patterns, to_pattern, GeneratorConfig, RegexGenerator, Token, WarningKind};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+
+const TRICKY: &[&str] = &[
+    r"^a|b|$",
+    r"x(?:)y|",
+    r"[a-c-][]x][\^q][\\-_][a\-z][&&][~~]",
+    r"[^0-9]\D\w\s\d\b\B\A\z",
+    r"\.\*\+\?\(\)\[\]\{\}\|\^\$\\",
+    r"a{,3}b{2}c{2,}d{1,5}?e*?f+?g??",
+    r"a{foo}x{1}{",
+    r"(?P<name>a(b)(?i:c|d))\2\1",
+    r"(?=look)(?i)z\n\t\r\f\v\a",
+    r"\x41é[\x00]",
+    "é日本[α-ω]",
+    r"((a|b)*|c+)?",
+];
+
+#[test]
+fn test_lex_serialize_lex_is_fixpoint() {
+    let pool = patterns::ALL.iter().map(|p| p.pattern);
+    for pattern in TRICKY.iter().copied().chain(pool) {
+        let tokens = parse(pattern).unwrap();
+        let serialized = to_pattern(&tokens);
+        assert_eq!(parse(&serialized).unwrap(), tokens, "{} -> {}", pattern, serialized);
+        assert_eq!(to_pattern(&parse(&serialized).unwrap()), serialized);
+    }
+}
+
+#[test]
+fn test_serialized_pattern_is_valid_regex() {
+    for pattern in [r"^[a-f0-9]{8}-(?:[a-f0-9]{4}-){3}[a-f0-9]{12}$", r"^\w+@[a-z]+\.(com|org)$", r"[\]\[\-^]+"] {
+        let serialized = to_pattern(&parse(pattern).unwrap());
+        let (original, round) = (Regex::new(pattern).unwrap(), Regex::new(&serialized).unwrap());
+        let mut g = RegexGenerator::builder(pattern)
+            .config(GeneratorConfig { max_len: 64, ..GeneratorConfig::default() })
+            .rng(StdRng::seed_from_u64(5))
+            .build()
+            .unwrap();
+        for s in g.generate_n(50).unwrap() {
+            assert!(original.is_match(&s) && round.is_match(&s), "{} -> {}: {}", pattern, serialized, s);
+        }
+    }
+    assert_eq!(Token::Quantifier { token: Box::new(Token::Literal('a')), min: 2, max: 3, greedy: true }.to_pattern(), "a{2,3}");
+}
+
+#[test]
+fn test_backref_patterns_verified_structurally() {
+    let mut g = RegexGenerator::builder(r"^(ab|cd)-\1$").allow_backrefs().rng(StdRng::seed_from_u64(1)).build().unwrap();
+    for s in g.generate_n(20).unwrap() {
+        assert_eq!(s[..2], s[3..]);
+    }
+    assert_eq!(g.warnings().iter().map(|w| w.kind.clone()).collect::<Vec<_>>(), [WarningKind::VerificationRelaxed]);
+}
@@ -0,0 +1,51 @@
+//! Tests for the canonical benchmark pattern corpus.
+
+use genrex::patterns;
+use genrex::RegexGenerator;
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+
+#[test]
+fn test_canonical_patterns_generate() {
+    for p in patterns::ALL {
+        let mut builder = RegexGenerator::builder(p.pattern)
+            .config(p.config())
+            .rng(StdRng::seed_from_u64(7));
+        if p.needs_backrefs {
+            builder = builder.allow_backrefs();
+        }
+        let mut generator = builder.build().expect("canonical pattern builds");
+        let s = generator.generate_one().unwrap_or_else(|e| panic!("{}: {:?}", p.name, e));
+        assert!((p.min_len..=p.max_len).contains(&s.len()), "{}: {}", p.name, s);
+        if !p.needs_backrefs {
+            assert!(Regex::new(p.pattern).unwrap().is_match(&s), "{}: {}", p.name, s);
+        }
+    }
+}
+
+#[test]
+fn test_length_metadata_matches_the_language() {
+    for p in patterns::ALL.iter().filter(|p| !p.needs_backrefs) {
+        let a = genrex::analyze(p.pattern).unwrap();
+        assert_eq!((a.min_len, a.max_len), (p.min_len, Some(p.max_len)), "{}", p.name);
+    }
+}
+
+#[test]
+fn test_backref_stress_repeats_groups() {
+    let mut generator = RegexGenerator::builder(patterns::BACKREF_STRESS.pattern)
+        .config(patterns::BACKREF_STRESS.config())
+        .rng(StdRng::seed_from_u64(3))
+        .allow_backrefs()
+        .build()
+        .unwrap();
+    let s = generator.generate_one().unwrap();
+    assert_eq!(&s[0..2], &s[8..10]);
+    assert_eq!(&s[2..4], &s[6..8]);
+}
+
+#[test]
+fn test_get_by_name() {
+    assert_eq!(patterns::get("uuid").unwrap().pattern, patterns::UUID.pattern);
+    assert!(patterns::get("nope").is_none());
+}
@@ -0,0 +1,60 @@
+//! Tests for password-policy generation.
+
+use genrex::{BigUint, GenError, PolicyBuilder};
+use regex::Regex;
+
+#[test]
+fn test_policy_outputs_satisfy_every_rule() {
+    let mut g = PolicyBuilder::new()
+        .charset("[a-zA-Z0-9!@#]")
+        .length(8, 12)
+        .require("[A-Z]")
+        .require("[0-9].*[0-9]")
+        .require("[!@#]")
+        .ban_all(["password", "1234"])
+        .seed(3)
+        .build()
+        .unwrap();
+    let charset = Regex::new("^[a-zA-Z0-9!@#]{8,12}$").unwrap();
+    let upper = Regex::new("[A-Z]").unwrap();
+    let digits = Regex::new("[0-9].*[0-9]").unwrap();
+    let symbol = Regex::new("[!@#]").unwrap();
+    for s in g.generate_n(300).unwrap() {
+        assert!(charset.is_match(&s) && upper.is_match(&s) && digits.is_match(&s) && symbol.is_match(&s), "{}", s);
+        assert!(!s.contains("password") && !s.contains("1234"), "{}", s);
+    }
+}
+
+#[test]
+fn test_policy_count_and_entropy() {
+    // Two lowercase letters, at least one of them a vowel, never "aa".
+    let g = PolicyBuilder::new().charset("[a-z]").length(2, 2).require("[aeiou]").ban("aa").seed(1).build().unwrap();
+    // 26^2 strings, minus the 21^2 without a vowel, minus "aa".
+    assert_eq!(g.count(), &BigUint::from(26u32 * 26 - 21 * 21 - 1));
+    let e = g.entropy();
+    assert_eq!(e.sampled_bits, Some(e.uniform_bits));
+    assert!((e.uniform_bits - 234f64.log2()).abs() < 1e-9);
+}
+
+#[test]
+fn test_policy_is_reproducible_and_uniform() {
+    let build = || PolicyBuilder::new().charset("[ab]").length(1, 2).seed(11).build().unwrap();
+    assert_eq!(build().generate_n(20).unwrap(), build().generate_n(20).unwrap());
+    // Six strings: a, b, aa, ab, ba, bb.
+    let mut counts = std::collections::HashMap::new();
+    for s in build().generate_n(6000).unwrap() {
+        *counts.entry(s).or_insert(0) += 1;
+    }
+    assert_eq!(counts.len(), 6);
+    assert!(counts.values().all(|&n| (800..1200).contains(&n)), "{:?}", counts);
+}
+
+#[test]
+fn test_policy_errors() {
+    let unsatisfiable = PolicyBuilder::new().charset("[a-z]").require("[0-9]").build();
+    assert!(matches!(unsatisfiable, Err(GenError::InvalidConfig(_))));
+    assert!(matches!(PolicyBuilder::new().charset("abc").build(), Err(GenError::InvalidConfig(_))));
+    assert!(matches!(PolicyBuilder::new().length(5, 4).build(), Err(GenError::InvalidConfig(_))));
+    assert!(matches!(PolicyBuilder::new().ban("").build(), Err(GenError::InvalidConfig(_))));
+    assert!(matches!(PolicyBuilder::new().require(r"(a)\1").build(), Err(GenError::Unsupported(_))));
+}
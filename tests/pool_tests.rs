@@ -0,0 +1,40 @@
+//! Tests for the background-refilled prefetch pool.
+
+use std::time::{Duration, Instant};
+
+use genrex::{GeneratorConfig, PrefetchPool, RegexGenerator};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+
+fn generator(pattern: &str) -> RegexGenerator {
+    RegexGenerator::builder(pattern)
+        .config(GeneratorConfig { min_len: 0, max_len: 32, max_attempts: 1000, timeout: None })
+        .rng(StdRng::seed_from_u64(40))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_pool_fills_and_refills() {
+    let re = Regex::new("^[a-z]{6}$").unwrap();
+    let pool = PrefetchPool::new(generator("^[a-z]{6}$"), 8);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while pool.len() < pool.capacity() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(pool.len(), 8);
+    assert!(re.is_match(&pool.try_take().unwrap()));
+    for _ in 0..50 {
+        assert!(re.is_match(&pool.take().unwrap()));
+    }
+    let generator = pool.into_generator();
+    assert!(generator.stats().accepted >= 51);
+}
+
+#[test]
+fn test_pool_reports_generation_failure() {
+    let pool = PrefetchPool::new(generator("^(a){genrex:unique}$"), 4);
+    assert_eq!(pool.take().unwrap(), "a");
+    assert!(matches!(pool.take(), Err(genrex::GenError::NoMatch)));
+    assert!(pool.try_take().is_none());
+}
@@ -0,0 +1,41 @@
+//! Tests for the named presets.
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use genrex::presets;
+use genrex::GenError;
+use rand::{rngs::StdRng, SeedableRng};
+use regex::Regex;
+
+#[test]
+fn test_presets_generate_within_bounds() {
+    for p in presets::ALL {
+        let mut generator = genrex::preset(p.name).unwrap().rng(StdRng::seed_from_u64(5)).build().expect("preset builds");
+        let re = Regex::new(p.pattern).unwrap();
+        for s in generator.generate_n(50).unwrap_or_else(|e| panic!("{}: {:?}", p.name, e)) {
+            assert!((p.min_len..=p.max_len).contains(&s.len()), "{}: {}", p.name, s);
+            assert!(re.is_match(&s), "{}: {}", p.name, s);
+        }
+    }
+}
+
+#[test]
+fn test_addresses_parse() {
+    let mut ipv4 = genrex::preset("ipv4").unwrap().rng(StdRng::seed_from_u64(1)).build().unwrap();
+    let mut ipv6 = genrex::preset("ipv6").unwrap().rng(StdRng::seed_from_u64(1)).build().unwrap();
+    for _ in 0..100 {
+        let s = ipv4.generate_one().unwrap();
+        assert_eq!(s.parse::<Ipv4Addr>().unwrap().to_string(), s);
+        assert!(ipv6.generate_one().unwrap().parse::<Ipv6Addr>().is_ok());
+    }
+}
+
+#[test]
+fn test_unknown_preset() {
+    assert!(presets::get("uuid").is_some());
+    assert!(presets::get("UUID").is_none());
+    match genrex::preset("nope") {
+        Err(GenError::InvalidConfig(msg)) => assert!(msg.contains("uuid, ipv4"), "{}", msg),
+        _ => panic!("expected an unknown preset error"),
+    }
+}
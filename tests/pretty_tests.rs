@@ -0,0 +1,29 @@
+//! Tests for the `TokenTree` pretty-printer.
+
+use genrex::{parse, TokenTree};
+
+#[test]
+fn test_tree_shows_groups_and_bounds() {
+    let tokens = parse(r"^(ab|c){2,}?x$").unwrap();
+    let tree = TokenTree::new(&tokens).to_string();
+    let expected = "\
+concat  ^(ab|c){2,}?x$
+├── anchor ^
+├── repeat {2,} lazy  (ab|c){2,}?
+│   └── group #1  (ab|c)
+│       └── concat  ab|c
+│           └── alternation of 2  ab|c
+│               ├── concat  ab
+│               │   ├── literal a
+│               │   └── literal b
+│               └── concat  c
+│                   └── literal c
+├── literal x
+└── anchor $
+";
+    assert_eq!(tree, expected);
+
+    let colored = TokenTree::new(&tokens).color(true).to_string();
+    assert!(colored.contains("\x1b[35mgroup #1\x1b[0m"));
+    assert_eq!(TokenTree::new(&parse("[a-c]").unwrap()).to_string(), "class [a-c]\n");
+}
@@ -0,0 +1,52 @@
+//! Tests for `RecordGenerator`.
+
+use std::collections::HashSet;
+
+use genrex::{GenError, RecordGenerator};
+use regex::Regex;
+
+fn users(seed: u64) -> RecordGenerator {
+    let mut records = RecordGenerator::new(seed);
+    records.add_field("id", r"^\d{2}$").unwrap().unique();
+    records.add_field("name", r"^[A-Z][a-z]{2,8}$").unwrap();
+    records.add_field("email", r"^[a-z]{3,6}@example\.com$").unwrap().nullable(0.5);
+    records
+}
+
+#[test]
+fn test_rows_follow_field_patterns() {
+    let mut records = users(4);
+    assert_eq!(records.field_names().collect::<Vec<_>>(), ["id", "name", "email"]);
+    let rows = records.generate_rows(60).unwrap();
+    let name = Regex::new(r"^[A-Z][a-z]{2,8}$").unwrap();
+    let ids: HashSet<&str> = rows.iter().map(|r| r["id"].as_str()).collect();
+    assert_eq!(ids.len(), 60);
+    assert!(rows.iter().all(|r| name.is_match(&r["name"])));
+    let nulls = rows.iter().filter(|r| !r.contains_key("email")).count();
+    assert!(nulls > 10 && nulls < 50, "{}", nulls);
+    assert_eq!(users(4).generate_rows(60).unwrap(), rows);
+
+    let mut small = RecordGenerator::new(1);
+    small.add_field("flag", "^[01]$").unwrap().unique();
+    small.generate_rows(2).unwrap();
+    assert!(matches!(small.generate_row(), Err(GenError::InvalidConfig(_))));
+    assert!(small.add_field("flag", "^x$").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_rows_deserialize_into_structs() {
+    #[derive(serde::Deserialize)]
+    struct User {
+        id: u32,
+        name: String,
+        email: Option<String>,
+    }
+    let mut records = users(9);
+    for _ in 0..20 {
+        let user: User = records.generate_as().unwrap();
+        assert!(user.id < 100);
+        assert!(user.name.chars().next().unwrap().is_ascii_uppercase());
+        assert!(user.email.is_none_or(|e| e.ends_with("@example.com")));
+    }
+}
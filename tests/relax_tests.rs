@@ -0,0 +1,107 @@
+//! Tests for relaxation policies retrying `NoMatch` under looser settings.
+
+use genrex::{Engine, GenError, GeneratorConfig, LengthUnit, RegexGenerator, RelaxPolicy, Relaxation};
+
+fn too_long(policy: Option<RelaxPolicy>) -> RegexGenerator {
+    let mut builder = RegexGenerator::builder("^[a-z]{3}$")
+        .config(GeneratorConfig { min_len: 5, max_len: 8, max_attempts: 20, ..GeneratorConfig::default() })
+        .length_unit(LengthUnit::Chars)
+        .seed(5);
+    if let Some(policy) = policy {
+        builder = builder.relax(policy);
+    }
+    builder.build().unwrap()
+}
+
+#[test]
+fn test_without_policy_no_match_fails() {
+    let mut g = too_long(None);
+    assert!(matches!(g.generate_one(), Err(GenError::NoMatch)));
+    assert!(g.last_relaxations().is_empty());
+}
+
+#[test]
+fn test_widened_lengths_reported() {
+    let mut g = too_long(Some(RelaxPolicy::new(3).widen_lengths(10)));
+    // 10% of 5 rounds down to nothing, so each round takes one off: the second reaches 3.
+    assert_eq!(g.generate_one().unwrap().len(), 3);
+    assert_eq!(g.last_relaxations(), &[Relaxation::Lengths { min_len: 3, max_len: 10 }]);
+    // The configured lengths are back in force for the next call, which relaxes again.
+    assert_eq!(g.generate_one().unwrap().len(), 3);
+    assert_eq!(g.last_relaxations(), &[Relaxation::Lengths { min_len: 3, max_len: 10 }]);
+    assert_eq!(g.stats().failures, 0);
+}
+
+#[test]
+fn test_too_few_rounds_fail() {
+    let mut g = too_long(Some(RelaxPolicy::new(1).widen_lengths(10)));
+    assert!(matches!(g.generate_one(), Err(GenError::NoMatch)));
+    assert_eq!(g.last_relaxations(), &[Relaxation::Lengths { min_len: 4, max_len: 9 }]);
+}
+
+#[test]
+fn test_switched_engine() {
+    let mut g = too_long(Some(RelaxPolicy::new(2).widen_lengths(10).switch_engine()));
+    let (value, report) = g.generate_one_with_report().unwrap();
+    assert_eq!(value.len(), 3);
+    assert_eq!(report.engine, Engine::Tokens);
+    assert!(g.last_relaxations().contains(&Relaxation::Engine(Engine::Tokens)));
+
+    let mut g = RegexGenerator::builder("^[a-z]{4}$").relax(RelaxPolicy::new(2).switch_engine()).build().unwrap();
+    assert_eq!(g.generate_one_with_report().unwrap().1.engine, Engine::Nfa);
+    assert!(g.last_relaxations().is_empty());
+}
+
+#[test]
+fn test_more_attempts() {
+    let build = |policy: RelaxPolicy| {
+        RegexGenerator::builder("^[0-9]{3}$")
+            .config(GeneratorConfig { max_attempts: 1, ..GeneratorConfig::default() })
+            .filter(|s| s.starts_with('7'))
+            .relax(policy)
+            .seed(9)
+            .build()
+            .unwrap()
+    };
+    let mut g = build(RelaxPolicy::new(4).more_attempts(10));
+    let mut relaxed = 0;
+    for _ in 0..20 {
+        assert!(g.generate_one().unwrap().starts_with('7'));
+        if let [Relaxation::Attempts(n)] = g.last_relaxations() {
+            assert!([10, 100, 1000, 10_000].contains(n));
+            relaxed += 1;
+        } else {
+            assert!(g.last_relaxations().is_empty());
+        }
+    }
+    // One attempt in ten passes, so most calls need more than the one configured.
+    assert!(relaxed > 10);
+}
+
+#[test]
+fn test_exact_len_not_relaxed() {
+    let mut g = too_long(Some(RelaxPolicy::new(3).widen_lengths(50)));
+    assert_eq!(g.generate_exact_len(3).unwrap().len(), 3);
+    assert!(matches!(g.generate_exact_len(4), Err(GenError::NoMatch)));
+}
+
+#[test]
+fn test_raised_max_repeat_reported() {
+    let build = |policy: RelaxPolicy| {
+        RegexGenerator::builder("^a+$")
+            .config(GeneratorConfig { min_len: 40, max_len: 200, max_attempts: 20, ..GeneratorConfig::default() })
+            .nfa(false)
+            .max_repeat(8)
+            .relax(policy)
+            .seed(3)
+            .build()
+            .unwrap()
+    };
+    // Nine repetitions at most never reach 40.
+    assert!(matches!(build(RelaxPolicy::new(2)).generate_one(), Err(GenError::NoMatch)));
+    let mut g = build(RelaxPolicy::new(3).raise_max_repeat(2));
+    let s = g.generate_one().unwrap();
+    // 16 and 32 extra repetitions still fall short; the third round allows 64.
+    assert!((40..=65).contains(&s.len()));
+    assert_eq!(g.last_relaxations(), &[Relaxation::MaxRepeat(64)]);
+}
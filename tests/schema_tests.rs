@@ -0,0 +1,150 @@
+//! Tests for sampling JSON documents from a JSON Schema.
+#![cfg(feature = "jsonschema")]
+
+use std::collections::HashSet;
+
+use genrex::{GenError, SchemaSampler};
+use regex::Regex;
+use serde_json::{json, Value};
+
+#[test]
+fn test_pattern_and_length_strings() {
+    let schema = json!({"type": "string", "pattern": "^[a-f0-9]+$", "minLength": 4, "maxLength": 6});
+    let mut sampler = SchemaSampler::new(schema).seed(1);
+    let re = Regex::new("^[a-f0-9]{4,6}$").unwrap();
+    for doc in sampler.generate_n(50).unwrap() {
+        assert!(re.is_match(doc.as_str().unwrap()), "{}", doc);
+    }
+}
+
+#[test]
+fn test_plain_strings_honor_lengths() {
+    let mut sampler = SchemaSampler::new(json!({"type": "string", "minLength": 3, "maxLength": 5})).seed(2);
+    for doc in sampler.generate_n(50).unwrap() {
+        let len = doc.as_str().unwrap().len();
+        assert!((3..=5).contains(&len), "{}", doc);
+    }
+}
+
+#[test]
+fn test_formats() {
+    let schema = json!({
+        "type": "object",
+        "required": ["id", "email", "when", "day"],
+        "properties": {
+            "id": {"type": "string", "format": "uuid"},
+            "email": {"type": "string", "format": "email"},
+            "when": {"type": "string", "format": "date-time"},
+            "day": {"type": "string", "format": "date"}
+        }
+    });
+    let mut sampler = SchemaSampler::new(schema).seed(3);
+    let uuid = Regex::new("^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$").unwrap();
+    let date = Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap();
+    for doc in sampler.generate_n(20).unwrap() {
+        assert!(uuid.is_match(doc["id"].as_str().unwrap()), "{}", doc);
+        assert!(doc["email"].as_str().unwrap().contains('@'), "{}", doc);
+        assert!(doc["when"].as_str().unwrap().contains('T'), "{}", doc);
+        assert!(date.is_match(doc["day"].as_str().unwrap()), "{}", doc);
+    }
+}
+
+#[test]
+fn test_objects_arrays_and_numbers() {
+    let schema = json!({
+        "type": "object",
+        "required": ["tags", "qty", "price", "active"],
+        "properties": {
+            "tags": {"type": "array", "items": {"enum": ["a", "b", "c"]}, "minItems": 1, "maxItems": 3, "uniqueItems": true},
+            "qty": {"type": "integer", "minimum": 10, "exclusiveMaximum": 20, "multipleOf": 5},
+            "price": {"type": "number", "exclusiveMinimum": 0, "maximum": 1},
+            "active": {"type": "boolean"},
+            "note": {"type": "null"}
+        }
+    });
+    let mut sampler = SchemaSampler::new(schema).seed(4);
+    for doc in sampler.generate_n(50).unwrap() {
+        let tags = doc["tags"].as_array().unwrap();
+        assert!((1..=3).contains(&tags.len()), "{}", doc);
+        let unique: HashSet<String> = tags.iter().map(Value::to_string).collect();
+        assert_eq!(unique.len(), tags.len(), "{}", doc);
+        let qty = doc["qty"].as_i64().unwrap();
+        assert!(qty == 10 || qty == 15, "{}", doc);
+        let price = doc["price"].as_f64().unwrap();
+        assert!(price > 0.0 && price <= 1.0, "{}", doc);
+        assert!(doc["active"].is_boolean());
+        assert!(doc.get("note").is_none_or(Value::is_null));
+    }
+}
+
+#[test]
+fn test_refs_and_combinators() {
+    let schema = json!({
+        "$defs": {
+            "code": {"type": "string", "pattern": "^[A-Z]{3}$"},
+            "node": {
+                "type": "object",
+                "required": ["code"],
+                "properties": {"code": {"$ref": "#/$defs/code"}, "child": {"$ref": "#/$defs/node"}}
+            }
+        },
+        "allOf": [
+            {"$ref": "#/$defs/node"},
+            {"required": ["kind"], "properties": {"kind": {"oneOf": [{"const": 1}, {"const": "one"}]}}}
+        ]
+    });
+    let mut sampler = SchemaSampler::new(schema).seed(5);
+    let code = Regex::new("^[A-Z]{3}$").unwrap();
+    for doc in sampler.generate_n(30).unwrap() {
+        let mut node = &doc;
+        loop {
+            assert!(code.is_match(node["code"].as_str().unwrap()), "{}", doc);
+            match node.get("child") {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+        assert!(doc["kind"] == json!(1) || doc["kind"] == json!("one"), "{}", doc);
+    }
+}
+
+#[test]
+fn test_ecmascript_patterns() {
+    // JSON Schema patterns are JavaScript regexes.
+    let schema = json!({"type": "string", "pattern": "^\\u0041\\d{2}$"});
+    let doc = SchemaSampler::new(schema).seed(6).generate_one().unwrap();
+    assert!(Regex::new(r"^A\d{2}$").unwrap().is_match(doc.as_str().unwrap()), "{}", doc);
+}
+
+#[test]
+fn test_seeded_documents_repeat() {
+    let schema = r#"{"type": "object", "properties": {"a": {"type": "string"}, "b": {"type": "integer"}}}"#;
+    let a = SchemaSampler::from_json(schema).unwrap().seed(7).generate_n(5).unwrap();
+    let b = SchemaSampler::from_json(schema).unwrap().seed(7).generate_n(5).unwrap();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_schema_errors() {
+    assert!(matches!(SchemaSampler::from_json("{"), Err(GenError::InvalidConfig(_))));
+    let mut bad_ref = SchemaSampler::new(json!({"$ref": "#/nope"}));
+    assert!(matches!(bad_ref.generate_one(), Err(GenError::InvalidConfig(_))));
+    let mut external = SchemaSampler::new(json!({"$ref": "other.json#/a"}));
+    assert!(matches!(external.generate_one(), Err(GenError::Unsupported(_))));
+    let mut empty = SchemaSampler::new(json!({"type": "integer", "minimum": 5, "maximum": 4}));
+    assert!(matches!(empty.generate_one(), Err(GenError::InvalidConfig(_))));
+    let mut lengths = SchemaSampler::new(json!({"type": "string", "minLength": 5, "maxLength": 4}));
+    assert!(lengths.generate_one().is_err());
+}
+
+#[test]
+fn test_combinator_ref_cycles_hit_the_depth_limit() {
+    let schemas = [
+        json!({"allOf": [{"$ref": "#"}]}),
+        json!({"anyOf": [{"$ref": "#/$defs/a"}], "$defs": {"a": {"anyOf": [{"$ref": "#"}]}}}),
+    ];
+    for schema in schemas {
+        let err = SchemaSampler::new(schema.clone()).generate_one().unwrap_err();
+        assert!(err.to_string().contains("$ref nesting exceeds"), "{}: {}", schema, err);
+    }
+}
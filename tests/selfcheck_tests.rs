@@ -0,0 +1,24 @@
+use genrex::{verify_self, GenError};
+
+#[test]
+fn test_verify_self_passes_for_supported_patterns() {
+    for pattern in [r"^[a-z]{3}-\d{2,4}$", r"(foo|bar)+baz?", r"\bword\b", r"[^\n]{0,5}x"] {
+        let report = verify_self(pattern, 200, 7).unwrap();
+        assert!(report.is_ok(), "{}", report);
+        assert_eq!(report.samples, 200);
+    }
+}
+
+#[test]
+fn test_verify_self_reports_generation_failures() {
+    let report = verify_self(r"a\bb", 2, 1).unwrap();
+    assert_eq!(report.failed, 2);
+    assert!(!report.is_ok());
+    assert!(report.to_string().contains("2 failed"));
+}
+
+#[test]
+fn test_verify_self_needs_a_compilable_pattern() {
+    assert!(matches!(verify_self(r"(a)\1", 10, 1), Err(GenError::InvalidRegex(_)) | Err(GenError::Unsupported(_))));
+    assert!(verify_self("(", 10, 1).is_err());
+}
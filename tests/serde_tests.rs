@@ -0,0 +1,35 @@
+//! Tests for the `serde` impls of configs, token trees and analyses.
+#![cfg(feature = "serde")]
+
+use std::time::Duration;
+
+use genrex::{GeneratorConfig, RegexGenerator, Token};
+
+#[test]
+fn test_config_round_trips_and_defaults_missing_fields() {
+    let config = GeneratorConfig { min_len: 3, max_len: 12, max_attempts: 50, timeout: Some(Duration::from_millis(250)) };
+    let json = serde_json::to_string(&config).unwrap();
+    let back: GeneratorConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!((back.min_len, back.max_len, back.max_attempts, back.timeout), (3, 12, 50, config.timeout));
+
+    let partial: GeneratorConfig = serde_json::from_str(r#"{"max_len": 8}"#).unwrap();
+    let default = GeneratorConfig::default();
+    assert_eq!((partial.min_len, partial.max_len, partial.max_attempts), (default.min_len, 8, default.max_attempts));
+    assert!(RegexGenerator::builder("[a-z]+").config(partial).build().is_ok());
+}
+
+#[test]
+fn test_tokens_round_trip() {
+    let tokens = genrex::parse(r"^(ab|c+?)[x-z]\1.{2,}$").unwrap();
+    let json = serde_json::to_string(&tokens).unwrap();
+    let back: Vec<Token> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, tokens);
+    assert_eq!(genrex::to_pattern(&back), genrex::to_pattern(&tokens));
+}
+
+#[test]
+fn test_analysis_round_trips() {
+    let analysis = genrex::analyze("[a-z]{10}").unwrap();
+    let back: genrex::Analysis = serde_json::from_str(&serde_json::to_string(&analysis).unwrap()).unwrap();
+    assert_eq!(back, analysis);
+}
@@ -0,0 +1,57 @@
+//! Tests for shrinking matches toward minimal ones.
+
+use genrex::{GeneratorConfig, RegexGenerator};
+use regex::Regex;
+
+/// Move to the first candidate that still fails `fails` until none does.
+fn minimize(g: &RegexGenerator, s: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut s = s.to_string();
+    while let Some(next) = g.shrink(&s).find(|c| fails(c)) {
+        s = next;
+    }
+    s
+}
+
+#[test]
+fn test_candidates_match_and_are_smaller() {
+    let pattern = r"^(foo|x)bar[0-9]+$";
+    let g = RegexGenerator::builder(pattern).seed(1).build().unwrap();
+    let re = Regex::new(pattern).unwrap();
+    let candidates: Vec<String> = g.shrink("foobar9876").collect();
+    assert_eq!(candidates[0], "xbar0");
+    assert!(candidates.contains(&"xbar9876".to_string()), "{:?}", candidates);
+    for c in &candidates {
+        assert!(re.is_match(c), "{}", c);
+        assert!((c.chars().count(), c.as_str()) < (10, "foobar9876"), "{}", c);
+    }
+    assert_eq!(g.shrink("xbar0").count(), 0);
+}
+
+#[test]
+fn test_minimizes_failing_input() {
+    let g = RegexGenerator::builder(r"^(foo|x)bar[0-9]+$").seed(1).build().unwrap();
+    assert_eq!(minimize(&g, "foobar9876", |s| s.contains('7')), "xbar7");
+
+    let g = RegexGenerator::builder(r"^[a-z]+(,[a-z]+)*$").seed(1).build().unwrap();
+    assert_eq!(minimize(&g, "hello,big,wide,world", |s| s.matches(',').count() >= 2), "a,a,a");
+}
+
+#[test]
+fn test_respects_length_bounds_and_filters() {
+    let g = RegexGenerator::builder(r"^[0-9]{2,8}$")
+        .config(GeneratorConfig { min_len: 4, ..GeneratorConfig::default() })
+        .filter(|s| !s.starts_with('0'))
+        .seed(1)
+        .build()
+        .unwrap();
+    let candidates: Vec<String> = g.shrink("987654").collect();
+    // The shortest match, "0000", fails the filter.
+    assert!(!candidates.is_empty());
+    assert!(candidates.iter().all(|c| c.len() >= 4 && !c.starts_with('0')), "{:?}", candidates);
+}
+
+#[test]
+fn test_unverifiable_patterns_yield_nothing() {
+    let g = RegexGenerator::builder(r"^(a+)-\1$").allow_backrefs().seed(1).build().unwrap();
+    assert_eq!(g.shrink("aaa-aaa").count(), 0);
+}
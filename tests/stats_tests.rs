@@ -0,0 +1,77 @@
+//! Tests for cumulative generation statistics.
+
+use genrex::{GeneratorConfig, RegexGenerator, RejectReason};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn test_stats_count_length_rejections() {
+    let cfg = GeneratorConfig { min_len: 3, max_len: 3, max_attempts: 10_000, timeout: None };
+    let mut generator = RegexGenerator::builder("^a{1,5}$")
+        .config(cfg)
+        .nfa(false)
+        .rng(StdRng::seed_from_u64(5))
+        .build()
+        .unwrap();
+    generator.generate_n(10).unwrap();
+    let stats = generator.stats();
+    assert_eq!(stats.accepted, 10);
+    assert_eq!(stats.failures, 0);
+    assert!(stats.rejected_for(RejectReason::Length) > 0);
+    assert_eq!(stats.attempts, stats.accepted + stats.rejected());
+    generator.reset_stats();
+    assert_eq!(generator.stats().attempts, 0);
+}
+
+#[test]
+fn test_report_covers_single_output() {
+    let cfg = GeneratorConfig { min_len: 3, max_len: 3, max_attempts: 10_000, timeout: None };
+    let mut generator = RegexGenerator::builder("^a{1,5}$")
+        .config(cfg)
+        .nfa(false)
+        .rng(StdRng::seed_from_u64(6))
+        .build()
+        .unwrap();
+    generator.generate_n(5).unwrap();
+    let before = generator.stats().attempts;
+    let (s, report) = generator.generate_one_with_report().unwrap();
+    assert_eq!(s, "aaa");
+    assert_eq!(report.engine, genrex::Engine::Tokens);
+    assert_eq!(report.attempts, generator.stats().attempts - before);
+    assert_eq!(report.attempts, report.rejected() + 1);
+    assert_eq!(report.rejected_for(RejectReason::RegexMismatch), 0);
+    assert_eq!(report.rejected(), report.rejected_for(RejectReason::Length));
+}
+
+#[test]
+fn test_timeout_is_distinct_from_no_match() {
+    let cfg = GeneratorConfig { min_len: 3, max_len: 3, max_attempts: 50, timeout: Some(std::time::Duration::ZERO) };
+    let mut generator = RegexGenerator::builder("^a{1,5}$").config(cfg.clone()).build().unwrap();
+    assert!(matches!(generator.generate_one(), Err(genrex::GenError::Timeout)));
+    assert_eq!(generator.stats().failures, 1);
+    let err: genrex::GenrexError = generator.generate_one().unwrap_err().into();
+    assert!(matches!(err, genrex::GenrexError::Timeout));
+
+    let cfg = GeneratorConfig { min_len: 9, max_len: 9, timeout: None, ..cfg };
+    let mut generator = RegexGenerator::builder("^a{1,5}$").config(cfg).build().unwrap();
+    assert!(matches!(generator.generate_one(), Err(genrex::GenError::NoMatch)));
+}
+
+#[test]
+fn test_lossy_batch_keeps_successes() {
+    // Few attempts per call: some calls give up, the batch carries on.
+    let cfg = GeneratorConfig { min_len: 3, max_len: 3, max_attempts: 2, timeout: None };
+    let mut generator = RegexGenerator::builder("^a{1,5}$")
+        .config(cfg)
+        .nfa(false)
+        .rng(StdRng::seed_from_u64(8))
+        .build()
+        .unwrap();
+    generator.generate_n(3).ok();
+    let (out, stats) = generator.generate_n_lossy(50);
+    assert!(!out.is_empty() && out.len() < 50, "{}", out.len());
+    assert!(out.iter().all(|s| s == "aaa"));
+    assert_eq!(stats.accepted as usize, out.len());
+    assert_eq!(stats.failures as usize, 50 - out.len());
+    assert_eq!(stats.rejected(), stats.rejected_for(RejectReason::Length));
+    assert_eq!(stats.attempts, stats.accepted + stats.rejected());
+}
@@ -0,0 +1,60 @@
+//! Tests for quota-bounded streaming.
+
+use std::io::{BufWriter, Write};
+use std::sync::{Arc, Mutex};
+
+use genrex::{BoundedStream, Quota, RegexGenerator};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn generator() -> RegexGenerator {
+    RegexGenerator::builder("^[a-z]{3,7}$").rng(StdRng::seed_from_u64(4)).build().unwrap()
+}
+
+#[test]
+fn test_item_quota() {
+    let items: Vec<String> = BoundedStream::new(generator(), Quota::items(5)).map(Result::unwrap).collect();
+    assert_eq!(items.len(), 5);
+}
+
+#[test]
+fn test_byte_quota_never_exceeded() {
+    let mut stream = BoundedStream::new(generator(), Quota::bytes(50));
+    let total: usize = stream.by_ref().map(|s| s.unwrap().len() + 1).sum();
+    assert!(total <= 50);
+    assert!(total > 50 - 8, "stream stopped early at {}", total);
+    assert_eq!(stream.summary().bytes, total as u64);
+}
+
+#[test]
+fn test_sink_flushed_on_drop() {
+    let buf = SharedBuf::default();
+    {
+        let stream = BoundedStream::new(generator(), Quota::items(3).and_bytes(1_000))
+            .sink(BufWriter::with_capacity(4096, buf.clone()))
+            .delimiter(b"\0");
+        assert_eq!(stream.count(), 3);
+    }
+    let written = buf.0.lock().unwrap().clone();
+    assert_eq!(written.iter().filter(|&&b| b == 0).count(), 3);
+}
+
+#[test]
+fn test_finish_reports_summary() {
+    let mut stream = BoundedStream::new(generator(), Quota::items(2)).sink(SharedBuf::default());
+    stream.by_ref().for_each(drop);
+    let summary = stream.finish().unwrap();
+    assert_eq!(summary.items, 2);
+}
@@ -0,0 +1,43 @@
+//! Tests for templates with embedded patterns.
+
+use genrex::{GenError, Template};
+use regex::Regex;
+
+#[test]
+fn test_template_generates_literals_and_patterns() {
+    let mut template = Template::parse("user-{[0-9]{6}}@{(gmail|corp)}.com").unwrap().seed(4);
+    let re = Regex::new(r"^user-[0-9]{6}@(gmail|corp)\.com$").unwrap();
+    for s in template.generate_n(20).unwrap() {
+        assert!(re.is_match(&s), "{}", s);
+    }
+}
+
+#[test]
+fn test_template_is_reproducible() {
+    let a = Template::parse("{[a-z]{4}}-{\\d{2}}").unwrap().seed(9).generate_n(10).unwrap();
+    let b = Template::parse("{[a-z]{4}}-{\\d{2}}").unwrap().seed(9).generate_n(10).unwrap();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_literal_text_is_not_a_regex() {
+    let mut template = Template::parse("a.b*c+(d)? {{x}} $").unwrap();
+    assert_eq!(template.generate_one().unwrap(), "a.b*c+(d)? {x} $");
+    assert_eq!(template.generators().count(), 0);
+}
+
+#[test]
+fn test_braces_in_classes_and_escapes() {
+    let mut template = Template::parse(r"<{[}{]}|{\}x}|{[]a]{2}}>").unwrap().seed(1);
+    assert_eq!(template.generators().count(), 3);
+    let s = template.generate_one().unwrap();
+    assert!(Regex::new(r"^<[{}]\|\}x\|[\]a]{2}>$").unwrap().is_match(&s), "{}", s);
+}
+
+#[test]
+fn test_malformed_templates() {
+    for bad in ["user-{[0-9]", "a}b", "x{}y"] {
+        assert!(matches!(Template::parse(bad), Err(GenError::InvalidConfig(_))), "{}", bad);
+    }
+    assert!(Template::parse("{(}").is_err());
+}
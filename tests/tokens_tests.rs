@@ -1,6 +1,6 @@
 //! Unit tests for regex token generation and description.
 
-use genrex::Token;
+use genrex::{RegexGenerator, Token};
 use rand::{rngs::StdRng, SeedableRng};
 use genrex::{RegexToken, TokenContext};
 
@@ -16,7 +16,7 @@ fn test_literal_token() {
 
 #[test]
 fn test_class_token() {
-    let tok = Token::Class(vec!['a', 'b', 'c']);
+    let tok = Token::Class(vec!['a', 'b', 'c'].into());
     let mut rng = StdRng::seed_from_u64(2);
     let mut ctx = TokenContext::new();
     let s = tok.generate(&mut rng, &mut ctx).unwrap();
@@ -107,7 +107,7 @@ fn test_backreference_token_simple() {
 fn test_backreference_token_repeated() {
     // Pattern equivalent: ([ab])\1\1 -> produces three identical chars from {a,b}
     let tok = Token::Concatenation(vec![
-        Token::Group(Box::new(Token::Class(vec!['a', 'b'])), 1),
+        Token::Group(Box::new(Token::Class(vec!['a', 'b'].into())), 1),
         Token::Backreference(1),
         Token::Backreference(1),
     ]);
@@ -120,6 +120,52 @@ fn test_backreference_token_repeated() {
     assert!(s.chars().all(|c| c == first));
 }
 
+#[test]
+fn test_backreference_repeats_last_iteration() {
+    // Pattern equivalent: (ab|cd){1,6}\1 -> the reference repeats the last pair generated
+    let pair = |a, b| Token::Concatenation(vec![Token::Literal(a), Token::Literal(b)]);
+    let tok = Token::Concatenation(vec![
+        Token::Quantifier {
+            token: Box::new(Token::Group(Box::new(Token::Alternation(vec![pair('a', 'b'), pair('c', 'd')])), 1)),
+            min: 1,
+            max: 6,
+            greedy: true,
+        },
+        Token::Backreference(1),
+    ]);
+    let mut rng = StdRng::seed_from_u64(13);
+    for _ in 0..20 {
+        let mut ctx = TokenContext::new();
+        let s = tok.generate(&mut rng, &mut ctx).unwrap();
+        let n = s.len();
+        assert!(n >= 4 && n % 2 == 0, "{}", s);
+        assert_eq!(&s[n - 2..], &s[n - 4..n - 2], "{}", s);
+        assert_eq!(ctx.get_capture(1).as_deref(), Some(&s[n - 2..]));
+    }
+}
+
+#[test]
+fn test_backreference_in_quantified_group() {
+    let check = |pattern: &str, ok: &dyn Fn(&str) -> bool| {
+        for nfa in [true, false] {
+            let mut g = RegexGenerator::builder(pattern).allow_backrefs().nfa(nfa).rng(StdRng::seed_from_u64(14)).build().unwrap();
+            for _ in 0..20 {
+                let s = g.generate_one().unwrap();
+                assert!(ok(&s), "{}: {}", pattern, s);
+                let (captures, explained) = (g.generate_with_captures().unwrap(), g.generate_explained().unwrap());
+                assert!(ok(&captures.0) && ok(&explained.value), "{}: {} / {}", pattern, captures.0, explained.value);
+            }
+        }
+    };
+    // The last iteration's capture is repeated.
+    check(r"^(ab|cd)+\1$", &|s| s.len() >= 4 && s[s.len() - 2..] == s[s.len() - 4..s.len() - 2]);
+    check(r"^(?:x([0-9]))+-\1$", &|s| s.as_bytes()[s.len() - 3] == s.as_bytes()[s.len() - 1]);
+    // An iteration that skips the group keeps the capture of an earlier one, as in PCRE.
+    check(r"^((a)|b){2,3}\2$", &|s| s.ends_with('a') && s[..s.len() - 1].contains('a'));
+    // A group that never took part leaves nothing to refer to, so such outputs are not produced.
+    check(r"^(?:(a)|b)+\1$", &|s| s.ends_with('a') && s[..s.len() - 1].contains('a'));
+}
+
 #[test]
 fn test_anchor_tokens() {
     let start = Token::AnchorStart;
@@ -171,3 +217,40 @@ fn test_quantifier_greedy_vs_non_greedy() {
         assert!(s_g.len() >= s_l.len(), "greedy len {} should be >= lazy len {}", s_g.len(), s_l.len());
     }
 }
+
+#[test]
+fn test_compiled_program_matches_token_generation() {
+    use genrex::RegexGenerator;
+    // Plain generation runs the compiled program; explained generation recurses through the
+    // tokens. With the same seed both must make the same draws.
+    let patterns = [
+        r"^(?:cat|dog|(b|c)ird)s?$",
+        r"^([a-z]{2,5})-(x|yz)*-\1$",
+        r"^(\d{3})?(?:[A-F]{1,4}|\w\.){2,6}$",
+        r"^((a|bc)(d?)){3}\3\2$",
+        r"^https://example\.com/(users|items)/[0-9]{1,6}\?q=[a-f]{4}$",
+    ];
+    for pattern in patterns {
+        for seed in 0..20 {
+            let build = || RegexGenerator::builder(pattern).allow_backrefs().nfa(false).seed(seed).build().unwrap();
+            let plain = build().generate_one();
+            let explained = build().generate_explained();
+            assert!(plain.is_ok(), "{} seed {}", pattern, seed);
+            assert_eq!(plain.ok(), explained.ok().map(|e| e.value), "{} seed {}", pattern, seed);
+        }
+    }
+}
+
+#[test]
+fn test_fused_literals_spend_fuel_per_char() {
+    use genrex::RegexGenerator;
+    // The program appends the fixed prefix in one go but must run out of fuel exactly where
+    // token-by-token generation does.
+    let pattern = r"^https://example\.com/[a-z]{3}$";
+    for fuel in 0..40 {
+        let build = || RegexGenerator::builder(pattern).nfa(false).fuel(fuel).seed(1).build().unwrap();
+        let plain = build().generate_one().ok();
+        let explained = build().generate_explained().ok().map(|e| e.value);
+        assert_eq!(plain, explained, "fuel {}", fuel);
+    }
+}
@@ -130,16 +130,10 @@ impl DummyGenerator {
 
 impl RegexStringGenerator for DummyGenerator {
     fn generate_one(&mut self) -> Result<String, GenrexError> {
-        self.inner.generate_one().map_err(|e| match e {
-            genrex::GenError::InvalidRegex(s) => GenrexError::InvalidRegex(s),
-            genrex::GenError::NoMatch => GenrexError::NoMatch,
-        })
+        self.inner.generate_one().map_err(GenrexError::from)
     }
     fn generate_n(&mut self, n: usize) -> Result<Vec<String>, GenrexError> {
-        self.inner.generate_n(n).map_err(|e| match e {
-            genrex::GenError::InvalidRegex(s) => GenrexError::InvalidRegex(s),
-            genrex::GenError::NoMatch => GenrexError::NoMatch,
-        })
+        self.inner.generate_n(n).map_err(GenrexError::from)
     }
     fn is_multiline(&self) -> bool {
         self.multiline
@@ -219,3 +213,27 @@ fn test_generate_with_strategy_success() {
     assert!(result.is_ok() || matches!(result, Err(GenrexError::NoMatch)));
     assert!(generator.is_multiline());
 }
+
+#[test]
+fn test_shortest_and_longest_strategies() {
+    let config = GeneratorConfig { min_len: 0, max_len: 12, max_attempts: 100, timeout: None };
+    let mut g = RegexGenerator::builder("^[a-c]{2,5}-(x|yz)*$").config(config).build().unwrap();
+    assert_eq!(g.generate_with_strategy("shortest").unwrap(), "aa-");
+    assert_eq!(g.generate_with_strategy("shortest").unwrap(), "aa-");
+    // Ties at the longest length go to the lexicographically smallest string.
+    assert_eq!(g.generate_with_strategy("longest").unwrap(), "aa-xxxxxxxxx");
+
+    // Length bounds from the config apply.
+    let config = GeneratorConfig { min_len: 4, max_len: 4, max_attempts: 100, timeout: None };
+    let mut g = RegexGenerator::builder("^\\d{1,6}$").config(config).build().unwrap();
+    assert_eq!(g.generate_with_strategy("shortest").unwrap(), "0000");
+    assert_eq!(g.generate_with_strategy("longest").unwrap(), "0000");
+
+    // Byte bounds are met by multi-byte text.
+    let config = GeneratorConfig { min_len: 4, max_len: 6, max_attempts: 100, timeout: None };
+    let mut g = RegexGenerator::builder("^é{1,5}$").config(config).build().unwrap();
+    assert_eq!(g.generate_with_strategy("shortest").unwrap(), "éé");
+    assert_eq!(g.generate_with_strategy("longest").unwrap(), "ééé");
+
+    assert!(matches!(g.generate_with_strategy("widest"), Err(GenrexError::UnsupportedFeature(_))));
+}
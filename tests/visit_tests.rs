@@ -0,0 +1,86 @@
+//! Tests for the token tree visitors.
+
+use genrex::visit::{walk_token, walk_token_mut};
+use genrex::{parse, Token, TokenVisitor, TokenVisitorMut};
+
+#[derive(Default)]
+struct Survey {
+    capturing: usize,
+    non_capturing: usize,
+    literals: String,
+    negated_classes: usize,
+}
+
+impl TokenVisitor for Survey {
+    fn visit_literal(&mut self, c: char) {
+        self.literals.push(c);
+    }
+
+    fn visit_class(&mut self, _chars: &[char], negated: bool) {
+        self.negated_classes += negated as usize;
+    }
+
+    fn visit_group(&mut self, inner: &Token, index: Option<usize>) {
+        match index {
+            Some(_) => self.capturing += 1,
+            None => self.non_capturing += 1,
+        }
+        self.visit_token(inner);
+    }
+}
+
+#[test]
+fn test_visitor_counts_groups_and_collects_literals() {
+    let tokens = parse(r"^id-(?:(\d+)|x([^a-c])y)+(?P<tail>z)?$").unwrap();
+    let mut survey = Survey::default();
+    for t in &tokens {
+        survey.visit_token(t);
+    }
+    assert_eq!((survey.capturing, survey.non_capturing), (3, 1));
+    assert_eq!(survey.literals, "id-xyz");
+    assert_eq!(survey.negated_classes, 1);
+}
+
+/// Collapses every class to its first character and caps repetition.
+struct Simplify;
+
+impl TokenVisitorMut for Simplify {
+    fn visit_token_mut(&mut self, token: &mut Token) {
+        if let Token::Class(chars) = token {
+            *token = Token::Literal(chars[0]);
+            return;
+        }
+        walk_token_mut(self, token);
+    }
+
+    fn visit_quantifier_mut(&mut self, token: &mut Token, min: &mut usize, max: &mut usize, _greedy: &mut bool) {
+        *max = (*max).min(*min + 1);
+        self.visit_token_mut(token);
+    }
+}
+
+#[test]
+fn test_mut_visitor_rewrites_in_place() {
+    let mut tokens = parse(r"[a-c]+(x|[0-9]{2,5})").unwrap();
+    for t in &mut tokens {
+        Simplify.visit_token_mut(t);
+    }
+    let mut survey = Survey::default();
+    for t in &tokens {
+        walk_token(&mut survey, t);
+    }
+    assert_eq!(survey.literals, "ax0");
+
+    struct Bounds(Vec<(usize, usize)>);
+    impl TokenVisitor for Bounds {
+        fn visit_quantifier(&mut self, token: &Token, min: usize, max: usize, _greedy: bool) {
+            self.0.push((min, max));
+            self.visit_token(token);
+        }
+    }
+    let mut bounds = Bounds(Vec::new());
+    for t in &tokens {
+        bounds.visit_token(t);
+    }
+    assert_eq!(bounds.0, [(1, 2), (2, 3)]);
+}
@@ -0,0 +1,104 @@
+//! Tests for weighted and readable character sampling (`realistic`, `char_weights`, `humanize`).
+
+use genrex::{CharWeights, RegexGenerator};
+
+fn counts(out: &str, c: char) -> usize {
+    out.chars().filter(|&x| x == c).count()
+}
+
+#[test]
+fn test_realistic_favors_common_letters() {
+    for nfa in [true, false] {
+        let mut g = RegexGenerator::builder("[a-z]{50}").realistic(true).nfa(nfa).seed(5).build().unwrap();
+        let out = g.generate_n(40).unwrap().concat();
+        assert!(counts(&out, 'e') > 10 * counts(&out, 'z').max(1), "nfa={}: {}", nfa, out);
+    }
+}
+
+#[test]
+fn test_realistic_false_is_uniform() {
+    let a = RegexGenerator::builder("[a-z]{50}").seed(9).build().unwrap().generate_one().unwrap();
+    let b = RegexGenerator::builder("[a-z]{50}").realistic(true).realistic(false).seed(9).build().unwrap().generate_one().unwrap();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_zero_weight_excludes_char() {
+    let weights = CharWeights::new().set('a', 0.0).set('b', 3.0);
+    for nfa in [true, false] {
+        let mut g = RegexGenerator::builder("[abc]{50}").char_weights(weights.clone()).nfa(nfa).seed(1).build().unwrap();
+        let out = g.generate_n(4).unwrap().concat();
+        assert!(!out.contains('a'), "nfa={}: {}", nfa, out);
+        assert!(counts(&out, 'b') > counts(&out, 'c'), "nfa={}: {}", nfa, out);
+    }
+}
+
+#[test]
+fn test_all_zero_weights_fall_back_to_uniform() {
+    let weights = CharWeights::new().default_weight(0.0);
+    let mut g = RegexGenerator::builder("[xy]{40}").char_weights(weights).seed(2).build().unwrap();
+    let out = g.generate_one().unwrap();
+    assert!(out.contains('x') && out.contains('y'), "{}", out);
+}
+
+#[test]
+fn test_weighted_outputs_still_match() {
+    let re = regex::Regex::new(r"^[A-Z][a-z]{3,8} [0-9]{2,4}$").unwrap();
+    let mut g = RegexGenerator::builder(r"[A-Z][a-z]{3,8} [0-9]{2,4}").realistic(true).seed(4).build().unwrap();
+    for s in g.generate_n(100).unwrap() {
+        assert!(re.is_match(&s), "{}", s);
+    }
+}
+
+#[test]
+fn test_english_weights() {
+    let w = CharWeights::english();
+    assert!(w.weight('e') > w.weight('E'));
+    assert!(w.weight('1') > w.weight('9'));
+    assert_eq!(w.weight('§'), 0.1);
+}
+
+fn is_vowel(c: char) -> bool {
+    "aeiou".contains(c)
+}
+
+#[test]
+fn test_humanize_alternates_consonants_and_vowels() {
+    for nfa in [true, false] {
+        let mut g = RegexGenerator::builder("[a-z]{10}").humanize(true).nfa(nfa).seed(6).build().unwrap();
+        for s in g.generate_n(50).unwrap() {
+            let chars: Vec<char> = s.chars().collect();
+            assert!(chars.windows(2).all(|w| is_vowel(w[0]) != is_vowel(w[1])), "nfa={}: {}", nfa, s);
+            assert!(!s.contains(['o', 'l']), "nfa={}: {}", nfa, s);
+        }
+    }
+}
+
+#[test]
+fn test_humanize_avoids_confusables_when_class_permits() {
+    let mut g = RegexGenerator::builder("[A-Z0-9]{8}").humanize(true).seed(8).build().unwrap();
+    let out = g.generate_n(50).unwrap().concat();
+    assert!(!out.contains(['0', 'O', '1', 'I']), "{}", out);
+
+    // A class of only confusables still generates.
+    let mut g = RegexGenerator::builder("[0O]{4}").humanize(true).seed(8).build().unwrap();
+    assert_eq!(g.generate_one().unwrap().len(), 4);
+}
+
+#[test]
+fn test_humanize_keeps_literals_and_matches() {
+    let re = regex::Regex::new(r"^user_[a-z]{4,6}[0-9]{2}$").unwrap();
+    let mut g = RegexGenerator::builder(r"user_[a-z]{4,6}[0-9]{2}").humanize(true).seed(3).build().unwrap();
+    for s in g.generate_n(50).unwrap() {
+        assert!(re.is_match(&s), "{}", s);
+        assert!(!s[5..].contains(['0', '1', 'o', 'l']), "{}", s);
+    }
+}
+
+#[test]
+fn test_weighted_picks_have_no_sampled_entropy() {
+    let plain = RegexGenerator::builder("[a-z]{8}").build().unwrap();
+    assert!(plain.entropy(..).unwrap().sampled_bits.is_some());
+    let weighted = RegexGenerator::builder("[a-z]{8}").humanize(true).build().unwrap();
+    assert!(weighted.entropy(..).unwrap().sampled_bits.is_none());
+}